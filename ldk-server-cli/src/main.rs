@@ -5,6 +5,7 @@ use ldk_server_client::ldk_server_protos::api::{
 	Bolt11ReceiveRequest, Bolt11SendRequest, Bolt12ReceiveRequest, Bolt12SendRequest,
 	OnchainReceiveRequest, OnchainSendRequest, OpenChannelRequest,
 };
+use ldk_server_client::ldk_server_protos::types::AddressType;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -12,13 +13,21 @@ struct Cli {
 	#[arg(short, long, default_value = "localhost:3000")]
 	base_url: String,
 
+	#[arg(long)]
+	auth_token: Option<String>,
+
 	#[command(subcommand)]
 	command: Commands,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
-	OnchainReceive,
+	OnchainReceive {
+		#[arg(long)]
+		address_type: Option<String>,
+		#[arg(long)]
+		scope: Option<String>,
+	},
 	OnchainSend {
 		#[arg(short, long)]
 		address: String,
@@ -26,20 +35,40 @@ enum Commands {
 		amount_sats: Option<u64>,
 		#[arg(long)]
 		send_all: Option<bool>,
+		#[arg(long)]
+		request_nonce: Option<String>,
+		#[arg(long)]
+		idempotency_key: Option<String>,
 	},
 	Bolt11Receive {
 		#[arg(short, long)]
 		description: String,
 		#[arg(short, long)]
-		expiry_secs: u32,
+		expiry_secs: Option<u32>,
 		#[arg(long)]
 		amount_msat: Option<u64>,
+		#[arg(long)]
+		require_exact_amount: bool,
+		#[arg(long)]
+		scope: Option<String>,
+		#[arg(long)]
+		require_inbound_capacity: bool,
 	},
 	Bolt11Send {
 		#[arg(short, long)]
 		invoice: String,
 		#[arg(long)]
 		amount_msat: Option<u64>,
+		#[arg(long)]
+		request_nonce: Option<String>,
+		#[arg(long)]
+		send_max: bool,
+		#[arg(long)]
+		max_attempts: Option<u32>,
+		#[arg(long)]
+		randomize_route: bool,
+		#[arg(long)]
+		timeout_secs: Option<u32>,
 	},
 	Bolt12Receive {
 		#[arg(short, long)]
@@ -50,6 +79,8 @@ enum Commands {
 		expiry_secs: Option<u32>,
 		#[arg(long)]
 		quantity: Option<u64>,
+		#[arg(long)]
+		scope: Option<String>,
 	},
 	Bolt12Send {
 		#[arg(short, long)]
@@ -60,6 +91,12 @@ enum Commands {
 		quantity: Option<u64>,
 		#[arg(short, long)]
 		payer_note: Option<String>,
+		#[arg(long)]
+		request_nonce: Option<String>,
+		#[arg(long)]
+		timeout_secs: Option<u32>,
+		#[arg(long)]
+		invoice_timeout_secs: Option<u32>,
 	},
 	OpenChannel {
 		#[arg(short, long)]
@@ -78,28 +115,92 @@ enum Commands {
 #[tokio::main]
 async fn main() {
 	let cli = Cli::parse();
-	let client = LdkServerClient::new(cli.base_url);
+	let mut client = LdkServerClient::new(cli.base_url);
+	if let Some(auth_token) = cli.auth_token {
+		client = client.with_auth_token(auth_token);
+	}
 
 	match cli.command {
-		Commands::OnchainReceive => {
-			handle_response(client.onchain_receive(OnchainReceiveRequest {}).await);
+		Commands::OnchainReceive { address_type, scope } => {
+			let address_type = address_type.map(|address_type| {
+				AddressType::from_str_name(&address_type.to_uppercase())
+					.unwrap_or_else(|| {
+						eprintln!("Invalid address type: {}", address_type);
+						std::process::exit(1);
+					})
+					.into()
+			});
+			handle_response(
+				client.onchain_receive(OnchainReceiveRequest { address_type, scope }).await,
+			);
 		},
-		Commands::OnchainSend { address, amount_sats, send_all } => {
+		Commands::OnchainSend {
+			address,
+			amount_sats,
+			send_all,
+			request_nonce,
+			idempotency_key,
+		} => {
 			handle_response(
-				client.onchain_send(OnchainSendRequest { address, amount_sats, send_all }).await,
+				client
+					.onchain_send(OnchainSendRequest {
+						address,
+						amount_sats,
+						send_all,
+						request_nonce,
+						idempotency_key,
+					})
+					.await,
 			);
 		},
-		Commands::Bolt11Receive { description, expiry_secs, amount_msat } => {
+		Commands::Bolt11Receive {
+			description,
+			expiry_secs,
+			amount_msat,
+			require_exact_amount,
+			scope,
+			require_inbound_capacity,
+		} => {
 			handle_response(
 				client
-					.bolt11_receive(Bolt11ReceiveRequest { description, expiry_secs, amount_msat })
+					.bolt11_receive(Bolt11ReceiveRequest {
+						description,
+						expiry_secs,
+						amount_msat,
+						require_exact_amount,
+						scope,
+						require_inbound_capacity,
+					})
 					.await,
 			);
 		},
-		Commands::Bolt11Send { invoice, amount_msat } => {
-			handle_response(client.bolt11_send(Bolt11SendRequest { invoice, amount_msat }).await);
+		Commands::Bolt11Send {
+			invoice,
+			amount_msat,
+			request_nonce,
+			send_max,
+			max_attempts,
+			randomize_route,
+			timeout_secs,
+		} => {
+			handle_response(
+				client
+					.bolt11_send(Bolt11SendRequest {
+						invoice,
+						amount_msat,
+						max_routing_fee_msat: None,
+						override_fee_limit: false,
+						request_nonce,
+						send_max,
+						max_attempts,
+						randomize_route,
+						timeout_secs,
+						invoice_fields: None,
+					})
+					.await,
+			);
 		},
-		Commands::Bolt12Receive { description, amount_msat, expiry_secs, quantity } => {
+		Commands::Bolt12Receive { description, amount_msat, expiry_secs, quantity, scope } => {
 			handle_response(
 				client
 					.bolt12_receive(Bolt12ReceiveRequest {
@@ -107,14 +208,34 @@ async fn main() {
 						amount_msat,
 						expiry_secs,
 						quantity,
+						use_blinded_paths: None,
+						scope,
 					})
 					.await,
 			);
 		},
-		Commands::Bolt12Send { offer, amount_msat, quantity, payer_note } => {
+		Commands::Bolt12Send {
+			offer,
+			amount_msat,
+			quantity,
+			payer_note,
+			request_nonce,
+			timeout_secs,
+			invoice_timeout_secs,
+		} => {
 			handle_response(
 				client
-					.bolt12_send(Bolt12SendRequest { offer, amount_msat, quantity, payer_note })
+					.bolt12_send(Bolt12SendRequest {
+						offer,
+						amount_msat,
+						quantity,
+						payer_note,
+						override_fee_limit: false,
+						request_nonce,
+						randomize_route: false,
+						timeout_secs,
+						invoice_timeout_secs,
+					})
 					.await,
 			);
 		},
@@ -0,0 +1,72 @@
+use std::fmt;
+
+/// The number of millisatoshis in a satoshi.
+const MSAT_PER_SAT: u64 = 1000;
+
+/// An amount of bitcoin, stored with millisatoshi precision.
+///
+/// Request fields suffixed with `_sats` expect satoshis and fields suffixed with `_msat` expect
+/// millisatoshis. Constructing amounts via [`Amount::from_sats`] or [`Amount::from_msats`] and
+/// converting them via [`Amount::to_sats`] or [`Amount::to_msats`] makes the unit explicit at
+/// every call site.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount {
+	msats: u64,
+}
+
+impl Amount {
+	/// Creates an amount from whole satoshis, returning `None` on overflow.
+	pub fn from_sats(sats: u64) -> Option<Self> {
+		sats.checked_mul(MSAT_PER_SAT).map(Self::from_msats)
+	}
+
+	/// Creates an amount from millisatoshis.
+	pub fn from_msats(msats: u64) -> Self {
+		Self { msats }
+	}
+
+	/// Returns the amount in millisatoshis, e.g., for `_msat` fields.
+	pub fn to_msats(&self) -> u64 {
+		self.msats
+	}
+
+	/// Returns the amount in satoshis, e.g., for `_sats` fields, or `None` if it isn't a whole
+	/// number of satoshis.
+	pub fn to_sats(&self) -> Option<u64> {
+		if self.msats % MSAT_PER_SAT == 0 {
+			Some(self.msats / MSAT_PER_SAT)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the amount in satoshis, rounding down any fraction of a satoshi.
+	pub fn to_sats_rounded_down(&self) -> u64 {
+		self.msats / MSAT_PER_SAT
+	}
+}
+
+impl fmt::Display for Amount {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} msat", self.msats)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_amount_conversions() {
+		let amount = Amount::from_sats(1_500).unwrap();
+		assert_eq!(amount.to_msats(), 1_500_000);
+		assert_eq!(amount.to_sats(), Some(1_500));
+		assert_eq!(amount, Amount::from_msats(1_500_000));
+
+		let amount = Amount::from_msats(1_500_001);
+		assert_eq!(amount.to_sats(), None);
+		assert_eq!(amount.to_sats_rounded_down(), 1_500);
+
+		assert_eq!(Amount::from_sats(u64::MAX), None);
+	}
+}
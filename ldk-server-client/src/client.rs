@@ -1,39 +1,184 @@
 use prost::Message;
 
 use crate::error::LdkServerError;
+use crate::stream::ChannelStream;
 use ldk_server_protos::api::{
-	Bolt11ReceiveRequest, Bolt11ReceiveResponse, Bolt11SendRequest, Bolt11SendResponse,
-	Bolt12ReceiveRequest, Bolt12ReceiveResponse, Bolt12SendRequest, Bolt12SendResponse,
-	CloseChannelRequest, CloseChannelResponse, ListChannelsRequest, ListChannelsResponse,
-	OnchainReceiveRequest, OnchainReceiveResponse, OnchainSendRequest, OnchainSendResponse,
-	OpenChannelRequest, OpenChannelResponse,
+	AwaitChannelReadyRequest, AwaitChannelReadyResponse, Bolt11ReceiveRequest,
+	Bolt11ReceiveResponse, Bolt11SendRequest, Bolt11SendResponse, Bolt12ReceiveRequest,
+	Bolt12ReceiveResponse, Bolt12SendRequest, Bolt12SendResponse, CanSendRequest, CanSendResponse,
+	CancelHoldInvoiceRequest, CancelHoldInvoiceResponse, ClaimLnurlWithdrawRequest,
+	ClaimLnurlWithdrawResponse, CloseChannelRequest, CloseChannelResponse,
+	CreateHoldInvoiceRequest, CreateHoldInvoiceResponse, CreateInvoiceGroupRequest,
+	CreateInvoiceGroupResponse, EstimateConfirmationRequest, EstimateConfirmationResponse,
+	ExportChannelMonitorsRequest, ExportChannelMonitorsResponse, GetAddressScopeRequest,
+	GetAddressScopeResponse, GetCapabilitiesRequest, GetCapabilitiesResponse,
+	GetCloseBalancesRequest, GetCloseBalancesResponse, GetDustExposureRequest,
+	GetDustExposureResponse, GetInboundPaymentStatusRequest, GetInboundPaymentStatusResponse,
+	GetInvoiceGroupStatusRequest, GetInvoiceGroupStatusResponse, GetLiquiditySummaryRequest,
+	GetLiquiditySummaryResponse, GetMempoolInfoRequest, GetMempoolInfoResponse,
+	GetOfferStatusRequest, GetOfferStatusResponse, GetPaymentInvoiceRequest,
+	GetPaymentInvoiceResponse, GetPeerHealthRequest, GetPeerHealthResponse,
+	GetWalletDescriptorRequest, GetWalletDescriptorResponse, ImportChannelMonitorsRequest,
+	ImportChannelMonitorsResponse, ListChannelsRequest, ListChannelsResponse, ListPeersRequest,
+	ListPeersResponse, ListPendingHtlcsRequest, ListPendingHtlcsResponse, OnchainReceiveRequest,
+	OnchainReceiveResponse, OnchainSendRequest, OnchainSendResponse, OpenChannelRequest,
+	OpenChannelResponse, PayLightningAddressRequest, PayLightningAddressResponse, PayUriRequest,
+	PayUriResponse, PingRequest, PingResponse, QueryEventsRequest, QueryEventsResponse,
+	RecoverChannelsRequest, RecoverChannelsResponse, ReloadConfigRequest, ReloadConfigResponse,
+	RotateAuthTokenRequest, RotateAuthTokenResponse, SelfTestRequest, SelfTestResponse,
+	SendManyBolt11Request, SendManyBolt11Response, SetDustExposureRequest, SetDustExposureResponse,
+	SettleHoldInvoiceRequest, SettleHoldInvoiceResponse, ShutdownRequest, ShutdownResponse,
+	SimulatePaymentFailureRequest, SimulatePaymentFailureResponse,
 };
-use reqwest::header::CONTENT_TYPE;
-use reqwest::Client;
+use ldk_server_protos::error::{ErrorCode, ErrorResponse};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, Proxy, Response};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
 
+const MESSAGE_FRAMING_HEADER: &str = "X-Message-Framing";
+const LENGTH_DELIMITED_FRAMING: &str = "length-delimited";
+
+/// The default maximum size of a response body the client is willing to buffer.
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 10 * 1024 * 1024;
+
 const ONCHAIN_RECEIVE_PATH: &str = "OnchainReceive";
 const ONCHAIN_SEND_PATH: &str = "OnchainSend";
 const BOLT11_RECEIVE_PATH: &str = "Bolt11Receive";
 const BOLT11_SEND_PATH: &str = "Bolt11Send";
+const SEND_MANY_BOLT11_PATH: &str = "SendManyBolt11";
 const BOLT12_RECEIVE_PATH: &str = "Bolt12Receive";
 const BOLT12_SEND_PATH: &str = "Bolt12Send";
 const OPEN_CHANNEL_PATH: &str = "OpenChannel";
 const CLOSE_CHANNEL_PATH: &str = "CloseChannel";
 const LIST_CHANNELS_PATH: &str = "ListChannels";
+const GET_INBOUND_PAYMENT_STATUS_PATH: &str = "GetInboundPaymentStatus";
+const CREATE_HOLD_INVOICE_PATH: &str = "CreateHoldInvoice";
+const SETTLE_HOLD_INVOICE_PATH: &str = "SettleHoldInvoice";
+const CANCEL_HOLD_INVOICE_PATH: &str = "CancelHoldInvoice";
+const PING_PATH: &str = "Ping";
+const EXPORT_CHANNEL_MONITORS_PATH: &str = "ExportChannelMonitors";
+const IMPORT_CHANNEL_MONITORS_PATH: &str = "ImportChannelMonitors";
+const AWAIT_CHANNEL_READY_PATH: &str = "AwaitChannelReady";
+const CAN_SEND_PATH: &str = "CanSend";
+const RELOAD_CONFIG_PATH: &str = "ReloadConfig";
+const GET_PEER_HEALTH_PATH: &str = "GetPeerHealth";
+const PAY_URI_PATH: &str = "PayUri";
+const PAY_LIGHTNING_ADDRESS_PATH: &str = "PayLightningAddress";
+const CLAIM_LNURL_WITHDRAW_PATH: &str = "ClaimLnurlWithdraw";
+const SELF_TEST_PATH: &str = "SelfTest";
+const QUERY_EVENTS_PATH: &str = "QueryEvents";
+const ROTATE_AUTH_TOKEN_PATH: &str = "RotateAuthToken";
+const SIMULATE_PAYMENT_FAILURE_PATH: &str = "SimulatePaymentFailure";
+const GET_MEMPOOL_INFO_PATH: &str = "GetMempoolInfo";
+const ESTIMATE_CONFIRMATION_PATH: &str = "EstimateConfirmation";
+const GET_CLOSE_BALANCES_PATH: &str = "GetCloseBalances";
+const GET_LIQUIDITY_SUMMARY_PATH: &str = "GetLiquiditySummary";
+const RECOVER_CHANNELS_PATH: &str = "RecoverChannels";
+const LIST_PEERS_PATH: &str = "ListPeers";
+const LIST_PENDING_HTLCS_PATH: &str = "ListPendingHtlcs";
+const GET_OFFER_STATUS_PATH: &str = "GetOfferStatus";
+const GET_ADDRESS_SCOPE_PATH: &str = "GetAddressScope";
+const GET_CAPABILITIES_PATH: &str = "GetCapabilities";
+const GET_WALLET_DESCRIPTOR_PATH: &str = "GetWalletDescriptor";
+const GET_DUST_EXPOSURE_PATH: &str = "GetDustExposure";
+const SET_DUST_EXPOSURE_PATH: &str = "SetDustExposure";
+const CREATE_INVOICE_GROUP_PATH: &str = "CreateInvoiceGroup";
+const GET_INVOICE_GROUP_STATUS_PATH: &str = "GetInvoiceGroupStatus";
+const SHUTDOWN_PATH: &str = "Shutdown";
+const GET_PAYMENT_INVOICE_PATH: &str = "GetPaymentInvoice";
 
 /// Client to access a hosted instance of LDK Server.
 #[derive(Clone)]
 pub struct LdkServerClient {
 	base_url: String,
 	client: Client,
+	max_response_bytes: usize,
+	length_delimited: bool,
+	auth_token: Option<String>,
+	proxy: Option<Proxy>,
+	connect_timeout: Option<Duration>,
+	/// The server's capabilities, once retrieved via [`LdkServerClient::get_capabilities`]. Shared
+	/// among clones of the client.
+	capabilities: Arc<Mutex<Option<GetCapabilitiesResponse>>>,
 }
 
 impl LdkServerClient {
 	/// Constructs a [`LdkServerClient`] using `base_url` as the ldk-server endpoint.
 	pub fn new(base_url: String) -> Self {
-		Self { base_url, client: Client::new() }
+		Self {
+			base_url,
+			client: Client::new(),
+			max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+			length_delimited: false,
+			auth_token: None,
+			proxy: None,
+			connect_timeout: None,
+			capabilities: Arc::new(Mutex::new(None)),
+		}
+	}
+
+	/// Sets the maximum size of a response body in bytes, defaults to [`DEFAULT_MAX_RESPONSE_BYTES`].
+	///
+	/// Requests whose response exceeds this limit are aborted with
+	/// [`LdkServerError::ResponseTooLarge`] rather than being buffered in memory.
+	pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+		self.max_response_bytes = max_response_bytes;
+		self
+	}
+
+	/// Sends requests as length-delimited messages, i.e., prefixed by their varint-encoded length,
+	/// and asks the server to respond in kind.
+	///
+	/// This allows the server to read exactly one message without consuming the request body to its
+	/// end. Servers that don't support length-delimited framing respond with bare messages, which
+	/// are decoded as before.
+	pub fn with_length_delimited_framing(mut self) -> Self {
+		self.length_delimited = true;
+		self
+	}
+
+	/// Authenticates requests with the server's `auth_token`, sent as a bearer token.
+	pub fn with_auth_token(mut self, auth_token: String) -> Self {
+		self.auth_token = Some(auth_token);
+		self
+	}
+
+	/// Sends all requests through the proxy at `proxy_url`, e.g., `http://127.0.0.1:8080` for an
+	/// HTTP proxy or `socks5h://127.0.0.1:9050` for Tor's SOCKS5 proxy.
+	///
+	/// With `socks5h`, the server's host name is resolved by the proxy, as is required to reach
+	/// onion services.
+	pub fn with_proxy(mut self, proxy_url: &str) -> Result<Self, LdkServerError> {
+		self.proxy = Some(Proxy::all(proxy_url)?);
+		self.client = self.build_client()?;
+		Ok(self)
+	}
+
+	/// Bounds the time spent establishing a connection to the server, or to the proxy configured via
+	/// [`LdkServerClient::with_proxy`], to `connect_timeout`.
+	///
+	/// This allows failing fast on unreachable hosts. Once connected, requests are free to take as
+	/// long as the operation they request does, e.g., while awaiting a payment's outcome.
+	pub fn with_connect_timeout(
+		mut self, connect_timeout: Duration,
+	) -> Result<Self, LdkServerError> {
+		self.connect_timeout = Some(connect_timeout);
+		self.client = self.build_client()?;
+		Ok(self)
+	}
+
+	fn build_client(&self) -> Result<Client, reqwest::Error> {
+		let mut builder = Client::builder();
+		if let Some(proxy) = &self.proxy {
+			builder = builder.proxy(proxy.clone());
+		}
+		if let Some(connect_timeout) = self.connect_timeout {
+			builder = builder.connect_timeout(connect_timeout);
+		}
+		builder.build()
 	}
 
 	/// Retrieve a new on-chain funding address.
@@ -72,6 +217,15 @@ impl LdkServerClient {
 		self.post_request(&request, &url).await
 	}
 
+	/// Send payments for multiple BOLT11 invoices.
+	/// For API contract/usage, refer to docs for [`SendManyBolt11Request`] and [`SendManyBolt11Response`].
+	pub async fn send_many_bolt11(
+		&self, request: SendManyBolt11Request,
+	) -> Result<SendManyBolt11Response, LdkServerError> {
+		let url = format!("http://{}/{SEND_MANY_BOLT11_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
 	/// Retrieve a new BOLT11 payable offer.
 	/// For API contract/usage, refer to docs for [`Bolt12ReceiveRequest`] and [`Bolt12ReceiveResponse`].
 	pub async fn bolt12_receive(
@@ -117,31 +271,834 @@ impl LdkServerClient {
 		self.post_request(&request, &url).await
 	}
 
+	/// Retrieves the status of an inbound payment identified by its payment hash.
+	/// For API contract/usage, refer to docs for [`GetInboundPaymentStatusRequest`] and [`GetInboundPaymentStatusResponse`].
+	pub async fn get_inbound_payment_status(
+		&self, request: GetInboundPaymentStatusRequest,
+	) -> Result<GetInboundPaymentStatusResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_INBOUND_PAYMENT_STATUS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve a new BOLT11 hold invoice for the given payment hash.
+	/// For API contract/usage, refer to docs for [`CreateHoldInvoiceRequest`] and [`CreateHoldInvoiceResponse`].
+	pub async fn create_hold_invoice(
+		&self, request: CreateHoldInvoiceRequest,
+	) -> Result<CreateHoldInvoiceResponse, LdkServerError> {
+		let url = format!("http://{}/{CREATE_HOLD_INVOICE_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Claims a payment held for a hold invoice using its preimage.
+	/// For API contract/usage, refer to docs for [`SettleHoldInvoiceRequest`] and [`SettleHoldInvoiceResponse`].
+	pub async fn settle_hold_invoice(
+		&self, request: SettleHoldInvoiceRequest,
+	) -> Result<SettleHoldInvoiceResponse, LdkServerError> {
+		let url = format!("http://{}/{SETTLE_HOLD_INVOICE_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Fails back a payment held for a hold invoice.
+	/// For API contract/usage, refer to docs for [`CancelHoldInvoiceRequest`] and [`CancelHoldInvoiceResponse`].
+	pub async fn cancel_hold_invoice(
+		&self, request: CancelHoldInvoiceRequest,
+	) -> Result<CancelHoldInvoiceResponse, LdkServerError> {
+		let url = format!("http://{}/{CANCEL_HOLD_INVOICE_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieves list of known channels, yielding channels one by one as they arrive rather than
+	/// buffering the whole response.
+	///
+	/// The [`LdkServerClient::with_max_response_bytes`] limit applies to each individual channel
+	/// rather than to the response as a whole.
+	/// For API contract/usage, refer to docs for [`ListChannelsRequest`] and [`ListChannelsResponse`].
+	pub async fn list_channels_stream(
+		&self, request: ListChannelsRequest,
+	) -> Result<ChannelStream, LdkServerError> {
+		let url = format!("http://{}/{LIST_CHANNELS_PATH}", self.base_url);
+		// Channels are decoded from the bare `ListChannelsResponse` encoding.
+		let response_raw = self.send_request(&request, &url, false).await?;
+		if response_raw.status().is_success() {
+			Ok(ChannelStream::new(response_raw, self.max_response_bytes))
+		} else {
+			let retry_after = retry_after(&response_raw);
+			let payload = self.read_body(response_raw).await?;
+			Err(decode_error_response(&payload, false, retry_after))
+		}
+	}
+
+	/// Echoes the request nonce along with the server's current time, e.g., to measure latency.
+	/// For API contract/usage, refer to docs for [`PingRequest`] and [`PingResponse`].
+	pub async fn ping(&self, request: PingRequest) -> Result<PingResponse, LdkServerError> {
+		let url = format!("http://{}/{PING_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Exports the node's channel monitors, encrypted with the given key.
+	/// For API contract/usage, refer to docs for [`ExportChannelMonitorsRequest`] and [`ExportChannelMonitorsResponse`].
+	pub async fn export_channel_monitors(
+		&self, request: ExportChannelMonitorsRequest,
+	) -> Result<ExportChannelMonitorsResponse, LdkServerError> {
+		let url = format!("http://{}/{EXPORT_CHANNEL_MONITORS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Imports channel monitors from an encrypted backup during recovery.
+	/// For API contract/usage, refer to docs for [`ImportChannelMonitorsRequest`] and [`ImportChannelMonitorsResponse`].
+	pub async fn import_channel_monitors(
+		&self, request: ImportChannelMonitorsRequest,
+	) -> Result<ImportChannelMonitorsResponse, LdkServerError> {
+		let url = format!("http://{}/{IMPORT_CHANNEL_MONITORS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Waits until the given channel is ready to be used.
+	/// For API contract/usage, refer to docs for [`AwaitChannelReadyRequest`] and [`AwaitChannelReadyResponse`].
+	pub async fn await_channel_ready(
+		&self, request: AwaitChannelReadyRequest,
+	) -> Result<AwaitChannelReadyResponse, LdkServerError> {
+		let url = format!("http://{}/{AWAIT_CHANNEL_READY_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Checks whether a payment of the given amount can currently be sent, without sending it.
+	/// For API contract/usage, refer to docs for [`CanSendRequest`] and [`CanSendResponse`].
+	pub async fn can_send(
+		&self, request: CanSendRequest,
+	) -> Result<CanSendResponse, LdkServerError> {
+		let url = format!("http://{}/{CAN_SEND_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Reloads the server's configuration file, applying settings that can be changed at runtime.
+	/// For API contract/usage, refer to docs for [`ReloadConfigRequest`] and [`ReloadConfigResponse`].
+	pub async fn reload_config(
+		&self, request: ReloadConfigRequest,
+	) -> Result<ReloadConfigResponse, LdkServerError> {
+		let url = format!("http://{}/{RELOAD_CONFIG_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the connection health of a peer.
+	/// For API contract/usage, refer to docs for [`GetPeerHealthRequest`] and [`GetPeerHealthResponse`].
+	pub async fn get_peer_health(
+		&self, request: GetPeerHealthRequest,
+	) -> Result<GetPeerHealthResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_PEER_HEALTH_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Pay a BIP21 URI using the best payment rail it offers.
+	/// For API contract/usage, refer to docs for [`PayUriRequest`] and [`PayUriResponse`].
+	pub async fn pay_uri(&self, request: PayUriRequest) -> Result<PayUriResponse, LdkServerError> {
+		let url = format!("http://{}/{PAY_URI_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Pay a lightning address via its LNURL-pay service.
+	/// For API contract/usage, refer to docs for [`PayLightningAddressRequest`] and [`PayLightningAddressResponse`].
+	pub async fn pay_lightning_address(
+		&self, request: PayLightningAddressRequest,
+	) -> Result<PayLightningAddressResponse, LdkServerError> {
+		let url = format!("http://{}/{PAY_LIGHTNING_ADDRESS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Claim funds from an LNURL-withdraw service.
+	/// For API contract/usage, refer to docs for [`ClaimLnurlWithdrawRequest`] and [`ClaimLnurlWithdrawResponse`].
+	pub async fn claim_lnurl_withdraw(
+		&self, request: ClaimLnurlWithdrawRequest,
+	) -> Result<ClaimLnurlWithdrawResponse, LdkServerError> {
+		let url = format!("http://{}/{CLAIM_LNURL_WITHDRAW_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Run the node's self-test.
+	/// For API contract/usage, refer to docs for [`SelfTestRequest`] and [`SelfTestResponse`].
+	pub async fn self_test(
+		&self, request: SelfTestRequest,
+	) -> Result<SelfTestResponse, LdkServerError> {
+		let url = format!("http://{}/{SELF_TEST_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Query the events emitted by the node.
+	/// For API contract/usage, refer to docs for [`QueryEventsRequest`] and [`QueryEventsResponse`].
+	pub async fn query_events(
+		&self, request: QueryEventsRequest,
+	) -> Result<QueryEventsResponse, LdkServerError> {
+		let url = format!("http://{}/{QUERY_EVENTS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Rotate the server's bearer auth token, after which clients are to be created with the new one.
+	/// For API contract/usage, refer to docs for [`RotateAuthTokenRequest`] and [`RotateAuthTokenResponse`].
+	pub async fn rotate_auth_token(
+		&self, request: RotateAuthTokenRequest,
+	) -> Result<RotateAuthTokenResponse, LdkServerError> {
+		let url = format!("http://{}/{ROTATE_AUTH_TOKEN_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Make the next Lightning send fail, on servers built with the `test-hooks` feature.
+	/// For API contract/usage, refer to docs for [`SimulatePaymentFailureRequest`] and [`SimulatePaymentFailureResponse`].
+	pub async fn simulate_payment_failure(
+		&self, request: SimulatePaymentFailureRequest,
+	) -> Result<SimulatePaymentFailureResponse, LdkServerError> {
+		let url = format!("http://{}/{SIMULATE_PAYMENT_FAILURE_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the mempool policy of the node's chain source.
+	/// For API contract/usage, refer to docs for [`GetMempoolInfoRequest`] and [`GetMempoolInfoResponse`].
+	pub async fn get_mempool_info(
+		&self, request: GetMempoolInfoRequest,
+	) -> Result<GetMempoolInfoResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_MEMPOOL_INFO_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Estimate how long it takes for a transaction to confirm.
+	/// For API contract/usage, refer to docs for [`EstimateConfirmationRequest`] and [`EstimateConfirmationResponse`].
+	pub async fn estimate_confirmation(
+		&self, request: EstimateConfirmationRequest,
+	) -> Result<EstimateConfirmationResponse, LdkServerError> {
+		let url = format!("http://{}/{ESTIMATE_CONFIRMATION_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the estimated on-chain balance per channel after a cooperative close.
+	/// For API contract/usage, refer to docs for [`GetCloseBalancesRequest`] and [`GetCloseBalancesResponse`].
+	pub async fn get_close_balances(
+		&self, request: GetCloseBalancesRequest,
+	) -> Result<GetCloseBalancesResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_CLOSE_BALANCES_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve an aggregate view of the liquidity across all channels.
+	/// For API contract/usage, refer to docs for [`GetLiquiditySummaryRequest`] and [`GetLiquiditySummaryResponse`].
+	pub async fn get_liquidity_summary(
+		&self, request: GetLiquiditySummaryRequest,
+	) -> Result<GetLiquiditySummaryResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_LIQUIDITY_SUMMARY_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Force-close the channels whose counterparty is unreachable to recover their funds.
+	/// For API contract/usage, refer to docs for [`RecoverChannelsRequest`] and [`RecoverChannelsResponse`].
+	pub async fn recover_channels(
+		&self, request: RecoverChannelsRequest,
+	) -> Result<RecoverChannelsResponse, LdkServerError> {
+		let url = format!("http://{}/{RECOVER_CHANNELS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the list of peers, one page at a time.
+	/// For API contract/usage, refer to docs for [`ListPeersRequest`] and [`ListPeersResponse`].
+	pub async fn list_peers(
+		&self, request: ListPeersRequest,
+	) -> Result<ListPeersResponse, LdkServerError> {
+		let url = format!("http://{}/{LIST_PEERS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the HTLCs currently in-flight in the node's channels.
+	/// For API contract/usage, refer to docs for [`ListPendingHtlcsRequest`] and [`ListPendingHtlcsResponse`].
+	pub async fn list_pending_htlcs(
+		&self, request: ListPendingHtlcsRequest,
+	) -> Result<ListPendingHtlcsResponse, LdkServerError> {
+		let url = format!("http://{}/{LIST_PENDING_HTLCS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the usage of a BOLT12 offer issued by the node.
+	/// For API contract/usage, refer to docs for [`GetOfferStatusRequest`] and [`GetOfferStatusResponse`].
+	pub async fn get_offer_status(
+		&self, request: GetOfferStatusRequest,
+	) -> Result<GetOfferStatusResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_OFFER_STATUS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the scope an on-chain address handed out by the node was requested for.
+	/// For API contract/usage, refer to docs for [`GetAddressScopeRequest`] and [`GetAddressScopeResponse`].
+	pub async fn get_address_scope(
+		&self, request: GetAddressScopeRequest,
+	) -> Result<GetAddressScopeResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_ADDRESS_SCOPE_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the public output descriptors of the node's on-chain wallet.
+	/// For API contract/usage, refer to docs for [`GetWalletDescriptorRequest`] and [`GetWalletDescriptorResponse`].
+	pub async fn get_wallet_descriptor(
+		&self, request: GetWalletDescriptorRequest,
+	) -> Result<GetWalletDescriptorResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_WALLET_DESCRIPTOR_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the maximum dust HTLC exposure of each channel.
+	/// For API contract/usage, refer to docs for [`GetDustExposureRequest`] and [`GetDustExposureResponse`].
+	pub async fn get_dust_exposure(
+		&self, request: GetDustExposureRequest,
+	) -> Result<GetDustExposureResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_DUST_EXPOSURE_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Limit the dust HTLC exposure of all existing channels.
+	/// For API contract/usage, refer to docs for [`SetDustExposureRequest`] and [`SetDustExposureResponse`].
+	pub async fn set_dust_exposure(
+		&self, request: SetDustExposureRequest,
+	) -> Result<SetDustExposureResponse, LdkServerError> {
+		let url = format!("http://{}/{SET_DUST_EXPOSURE_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Create multiple BOLT11 invoices which together fulfill a single order.
+	/// For API contract/usage, refer to docs for [`CreateInvoiceGroupRequest`] and [`CreateInvoiceGroupResponse`].
+	pub async fn create_invoice_group(
+		&self, request: CreateInvoiceGroupRequest,
+	) -> Result<CreateInvoiceGroupResponse, LdkServerError> {
+		let url = format!("http://{}/{CREATE_INVOICE_GROUP_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the progress of an invoice group.
+	/// For API contract/usage, refer to docs for [`GetInvoiceGroupStatusRequest`] and [`GetInvoiceGroupStatusResponse`].
+	pub async fn get_invoice_group_status(
+		&self, request: GetInvoiceGroupStatusRequest,
+	) -> Result<GetInvoiceGroupStatusResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_INVOICE_GROUP_STATUS_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Gracefully stops the node and exits the server process.
+	/// For API contract/usage, refer to docs for [`ShutdownRequest`] and [`ShutdownResponse`].
+	pub async fn shutdown(
+		&self, request: ShutdownRequest,
+	) -> Result<ShutdownResponse, LdkServerError> {
+		let url = format!("http://{}/{SHUTDOWN_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the invoice or offer an outbound payment was sent to.
+	/// For API contract/usage, refer to docs for [`GetPaymentInvoiceRequest`] and [`GetPaymentInvoiceResponse`].
+	pub async fn get_payment_invoice(
+		&self, request: GetPaymentInvoiceRequest,
+	) -> Result<GetPaymentInvoiceResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_PAYMENT_INVOICE_PATH}", self.base_url);
+		self.post_request(&request, &url).await
+	}
+
+	/// Retrieve the endpoints and features the server has enabled.
+	///
+	/// The capabilities are only requested from the server once and cached afterwards, use
+	/// [`LdkServerClient::refresh_capabilities`] to pick up changes to the server's configuration.
+	/// For API contract/usage, refer to docs for [`GetCapabilitiesRequest`] and [`GetCapabilitiesResponse`].
+	pub async fn get_capabilities(&self) -> Result<GetCapabilitiesResponse, LdkServerError> {
+		if let Some(capabilities) = self.capabilities.lock().unwrap().clone() {
+			return Ok(capabilities);
+		}
+		self.refresh_capabilities().await
+	}
+
+	/// Retrieve the endpoints and features the server has enabled, replacing the cached ones.
+	pub async fn refresh_capabilities(&self) -> Result<GetCapabilitiesResponse, LdkServerError> {
+		let url = format!("http://{}/{GET_CAPABILITIES_PATH}", self.base_url);
+		let capabilities: GetCapabilitiesResponse =
+			self.post_request(&GetCapabilitiesRequest {}, &url).await?;
+		*self.capabilities.lock().unwrap() = Some(capabilities.clone());
+		Ok(capabilities)
+	}
+
 	async fn post_request<Rq: Message, Rs: Message + Default>(
 		&self, request: &Rq, url: &str,
 	) -> Result<Rs, LdkServerError> {
-		let request_body = request.encode_to_vec();
-		let response_raw = match self
-			.client
-			.post(url)
-			.header(CONTENT_TYPE, APPLICATION_OCTET_STREAM)
-			.body(request_body)
-			.send()
-			.await
-		{
-			Ok(response) => response,
-			Err(e) => {
-				return Err(LdkServerError::InternalError(e.to_string()));
-			},
-		};
+		let response_raw = self.send_request(request, url, self.length_delimited).await?;
 		let status = response_raw.status();
-		let payload = response_raw.bytes().await?;
+		let length_delimited = response_raw
+			.headers()
+			.get(MESSAGE_FRAMING_HEADER)
+			.map_or(false, |framing| framing == LENGTH_DELIMITED_FRAMING);
+		let retry_after = retry_after(&response_raw);
+		let payload = self.read_body(response_raw).await?;
 
 		if status.is_success() {
-			Ok(Rs::decode(&payload[..])?)
+			if length_delimited {
+				Ok(Rs::decode_length_delimited(&payload[..])?)
+			} else {
+				Ok(Rs::decode(&payload[..])?)
+			}
 		} else {
-			//TODO: Error handling and error response parsing.
-			Err(LdkServerError::InternalError("Unknown Error".to_string()))
+			Err(decode_error_response(&payload, length_delimited, retry_after))
+		}
+	}
+
+	async fn send_request<Rq: Message>(
+		&self, request: &Rq, url: &str, length_delimited: bool,
+	) -> Result<Response, LdkServerError> {
+		let mut request_builder =
+			self.client.post(url).header(CONTENT_TYPE, APPLICATION_OCTET_STREAM);
+		if let Some(auth_token) = &self.auth_token {
+			request_builder =
+				request_builder.header(AUTHORIZATION, format!("Bearer {}", auth_token));
+		}
+		let request_body = if length_delimited {
+			request_builder =
+				request_builder.header(MESSAGE_FRAMING_HEADER, LENGTH_DELIMITED_FRAMING);
+			request.encode_length_delimited_to_vec()
+		} else {
+			request.encode_to_vec()
+		};
+		match request_builder.body(request_body).send().await {
+			Ok(response) => Ok(response),
+			Err(e) => Err(LdkServerError::InternalError(e.to_string())),
+		}
+	}
+
+	async fn read_body(&self, mut response: Response) -> Result<Vec<u8>, LdkServerError> {
+		let too_large = LdkServerError::ResponseTooLarge(self.max_response_bytes);
+		if response.content_length().map_or(false, |len| len > self.max_response_bytes as u64) {
+			return Err(too_large);
+		}
+
+		let mut payload = Vec::new();
+		while let Some(chunk) = response.chunk().await? {
+			if payload.len() + chunk.len() > self.max_response_bytes {
+				return Err(too_large);
+			}
+			payload.extend_from_slice(&chunk);
+		}
+		Ok(payload)
+	}
+}
+
+// Returns the delay given by the `Retry-After` header of `response`, if it's given in seconds.
+fn retry_after(response: &Response) -> Option<Duration> {
+	let retry_after = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+	retry_after.trim().parse().ok().map(Duration::from_secs)
+}
+
+// Converts the `ErrorResponse` the server sent along a non-success status into an error.
+fn decode_error_response(
+	payload: &[u8], length_delimited: bool, retry_after: Option<Duration>,
+) -> LdkServerError {
+	let decoded = if length_delimited {
+		ErrorResponse::decode_length_delimited(payload)
+	} else {
+		ErrorResponse::decode(payload)
+	};
+	let error_response = match decoded {
+		Ok(error_response) => error_response,
+		Err(e) => return e.into(),
+	};
+	let message = error_response.message;
+	match ErrorCode::from_i32(error_response.error_code).unwrap_or(ErrorCode::UnknownError) {
+		ErrorCode::InsufficientFundsError => LdkServerError::InsufficientFunds {
+			needed_sats: error_response.needed_sats,
+			available_sats: error_response.available_sats,
+		},
+		ErrorCode::InsufficientInboundCapacityError => {
+			LdkServerError::InsufficientInboundCapacity {
+				needed_sats: error_response.needed_sats,
+				available_sats: error_response.available_sats,
+			}
+		},
+		ErrorCode::InvalidRequestError => LdkServerError::InvalidRequest {
+			message,
+			validation_errors: error_response.validation_errors,
+		},
+		ErrorCode::AuthError => LdkServerError::Auth(message),
+		ErrorCode::NotFoundError => LdkServerError::NotFound {
+			message,
+			supported_endpoints: error_response.supported_endpoints,
+		},
+		ErrorCode::ConflictError => LdkServerError::Conflict(message),
+		ErrorCode::TimeoutError => LdkServerError::Timeout(message),
+		ErrorCode::ServiceUnavailableError => {
+			LdkServerError::ServiceUnavailable { message, retry_after }
+		},
+		ErrorCode::InsufficientStorageError => LdkServerError::InsufficientStorage(message),
+		error_code => LdkServerError::Server { error_code, message },
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_server_protos::types::Channel;
+	use std::time::Instant;
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use tokio::net::TcpListener;
+	use tokio::sync::oneshot;
+	use tokio::time::timeout;
+
+	// Spawns a server answering a single request with a body of `body_len` bytes.
+	async fn spawn_mock_server(body_len: usize, with_content_length: bool) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut buf = [0u8; 4096];
+			let _ = stream.read(&mut buf).await;
+			let header = if with_content_length {
+				format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body_len)
+			} else {
+				"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n".to_string()
+			};
+			let _ = stream.write_all(header.as_bytes()).await;
+			let _ = stream.write_all(&vec![0u8; body_len]).await;
+			let _ = stream.shutdown().await;
+		});
+		addr.to_string()
+	}
+
+	#[tokio::test]
+	async fn test_oversized_response_is_aborted() {
+		for with_content_length in [true, false] {
+			let base_url = spawn_mock_server(2048, with_content_length).await;
+			let client = LdkServerClient::new(base_url).with_max_response_bytes(1024);
+			match client.onchain_receive(OnchainReceiveRequest::default()).await {
+				Err(LdkServerError::ResponseTooLarge(1024)) => {},
+				res => panic!("Unexpected result: {:?}", res),
+			}
+		}
+	}
+
+	#[tokio::test]
+	async fn test_list_channels_stream_is_incremental() {
+		let channels: Vec<Channel> = (0..100)
+			.map(|i| Channel { channel_id: format!("{:064x}", i), ..Default::default() })
+			.collect();
+		let body = ListChannelsResponse { channels: channels.clone(), ..Default::default() }
+			.encode_to_vec();
+		let first_record_len = prost::encoding::message::encoded_len(1, &channels[0]);
+
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let base_url = listener.local_addr().unwrap().to_string();
+		let (first_received_sender, first_received) = oneshot::channel::<()>();
+		tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut buf = [0u8; 4096];
+			let _ = stream.read(&mut buf).await;
+			let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+			stream.write_all(header.as_bytes()).await.unwrap();
+			stream.write_all(&body[..first_record_len]).await.unwrap();
+			// Only send the remaining channels once the first one was delivered to the caller.
+			first_received.await.unwrap();
+			stream.write_all(&body[first_record_len..]).await.unwrap();
+		});
+
+		let client = LdkServerClient::new(base_url);
+		let mut stream =
+			client.list_channels_stream(ListChannelsRequest { sync_first: false }).await.unwrap();
+		let first_channel = timeout(Duration::from_secs(10), stream.next()).await.unwrap();
+		assert_eq!(first_channel.unwrap().unwrap(), channels[0]);
+		first_received_sender.send(()).unwrap();
+
+		let mut received = vec![channels[0].clone()];
+		while let Some(channel) = stream.next().await {
+			received.push(channel.unwrap());
+		}
+		assert_eq!(received, channels);
+	}
+
+	#[tokio::test]
+	async fn test_length_delimited_round_trip() {
+		let request = GetInboundPaymentStatusRequest { payment_hash: "00".repeat(32) };
+		let expected_request = request.encode_length_delimited_to_vec();
+		let response = GetInboundPaymentStatusResponse {
+			claimed_amount_msat: Some(1000),
+			..Default::default()
+		};
+		let response_body = response.encode_length_delimited_to_vec();
+
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let base_url = listener.local_addr().unwrap().to_string();
+		tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut raw_request = Vec::new();
+			while !raw_request.ends_with(&expected_request) {
+				let mut buf = [0u8; 4096];
+				let len = stream.read(&mut buf).await.unwrap();
+				assert!(len > 0, "Request ended unexpectedly");
+				raw_request.extend_from_slice(&buf[..len]);
+			}
+			let raw_request = String::from_utf8_lossy(&raw_request).to_lowercase();
+			assert!(raw_request.contains("x-message-framing: length-delimited"));
+
+			let header = format!(
+				"HTTP/1.1 200 OK\r\nX-Message-Framing: length-delimited\r\nContent-Length: {}\r\n\r\n",
+				response_body.len()
+			);
+			stream.write_all(header.as_bytes()).await.unwrap();
+			stream.write_all(&response_body).await.unwrap();
+		});
+
+		let client = LdkServerClient::new(base_url).with_length_delimited_framing();
+		assert_eq!(client.get_inbound_payment_status(request).await.unwrap(), response);
+	}
+
+	#[tokio::test]
+	async fn test_auth_token_is_sent() {
+		let response_body = PingResponse::default().encode_to_vec();
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let base_url = listener.local_addr().unwrap().to_string();
+		tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut buf = [0u8; 4096];
+			let len = stream.read(&mut buf).await.unwrap();
+			let raw_request = String::from_utf8_lossy(&buf[..len]).to_lowercase();
+			assert!(raw_request.contains("authorization: bearer secret\r\n"));
+			let header =
+				format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", response_body.len());
+			stream.write_all(header.as_bytes()).await.unwrap();
+			stream.write_all(&response_body).await.unwrap();
+		});
+
+		let client = LdkServerClient::new(base_url).with_auth_token("secret".to_string());
+		client.ping(PingRequest::default()).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_capabilities_are_cached() {
+		let response = GetCapabilitiesResponse {
+			endpoints: vec![GET_CAPABILITIES_PATH.to_string()],
+			length_delimited_framing: true,
+			request_nonce_required: false,
+		};
+		let response_body = response.encode_to_vec();
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let base_url = listener.local_addr().unwrap().to_string();
+		// Only the first request is answered, later ones have to be served from the cache.
+		tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut buf = [0u8; 4096];
+			let _ = stream.read(&mut buf).await;
+			let header = format!(
+				"HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+				response_body.len()
+			);
+			stream.write_all(header.as_bytes()).await.unwrap();
+			stream.write_all(&response_body).await.unwrap();
+		});
+
+		let client = LdkServerClient::new(base_url);
+		assert_eq!(client.get_capabilities().await.unwrap(), response);
+		assert_eq!(client.clone().get_capabilities().await.unwrap(), response);
+		assert!(client.refresh_capabilities().await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_requests_egress_through_proxy() {
+		let response = PingResponse { nonce: vec![42].into(), server_time_ms: 1_000 };
+		let response_body = response.encode_to_vec();
+		// The server's host is never resolved locally, so requests can only succeed via the proxy.
+		let base_url = "ldk-server.invalid:3000".to_string();
+
+		// An HTTP proxy receives requests in absolute form.
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+		let body = response_body.clone();
+		tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut buf = [0u8; 4096];
+			let len = stream.read(&mut buf).await.unwrap();
+			assert!(buf[..len].starts_with(b"POST http://ldk-server.invalid:3000/Ping HTTP/1.1"));
+			let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+			stream.write_all(header.as_bytes()).await.unwrap();
+			stream.write_all(&body).await.unwrap();
+		});
+		let client = LdkServerClient::new(base_url.clone()).with_proxy(&proxy_url).unwrap();
+		assert_eq!(client.ping(PingRequest::default()).await.unwrap(), response);
+
+		// A SOCKS5 proxy is asked to connect to the server's host, followed by the request itself.
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let proxy_url = format!("socks5h://{}", listener.local_addr().unwrap());
+		let body = response_body.clone();
+		tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut greeting = [0u8; 3];
+			stream.read_exact(&mut greeting).await.unwrap();
+			assert_eq!(greeting, [5, 1, 0]);
+			stream.write_all(&[5, 0]).await.unwrap();
+
+			let host = b"ldk-server.invalid";
+			let mut connect = vec![0u8; 7 + host.len()];
+			stream.read_exact(&mut connect).await.unwrap();
+			assert_eq!(connect[..5], [5, 1, 0, 3, host.len() as u8]);
+			assert_eq!(&connect[5..5 + host.len()], host);
+			assert_eq!(connect[5 + host.len()..], 3000u16.to_be_bytes());
+			stream.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 0]).await.unwrap();
+
+			let mut buf = [0u8; 4096];
+			let len = stream.read(&mut buf).await.unwrap();
+			assert!(buf[..len].starts_with(b"POST /Ping HTTP/1.1"));
+			let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+			stream.write_all(header.as_bytes()).await.unwrap();
+			stream.write_all(&body).await.unwrap();
+		});
+		let client = LdkServerClient::new(base_url).with_proxy(&proxy_url).unwrap();
+		assert_eq!(client.ping(PingRequest::default()).await.unwrap(), response);
+
+		assert!(LdkServerClient::new("localhost:3000".to_string())
+			.with_proxy("not a url")
+			.is_err());
+	}
+
+	// Spawns a server answering a single request with a `402 Payment Required` carrying `body`.
+	async fn spawn_payment_required_server(body: Vec<u8>) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut buf = [0u8; 4096];
+			let _ = stream.read(&mut buf).await;
+			let header =
+				format!("HTTP/1.1 402 Payment Required\r\nContent-Length: {}\r\n\r\n", body.len());
+			let _ = stream.write_all(header.as_bytes()).await;
+			let _ = stream.write_all(&body).await;
+		});
+		addr.to_string()
+	}
+
+	#[tokio::test]
+	async fn test_connect_timeout_fires() {
+		// Connections to a non-routable address hang until they time out.
+		let client = LdkServerClient::new("10.255.255.1:3000".to_string())
+			.with_connect_timeout(Duration::from_millis(200))
+			.unwrap();
+		let start = Instant::now();
+		let res =
+			timeout(Duration::from_secs(10), client.ping(PingRequest::default())).await.unwrap();
+		assert!(matches!(res, Err(LdkServerError::InternalError(_))));
+		assert!(start.elapsed() < Duration::from_secs(5));
+	}
+
+	#[tokio::test]
+	async fn test_onchain_insufficient_funds() {
+		let error_response = ErrorResponse {
+			message: "Insufficient funds".to_string(),
+			error_code: ErrorCode::InsufficientFundsError as i32,
+			needed_sats: Some(100_000),
+			available_sats: Some(25_000),
+			validation_errors: Vec::new(),
+			supported_endpoints: Vec::new(),
+		};
+		let base_url = spawn_payment_required_server(error_response.encode_to_vec()).await;
+		let client = LdkServerClient::new(base_url);
+		match client.onchain_send(OnchainSendRequest::default()).await {
+			Err(LdkServerError::InsufficientFunds {
+				needed_sats: Some(100_000),
+				available_sats: Some(25_000),
+			}) => {},
+			res => panic!("Unexpected result: {:?}", res),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_lightning_insufficient_funds() {
+		let error_response = ErrorResponse {
+			message: "Insufficient funds".to_string(),
+			error_code: ErrorCode::InsufficientFundsError as i32,
+			needed_sats: Some(1001),
+			available_sats: Some(500),
+			validation_errors: Vec::new(),
+			supported_endpoints: Vec::new(),
+		};
+		let base_url = spawn_payment_required_server(error_response.encode_to_vec()).await;
+		let client = LdkServerClient::new(base_url);
+		match client.bolt11_send(Bolt11SendRequest::default()).await {
+			Err(LdkServerError::InsufficientFunds {
+				needed_sats: Some(1001),
+				available_sats: Some(500),
+			}) => {},
+			res => panic!("Unexpected result: {:?}", res),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_response_within_limit_is_read() {
+		let base_url = spawn_mock_server(0, true).await;
+		let client = LdkServerClient::new(base_url).with_max_response_bytes(1024);
+		assert!(client.onchain_receive(OnchainReceiveRequest::default()).await.is_ok());
+	}
+
+	#[test]
+	fn test_error_codes_are_typed() {
+		fn decode(error_code: i32) -> LdkServerError {
+			let error_response = ErrorResponse {
+				message: "message".to_string(),
+				error_code,
+				supported_endpoints: vec!["GetNodeInfo".to_string()],
+				..Default::default()
+			};
+			decode_error_response(&error_response.encode_to_vec(), false, None)
+		}
+
+		assert!(matches!(decode(ErrorCode::AuthError as i32), LdkServerError::Auth(_)));
+		assert!(matches!(
+			decode(ErrorCode::InsufficientInboundCapacityError as i32),
+			LdkServerError::InsufficientInboundCapacity { .. }
+		));
+		match decode(ErrorCode::NotFoundError as i32) {
+			LdkServerError::NotFound { supported_endpoints, .. } => {
+				assert_eq!(supported_endpoints, vec!["GetNodeInfo".to_string()])
+			},
+			e => panic!("Unexpected error: {:?}", e),
+		}
+		assert!(matches!(decode(ErrorCode::ConflictError as i32), LdkServerError::Conflict(_)));
+		assert!(matches!(decode(ErrorCode::TimeoutError as i32), LdkServerError::Timeout(_)));
+		assert!(matches!(
+			decode(ErrorCode::ServiceUnavailableError as i32),
+			LdkServerError::ServiceUnavailable { retry_after: None, .. }
+		));
+		assert!(matches!(
+			decode(ErrorCode::InsufficientStorageError as i32),
+			LdkServerError::InsufficientStorage(_)
+		));
+		assert!(matches!(
+			decode(ErrorCode::LightningError as i32),
+			LdkServerError::Server { error_code: ErrorCode::LightningError, .. }
+		));
+		assert!(matches!(
+			decode(i32::MAX),
+			LdkServerError::Server { error_code: ErrorCode::UnknownError, .. }
+		));
+	}
+
+	#[tokio::test]
+	async fn test_service_unavailable_carries_retry_after() {
+		let body = ErrorResponse {
+			message: "Chain source unavailable".to_string(),
+			error_code: ErrorCode::ServiceUnavailableError as i32,
+			..Default::default()
+		}
+		.encode_to_vec();
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let base_url = listener.local_addr().unwrap().to_string();
+		tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut buf = [0u8; 4096];
+			let _ = stream.read(&mut buf).await;
+			let header = format!(
+				"HTTP/1.1 503 Service Unavailable\r\nRetry-After: 30\r\nContent-Length: {}\r\n\r\n",
+				body.len()
+			);
+			let _ = stream.write_all(header.as_bytes()).await;
+			let _ = stream.write_all(&body).await;
+		});
+
+		let client = LdkServerClient::new(base_url);
+		match client.onchain_send(OnchainSendRequest::default()).await {
+			Err(LdkServerError::ServiceUnavailable { retry_after, .. }) => {
+				assert_eq!(retry_after, Some(Duration::from_secs(30)))
+			},
+			res => panic!("Unexpected result: {:?}", res),
 		}
 	}
 }
@@ -0,0 +1,180 @@
+use crate::amount::Amount;
+use crate::client::LdkServerClient;
+use crate::error::LdkServerError;
+use ldk_server_protos::api::{
+	Bolt11ReceiveRequest, Bolt11SendRequest, Bolt12ReceiveRequest, Bolt12SendRequest,
+	OnchainReceiveRequest,
+};
+use std::fmt;
+
+/// A BOLT11 invoice issued by the node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invoice {
+	/// The encoded invoice, to be handed to the payer.
+	pub invoice: String,
+}
+
+/// A BOLT12 offer issued by the node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Offer {
+	/// The encoded offer, to be handed to payers.
+	pub offer: String,
+}
+
+/// The identifier of a payment, as used by the payment endpoints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PaymentId(pub [u8; 32]);
+
+impl TryFrom<&[u8]> for PaymentId {
+	type Error = LdkServerError;
+
+	fn try_from(bytes: &[u8]) -> Result<Self, LdkServerError> {
+		<[u8; 32]>::try_from(bytes).map(PaymentId).map_err(|_| {
+			LdkServerError::InternalError(format!("Invalid payment id of {} bytes", bytes.len()))
+		})
+	}
+}
+
+impl fmt::Display for PaymentId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for byte in self.0 {
+			write!(f, "{:02x}", byte)?;
+		}
+		Ok(())
+	}
+}
+
+/// Convenience methods taking Rust arguments and returning domain types, built on top of the
+/// methods mirroring the API one-to-one.
+impl LdkServerClient {
+	/// Retrieve a new on-chain address to receive funds to.
+	pub async fn receive_onchain(&self) -> Result<String, LdkServerError> {
+		let request = OnchainReceiveRequest { address_type: None, scope: None };
+		Ok(self.onchain_receive(request).await?.address)
+	}
+
+	/// Retrieve a new BOLT11 invoice for the given `amount`, or a variable-amount invoice if unset.
+	///
+	/// The invoice expires after `expiry_secs`, or after the server's default expiry if unset.
+	pub async fn receive_bolt11(
+		&self, amount: Option<Amount>, description: &str, expiry_secs: Option<u32>,
+	) -> Result<Invoice, LdkServerError> {
+		let request = bolt11_receive_request(amount, description, expiry_secs);
+		let response = self.bolt11_receive(request).await?;
+		Ok(Invoice { invoice: response.invoice })
+	}
+
+	/// Pay a BOLT11 invoice, setting `amount` for variable-amount invoices.
+	pub async fn pay_bolt11(
+		&self, invoice: &str, amount: Option<Amount>,
+	) -> Result<PaymentId, LdkServerError> {
+		let response = self.bolt11_send(bolt11_send_request(invoice, amount)).await?;
+		PaymentId::try_from(&response.payment_id[..])
+	}
+
+	/// Retrieve a new BOLT12 offer for the given `amount`, or a variable-amount offer if unset.
+	pub async fn receive_bolt12(
+		&self, amount: Option<Amount>, description: &str,
+	) -> Result<Offer, LdkServerError> {
+		let response = self.bolt12_receive(bolt12_receive_request(amount, description)).await?;
+		Ok(Offer { offer: response.offer })
+	}
+
+	/// Pay a BOLT12 offer, setting `amount` for variable-amount offers.
+	pub async fn pay_bolt12(
+		&self, offer: &str, amount: Option<Amount>,
+	) -> Result<PaymentId, LdkServerError> {
+		let response = self.bolt12_send(bolt12_send_request(offer, amount)).await?;
+		PaymentId::try_from(&response.payment_id[..])
+	}
+}
+
+fn bolt11_receive_request(
+	amount: Option<Amount>, description: &str, expiry_secs: Option<u32>,
+) -> Bolt11ReceiveRequest {
+	Bolt11ReceiveRequest {
+		amount_msat: amount.map(|amount| amount.to_msats()),
+		description: description.to_string(),
+		expiry_secs,
+		require_exact_amount: false,
+		scope: None,
+		require_inbound_capacity: false,
+	}
+}
+
+fn bolt11_send_request(invoice: &str, amount: Option<Amount>) -> Bolt11SendRequest {
+	Bolt11SendRequest {
+		invoice: invoice.to_string(),
+		amount_msat: amount.map(|amount| amount.to_msats()),
+		max_routing_fee_msat: None,
+		override_fee_limit: false,
+		request_nonce: None,
+		send_max: false,
+		max_attempts: None,
+		randomize_route: false,
+		timeout_secs: None,
+		invoice_fields: None,
+	}
+}
+
+fn bolt12_receive_request(amount: Option<Amount>, description: &str) -> Bolt12ReceiveRequest {
+	Bolt12ReceiveRequest {
+		description: description.to_string(),
+		amount_msat: amount.map(|amount| amount.to_msats()),
+		expiry_secs: None,
+		quantity: None,
+		use_blinded_paths: None,
+		scope: None,
+	}
+}
+
+fn bolt12_send_request(offer: &str, amount: Option<Amount>) -> Bolt12SendRequest {
+	Bolt12SendRequest {
+		offer: offer.to_string(),
+		amount_msat: amount.map(|amount| amount.to_msats()),
+		quantity: None,
+		payer_note: None,
+		override_fee_limit: false,
+		request_nonce: None,
+		randomize_route: false,
+		timeout_secs: None,
+		invoice_timeout_secs: None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_wrapper_requests() {
+		let amount = Amount::from_sats(21).unwrap();
+
+		let request = bolt11_receive_request(Some(amount), "coffee", Some(3600));
+		assert_eq!(request.amount_msat, Some(21_000));
+		assert_eq!(request.description, "coffee");
+		assert_eq!(request.expiry_secs, Some(3600));
+		assert_eq!(bolt11_receive_request(None, "coffee", None).amount_msat, None);
+		assert_eq!(bolt11_receive_request(None, "coffee", None).expiry_secs, None);
+
+		let request = bolt11_send_request("lnbcrt1", Some(Amount::from_msats(1_500)));
+		assert_eq!(request.invoice, "lnbcrt1");
+		assert_eq!(request.amount_msat, Some(1_500));
+
+		let request = bolt12_receive_request(Some(amount), "coffee");
+		assert_eq!(request.amount_msat, Some(21_000));
+		assert_eq!(request.description, "coffee");
+
+		let request = bolt12_send_request("lno1", None);
+		assert_eq!(request.offer, "lno1");
+		assert_eq!(request.amount_msat, None);
+	}
+
+	#[test]
+	fn test_payment_id_from_response_bytes() {
+		let payment_id = PaymentId::try_from(&[0xab; 32][..]).unwrap();
+		assert_eq!(payment_id, PaymentId([0xab; 32]));
+		assert_eq!(payment_id.to_string(), "ab".repeat(32));
+		assert!(PaymentId::try_from(&[0xab; 31][..]).is_err());
+	}
+}
@@ -1,10 +1,82 @@
+use ldk_server_protos::error::{ErrorCode, ValidationError};
 use prost::DecodeError;
+use std::time::Duration;
 
 /// When there is an error in request to LDK Server, the response contains a relevant error code.
 #[derive(Debug)]
 pub enum LdkServerError {
 	/// There is an unknown error. (Placeholder until error handling is done.)
 	InternalError(String),
+
+	/// The response body exceeded the maximum size configured via
+	/// [`LdkServerClient::with_max_response_bytes`].
+	///
+	/// [`LdkServerClient::with_max_response_bytes`]: crate::client::LdkServerClient::with_max_response_bytes
+	ResponseTooLarge(usize),
+
+	/// The node lacks the on-chain or Lightning funds to carry out the requested operation.
+	///
+	/// The amounts are set if the server was able to determine them.
+	InsufficientFunds {
+		/// The amount in satoshis the operation required.
+		needed_sats: Option<u64>,
+		/// The amount in satoshis that was available to the operation.
+		available_sats: Option<u64>,
+	},
+
+	/// The node lacks the inbound capacity to receive the requested amount.
+	InsufficientInboundCapacity {
+		/// The amount in satoshis to be received.
+		needed_sats: Option<u64>,
+		/// The inbound capacity in satoshis available to receive it.
+		available_sats: Option<u64>,
+	},
+
+	/// The request was invalid.
+	InvalidRequest {
+		/// A description of the error, intended for a human audience.
+		message: String,
+		/// The request fields that failed validation, if the error is attributable to them.
+		validation_errors: Vec<ValidationError>,
+	},
+
+	/// The request wasn't authenticated, or the authentication failed.
+	Auth(String),
+
+	/// The requested resource or endpoint could not be found.
+	NotFound {
+		/// A description of the error, intended for a human audience.
+		message: String,
+		/// The endpoints the server supports, if the request was to an unknown endpoint and the
+		/// server is configured to list them.
+		supported_endpoints: Vec<String>,
+	},
+
+	/// The request conflicts with a previous one, e.g., as it reused its request nonce.
+	Conflict(String),
+
+	/// The operation didn't complete within the requested time.
+	Timeout(String),
+
+	/// The node's chain source is unavailable.
+	ServiceUnavailable {
+		/// A description of the error, intended for a human audience.
+		message: String,
+		/// The delay after which the request may be retried, if the server gave one.
+		retry_after: Option<Duration>,
+	},
+
+	/// The node's store can't be written to, e.g., as the server's disk is full.
+	InsufficientStorage(String),
+
+	/// The server failed the request with any other error code.
+	Server {
+		/// The error code the server failed the request with, or [`ErrorCode::UnknownError`] if
+		/// it's unknown to this client.
+		error_code: ErrorCode,
+		/// A description of the error, intended for a human audience.
+		message: String,
+	},
 }
 
 impl From<DecodeError> for LdkServerError {
@@ -4,11 +4,21 @@
 #![deny(rustdoc::private_intra_doc_links)]
 #![deny(missing_docs)]
 
+/// Implements the [`amount::Amount`] type used to convert between satoshi and millisatoshi amounts.
+pub mod amount;
+
 /// Implements a ldk-ldk-server-client ([`client::LdkServerClient`]) to access a hosted instance of LDK Server.
 pub mod client;
 
+/// Implements domain types, e.g., [`domain::Invoice`], returned by the convenience methods of
+/// [`client::LdkServerClient`].
+pub mod domain;
+
 /// Implements the error type ([`error::LdkServerError`]) returned on interacting with [`client::LdkServerClient`]
 pub mod error;
 
+/// Implements incremental decoding of list responses, e.g., [`stream::ChannelStream`].
+pub mod stream;
+
 /// Request/Response structs required for interacting with the ldk-ldk-server-client.
 pub use ldk_server_protos;
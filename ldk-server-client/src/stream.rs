@@ -0,0 +1,129 @@
+use crate::error::LdkServerError;
+use ldk_server_protos::types::Channel;
+use prost::encoding::{decode_key, decode_varint, WireType};
+use prost::Message;
+use reqwest::Response;
+
+// The field number of `ListChannelsResponse.channels`.
+const CHANNELS_FIELD_NUMBER: u32 = 1;
+
+// The maximum length of a varint-encoded `u64`.
+const MAX_VARINT_LEN: usize = 10;
+
+/// A stream of [`Channel`]s, decoded incrementally from a `ListChannelsResponse`.
+///
+/// Entries of a repeated message field are encoded as a sequence of length-delimited records, so
+/// each channel can be decoded as soon as its bytes have arrived, without buffering the whole
+/// response.
+pub struct ChannelStream {
+	response: Response,
+	buffer: Vec<u8>,
+	max_message_bytes: usize,
+	finished: bool,
+}
+
+impl ChannelStream {
+	pub(crate) fn new(response: Response, max_message_bytes: usize) -> Self {
+		Self { response, buffer: Vec::new(), max_message_bytes, finished: false }
+	}
+
+	/// Returns the next channel, or `None` once the response has been consumed entirely.
+	pub async fn next(&mut self) -> Option<Result<Channel, LdkServerError>> {
+		loop {
+			match self.decode_next() {
+				Ok(Some(channel)) => return Some(Ok(channel)),
+				Ok(None) => {},
+				Err(e) => return Some(Err(self.fail(e))),
+			}
+
+			if self.finished {
+				if self.buffer.is_empty() {
+					return None;
+				}
+				let e = LdkServerError::InternalError("Response ended unexpectedly".to_string());
+				return Some(Err(self.fail(e)));
+			}
+
+			// The buffer holds at most a single incomplete record at this point.
+			if self.buffer.len() > self.max_message_bytes + 2 * MAX_VARINT_LEN {
+				let e = LdkServerError::ResponseTooLarge(self.max_message_bytes);
+				return Some(Err(self.fail(e)));
+			}
+
+			match self.response.chunk().await {
+				Ok(Some(chunk)) => self.buffer.extend_from_slice(&chunk),
+				Ok(None) => self.finished = true,
+				Err(e) => return Some(Err(self.fail(e.into()))),
+			}
+		}
+	}
+
+	fn fail(&mut self, e: LdkServerError) -> LdkServerError {
+		self.finished = true;
+		self.buffer.clear();
+		e
+	}
+
+	// Decodes the next channel from the buffered bytes, skipping any unknown fields. Returns `None`
+	// if more bytes are needed.
+	fn decode_next(&mut self) -> Result<Option<Channel>, LdkServerError> {
+		loop {
+			let mut buf = &self.buffer[..];
+			if !has_varint(buf) {
+				return Ok(None);
+			}
+			let (field_number, wire_type) = decode_key(&mut buf)?;
+
+			let value_len = match wire_type {
+				WireType::Varint => {
+					if !has_varint(buf) {
+						return Ok(None);
+					}
+					decode_varint(&mut buf)?;
+					0
+				},
+				WireType::SixtyFourBit => 8,
+				WireType::LengthDelimited => {
+					if !has_varint(buf) {
+						return Ok(None);
+					}
+					let len = decode_varint(&mut buf)?;
+					if len > self.max_message_bytes as u64 {
+						return Err(LdkServerError::ResponseTooLarge(self.max_message_bytes));
+					}
+					len as usize
+				},
+				WireType::ThirtyTwoBit => 4,
+				WireType::StartGroup | WireType::EndGroup => {
+					return Err(LdkServerError::InternalError(format!(
+						"Unsupported wire type: {:?}",
+						wire_type
+					)));
+				},
+			};
+			if buf.len() < value_len {
+				return Ok(None);
+			}
+
+			let channel = if field_number == CHANNELS_FIELD_NUMBER
+				&& wire_type == WireType::LengthDelimited
+			{
+				Some(Channel::decode(&buf[..value_len])?)
+			} else {
+				None
+			};
+			let record_len = self.buffer.len() - buf.len() + value_len;
+			self.buffer.drain(..record_len);
+
+			if channel.is_some() {
+				return Ok(channel);
+			}
+		}
+	}
+}
+
+// Returns whether `buf` starts with a complete varint, or with more bytes than any valid varint
+// spans, such that decoding it doesn't fail merely for lack of bytes.
+fn has_varint(buf: &[u8]) -> bool {
+	buf.len() >= MAX_VARINT_LEN || buf.iter().any(|byte| byte & 0x80 == 0)
+}
@@ -46,12 +46,28 @@ pub struct GetNodeInfoResponse {
 	/// Will be `None` if we have no public channels or we haven’t broadcasted since the node was initialized.
 	#[prost(uint64, optional, tag = "8")]
 	pub latest_node_announcement_broadcast_timestamp: ::core::option::Option<u64>,
+	/// The alias the node announces to the gossip network, if configured.
+	#[prost(string, optional, tag = "9")]
+	pub node_alias: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Retrieve a new on-chain funding address.
 /// See more: <https://docs.rs/ldk-node/latest/ldk_node/payment/struct.OnchainPayment.html#method.new_address>
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct OnchainReceiveRequest {}
+pub struct OnchainReceiveRequest {
+	/// The script type of the address to generate. If unset, the node's standard address type is used.
+	///
+	/// Requests for address types the node's wallet is unable to generate are rejected with an
+	/// `InvalidRequestError`.
+	#[prost(enumeration = "super::types::AddressType", optional, tag = "1")]
+	pub address_type: ::core::option::Option<i32>,
+	/// An identifier of the account the address is handed out to, e.g., a tenant of a custodial
+	/// setup. Deposits to the address can be attributed to it via `GetAddressScope`.
+	///
+	/// Scopes must be non-empty and at most 64 bytes long.
+	#[prost(string, optional, tag = "2")]
+	pub scope: ::core::option::Option<::prost::alloc::string::String>,
+}
 /// The response `content` for the `OnchainReceive` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`..
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -62,6 +78,9 @@ pub struct OnchainReceiveResponse {
 	pub address: ::prost::alloc::string::String,
 }
 /// Send an on-chain payment to the given address.
+///
+/// The fee is chosen by the node's wallet and isn't capped by the server, as LDK Node doesn't expose
+/// the fee of an on-chain send before broadcasting its transaction.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct OnchainSendRequest {
@@ -83,6 +102,27 @@ pub struct OnchainSendRequest {
 	/// See more: <https://docs.rs/ldk-node/latest/ldk_node/payment/struct.OnchainPayment.html#method.send_all_to_address>
 	#[prost(bool, optional, tag = "3")]
 	pub send_all: ::core::option::Option<bool>,
+	/// A unique value protecting the request against being replayed, of at most 64 bytes. It starts
+	/// with the time the request was created at, in seconds since the UNIX epoch, followed by a `-`
+	/// and, e.g., a random string, such as `1700000000-9f86d081884c7d65`. Required if the server
+	/// is configured with `require_request_nonce`.
+	///
+	/// Requests reusing the nonce of an earlier request, also from before a restart, are rejected
+	/// with `CONFLICT_ERROR`. As the server only tracks nonces for its configured
+	/// `request_nonce_window_secs`, nonces created further than that from the server's time are
+	/// rejected with `INVALID_REQUEST_ERROR`.
+	#[prost(string, optional, tag = "4")]
+	pub request_nonce: ::core::option::Option<::prost::alloc::string::String>,
+	/// A unique value identifying the send, e.g., a random string of at most 64 bytes, making it safe
+	/// to retry, e.g., after the request timed out. Retries carrying the key of an earlier successful
+	/// send return the txid of its transaction rather than sending again.
+	///
+	/// Reusing a key for a send to a different address or of a different amount is rejected with
+	/// `CONFLICT_ERROR`, as is retrying a send that was interrupted, e.g., by a crash of the server, as
+	/// its transaction may or may not have been broadcast. Retries aren't checked for `request_nonce`
+	/// reuse.
+	#[prost(string, optional, tag = "5")]
+	pub idempotency_key: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// The response `content` for the `OnchainSend` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
@@ -107,11 +147,35 @@ pub struct Bolt11ReceiveRequest {
 	pub amount_msat: ::core::option::Option<u64>,
 	/// An optional description to attach along with the invoice.
 	/// Will be set in the description field of the encoded payment request.
+	///
+	/// Descriptions longer than the server's `max_invoice_description_len`, which defaults to the
+	/// BOLT11 limit of 639 bytes, or containing control characters are rejected.
 	#[prost(string, tag = "2")]
 	pub description: ::prost::alloc::string::String,
 	/// Invoice expiry time in seconds.
-	#[prost(uint32, tag = "3")]
-	pub expiry_secs: u32,
+	/// If unset, the server's `default_invoice_expiry_secs` is used, which defaults to one hour.
+	#[prost(uint32, optional, tag = "3")]
+	pub expiry_secs: ::core::option::Option<u32>,
+	/// If set to true, payments are only claimed if they match `amount_msat` exactly, i.e.,
+	/// overpayments are failed back as well. Requires `amount_msat` to be set.
+	///
+	/// Note that payments below `amount_msat` are always rejected, while by default payments exceeding
+	/// it are accepted and claimed in full, as permitted by BOLT11.
+	#[prost(bool, tag = "4")]
+	pub require_exact_amount: bool,
+	/// An identifier of the account the invoice is issued for, e.g., a tenant of a custodial setup.
+	/// Payments to the invoice are attributed to it, see `GetInboundPaymentStatusResponse.scope`.
+	///
+	/// Scopes must be non-empty and at most 64 bytes long.
+	#[prost(string, optional, tag = "5")]
+	pub scope: ::core::option::Option<::prost::alloc::string::String>,
+	/// If set to true, the request fails with `INSUFFICIENT_INBOUND_CAPACITY_ERROR` if `amount_msat`
+	/// exceeds the node's current inbound capacity, stating the capacity available in
+	/// `ErrorResponse.available_sats`.
+	/// Otherwise, such invoices are issued with a warning, as payments to them fail unless more
+	/// inbound capacity is obtained in the meantime.
+	#[prost(bool, tag = "6")]
+	pub require_inbound_capacity: bool,
 }
 /// The response `content` for the `Bolt11Receive` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
@@ -123,13 +187,17 @@ pub struct Bolt11ReceiveResponse {
 	/// to the recipient.
 	#[prost(string, tag = "1")]
 	pub invoice: ::prost::alloc::string::String,
+	/// Non-fatal advisories about the invoice, e.g., that its amount exceeds the node's current
+	/// inbound capacity, intended for a human audience.
+	#[prost(string, repeated, tag = "2")]
+	pub warnings: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Send a payment for a BOLT11 invoice.
 /// See more: <https://docs.rs/ldk-node/latest/ldk_node/payment/struct.Bolt11Payment.html#method.send>
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Bolt11SendRequest {
-	/// An invoice for a payment within the Lightning Network.
+	/// An invoice for a payment within the Lightning Network. Required unless `invoice_fields` is set.
 	#[prost(string, tag = "1")]
 	pub invoice: ::prost::alloc::string::String,
 	/// Set this field when paying a so-called "zero-amount" invoice, i.e., an invoice that leaves the
@@ -137,6 +205,60 @@ pub struct Bolt11SendRequest {
 	/// This operation will fail if the amount specified is less than the value required by the given invoice.
 	#[prost(uint64, optional, tag = "2")]
 	pub amount_msat: ::core::option::Option<u64>,
+	/// The maximum total routing fee in millisatoshis to pay. Defaults to the server's configured
+	/// `max_routing_fee_msat`, if any.
+	///
+	/// Requests exceeding the server's limit are rejected unless `override_fee_limit` is set.
+	#[prost(uint64, optional, tag = "3")]
+	pub max_routing_fee_msat: ::core::option::Option<u64>,
+	/// Whether to allow exceeding the server's configured `max_routing_fee_msat`.
+	#[prost(bool, tag = "4")]
+	pub override_fee_limit: bool,
+	/// A unique value protecting the request against being replayed, of at most 64 bytes. It starts
+	/// with the time the request was created at, in seconds since the UNIX epoch, followed by a `-`
+	/// and, e.g., a random string, such as `1700000000-9f86d081884c7d65`. Required if the server
+	/// is configured with `require_request_nonce`.
+	///
+	/// Requests reusing the nonce of an earlier request, also from before a restart, are rejected
+	/// with `CONFLICT_ERROR`. As the server only tracks nonces for its configured
+	/// `request_nonce_window_secs`, nonces created further than that from the server's time are
+	/// rejected with `INVALID_REQUEST_ERROR`.
+	#[prost(string, optional, tag = "5")]
+	pub request_nonce: ::core::option::Option<::prost::alloc::string::String>,
+	/// If set to true, sends the maximum amount our usable channels allow, e.g., to drain them before
+	/// closing. Requires a zero-amount invoice and `amount_msat` to be unset.
+	///
+	/// Part of the capacity is held back to pay routing fees, namely `max_routing_fee_msat` if set
+	/// and LDK's default fee limit of 1% plus 50 satoshis otherwise.
+	#[prost(bool, tag = "6")]
+	pub send_max: bool,
+	/// If set, waits for the payment to resolve and retries it while it fails for lack of a route,
+	/// making at most `max_attempts` attempts in total (at most 10). Each attempt already explores
+	/// alternate paths for some seconds.
+	///
+	/// Payments are never retried once they succeeded, nor while they are still pending after a
+	/// minute. Otherwise, the payment is sent as a single attempt without awaiting its outcome.
+	#[prost(uint32, optional, tag = "7")]
+	pub max_attempts: ::core::option::Option<u32>,
+	/// If set to true, varies the route from payment to payment rather than always choosing the
+	/// cheapest one, making payments harder to fingerprint by the paths they take.
+	///
+	/// This randomizes how much of a channel's estimated capacity a payment may use, which shapes the
+	/// channels the pathfinder considers. The route chosen may thus pay higher fees, and large payments
+	/// may fail to find a route where they otherwise would.
+	#[prost(bool, tag = "8")]
+	pub randomize_route: bool,
+	/// The time in seconds after which the payment is abandoned and reported as failed if it's still
+	/// pending, overriding the server's `outbound_payment_timeout_secs`. Payments with HTLCs still in
+	/// flight are kept pending, as they may still succeed.
+	#[prost(uint32, optional, tag = "9")]
+	pub timeout_secs: ::core::option::Option<u32>,
+	/// The fields of the invoice to pay, for clients that already decoded it, as an alternative to
+	/// `invoice`. The server rebuilds the invoice from the fields rather than parsing its string, and
+	/// fails with an `InvalidRequestError` if its signature doesn't match them. Invoices with fields
+	/// unknown to the server can only be paid via `invoice`.
+	#[prost(message, optional, tag = "10")]
+	pub invoice_fields: ::core::option::Option<super::types::Bolt11InvoiceFields>,
 }
 /// The response `content` for the `Bolt11Send` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
@@ -146,6 +268,17 @@ pub struct Bolt11SendResponse {
 	/// An identifier used to uniquely identify a payment.
 	#[prost(bytes = "bytes", tag = "1")]
 	pub payment_id: ::prost::bytes::Bytes,
+	/// The amount in millisatoshis sent, if determined by the server due to `send_max`.
+	#[prost(uint64, optional, tag = "2")]
+	pub amount_msat: ::core::option::Option<u64>,
+	/// The number of attempts made, if `max_attempts` was set. The payment's final status can be
+	/// retrieved via `GetPaymentDetails`.
+	#[prost(uint32, optional, tag = "3")]
+	pub attempts: ::core::option::Option<u32>,
+	/// Non-fatal advisories about the payment, e.g., that its routing fee isn't limited to the
+	/// server's configured `max_routing_fee_msat`, intended for a human audience.
+	#[prost(string, repeated, tag = "4")]
+	pub warnings: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Returns a BOLT12 offer for the given amount, if specified.
 ///
@@ -168,6 +301,20 @@ pub struct Bolt12ReceiveRequest {
 	/// If set, it represents the number of items requested, can only be set for fixed-amount offers.
 	#[prost(uint64, optional, tag = "4")]
 	pub quantity: ::core::option::Option<u64>,
+	/// Whether the offer is to be reachable only via blinded paths through the introduction points
+	/// chosen among our peers, hiding our node id from payers. Defaults to `true`.
+	///
+	/// Offers are currently always generated with blinded paths, requests setting this to `false` are
+	/// rejected.
+	#[prost(bool, optional, tag = "5")]
+	pub use_blinded_paths: ::core::option::Option<bool>,
+	/// An identifier of the account the offer is issued for, e.g., a tenant of a custodial setup.
+	/// Payments for the offer are attributed to it, see `GetInboundPaymentStatusResponse.scope` and
+	/// `GetOfferStatusResponse.scope`.
+	///
+	/// Scopes must be non-empty and at most 64 bytes long.
+	#[prost(string, optional, tag = "6")]
+	pub scope: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// The response `content` for the `Bolt12Receive` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
@@ -179,11 +326,18 @@ pub struct Bolt12ReceiveResponse {
 	/// to the recipient.
 	#[prost(string, tag = "1")]
 	pub offer: ::prost::alloc::string::String,
+	/// The number of blinded paths included in the offer.
+	#[prost(uint32, tag = "2")]
+	pub blinded_path_count: u32,
 }
 /// Send a payment for a BOLT12 offer.
 /// See more:
 /// - <https://docs.rs/ldk-node/latest/ldk_node/payment/struct.Bolt12Payment.html#method.send>
 /// - <https://docs.rs/ldk-node/latest/ldk_node/payment/struct.Bolt12Payment.html#method.send_using_amount>
+///
+/// **Caution**: LDK Node doesn't allow limiting the routing fee of BOLT12 payments. While the server
+/// is configured with a `max_routing_fee_msat`, every request is rejected with an
+/// `INVALID_REQUEST_ERROR` unless it sets `override_fee_limit`.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Bolt12SendRequest {
@@ -201,6 +355,38 @@ pub struct Bolt12SendRequest {
 	/// If set, it will be seen by the recipient and reflected back in the invoice.
 	#[prost(string, optional, tag = "4")]
 	pub payer_note: ::core::option::Option<::prost::alloc::string::String>,
+	/// Whether to send regardless of the server's configured `max_routing_fee_msat`.
+	///
+	/// As the routing fee of BOLT12 payments can't be limited, they are rejected if the server
+	/// configures a `max_routing_fee_msat` unless this is set.
+	#[prost(bool, tag = "5")]
+	pub override_fee_limit: bool,
+	/// A unique value protecting the request against being replayed, of at most 64 bytes. It starts
+	/// with the time the request was created at, in seconds since the UNIX epoch, followed by a `-`
+	/// and, e.g., a random string, such as `1700000000-9f86d081884c7d65`. Required if the server
+	/// is configured with `require_request_nonce`.
+	///
+	/// Requests reusing the nonce of an earlier request, also from before a restart, are rejected
+	/// with `CONFLICT_ERROR`. As the server only tracks nonces for its configured
+	/// `request_nonce_window_secs`, nonces created further than that from the server's time are
+	/// rejected with `INVALID_REQUEST_ERROR`.
+	#[prost(string, optional, tag = "6")]
+	pub request_nonce: ::core::option::Option<::prost::alloc::string::String>,
+	/// Route randomization, see `Bolt11SendRequest.randomize_route`. Not supported for BOLT12
+	/// payments, requests setting it are rejected with an `InvalidRequestError`.
+	#[prost(bool, tag = "7")]
+	pub randomize_route: bool,
+	/// The payment timeout, see `Bolt11SendRequest.timeout_secs`.
+	#[prost(uint32, optional, tag = "8")]
+	pub timeout_secs: ::core::option::Option<u32>,
+	/// The time in seconds to wait for the recipient to respond to our invoice request, after which
+	/// the payment is abandoned and reported as failed like with `timeout_secs`, which bounds the
+	/// payment as a whole.
+	///
+	/// As it isn't known whether the invoice was received, this applies to BOLT12 payments that are
+	/// still pending without any HTLCs in flight, which includes payments failing to find a route.
+	#[prost(uint32, optional, tag = "9")]
+	pub invoice_timeout_secs: ::core::option::Option<u32>,
 }
 /// The response `content` for the `Bolt12Send` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
@@ -213,6 +399,10 @@ pub struct Bolt12SendResponse {
 }
 /// Creates a new outbound channel to the given remote node.
 /// See more: <https://docs.rs/ldk-node/latest/ldk_node/struct.Node.html#method.connect_open_channel>
+///
+/// Whether the channel is usable before its funding transaction confirms is up to the counterparty,
+/// as LDK Node doesn't allow requesting zero-confirmation channels. Only channels opened to us by
+/// the server's `trusted_peers_0conf` can be accepted as such.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct OpenChannelRequest {
@@ -224,6 +414,7 @@ pub struct OpenChannelRequest {
 	#[prost(string, tag = "2")]
 	pub address: ::prost::alloc::string::String,
 	/// The amount of satoshis the caller is willing to commit to the channel.
+	/// Must be at least 2000 sats, failing with `INVALID_REQUEST_ERROR` otherwise.
 	#[prost(uint64, tag = "3")]
 	pub channel_amount_sats: u64,
 	/// The amount of satoshis to push to the remote side as part of the initial commitment state.
@@ -244,6 +435,10 @@ pub struct OpenChannelResponse {
 	/// The channel id of the created channel that user can use to refer to channel.
 	#[prost(bytes = "bytes", tag = "1")]
 	pub user_channel_id: ::prost::bytes::Bytes,
+	/// Non-fatal advisories about the channel, e.g., that it is smaller than recommended, intended for
+	/// a human audience.
+	#[prost(string, repeated, tag = "2")]
+	pub warnings: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Update the config for a previously opened channel.
 /// See more: <https://docs.rs/ldk-node/latest/ldk_node/struct.Node.html#method.update_channel_config>
@@ -284,6 +479,17 @@ pub struct CloseChannelRequest {
 	/// The reason for force-closing, can only be set while force closing a channel.
 	#[prost(string, optional, tag = "4")]
 	pub force_close_reason: ::core::option::Option<::prost::alloc::string::String>,
+	/// The number of seconds after which a cooperative close is considered timed out if the channel
+	/// still isn't closed, e.g., because the peer is offline or doesn't respond.
+	///
+	/// Can only be set while closing a channel cooperatively. Timed-out closes are logged, and
+	/// escalated to a force-close if `force_close_on_timeout` is set.
+	#[prost(uint32, optional, tag = "5")]
+	pub cooperative_timeout_secs: ::core::option::Option<u32>,
+	/// Whether to force-close the channel once `cooperative_timeout_secs` passed, can only be set
+	/// along with `cooperative_timeout_secs`.
+	#[prost(bool, tag = "6")]
+	pub force_close_on_timeout: bool,
 }
 /// The response `content` for the `CloseChannel` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
@@ -294,7 +500,14 @@ pub struct CloseChannelResponse {}
 /// See more: <https://docs.rs/ldk-node/latest/ldk_node/struct.Node.html#method.list_channels>
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct ListChannelsRequest {}
+pub struct ListChannelsRequest {
+	/// If set, the wallets are synced with the chain source before the channels are listed, so that
+	/// recent on-chain events, e.g. a confirmed funding transaction, are reflected.
+	/// Otherwise, the state as of the last background sync is returned. Syncing may take several
+	/// seconds and fails with a `ServiceUnavailableError` if the chain source is unreachable.
+	#[prost(bool, tag = "1")]
+	pub sync_first: bool,
+}
 /// The response `content` for the `ListChannels` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -303,6 +516,13 @@ pub struct ListChannelsResponse {
 	/// List of channels.
 	#[prost(message, repeated, tag = "1")]
 	pub channels: ::prost::alloc::vec::Vec<super::types::Channel>,
+	/// Whether the wallets were synced for this request, i.e., `FRESH` if `sync_first` was set.
+	#[prost(enumeration = "super::types::DataFreshness", tag = "2")]
+	pub data_freshness: i32,
+	/// How long ago, in milliseconds, the Lightning wallet was last synced. Unset if it was never
+	/// synced.
+	#[prost(uint64, optional, tag = "3")]
+	pub data_age_ms: ::core::option::Option<u64>,
 }
 /// Returns payment details for a given payment_id.
 /// See more: <https://docs.rs/ldk-node/latest/ldk_node/struct.Node.html#method.payment>
@@ -312,6 +532,9 @@ pub struct GetPaymentDetailsRequest {
 	/// An identifier used to uniquely identify a payment in hex-encoded form.
 	#[prost(string, tag = "1")]
 	pub payment_id: ::prost::alloc::string::String,
+	/// Whether to include `diagnostics` in the response, for debugging failed payments.
+	#[prost(bool, tag = "2")]
+	pub verbose: bool,
 }
 /// The response `content` for the `GetPaymentDetails` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
@@ -322,12 +545,156 @@ pub struct GetPaymentDetailsResponse {
 	/// Will be `None` if payment doesn't exist.
 	#[prost(message, optional, tag = "1")]
 	pub payment: ::core::option::Option<super::types::Payment>,
+	/// Details recorded while the payment was in flight.
+	/// Will only be set if `verbose` was requested and the payment exists.
+	#[prost(message, optional, tag = "2")]
+	pub diagnostics: ::core::option::Option<super::types::PaymentDiagnostics>,
+	/// The hex-encoded preimage of a succeeded outbound payment, which hashes to its payment hash and
+	/// thereby serves as proof of payment. Unset for any other payment.
+	#[prost(string, optional, tag = "3")]
+	pub payment_preimage: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Returns the status of an inbound payment for a given payment_hash.
+///
+/// This is intended for reconciling invoices issued by this node, where only the payment hash of
+/// the invoice is known to the caller.
+/// See more: <https://docs.rs/ldk-node/latest/ldk_node/struct.Node.html#method.payment>
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInboundPaymentStatusRequest {
+	/// The hex-encoded payment hash of an invoice issued by this node.
+	#[prost(string, tag = "1")]
+	pub payment_hash: ::prost::alloc::string::String,
+}
+/// The response `content` for the `GetInboundPaymentStatus` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInboundPaymentStatusResponse {
+	/// The status of the inbound payment.
+	#[prost(enumeration = "super::types::InboundPaymentStatus", tag = "1")]
+	pub status: i32,
+	/// The amount, in millisatoshis, that was claimed.
+	///
+	/// Will be `None` unless the payment was claimed.
+	#[prost(uint64, optional, tag = "2")]
+	pub claimed_amount_msat: ::core::option::Option<u64>,
+	/// The amount the invoice was issued for, if known and not a variable-amount invoice.
+	#[prost(uint64, optional, tag = "3")]
+	pub expected_amount_msat: ::core::option::Option<u64>,
+	/// The note the payer attached to their BOLT12 invoice request, if any.
+	///
+	/// Will be `None` for BOLT11 and spontaneous payments, as the payer's identity isn't revealed to us.
+	#[prost(string, optional, tag = "4")]
+	pub payer_note: ::core::option::Option<::prost::alloc::string::String>,
+	/// The scope the invoice or offer paid was issued for, if any.
+	#[prost(string, optional, tag = "5")]
+	pub scope: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Return a BOLT11 hold invoice for the given payment hash.
+///
+/// Payments to a hold invoice aren't claimed upon arrival, but held until they are either settled via
+/// `SettleHoldInvoice` or cancelled via `CancelHoldInvoice`. If neither happens before the claim
+/// deadline is reached, the payment is failed back automatically.
+/// See more: <https://docs.rs/ldk-node/latest/ldk_node/payment/struct.Bolt11Payment.html#method.receive_for_hash>
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateHoldInvoiceRequest {
+	/// The amount in millisatoshi to receive. If unset, a "zero-amount" or variable-amount invoice is returned.
+	#[prost(uint64, optional, tag = "1")]
+	pub amount_msat: ::core::option::Option<u64>,
+	/// The hex-encoded payment hash of the invoice, for which only the caller knows the preimage.
+	#[prost(string, tag = "2")]
+	pub payment_hash: ::prost::alloc::string::String,
+	/// An optional description to attach along with the invoice.
+	#[prost(string, tag = "3")]
+	pub description: ::prost::alloc::string::String,
+	/// Invoice expiry time in seconds.
+	#[prost(uint32, tag = "4")]
+	pub expiry_secs: u32,
+	/// An identifier of the account the invoice is issued for, e.g., a tenant of a custodial setup.
+	/// Payments to the invoice are attributed to it, see `GetInboundPaymentStatusResponse.scope`.
+	///
+	/// Scopes must be non-empty and at most 64 bytes long.
+	#[prost(string, optional, tag = "5")]
+	pub scope: ::core::option::Option<::prost::alloc::string::String>,
 }
+/// The response `content` for the `CreateHoldInvoice` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateHoldInvoiceResponse {
+	/// An invoice for a payment within the Lightning Network.
+	#[prost(string, tag = "1")]
+	pub invoice: ::prost::alloc::string::String,
+}
+/// Claim a payment held for a hold invoice by revealing its preimage.
+/// See more: <https://docs.rs/ldk-node/latest/ldk_node/payment/struct.Bolt11Payment.html#method.claim_for_hash>
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SettleHoldInvoiceRequest {
+	/// The hex-encoded preimage of the hold invoice's payment hash.
+	#[prost(string, tag = "1")]
+	pub preimage: ::prost::alloc::string::String,
+}
+/// The response `content` for the `SettleHoldInvoice` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SettleHoldInvoiceResponse {}
+/// Fail back a payment held for a hold invoice.
+/// See more: <https://docs.rs/ldk-node/latest/ldk_node/payment/struct.Bolt11Payment.html#method.fail_for_hash>
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelHoldInvoiceRequest {
+	/// The hex-encoded payment hash of the hold invoice.
+	#[prost(string, tag = "1")]
+	pub payment_hash: ::prost::alloc::string::String,
+}
+/// The response `content` for the `CancelHoldInvoice` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelHoldInvoiceResponse {}
 /// Retrieves list of all payments.
+///
+/// Payments are only paged if `page_token` or `count` is set. Otherwise, all payments matching the
+/// filters are returned in a single response.
 /// See more: <https://docs.rs/ldk-node/latest/ldk_node/struct.Node.html#method.list_payments>
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct ListPaymentsRequest {}
+pub struct ListPaymentsRequest {
+	/// An opaque token identifying the page to retrieve, as returned in `ListPaymentsResponse.next_page_token`.
+	/// If unset, the first page is returned.
+	/// Payments are ordered by payment id and each page continues after the last payment of the
+	/// previous one, so payments created while paging don't shift later pages.
+	/// Filters are applied before pagination, so the same filters must be passed along with a token,
+	/// while `count` may change between pages.
+	/// Tokens are signed with a key the server generates on startup, so every restart invalidates all
+	/// outstanding tokens. They also expire after some time. Invalid or expired tokens, as well as
+	/// tokens passed along with other filters, are rejected with an `InvalidRequestError`, after which
+	/// paging has to start over from the first page.
+	#[prost(string, optional, tag = "1")]
+	pub page_token: ::core::option::Option<::prost::alloc::string::String>,
+	/// The maximum number of payments to return, capped by the server's maximum page size of 100.
+	/// If unset while `page_token` is set, the server's maximum page size is used.
+	#[prost(uint32, optional, tag = "2")]
+	pub count: ::core::option::Option<u32>,
+	/// If set, only payments with the given status are returned.
+	#[prost(enumeration = "super::types::PaymentStatus", optional, tag = "3")]
+	pub status: ::core::option::Option<i32>,
+	/// If set, only payments in the given direction are returned.
+	#[prost(enumeration = "super::types::PaymentDirection", optional, tag = "4")]
+	pub direction: ::core::option::Option<i32>,
+	/// If set, only payments last updated at or after this time, in milliseconds since the UNIX
+	/// epoch, are returned.
+	#[prost(uint64, optional, tag = "5")]
+	pub start_ms: ::core::option::Option<u64>,
+	/// If set, only payments last updated before this time, in milliseconds since the UNIX epoch,
+	/// are returned.
+	#[prost(uint64, optional, tag = "6")]
+	pub end_ms: ::core::option::Option<u64>,
+}
 /// The response `content` for the `ListPayments` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -336,12 +703,23 @@ pub struct ListPaymentsResponse {
 	/// List of payments.
 	#[prost(message, repeated, tag = "1")]
 	pub payments: ::prost::alloc::vec::Vec<super::types::Payment>,
+	/// A token to be passed as `ListPaymentsRequest.page_token` to retrieve the next page.
+	/// Unset if there are no more payments.
+	#[prost(string, optional, tag = "2")]
+	pub next_page_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Retrieves an overview of all known balances.
 /// See more: <https://docs.rs/ldk-node/latest/ldk_node/struct.Node.html#method.list_balances>
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetBalancesRequest {}
+pub struct GetBalancesRequest {
+	/// If set, the wallets are synced with the chain source before the balances are computed, so
+	/// that recently broadcast or confirmed transactions are reflected, e.g. before a large send.
+	/// Otherwise, the state as of the last background sync is returned. Syncing may take several
+	/// seconds and fails with a `ServiceUnavailableError` if the chain source is unreachable.
+	#[prost(bool, tag = "1")]
+	pub sync_first: bool,
+}
 /// The response `content` for the `GetBalances` API, when HttpStatusCode is OK (200).
 /// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -385,4 +763,834 @@ pub struct GetBalancesResponse {
 	#[prost(message, repeated, tag = "6")]
 	pub pending_balances_from_channel_closures:
 		::prost::alloc::vec::Vec<super::types::PendingSweepBalance>,
+	/// Whether the wallets were synced for this request, i.e., `FRESH` if `sync_first` was set.
+	#[prost(enumeration = "super::types::DataFreshness", tag = "7")]
+	pub data_freshness: i32,
+	/// How long ago, in milliseconds, the older of the on-chain and Lightning wallets was last synced.
+	/// Unset if either wallet was never synced.
+	#[prost(uint64, optional, tag = "8")]
+	pub data_age_ms: ::core::option::Option<u64>,
+}
+/// Echoes the given nonce along with the server's current time, without touching any node state.
+///
+/// This is intended for measuring round-trip latency and detecting clock skew. Unlike other requests,
+/// `Ping` requests aren't required to carry the auth token if `allow_unauthenticated_ping` is set.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PingRequest {
+	/// An arbitrary value echoed back in the response.
+	#[prost(bytes = "bytes", tag = "1")]
+	pub nonce: ::prost::bytes::Bytes,
+}
+/// The response `content` for the `Ping` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PingResponse {
+	/// The nonce given in the request.
+	#[prost(bytes = "bytes", tag = "1")]
+	pub nonce: ::prost::bytes::Bytes,
+	/// The server's current time in milliseconds since the UNIX epoch.
+	#[prost(uint64, tag = "2")]
+	pub server_time_ms: u64,
+}
+/// Exports the node's channel monitors, encrypted with the given key, for disaster recovery.
+///
+/// Channel monitors hold the latest channel states and are required in addition to the seed to
+/// recover funds in channels. The backup needs to be re-exported whenever channels are updated to
+/// be of use.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportChannelMonitorsRequest {
+	/// The 32-byte key used to encrypt the backup with ChaCha20-Poly1305.
+	#[prost(bytes = "bytes", tag = "1")]
+	pub encryption_key: ::prost::bytes::Bytes,
+}
+/// The response `content` for the `ExportChannelMonitors` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportChannelMonitorsResponse {
+	/// The encrypted backup, the random 12-byte nonce followed by the encrypted and authenticated
+	/// `types.ChannelMonitorBackup`.
+	#[prost(bytes = "bytes", tag = "1")]
+	pub encrypted_backup: ::prost::bytes::Bytes,
+	/// The number of channel monitors contained in the backup.
+	#[prost(uint32, tag = "2")]
+	pub monitor_count: u32,
+}
+/// Imports channel monitors from a backup created via `ExportChannelMonitors` during recovery.
+///
+/// Only allowed while the server runs in recovery mode, i.e., was started with `--recovery` such
+/// that the node is not running. Every monitor is checked to deserialize and to be stored under the
+/// key of its funding outpoint, the backup is rejected as a whole otherwise.
+///
+/// Monitors already known to the node are never overwritten. Imported monitors are only loaded once
+/// the node is restarted, at which point the channels unknown to the node are force-closed to
+/// recover their funds.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportChannelMonitorsRequest {
+	/// The 32-byte key the backup was encrypted with.
+	#[prost(bytes = "bytes", tag = "1")]
+	pub encryption_key: ::prost::bytes::Bytes,
+	/// The backup as returned by `ExportChannelMonitors`.
+	#[prost(bytes = "bytes", tag = "2")]
+	pub encrypted_backup: ::prost::bytes::Bytes,
+}
+/// The response `content` for the `ImportChannelMonitors` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportChannelMonitorsResponse {
+	/// The number of channel monitors imported from the backup.
+	#[prost(uint32, tag = "1")]
+	pub imported_count: u32,
+	/// The number of channel monitors skipped as they were already known to the node.
+	#[prost(uint32, tag = "2")]
+	pub skipped_count: u32,
+}
+/// Force-closes the channels whose counterparty is currently unreachable to recover their funds on
+/// chain, e.g., after restarting the node with monitors imported via `ImportChannelMonitors`.
+///
+/// Only the channels listed in `channel_ids` are considered or, if none are listed, the channels
+/// whose monitors were imported via `ImportChannelMonitors`. Of these, channels with a connected
+/// counterparty are left untouched, as they can be closed cooperatively.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecoverChannelsRequest {
+	/// Must be set to true to confirm that the affected channels are to be force-closed.
+	#[prost(bool, tag = "1")]
+	pub confirm_force_close: bool,
+	/// The hex-encoded ids of the channels to recover. If empty, the channels whose monitors were
+	/// imported are recovered.
+	#[prost(string, repeated, tag = "2")]
+	pub channel_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// The response `content` for the `RecoverChannels` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecoverChannelsResponse {
+	/// The recovery action taken for each of our channels.
+	#[prost(message, repeated, tag = "1")]
+	pub recoveries: ::prost::alloc::vec::Vec<super::types::ChannelRecovery>,
+}
+/// Waits until the given channel is ready to be used, e.g., after opening it via `OpenChannel`.
+///
+/// Fails with `TIMEOUT_ERROR` if the channel didn't become ready within `timeout_secs`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AwaitChannelReadyRequest {
+	/// The local `user_channel_id` of the channel, as returned by `OpenChannel`.
+	#[prost(bytes = "bytes", tag = "1")]
+	pub user_channel_id: ::prost::bytes::Bytes,
+	/// The maximum time in seconds to wait for the channel to become ready. Defaults to 60 seconds,
+	/// at most 600 seconds are allowed.
+	#[prost(uint32, optional, tag = "2")]
+	pub timeout_secs: ::core::option::Option<u32>,
+}
+/// The response `content` for the `AwaitChannelReady` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AwaitChannelReadyResponse {
+	/// The channel, once it is ready.
+	#[prost(message, optional, tag = "1")]
+	pub channel: ::core::option::Option<super::types::Channel>,
+}
+/// Checks whether a payment of the given amount can currently be sent, without sending it.
+///
+/// This only considers the outbound liquidity of our usable channels and whether the destination is
+/// known, so a payment may still fail for lack of liquidity further along the route.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanSendRequest {
+	/// The amount in millisatoshis to check.
+	#[prost(uint64, tag = "1")]
+	pub amount_msat: u64,
+	/// The hex-encoded public key of the recipient, if known.
+	#[prost(string, optional, tag = "2")]
+	pub destination: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// The response `content` for the `CanSend` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanSendResponse {
+	/// Whether the payment can currently be sent.
+	#[prost(bool, tag = "1")]
+	pub sendable: bool,
+	/// The maximum amount in millisatoshis we can currently send across all our usable channels.
+	#[prost(uint64, tag = "2")]
+	pub max_sendable_msat: u64,
+}
+/// Reloads the configuration file the server was started with, applying changed settings that are
+/// safe to change at runtime.
+///
+/// These are `header_read_timeout_secs`, `chain_source_failure_threshold`,
+/// `chain_source_retry_after_secs`, `disabled_endpoints`, `max_routing_fee_msat`,
+/// `require_request_nonce`, `request_nonce_window_secs`, `max_invoice_description_len`,
+/// `default_invoice_expiry_secs`, `list_supported_endpoints`, `onchain_receive_dedup_window_secs`,
+/// `outbound_payment_timeout_secs`, `max_buffered_events`, `buffered_events_policy`,
+/// `cors_allowed_origins`, `cors_allowed_methods` and `cors_allowed_headers`. If any other setting changed, the configuration is rejected as a whole and nothing is applied.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReloadConfigRequest {}
+/// The response `content` for the `ReloadConfig` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReloadConfigResponse {
+	/// The names of the settings that were changed.
+	#[prost(string, repeated, tag = "1")]
+	pub updated_settings: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Reports the connection health of a peer, as observed since the server started.
+///
+/// Fails with `NOT_FOUND_ERROR` if the peer was never seen.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPeerHealthRequest {
+	/// The hex-encoded public key of the peer.
+	#[prost(string, tag = "1")]
+	pub node_id: ::prost::alloc::string::String,
+}
+/// The response `content` for the `GetPeerHealth` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPeerHealthResponse {
+	/// Whether we are currently connected to the peer.
+	#[prost(bool, tag = "1")]
+	pub is_connected: bool,
+	/// The time in seconds the current connection has been up, if connected.
+	#[prost(uint64, optional, tag = "2")]
+	pub connection_uptime_secs: ::core::option::Option<u64>,
+	/// The time the peer last disconnected, as seconds since the UNIX epoch, if it ever did.
+	#[prost(uint64, optional, tag = "3")]
+	pub last_disconnected_at: ::core::option::Option<u64>,
+	/// The number of times the peer reconnected after being disconnected.
+	#[prost(uint32, tag = "4")]
+	pub reconnect_count: u32,
+}
+/// Pays a BIP21 `bitcoin:` URI, e.g., as encoded in a unified QR code, using the best payment rail
+/// it offers.
+///
+/// BOLT12 offers (`lno`) are preferred over BOLT11 invoices (`lightning`), which are preferred over
+/// the on-chain address. Lightning rails are only used if our usable channels can cover the amount,
+/// and BOLT12 offers are skipped if the server is configured with a `max_routing_fee_msat`, as
+/// their routing fee can't be limited. Paying on-chain requires the URI to carry an `amount`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PayUriRequest {
+	/// The BIP21 URI to pay.
+	#[prost(string, tag = "1")]
+	pub uri: ::prost::alloc::string::String,
+	/// A unique value protecting the request against being replayed, see `Bolt11SendRequest`.
+	#[prost(string, optional, tag = "2")]
+	pub request_nonce: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// The response `content` for the `PayUri` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PayUriResponse {
+	/// The payment rail used.
+	#[prost(enumeration = "super::types::PaymentRail", tag = "1")]
+	pub rail: i32,
+	/// The identifier of the payment, if paid over Lightning.
+	#[prost(bytes = "bytes", optional, tag = "2")]
+	pub payment_id: ::core::option::Option<::prost::bytes::Bytes>,
+	/// The id of the transaction, if paid on-chain.
+	#[prost(string, optional, tag = "3")]
+	pub txid: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Retrieves the mempool policy of the node's chain source, e.g., to avoid building transactions
+/// that won't be relayed.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMempoolInfoRequest {}
+/// The response `content` for the `GetMempoolInfo` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMempoolInfoResponse {
+	/// The minimum feerate in satoshis per 1000 weight units for transactions to be accepted into the
+	/// chain source's mempool, which rises above `min_relay_feerate_sat_per_1000_weight` when the
+	/// mempool is full.
+	#[prost(uint64, tag = "1")]
+	pub mempool_min_feerate_sat_per_1000_weight: u64,
+	/// The minimum feerate in satoshis per 1000 weight units for transactions to be relayed.
+	#[prost(uint64, tag = "2")]
+	pub min_relay_feerate_sat_per_1000_weight: u64,
+	/// Whether the chain source's mempool accepts replacements of transactions not signaling
+	/// replaceability (full-RBF).
+	#[prost(bool, tag = "3")]
+	pub full_rbf: bool,
+	/// Whether on-chain transactions sent by the node signal replaceability (BIP125), which they
+	/// currently always do.
+	#[prost(bool, tag = "4")]
+	pub signals_rbf: bool,
+}
+/// Estimates the on-chain balance we would receive per channel if each of our channels were
+/// cooperatively closed now.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCloseBalancesRequest {}
+/// The response `content` for the `GetCloseBalances` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCloseBalancesResponse {
+	/// The estimated close balances, one per open channel.
+	#[prost(message, repeated, tag = "1")]
+	pub close_balances: ::prost::alloc::vec::Vec<super::types::ChannelCloseBalance>,
+}
+/// Retrieves an aggregate view of the liquidity across all our channels.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLiquiditySummaryRequest {}
+/// The response `content` for the `GetLiquiditySummary` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLiquiditySummaryResponse {
+	/// The total inbound capacity across all channels, in millisatoshis.
+	#[prost(uint64, tag = "1")]
+	pub total_inbound_capacity_msat: u64,
+	/// The total outbound capacity across all channels, in millisatoshis.
+	#[prost(uint64, tag = "2")]
+	pub total_outbound_capacity_msat: u64,
+	/// The number of channels which are currently usable for sending and receiving payments.
+	#[prost(uint32, tag = "3")]
+	pub usable_channel_count: u32,
+	/// The largest inbound capacity of a single channel, in millisatoshis.
+	#[prost(uint64, tag = "4")]
+	pub max_channel_inbound_capacity_msat: u64,
+	/// The largest outbound capacity of a single channel, in millisatoshis.
+	#[prost(uint64, tag = "5")]
+	pub max_channel_outbound_capacity_msat: u64,
+}
+/// Retrieves the list of peers, ordered by node id.
+/// See more: <https://docs.rs/ldk-node/latest/ldk_node/struct.Node.html#method.list_peers>
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPeersRequest {
+	/// An opaque token identifying the page to retrieve, as returned in `ListPeersResponse.next_page_token`.
+	/// If unset, the first page is returned.
+	/// Tokens expire after some time and don't survive a server restart. Invalid or expired tokens are
+	/// rejected with an `InvalidRequestError`.
+	#[prost(string, optional, tag = "1")]
+	pub page_token: ::core::option::Option<::prost::alloc::string::String>,
+	/// The maximum number of peers to return, capped by the server.
+	/// If unset, the server's maximum page size is used.
+	#[prost(uint32, optional, tag = "2")]
+	pub count: ::core::option::Option<u32>,
+}
+/// The response `content` for the `ListPeers` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPeersResponse {
+	/// List of peers.
+	#[prost(message, repeated, tag = "1")]
+	pub peers: ::prost::alloc::vec::Vec<super::types::Peer>,
+	/// A token to be passed as `ListPeersRequest.page_token` to retrieve the next page.
+	/// Unset if there are no more peers.
+	#[prost(string, optional, tag = "2")]
+	pub next_page_token: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Retrieves the usage of a BOLT12 offer issued by us, i.e., the payments received for it so far.
+/// Offers which weren't issued via `Bolt12Receive` are rejected with a `NotFoundError`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOfferStatusRequest {
+	/// The BOLT12 offer, as returned in `Bolt12ReceiveResponse.offer`.
+	#[prost(string, tag = "1")]
+	pub offer: ::prost::alloc::string::String,
+}
+/// The response `content` for the `GetOfferStatus` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOfferStatusResponse {
+	/// The number of payments received for the offer.
+	#[prost(uint64, tag = "1")]
+	pub payment_count: u64,
+	/// The total amount received for the offer, in millisatoshis.
+	#[prost(uint64, tag = "2")]
+	pub total_received_msat: u64,
+	/// The scope the offer was issued for, if any.
+	#[prost(string, optional, tag = "3")]
+	pub scope: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Retrieves the scope an on-chain address handed out via `OnchainReceive` was requested for, to
+/// attribute deposits to it. Addresses which weren't handed out via `OnchainReceive` are rejected
+/// with a `NotFoundError`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAddressScopeRequest {
+	/// The on-chain address, as returned in `OnchainReceiveResponse.address`.
+	#[prost(string, tag = "1")]
+	pub address: ::prost::alloc::string::String,
+}
+/// The response `content` for the `GetAddressScope` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAddressScopeResponse {
+	/// The scope the address was handed out for, if any.
+	#[prost(string, optional, tag = "1")]
+	pub scope: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Lists the HTLCs currently in-flight in our open channels, e.g., to diagnose payments that are
+/// stuck or liquidity locked up by them.
+///
+/// Dust HTLCs, which aren't reflected in the commitment transaction, and inbound HTLCs we're already
+/// claiming aren't listed.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPendingHtlcsRequest {}
+/// The response `content` for the `ListPendingHtlcs` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPendingHtlcsResponse {
+	/// The pending HTLCs, ordered by their `cltv_expiry`, i.e., the ones expiring first come first.
+	#[prost(message, repeated, tag = "1")]
+	pub htlcs: ::prost::alloc::vec::Vec<super::types::PendingHtlc>,
+}
+/// Retrieves the capabilities of the server, i.e., the endpoints and features it has enabled, so that
+/// clients can adapt to servers of different versions and configurations.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCapabilitiesRequest {}
+/// The response `content` for the `GetCapabilities` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCapabilitiesResponse {
+	/// The paths of the endpoints the server has enabled, without the leading '/'.
+	#[prost(string, repeated, tag = "1")]
+	pub endpoints: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+	/// Whether the server supports length-delimited framing of requests and responses, negotiated via
+	/// the `X-Message-Framing` header.
+	#[prost(bool, tag = "2")]
+	pub length_delimited_framing: bool,
+	/// Whether payment requests are rejected unless they carry a `request_nonce`.
+	#[prost(bool, tag = "3")]
+	pub request_nonce_required: bool,
+}
+/// Retrieves the public output descriptors of the node's on-chain wallet, e.g., to import them as a
+/// watch-only wallet elsewhere to independently verify the node's on-chain balance.
+///
+/// The descriptors only contain extended public keys, never private ones. Still, they reveal all of
+/// the wallet's addresses and thus its history, so they are only served if the server is configured
+/// with an `auth_token`, and requests are rejected with `AUTH_ERROR` otherwise.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetWalletDescriptorRequest {}
+/// The response `content` for the `GetWalletDescriptor` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetWalletDescriptorResponse {
+	/// The descriptor of the wallet's receiving addresses, including its checksum.
+	#[prost(string, tag = "1")]
+	pub descriptor: ::prost::alloc::string::String,
+	/// The descriptor of the wallet's change addresses, including its checksum.
+	#[prost(string, tag = "2")]
+	pub change_descriptor: ::prost::alloc::string::String,
+}
+/// Pays multiple BOLT11 invoices at once, e.g., for payroll-style payouts.
+///
+/// Payments are initiated one after another without awaiting their outcome. A payment that can't be
+/// initiated, e.g., as its invoice is invalid, doesn't prevent the others from being made, but is
+/// reported in its `SendManyBolt11Result.error`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SendManyBolt11Request {
+	/// The invoices to pay, at least 1 and at most 100.
+	#[prost(message, repeated, tag = "1")]
+	pub payments: ::prost::alloc::vec::Vec<super::types::SendManyBolt11Payment>,
+	/// The maximum total routing fee in millisatoshis to pay for each of the payments. Defaults to the
+	/// server's configured `max_routing_fee_msat`, if any.
+	///
+	/// Requests exceeding the server's limit are rejected unless `override_fee_limit` is set.
+	#[prost(uint64, optional, tag = "2")]
+	pub max_routing_fee_msat: ::core::option::Option<u64>,
+	/// Whether to allow exceeding the server's configured `max_routing_fee_msat`.
+	#[prost(bool, tag = "3")]
+	pub override_fee_limit: bool,
+	/// A unique value protecting the request against being replayed, of at most 64 bytes. It starts
+	/// with the time the request was created at, in seconds since the UNIX epoch, followed by a `-`
+	/// and, e.g., a random string, such as `1700000000-9f86d081884c7d65`. Required if the server
+	/// is configured with `require_request_nonce`.
+	///
+	/// Requests reusing the nonce of an earlier request, also from before a restart, are rejected
+	/// with `CONFLICT_ERROR`. As the server only tracks nonces for its configured
+	/// `request_nonce_window_secs`, nonces created further than that from the server's time are
+	/// rejected with `INVALID_REQUEST_ERROR`.
+	#[prost(string, optional, tag = "4")]
+	pub request_nonce: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// The response `content` for the `SendManyBolt11` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SendManyBolt11Response {
+	/// The outcome of each payment, in the order of `SendManyBolt11Request.payments`.
+	#[prost(message, repeated, tag = "1")]
+	pub results: ::prost::alloc::vec::Vec<super::types::SendManyBolt11Result>,
+}
+/// Returns the maximum dust HTLC exposure of each of the node's channels.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDustExposureRequest {}
+/// The response `content` for the `GetDustExposure` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDustExposureResponse {
+	/// The maximum dust HTLC exposure of each channel.
+	#[prost(message, repeated, tag = "1")]
+	pub channels: ::prost::alloc::vec::Vec<super::types::ChannelDustExposure>,
+}
+/// Limits the dust HTLC exposure of all of the node's existing channels to a fixed amount, e.g., to
+/// harden them against dust attacks.
+///
+/// Channels opened later use the node's default configuration, unless `OpenChannel` specifies a
+/// `channel_config` overriding it.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetDustExposureRequest {
+	/// The maximum total dust exposure in millisatoshis, at least 1,000 and at most 1,000,000,000.
+	#[prost(uint64, tag = "1")]
+	pub max_dust_htlc_exposure_msat: u64,
+}
+/// The response `content` for the `SetDustExposure` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetDustExposureResponse {
+	/// The number of channels whose limit was updated.
+	#[prost(uint32, tag = "1")]
+	pub updated_channel_count: u32,
+}
+/// Creates multiple BOLT11 invoices which together fulfill a single order, e.g., to receive a large
+/// payment across channels whose inbound capacity wouldn't fit it as a whole.
+///
+/// The total amount is split evenly across the invoices. The progress of the order can be retrieved
+/// via `GetInvoiceGroupStatus`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateInvoiceGroupRequest {
+	/// The total amount of the order in millisatoshis.
+	#[prost(uint64, tag = "1")]
+	pub total_amount_msat: u64,
+	/// The number of invoices to split the order into, at least 2 and at most 10.
+	#[prost(uint32, tag = "2")]
+	pub parts: u32,
+	/// The description to embed in each of the invoices.
+	#[prost(string, tag = "3")]
+	pub description: ::prost::alloc::string::String,
+	/// The expiry time of the invoices in seconds. Defaults to the server's configured
+	/// `default_invoice_expiry_secs`.
+	#[prost(uint32, optional, tag = "4")]
+	pub expiry_secs: ::core::option::Option<u32>,
+	/// An identifier of the account the invoices are issued for, e.g., a tenant of a custodial setup.
+	/// Payments to them are attributed to it, see `GetInboundPaymentStatusResponse.scope` and
+	/// `GetInvoiceGroupStatusResponse.scope`.
+	///
+	/// Scopes must be non-empty and at most 64 bytes long.
+	#[prost(string, optional, tag = "5")]
+	pub scope: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// The response `content` for the `CreateInvoiceGroup` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateInvoiceGroupResponse {
+	/// The identifier of the group, to retrieve its status with.
+	#[prost(string, tag = "1")]
+	pub group_id: ::prost::alloc::string::String,
+	/// The invoices of the group, all of which need to be paid to fulfill the order.
+	#[prost(string, repeated, tag = "2")]
+	pub invoices: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Returns the progress of an invoice group created via `CreateInvoiceGroup`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInvoiceGroupStatusRequest {
+	/// The identifier of the group.
+	#[prost(string, tag = "1")]
+	pub group_id: ::prost::alloc::string::String,
+}
+/// The response `content` for the `GetInvoiceGroupStatus` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInvoiceGroupStatusResponse {
+	/// The overall status of the group.
+	#[prost(enumeration = "super::types::InvoiceGroupStatus", tag = "1")]
+	pub status: i32,
+	/// The total amount of the order in millisatoshis.
+	#[prost(uint64, tag = "2")]
+	pub total_amount_msat: u64,
+	/// The amount received across the group's invoices so far, in millisatoshis.
+	#[prost(uint64, tag = "3")]
+	pub received_amount_msat: u64,
+	/// The number of invoices in the group.
+	#[prost(uint32, tag = "4")]
+	pub parts: u32,
+	/// The number of the group's invoices that have been paid.
+	#[prost(uint32, tag = "5")]
+	pub paid_parts: u32,
+	/// The scope the group's invoices were issued for, if any.
+	#[prost(string, optional, tag = "6")]
+	pub scope: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Gracefully stops the node and exits the server process, once the requests in flight, including
+/// this one, were answered.
+///
+/// Only available if the server is configured with an `auth_token`, failing with `AUTH_ERROR`
+/// otherwise, such that unauthenticated clients can never stop the node.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShutdownRequest {
+	/// Must be the id of the node to shut down, i.e., its hex-encoded public key, guarding against
+	/// accidentally stopping the wrong node. Requests with any other value are rejected with
+	/// `INVALID_REQUEST_ERROR`.
+	#[prost(string, tag = "1")]
+	pub confirmation: ::prost::alloc::string::String,
+}
+/// The response `content` for the `Shutdown` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShutdownResponse {}
+/// Estimates how long it takes for the transaction with the given id to confirm, based on its
+/// feerate and the feerates the chain source currently estimates for confirming within a given
+/// number of blocks. Depends on the chain source, see `GetMempoolInfoRequest`.
+///
+/// Fails with `NOT_FOUND_ERROR` if the transaction is neither in the chain source's mempool nor
+/// known to be confirmed. Confirmed transactions are looked up in bitcoind's wallet first, such that
+/// transactions unrelated to it are only found if bitcoind runs with `-txindex=1`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EstimateConfirmationRequest {
+	/// The id of the transaction.
+	#[prost(string, tag = "1")]
+	pub txid: ::prost::alloc::string::String,
+}
+/// The response `content` for the `EstimateConfirmation` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EstimateConfirmationResponse {
+	/// Whether the transaction is already confirmed, in which case no estimate is given.
+	#[prost(bool, tag = "1")]
+	pub confirmed: bool,
+	/// The number of confirmations of a confirmed transaction.
+	#[prost(uint32, optional, tag = "2")]
+	pub confirmations: ::core::option::Option<u32>,
+	/// The feerate of an unconfirmed transaction, in satoshis per 1000 weight units. Transactions
+	/// spending unconfirmed outputs are estimated at the lower feerate of them and their ancestors.
+	#[prost(uint64, optional, tag = "3")]
+	pub feerate_sat_per_1000_weight: ::core::option::Option<u64>,
+	/// The number of blocks an unconfirmed transaction is estimated to confirm within. Unset if its
+	/// feerate is too low to estimate when it confirms, or if the chain source lacks the data to
+	/// estimate feerates, as is typical on regtest.
+	#[prost(uint32, optional, tag = "4")]
+	pub estimated_blocks: ::core::option::Option<u32>,
+	/// `estimated_blocks` in minutes, assuming a block every 10 minutes on average.
+	#[prost(uint32, optional, tag = "5")]
+	pub estimated_minutes: ::core::option::Option<u32>,
+}
+/// Returns the BOLT11 invoice or BOLT12 offer an outbound payment with the given id was sent to, for
+/// re-displaying what was paid, e.g., on a receipt.
+///
+/// Only available for payments sent via this API, failing with `NOT_FOUND_ERROR` otherwise.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPaymentInvoiceRequest {
+	/// The hex-encoded id of the payment.
+	#[prost(string, tag = "1")]
+	pub payment_id: ::prost::alloc::string::String,
+}
+/// The response `content` for the `GetPaymentInvoice` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPaymentInvoiceResponse {
+	/// The BOLT11 invoice paid, if the payment was made to an invoice.
+	#[prost(string, optional, tag = "1")]
+	pub bolt11_invoice: ::core::option::Option<::prost::alloc::string::String>,
+	/// The BOLT12 offer paid, if the payment was made to an offer.
+	#[prost(string, optional, tag = "2")]
+	pub bolt12_offer: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Pays a lightning address, i.e., an email-like `user@domain` address (LUD-16), by fetching an
+/// invoice for the given amount from the recipient's LNURL-pay service and paying it.
+///
+/// Amounts outside the range the service accepts and comments it doesn't accept are rejected with
+/// `INVALID_REQUEST_ERROR`. Failures of the service, e.g., it being unreachable or returning an
+/// invoice other than requested, are reported as `LIGHTNING_ERROR`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PayLightningAddressRequest {
+	/// The lightning address to pay, e.g., `alice@example.com`.
+	#[prost(string, tag = "1")]
+	pub address: ::prost::alloc::string::String,
+	/// The amount to pay in millisatoshis.
+	#[prost(uint64, tag = "2")]
+	pub amount_msat: u64,
+	/// A comment for the recipient, if their service accepts comments (LUD-12).
+	#[prost(string, optional, tag = "3")]
+	pub comment: ::core::option::Option<::prost::alloc::string::String>,
+	/// A unique value protecting the request against being replayed, see `Bolt11SendRequest`.
+	#[prost(string, optional, tag = "4")]
+	pub request_nonce: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// The response `content` for the `PayLightningAddress` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PayLightningAddressResponse {
+	/// An identifier used to uniquely identify a payment.
+	#[prost(bytes = "bytes", tag = "1")]
+	pub payment_id: ::prost::bytes::Bytes,
+	/// The BOLT11 invoice fetched from the recipient's service and paid.
+	#[prost(string, tag = "2")]
+	pub invoice: ::prost::alloc::string::String,
+}
+/// Claims funds from an LNURL-withdraw service (LUD-03), e.g., a voucher, by issuing a BOLT11
+/// invoice for the amount to withdraw and submitting it to the service, which then pays it.
+///
+/// Amounts outside the range the service allows are rejected with `INVALID_REQUEST_ERROR`. Failures
+/// of the service, e.g., it being unreachable or refusing the invoice, are reported as
+/// `LIGHTNING_ERROR`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClaimLnurlWithdrawRequest {
+	/// The bech32-encoded LNURL (`lnurl1...`), or `lnurlw://` URL, of the withdrawal.
+	#[prost(string, tag = "1")]
+	pub lnurl: ::prost::alloc::string::String,
+	/// The amount to withdraw in millisatoshis.
+	///
+	/// Defaults to the maximum the service allows.
+	#[prost(uint64, optional, tag = "2")]
+	pub amount_msat: ::core::option::Option<u64>,
+}
+/// The response `content` for the `ClaimLnurlWithdraw` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClaimLnurlWithdrawResponse {
+	/// The BOLT11 invoice submitted to the service.
+	///
+	/// The withdrawal completes once the service pays it, which can be tracked like any payment to it.
+	#[prost(string, tag = "1")]
+	pub invoice: ::prost::alloc::string::String,
+	/// The amount withdrawn in millisatoshis.
+	#[prost(uint64, tag = "2")]
+	pub amount_msat: u64,
+}
+/// Runs a set of checks of whether the node is fully functional, e.g., after a deployment or an
+/// upgrade: whether the chain source is reachable, the wallets can be synced, a channel is usable,
+/// and the fee estimator has fee rates. Failed checks don't fail the request.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SelfTestRequest {}
+/// The response `content` for the `SelfTest` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SelfTestResponse {
+	/// Whether all checks passed.
+	#[prost(bool, tag = "1")]
+	pub passed: bool,
+	/// The outcome of each check.
+	#[prost(message, repeated, tag = "2")]
+	pub checks: ::prost::alloc::vec::Vec<super::types::SelfTestCheck>,
+}
+/// Queries the events emitted by the node, as persisted across restarts, e.g., for audits or
+/// reconciliation. Events are returned oldest first, and retained for 30 days.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryEventsRequest {
+	/// If non-empty, only events of the given types are returned. See `NodeEvent.event_type` for the
+	/// known types; unknown types are rejected with an `InvalidRequestError`.
+	#[prost(string, repeated, tag = "1")]
+	pub event_types: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+	/// If set, only events handled at or after this time, in milliseconds since the UNIX epoch, are
+	/// returned.
+	#[prost(uint64, optional, tag = "2")]
+	pub start_ms: ::core::option::Option<u64>,
+	/// If set, only events handled before this time, in milliseconds since the UNIX epoch, are
+	/// returned.
+	#[prost(uint64, optional, tag = "3")]
+	pub end_ms: ::core::option::Option<u64>,
+	/// An opaque token identifying the page to retrieve, as returned in
+	/// `QueryEventsResponse.next_page_token`. If unset, the first page is returned.
+	/// Filters are applied before pagination, so the same filters must be passed along with a token.
+	/// Tokens expire after some time and don't survive a server restart.
+	#[prost(string, optional, tag = "4")]
+	pub page_token: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// The response `content` for the `QueryEvents` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryEventsResponse {
+	/// The events matching the query.
+	#[prost(message, repeated, tag = "1")]
+	pub events: ::prost::alloc::vec::Vec<super::types::NodeEvent>,
+	/// A token to be passed as `QueryEventsRequest.page_token` to retrieve the next page.
+	/// Unset if there are no more events.
+	#[prost(string, optional, tag = "2")]
+	pub next_page_token: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Makes the next Lightning send, i.e., via `Bolt11Send`, `SendManyBolt11`, `Bolt12Send` or
+/// `PayLightningAddress`, fail with the given reason instead of being sent, such that clients can
+/// exercise their error handling deterministically.
+///
+/// For integration tests only: the endpoint is only served by servers built with the `test-hooks`
+/// feature. The send fails with a `LIGHTNING_ERROR` naming the reason.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulatePaymentFailureRequest {
+	/// The reason the next send fails with.
+	#[prost(enumeration = "super::types::PaymentFailureReason", tag = "1")]
+	pub reason: i32,
+}
+/// The response `content` for the `SimulatePaymentFailure` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulatePaymentFailureResponse {}
+/// Replaces the bearer auth token requests are authenticated with, without restarting the server.
+/// The replaced token is still accepted for a grace period, such that clients with requests in
+/// flight aren't locked out.
+///
+/// Only requests authenticated with the current token may rotate it. Fails with `AUTH_ERROR` if no
+/// `auth_token` is configured. The new token is persisted and supersedes the configured
+/// `auth_token` across restarts and config reloads, until the `auth_token` in the configuration
+/// is changed.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RotateAuthTokenRequest {
+	/// The new auth token, which must be printable ASCII without whitespace.
+	#[prost(string, tag = "1")]
+	pub new_token: ::prost::alloc::string::String,
+}
+/// The response `content` for the `RotateAuthToken` API, when HttpStatusCode is OK (200).
+/// When HttpStatusCode is not OK (non-200), the response `content` contains a serialized `ErrorResponse`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RotateAuthTokenResponse {
+	/// The time in seconds during which the replaced token is still accepted.
+	#[prost(uint32, tag = "1")]
+	pub grace_period_secs: u32,
 }
@@ -16,6 +16,33 @@ pub struct ErrorResponse {
 	/// `UnknownError`.
 	#[prost(enumeration = "ErrorCode", tag = "2")]
 	pub error_code: i32,
+	/// The amount in satoshis the failed operation required. Only set for `INSUFFICIENT_FUNDS_ERROR`
+	/// and `INSUFFICIENT_INBOUND_CAPACITY_ERROR`.
+	#[prost(uint64, optional, tag = "3")]
+	pub needed_sats: ::core::option::Option<u64>,
+	/// The amount in satoshis that was available to the failed operation. Only set for
+	/// `INSUFFICIENT_FUNDS_ERROR` and `INSUFFICIENT_INBOUND_CAPACITY_ERROR`.
+	#[prost(uint64, optional, tag = "4")]
+	pub available_sats: ::core::option::Option<u64>,
+	/// The request fields that failed validation. Only set for `INVALID_REQUEST_ERROR`s, and only
+	/// for validation attributable to specific fields.
+	#[prost(message, repeated, tag = "5")]
+	pub validation_errors: ::prost::alloc::vec::Vec<ValidationError>,
+	/// The paths of the endpoints the server supports, without the leading '/'. Only set for
+	/// `NOT_FOUND_ERROR`s of requests to unknown endpoints, if the server is configured to list them.
+	#[prost(string, repeated, tag = "6")]
+	pub supported_endpoints: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// A request field that failed validation.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValidationError {
+	/// The name of the offending field, e.g., `channel_amount_sats`.
+	#[prost(string, tag = "1")]
+	pub field: ::prost::alloc::string::String,
+	/// Why the field's value is invalid, intended for a human audience.
+	#[prost(string, tag = "2")]
+	pub reason: ::prost::alloc::string::String,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -37,6 +64,24 @@ pub enum ErrorCode {
 	LightningError = 3,
 	/// Used when an internal server error occurred. The client is probably at no fault.
 	InternalServerError = 4,
+	/// Used when the requested resource, e.g. a payment, could not be found.
+	NotFoundError = 5,
+	/// Used when the node lacks the on-chain or Lightning funds to carry out the requested operation.
+	InsufficientFundsError = 6,
+	/// Used when the node's chain source is unavailable. The request may be retried after the delay
+	/// given by the `Retry-After` header.
+	ServiceUnavailableError = 7,
+	/// Used when an operation didn't complete within the requested time.
+	TimeoutError = 8,
+	/// Used when a request conflicts with a previous one, e.g., as it reused its `request_nonce`.
+	ConflictError = 9,
+	/// Used when the node's store can't be written to, e.g., as the disk is full or the store became
+	/// read-only, and the requested operation requires persisting state.
+	InsufficientStorageError = 10,
+	/// Used when the requested endpoint was disabled by the operator.
+	EndpointDisabledError = 11,
+	/// Used when the node lacks the inbound capacity to receive a requested amount.
+	InsufficientInboundCapacityError = 12,
 }
 impl ErrorCode {
 	/// String value of the enum field names used in the ProtoBuf definition.
@@ -50,6 +95,14 @@ impl ErrorCode {
 			ErrorCode::AuthError => "AUTH_ERROR",
 			ErrorCode::LightningError => "LIGHTNING_ERROR",
 			ErrorCode::InternalServerError => "INTERNAL_SERVER_ERROR",
+			ErrorCode::NotFoundError => "NOT_FOUND_ERROR",
+			ErrorCode::InsufficientFundsError => "INSUFFICIENT_FUNDS_ERROR",
+			ErrorCode::ServiceUnavailableError => "SERVICE_UNAVAILABLE_ERROR",
+			ErrorCode::TimeoutError => "TIMEOUT_ERROR",
+			ErrorCode::ConflictError => "CONFLICT_ERROR",
+			ErrorCode::InsufficientStorageError => "INSUFFICIENT_STORAGE_ERROR",
+			ErrorCode::EndpointDisabledError => "ENDPOINT_DISABLED_ERROR",
+			ErrorCode::InsufficientInboundCapacityError => "INSUFFICIENT_INBOUND_CAPACITY_ERROR",
 		}
 	}
 	/// Creates an enum from field names used in the ProtoBuf definition.
@@ -60,6 +113,14 @@ impl ErrorCode {
 			"AUTH_ERROR" => Some(Self::AuthError),
 			"LIGHTNING_ERROR" => Some(Self::LightningError),
 			"INTERNAL_SERVER_ERROR" => Some(Self::InternalServerError),
+			"NOT_FOUND_ERROR" => Some(Self::NotFoundError),
+			"INSUFFICIENT_FUNDS_ERROR" => Some(Self::InsufficientFundsError),
+			"SERVICE_UNAVAILABLE_ERROR" => Some(Self::ServiceUnavailableError),
+			"TIMEOUT_ERROR" => Some(Self::TimeoutError),
+			"CONFLICT_ERROR" => Some(Self::ConflictError),
+			"INSUFFICIENT_STORAGE_ERROR" => Some(Self::InsufficientStorageError),
+			"ENDPOINT_DISABLED_ERROR" => Some(Self::EndpointDisabledError),
+			"INSUFFICIENT_INBOUND_CAPACITY_ERROR" => Some(Self::InsufficientInboundCapacityError),
 			_ => None,
 		}
 	}
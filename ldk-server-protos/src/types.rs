@@ -226,7 +226,8 @@ pub struct Channel {
 	/// The current number of confirmations on the funding transaction.
 	#[prost(uint32, optional, tag = "11")]
 	pub confirmations: ::core::option::Option<u32>,
-	/// Is `true` if the channel was initiated (and therefore funded) by us.
+	/// Is `true` if the channel was initiated (and therefore funded) by us. The funder pays the
+	/// on-chain fees of the channel's commitment and closing transactions.
 	#[prost(bool, tag = "12")]
 	pub is_outbound: bool,
 	/// Is `true` if both parties have exchanged `channel_ready` messages, and the channel is
@@ -241,7 +242,9 @@ pub struct Channel {
 	/// This is a strict superset of `is_channel_ready`.
 	#[prost(bool, tag = "14")]
 	pub is_usable: bool,
-	/// Is `true` if this channel is (or will be) publicly-announced
+	/// Is `true` if this channel is (or will be) publicly-announced, i.e., it is a public channel
+	/// which other nodes may route payments over. Unannounced channels are private to us and our
+	/// counterparty.
 	#[prost(bool, tag = "15")]
 	pub is_announced: bool,
 	/// Set of configurable parameters set by self that affect channel operation.
@@ -294,6 +297,17 @@ pub struct Channel {
 	/// such that the outgoing HTLC is forwardable to this counterparty.
 	#[prost(uint32, optional, tag = "25")]
 	pub counterparty_forwarding_info_cltv_expiry_delta: ::core::option::Option<u32>,
+	/// The number of confirmations the funding transaction still needs before the channel is
+	/// considered "locked", derived from `confirmations_required` and `confirmations`.
+	///
+	/// Will be `0` once the channel is ready, and `None` while `confirmations_required` is unknown.
+	#[prost(uint32, optional, tag = "26")]
+	pub confirmations_remaining: ::core::option::Option<u32>,
+	/// The alias the counterparty announced in the network graph, empty if unknown.
+	///
+	/// The alias is chosen by the counterparty and may thus be misleading.
+	#[prost(string, tag = "27")]
+	pub counterparty_alias: ::prost::alloc::string::String,
 }
 /// ChannelConfig represents the configuration settings for a channel in a Lightning Network node.
 /// See more: <https://docs.rs/lightning/latest/lightning/util/config/struct.ChannelConfig.html>
@@ -647,6 +661,175 @@ pub struct AwaitingThresholdConfirmations {
 	#[prost(uint64, tag = "5")]
 	pub amount_satoshis: u64,
 }
+/// Details about an outbound payment, recorded from the events emitted while it was in flight.
+///
+/// Per-attempt path and HTLC details are not exposed by LDK Node and thus not included.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PaymentDiagnostics {
+	/// The total fee in millisatoshis paid to intermediate hops. Only set for succeeded payments.
+	#[prost(uint64, optional, tag = "1")]
+	pub fee_paid_msat: ::core::option::Option<u64>,
+	/// The reason the payment failed. Only set for failed payments.
+	#[prost(enumeration = "PaymentFailureReason", optional, tag = "2")]
+	pub failure_reason: ::core::option::Option<i32>,
+}
+/// The plaintext content of a backup created via `ExportChannelMonitors`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChannelMonitorBackup {
+	/// The backed up channel monitors.
+	#[prost(message, repeated, tag = "1")]
+	pub monitors: ::prost::alloc::vec::Vec<ChannelMonitorBackupEntry>,
+}
+/// A single serialized channel monitor contained in a `ChannelMonitorBackup`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChannelMonitorBackupEntry {
+	/// The key the monitor is persisted under, derived from the channel's funding outpoint.
+	#[prost(string, tag = "1")]
+	pub key: ::prost::alloc::string::String,
+	/// The serialized channel monitor.
+	#[prost(bytes = "bytes", tag = "2")]
+	pub monitor: ::prost::bytes::Bytes,
+}
+/// The estimated on-chain balance we would receive if a channel were cooperatively closed now.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChannelCloseBalance {
+	/// The identifier of the channel.
+	#[prost(string, tag = "1")]
+	pub channel_id: ::prost::alloc::string::String,
+	/// The identifier of our channel counterparty.
+	#[prost(string, tag = "2")]
+	pub counterparty_node_id: ::prost::alloc::string::String,
+	/// Our balance in the channel, in satoshis, excluding pending HTLCs and before paying any
+	/// closing transaction fee.
+	#[prost(uint64, tag = "3")]
+	pub balance_sats: u64,
+	/// The estimated fee of the closing transaction at the channel's current feerate, in satoshis.
+	/// This is only paid by us if we funded the channel, and is 0 otherwise.
+	#[prost(uint64, tag = "4")]
+	pub estimated_closing_fee_sats: u64,
+	/// The estimated amount of our output in the closing transaction, in satoshis.
+	#[prost(uint64, tag = "5")]
+	pub estimated_close_output_sats: u64,
+}
+/// The recovery action taken for a channel via `RecoverChannels`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChannelRecovery {
+	/// The identifier of the channel.
+	#[prost(string, tag = "1")]
+	pub channel_id: ::prost::alloc::string::String,
+	/// The identifier of our channel counterparty.
+	#[prost(string, tag = "2")]
+	pub counterparty_node_id: ::prost::alloc::string::String,
+	/// The action taken.
+	#[prost(enumeration = "ChannelRecoveryAction", tag = "3")]
+	pub action: i32,
+	/// Why force-closing the channel failed, only set for `FORCE_CLOSE_FAILED`.
+	#[prost(string, optional, tag = "4")]
+	pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// A peer the node knows about, either since it is connected or since it is persisted.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Peer {
+	/// The node id of the peer.
+	#[prost(string, tag = "1")]
+	pub node_id: ::prost::alloc::string::String,
+	/// The network address of the peer.
+	#[prost(string, tag = "2")]
+	pub address: ::prost::alloc::string::String,
+	/// Whether the peer is persisted, i.e., the node reconnects to it on restart.
+	#[prost(bool, tag = "3")]
+	pub is_persisted: bool,
+	/// Whether the peer is currently connected.
+	#[prost(bool, tag = "4")]
+	pub is_connected: bool,
+}
+/// An HTLC currently in-flight in one of our channels.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PendingHtlc {
+	/// The identifier of the channel.
+	#[prost(string, tag = "1")]
+	pub channel_id: ::prost::alloc::string::String,
+	/// The identifier of our channel counterparty.
+	#[prost(string, tag = "2")]
+	pub counterparty_node_id: ::prost::alloc::string::String,
+	/// Whether the HTLC was offered to us (`INBOUND`) or by us (`OUTBOUND`).
+	#[prost(enumeration = "PaymentDirection", tag = "3")]
+	pub direction: i32,
+	/// The amount of the HTLC, in satoshis, rounded down.
+	#[prost(uint64, tag = "4")]
+	pub amount_sats: u64,
+	/// The block height at which the HTLC expires. Outbound HTLCs may be timed out on chain by us,
+	/// while inbound ones may be timed out by our counterparty from then on.
+	#[prost(uint32, tag = "5")]
+	pub cltv_expiry: u32,
+	/// The hex-encoded hash of the payment the HTLC is part of.
+	#[prost(string, tag = "6")]
+	pub payment_hash: ::prost::alloc::string::String,
+}
+/// A BOLT11 invoice to pay via `SendManyBolt11`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SendManyBolt11Payment {
+	/// An invoice for a payment within the Lightning Network.
+	#[prost(string, tag = "1")]
+	pub invoice: ::prost::alloc::string::String,
+	/// The amount to pay in millisatoshis, required for "zero-amount" invoices and not allowed to be
+	/// less than the amount of the invoice otherwise.
+	#[prost(uint64, optional, tag = "2")]
+	pub amount_msat: ::core::option::Option<u64>,
+}
+/// The outcome of a payment requested via `SendManyBolt11`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SendManyBolt11Result {
+	/// The identifier of the payment, set if it was initiated. Its final status can be retrieved via
+	/// `GetPaymentDetails`.
+	#[prost(bytes = "bytes", optional, tag = "1")]
+	pub payment_id: ::core::option::Option<::prost::bytes::Bytes>,
+	/// Why the payment couldn't be initiated, e.g., as the invoice was invalid, set otherwise.
+	#[prost(message, optional, tag = "2")]
+	pub error: ::core::option::Option<super::error::ErrorResponse>,
+}
+/// The maximum dust HTLC exposure of a channel.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChannelDustExposure {
+	/// The local `user_channel_id` of the channel.
+	#[prost(string, tag = "1")]
+	pub user_channel_id: ::prost::alloc::string::String,
+	/// The hex-encoded public key of the channel's counterparty.
+	#[prost(string, tag = "2")]
+	pub counterparty_node_id: ::prost::alloc::string::String,
+	/// The channel's limit on the exposure to potential loss to on-chain fees on close, including
+	/// in-flight HTLCs which are burned to fees as they are too small to claim on-chain.
+	/// See more: <https://docs.rs/lightning/latest/lightning/util/config/struct.ChannelConfig.html#structfield.max_dust_htlc_exposure>
+	#[prost(oneof = "channel_dust_exposure::MaxDustHtlcExposure", tags = "3, 4")]
+	pub max_dust_htlc_exposure: ::core::option::Option<channel_dust_exposure::MaxDustHtlcExposure>,
+}
+/// Nested message and enum types in `ChannelDustExposure`.
+pub mod channel_dust_exposure {
+	/// The channel's limit on the exposure to potential loss to on-chain fees on close, including
+	/// in-flight HTLCs which are burned to fees as they are too small to claim on-chain.
+	/// See more: <https://docs.rs/lightning/latest/lightning/util/config/struct.ChannelConfig.html#structfield.max_dust_htlc_exposure>
+	#[allow(clippy::derive_partial_eq_without_eq)]
+	#[derive(Clone, PartialEq, ::prost::Oneof)]
+	pub enum MaxDustHtlcExposure {
+		/// A fixed limit on the total dust exposure in millisatoshis.
+		#[prost(uint64, tag = "3")]
+		FixedLimitMsat(u64),
+		/// A multiplier on the ConfirmationTarget::OnChainSweep feerate (in sats/KW) determining the
+		/// maximum allowed dust exposure.
+		#[prost(uint64, tag = "4")]
+		FeeRateMultiplier(u64),
+	}
+}
 /// Represents the direction of a payment.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -709,3 +892,417 @@ impl PaymentStatus {
 		}
 	}
 }
+/// Represents the status of an inbound payment for an invoice issued by us.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum InboundPaymentStatus {
+	/// No payment has been claimed for the invoice yet.
+	///
+	/// This includes multi-part payments of which only some parts arrived so far. Such partial
+	/// payments are not visible to us, LDK fails them back on its own once they haven't completed
+	/// within about three minutes.
+	Unclaimed = 0,
+	/// The payment was received and claimed.
+	Claimed = 1,
+	/// The payment can no longer be claimed, e.g., because the invoice expired unpaid or the received
+	/// HTLCs were failed back.
+	Expired = 2,
+}
+impl InboundPaymentStatus {
+	/// String value of the enum field names used in the ProtoBuf definition.
+	///
+	/// The values are not transformed in any way and thus are considered stable
+	/// (if the ProtoBuf definition does not change) and safe for programmatic use.
+	pub fn as_str_name(&self) -> &'static str {
+		match self {
+			InboundPaymentStatus::Unclaimed => "UNCLAIMED",
+			InboundPaymentStatus::Claimed => "CLAIMED",
+			InboundPaymentStatus::Expired => "EXPIRED",
+		}
+	}
+	/// Creates an enum from field names used in the ProtoBuf definition.
+	pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+		match value {
+			"UNCLAIMED" => Some(Self::Unclaimed),
+			"CLAIMED" => Some(Self::Claimed),
+			"EXPIRED" => Some(Self::Expired),
+			_ => None,
+		}
+	}
+}
+/// The script type of an on-chain address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AddressType {
+	/// A native SegWit version 0 (P2WPKH) address, the node's standard address type.
+	SegwitV0 = 0,
+	/// A Taproot (P2TR) address, i.e., a SegWit version 1 address.
+	Taproot = 1,
+}
+impl AddressType {
+	/// String value of the enum field names used in the ProtoBuf definition.
+	///
+	/// The values are not transformed in any way and thus are considered stable
+	/// (if the ProtoBuf definition does not change) and safe for programmatic use.
+	pub fn as_str_name(&self) -> &'static str {
+		match self {
+			AddressType::SegwitV0 => "SEGWIT_V0",
+			AddressType::Taproot => "TAPROOT",
+		}
+	}
+	/// Creates an enum from field names used in the ProtoBuf definition.
+	pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+		match value {
+			"SEGWIT_V0" => Some(Self::SegwitV0),
+			"TAPROOT" => Some(Self::Taproot),
+			_ => None,
+		}
+	}
+}
+/// The reason an outbound payment failed.
+/// See more: <https://docs.rs/lightning/latest/lightning/events/enum.PaymentFailureReason.html>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PaymentFailureReason {
+	/// The recipient rejected the payment.
+	RecipientRejected = 0,
+	/// The payment was abandoned.
+	UserAbandoned = 1,
+	/// All retry attempts were exhausted while trying to send the payment.
+	RetriesExhausted = 2,
+	/// The payment expired while retrying.
+	PaymentExpired = 3,
+	/// No route to the recipient could be found while retrying.
+	RouteNotFound = 4,
+	/// An unexpected error occurred while routing the payment.
+	UnexpectedError = 5,
+	/// The invoice required features unknown to us.
+	UnknownRequiredFeatures = 6,
+	/// No BOLT12 invoice was received in time.
+	InvoiceRequestExpired = 7,
+	/// The recipient rejected the BOLT12 invoice request.
+	InvoiceRequestRejected = 8,
+}
+impl PaymentFailureReason {
+	/// String value of the enum field names used in the ProtoBuf definition.
+	///
+	/// The values are not transformed in any way and thus are considered stable
+	/// (if the ProtoBuf definition does not change) and safe for programmatic use.
+	pub fn as_str_name(&self) -> &'static str {
+		match self {
+			PaymentFailureReason::RecipientRejected => "RECIPIENT_REJECTED",
+			PaymentFailureReason::UserAbandoned => "USER_ABANDONED",
+			PaymentFailureReason::RetriesExhausted => "RETRIES_EXHAUSTED",
+			PaymentFailureReason::PaymentExpired => "PAYMENT_EXPIRED",
+			PaymentFailureReason::RouteNotFound => "ROUTE_NOT_FOUND",
+			PaymentFailureReason::UnexpectedError => "UNEXPECTED_ERROR",
+			PaymentFailureReason::UnknownRequiredFeatures => "UNKNOWN_REQUIRED_FEATURES",
+			PaymentFailureReason::InvoiceRequestExpired => "INVOICE_REQUEST_EXPIRED",
+			PaymentFailureReason::InvoiceRequestRejected => "INVOICE_REQUEST_REJECTED",
+		}
+	}
+	/// Creates an enum from field names used in the ProtoBuf definition.
+	pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+		match value {
+			"RECIPIENT_REJECTED" => Some(Self::RecipientRejected),
+			"USER_ABANDONED" => Some(Self::UserAbandoned),
+			"RETRIES_EXHAUSTED" => Some(Self::RetriesExhausted),
+			"PAYMENT_EXPIRED" => Some(Self::PaymentExpired),
+			"ROUTE_NOT_FOUND" => Some(Self::RouteNotFound),
+			"UNEXPECTED_ERROR" => Some(Self::UnexpectedError),
+			"UNKNOWN_REQUIRED_FEATURES" => Some(Self::UnknownRequiredFeatures),
+			"INVOICE_REQUEST_EXPIRED" => Some(Self::InvoiceRequestExpired),
+			"INVOICE_REQUEST_REJECTED" => Some(Self::InvoiceRequestRejected),
+			_ => None,
+		}
+	}
+}
+/// A way of paying a BIP21 URI.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PaymentRail {
+	/// The payment was sent to the URI's BOLT12 offer.
+	Bolt12 = 0,
+	/// The payment was sent to the URI's BOLT11 invoice.
+	Bolt11 = 1,
+	/// The payment was sent to the URI's on-chain address.
+	Onchain = 2,
+}
+impl PaymentRail {
+	/// String value of the enum field names used in the ProtoBuf definition.
+	///
+	/// The values are not transformed in any way and thus are considered stable
+	/// (if the ProtoBuf definition does not change) and safe for programmatic use.
+	pub fn as_str_name(&self) -> &'static str {
+		match self {
+			PaymentRail::Bolt12 => "BOLT12",
+			PaymentRail::Bolt11 => "BOLT11",
+			PaymentRail::Onchain => "ONCHAIN",
+		}
+	}
+	/// Creates an enum from field names used in the ProtoBuf definition.
+	pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+		match value {
+			"BOLT12" => Some(Self::Bolt12),
+			"BOLT11" => Some(Self::Bolt11),
+			"ONCHAIN" => Some(Self::Onchain),
+			_ => None,
+		}
+	}
+}
+/// The action taken to recover the funds of a channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ChannelRecoveryAction {
+	/// The channel was force-closed, its funds will be swept to the on-chain wallet.
+	ForceClosed = 0,
+	/// The channel was left untouched as its counterparty is connected.
+	PeerConnected = 1,
+	/// Force-closing the channel failed.
+	ForceCloseFailed = 2,
+}
+impl ChannelRecoveryAction {
+	/// String value of the enum field names used in the ProtoBuf definition.
+	///
+	/// The values are not transformed in any way and thus are considered stable
+	/// (if the ProtoBuf definition does not change) and safe for programmatic use.
+	pub fn as_str_name(&self) -> &'static str {
+		match self {
+			ChannelRecoveryAction::ForceClosed => "FORCE_CLOSED",
+			ChannelRecoveryAction::PeerConnected => "PEER_CONNECTED",
+			ChannelRecoveryAction::ForceCloseFailed => "FORCE_CLOSE_FAILED",
+		}
+	}
+	/// Creates an enum from field names used in the ProtoBuf definition.
+	pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+		match value {
+			"FORCE_CLOSED" => Some(Self::ForceClosed),
+			"PEER_CONNECTED" => Some(Self::PeerConnected),
+			"FORCE_CLOSE_FAILED" => Some(Self::ForceCloseFailed),
+			_ => None,
+		}
+	}
+}
+/// Represents the progress of an invoice group towards fulfilling its order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum InvoiceGroupStatus {
+	/// None of the group's invoices has been paid yet.
+	Unpaid = 0,
+	/// Some, but not all, of the group's invoices have been paid.
+	PartiallyPaid = 1,
+	/// All of the group's invoices have been paid, fulfilling the order.
+	Completed = 2,
+	/// Some of the group's invoices can no longer be paid, such that the order can't be fulfilled.
+	Expired = 3,
+}
+impl InvoiceGroupStatus {
+	/// String value of the enum field names used in the ProtoBuf definition.
+	///
+	/// The values are not transformed in any way and thus are considered stable
+	/// (if the ProtoBuf definition does not change) and safe for programmatic use.
+	pub fn as_str_name(&self) -> &'static str {
+		match self {
+			InvoiceGroupStatus::Unpaid => "UNPAID",
+			InvoiceGroupStatus::PartiallyPaid => "PARTIALLY_PAID",
+			InvoiceGroupStatus::Completed => "COMPLETED",
+			InvoiceGroupStatus::Expired => "EXPIRED",
+		}
+	}
+	/// Creates an enum from field names used in the ProtoBuf definition.
+	pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+		match value {
+			"UNPAID" => Some(Self::Unpaid),
+			"PARTIALLY_PAID" => Some(Self::PartiallyPaid),
+			"COMPLETED" => Some(Self::Completed),
+			"EXPIRED" => Some(Self::Expired),
+			_ => None,
+		}
+	}
+}
+/// The outcome of one of the checks run by `SelfTest`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SelfTestCheck {
+	/// The name of the check, i.e., `chain_source`, `wallet_sync`, `usable_channel` or
+	/// `fee_estimator`.
+	#[prost(string, tag = "1")]
+	pub name: ::prost::alloc::string::String,
+	/// Whether the check passed.
+	#[prost(bool, tag = "2")]
+	pub passed: bool,
+	/// What the check found, e.g., the chain tip's height, or why it failed.
+	#[prost(string, tag = "3")]
+	pub detail: ::prost::alloc::string::String,
+}
+/// An event emitted by the node, as persisted for `QueryEvents`. Fields not carried by the event's
+/// type are unset.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeEvent {
+	/// The type of the event, i.e., `payment_successful`, `payment_failed`, `payment_received`,
+	/// `payment_claimable`, `channel_pending`, `channel_ready` or `channel_closed`.
+	#[prost(string, tag = "1")]
+	pub event_type: ::prost::alloc::string::String,
+	/// The time the event was handled, in milliseconds since the UNIX epoch.
+	#[prost(uint64, tag = "2")]
+	pub timestamp_ms: u64,
+	/// The hex-encoded id of the channel the event is about.
+	#[prost(string, optional, tag = "3")]
+	pub channel_id: ::core::option::Option<::prost::alloc::string::String>,
+	/// The local identifier of the channel the event is about.
+	#[prost(string, optional, tag = "4")]
+	pub user_channel_id: ::core::option::Option<::prost::alloc::string::String>,
+	/// The hex-encoded public key of the channel's counterparty.
+	#[prost(string, optional, tag = "5")]
+	pub counterparty_node_id: ::core::option::Option<::prost::alloc::string::String>,
+	/// The hex-encoded id of the payment the event is about.
+	#[prost(string, optional, tag = "6")]
+	pub payment_id: ::core::option::Option<::prost::alloc::string::String>,
+	/// The hex-encoded hash of the payment the event is about.
+	#[prost(string, optional, tag = "7")]
+	pub payment_hash: ::core::option::Option<::prost::alloc::string::String>,
+	/// The amount received or claimable in millisatoshis.
+	#[prost(uint64, optional, tag = "8")]
+	pub amount_msat: ::core::option::Option<u64>,
+	/// The fees paid for a successful payment in millisatoshis.
+	#[prost(uint64, optional, tag = "9")]
+	pub fee_paid_msat: ::core::option::Option<u64>,
+	/// Why a payment failed or a channel closed, if known.
+	#[prost(string, optional, tag = "10")]
+	pub reason: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Whether the data returned by a read was freshly synced with the chain source for the request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DataFreshness {
+	/// The data is as of the last background sync.
+	Cached = 0,
+	/// The wallets were synced with the chain source before the data was read.
+	Fresh = 1,
+}
+impl DataFreshness {
+	/// String value of the enum field names used in the ProtoBuf definition.
+	///
+	/// The values are not transformed in any way and thus are considered stable
+	/// (if the ProtoBuf definition does not change) and safe for programmatic use.
+	pub fn as_str_name(&self) -> &'static str {
+		match self {
+			DataFreshness::Cached => "CACHED",
+			DataFreshness::Fresh => "FRESH",
+		}
+	}
+	/// Creates an enum from field names used in the ProtoBuf definition.
+	pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+		match value {
+			"CACHED" => Some(Self::Cached),
+			"FRESH" => Some(Self::Fresh),
+			_ => None,
+		}
+	}
+}
+/// The fields of a BOLT11 invoice as decoded by a client, allowing it to be paid without the server
+/// parsing the invoice string again. Fields must be given exactly as encoded in the invoice, such that
+/// its signature can be verified.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Bolt11InvoiceFields {
+	/// The human-readable part of the invoice, e.g., `lnbcrt10u`, encoding its currency and amount.
+	#[prost(string, tag = "1")]
+	pub hrp: ::prost::alloc::string::String,
+	/// The time the invoice was created, in seconds since the UNIX epoch.
+	#[prost(uint64, tag = "2")]
+	pub timestamp: u64,
+	/// The tagged fields of the invoice, in the order they're encoded in.
+	#[prost(message, repeated, tag = "3")]
+	pub tagged_fields: ::prost::alloc::vec::Vec<Bolt11TaggedField>,
+	/// The 64-byte compact signature of the invoice, followed by its one-byte recovery id.
+	#[prost(bytes = "bytes", tag = "4")]
+	pub signature: ::prost::bytes::Bytes,
+}
+/// A tagged field of a BOLT11 invoice.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Bolt11TaggedField {
+	#[prost(oneof = "bolt11_tagged_field::Field", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11")]
+	pub field: ::core::option::Option<bolt11_tagged_field::Field>,
+}
+/// Nested message and enum types in `Bolt11TaggedField`.
+pub mod bolt11_tagged_field {
+	#[allow(clippy::derive_partial_eq_without_eq)]
+	#[derive(Clone, PartialEq, ::prost::Oneof)]
+	pub enum Field {
+		/// The hex-encoded payment hash.
+		#[prost(string, tag = "1")]
+		PaymentHash(::prost::alloc::string::String),
+		/// The description of the purpose of the payment.
+		#[prost(string, tag = "2")]
+		Description(::prost::alloc::string::String),
+		/// The hex-encoded public key of the payee.
+		#[prost(string, tag = "3")]
+		PayeePubKey(::prost::alloc::string::String),
+		/// The hex-encoded hash of the description of the purpose of the payment.
+		#[prost(string, tag = "4")]
+		DescriptionHash(::prost::alloc::string::String),
+		/// The time in seconds after the invoice's creation after which it expires.
+		#[prost(uint64, tag = "5")]
+		ExpiryTimeSecs(u64),
+		/// The minimum CLTV expiry delta of the final hop.
+		#[prost(uint64, tag = "6")]
+		MinFinalCltvExpiryDelta(u64),
+		/// An on-chain address to pay to if the payment can't be made over Lightning.
+		#[prost(message, tag = "7")]
+		Fallback(super::Bolt11Fallback),
+		/// A route hint for reaching the payee over private channels.
+		#[prost(message, tag = "8")]
+		PrivateRoute(super::Bolt11RouteHint),
+		/// The hex-encoded payment secret.
+		#[prost(string, tag = "9")]
+		PaymentSecret(::prost::alloc::string::String),
+		/// The payment metadata to include in the payment's onion.
+		#[prost(bytes, tag = "10")]
+		PaymentMetadata(::prost::bytes::Bytes),
+		/// The invoice's feature bits, in little-endian byte order.
+		#[prost(bytes, tag = "11")]
+		Features(::prost::bytes::Bytes),
+	}
+}
+/// An on-chain fallback address of a BOLT11 invoice.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Bolt11Fallback {
+	/// The witness version of a segwit address, or 17 for a P2PKH and 18 for a P2SH address.
+	#[prost(uint32, tag = "1")]
+	pub version: u32,
+	/// The witness program of a segwit address, or the hash of a P2PKH or P2SH address.
+	#[prost(bytes = "bytes", tag = "2")]
+	pub program: ::prost::bytes::Bytes,
+}
+/// A route hint of a BOLT11 invoice, leading to the payee.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Bolt11RouteHint {
+	/// The hops of the route, starting from the first one.
+	#[prost(message, repeated, tag = "1")]
+	pub hops: ::prost::alloc::vec::Vec<Bolt11RouteHintHop>,
+}
+/// A hop of a BOLT11 invoice's route hint.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Bolt11RouteHintHop {
+	/// The hex-encoded public key of the node at the start of the channel.
+	#[prost(string, tag = "1")]
+	pub src_node_id: ::prost::alloc::string::String,
+	/// The short channel id of the channel.
+	#[prost(uint64, tag = "2")]
+	pub short_channel_id: u64,
+	/// The base fee in millisatoshis charged for forwarding over the channel.
+	#[prost(uint32, tag = "3")]
+	pub fee_base_msat: u32,
+	/// The fee in millionths of the amount charged for forwarding over the channel.
+	#[prost(uint32, tag = "4")]
+	pub fee_proportional_millionths: u32,
+	/// The CLTV expiry delta of the channel.
+	#[prost(uint32, tag = "5")]
+	pub cltv_expiry_delta: u32,
+}
@@ -0,0 +1,177 @@
+use crate::api::error::LdkServerErrorCode::{InvalidRequestError, NotFoundError, TimeoutError};
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use crate::util::proto_adapter::channel_to_proto;
+use ldk_node::UserChannelId;
+use ldk_server_protos::api::{AwaitChannelReadyRequest, AwaitChannelReadyResponse};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+
+pub(crate) const AWAIT_CHANNEL_READY_PATH: &str = "AwaitChannelReady";
+
+const DEFAULT_TIMEOUT_SECS: u32 = 60;
+
+// Every waiting request occupies a worker thread, so requests can't wait indefinitely.
+const MAX_TIMEOUT_SECS: u32 = 10 * 60;
+
+pub(crate) fn handle_await_channel_ready_request(
+	context: Context, request: AwaitChannelReadyRequest,
+) -> Result<AwaitChannelReadyResponse, LdkServerError> {
+	let user_channel_id_bytes: [u8; 16] =
+		request.user_channel_id.as_ref().try_into().map_err(|_| {
+			LdkServerError::new(InvalidRequestError, "Invalid user_channel_id, must be 16 bytes.")
+		})?;
+	let user_channel_id = UserChannelId(u128::from_be_bytes(user_channel_id_bytes));
+	let timeout = validate_timeout(request.timeout_secs)?;
+
+	// Subscribe before checking the channel's state, so its `ChannelReady` event can't be missed.
+	let mut channel_ready_receiver = context.channel_ready_sender.subscribe();
+	let deadline = Instant::now() + timeout;
+
+	// Handlers are synchronous, so we block this worker thread rather than the runtime.
+	let channel = tokio::task::block_in_place(|| {
+		tokio::runtime::Handle::current().block_on(await_channel_ready(
+			&mut channel_ready_receiver,
+			user_channel_id,
+			deadline,
+			|| {
+				context
+					.node
+					.list_channels()
+					.into_iter()
+					.find(|c| c.user_channel_id == user_channel_id)
+			},
+			|channel| channel.is_channel_ready,
+		))
+	})?;
+
+	let response =
+		AwaitChannelReadyResponse { channel: Some(channel_to_proto(channel, String::new())) };
+	Ok(response)
+}
+
+fn validate_timeout(timeout_secs: Option<u32>) -> Result<Duration, LdkServerError> {
+	let timeout_secs = timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
+	if timeout_secs > MAX_TIMEOUT_SECS {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"timeout_secs",
+			format!("must not exceed {} seconds", MAX_TIMEOUT_SECS),
+		)]));
+	}
+	Ok(Duration::from_secs(timeout_secs.into()))
+}
+
+// Waits until the channel returned by `find_channel` is ready, re-checking it whenever
+// `channel_ready_receiver` reports the channel of `user_channel_id` became ready. Fails once the
+// channel is gone or the `deadline` is reached.
+async fn await_channel_ready<C>(
+	channel_ready_receiver: &mut broadcast::Receiver<UserChannelId>,
+	user_channel_id: UserChannelId, deadline: Instant, find_channel: impl Fn() -> Option<C>,
+	is_ready: impl Fn(&C) -> bool,
+) -> Result<C, LdkServerError> {
+	loop {
+		match find_channel() {
+			Some(channel) if is_ready(&channel) => return Ok(channel),
+			Some(_) => {},
+			None => return Err(channel_not_found()),
+		}
+		loop {
+			match tokio::time::timeout_at(deadline, channel_ready_receiver.recv()).await {
+				Ok(Ok(ready_id)) if ready_id != user_channel_id => continue,
+				// Re-check on lagging behind, as we may have missed the event.
+				Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => break,
+				Ok(Err(RecvError::Closed)) | Err(_) => {
+					return Err(LdkServerError::new(
+						TimeoutError,
+						"Timed out waiting for the channel to become ready.",
+					));
+				},
+			}
+		}
+	}
+}
+
+fn channel_not_found() -> LdkServerError {
+	LdkServerError::new(NotFoundError, "No channel found for the given user_channel_id.")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicBool, Ordering};
+	use std::sync::Arc;
+
+	#[test]
+	fn test_timeout_is_capped() {
+		assert_eq!(validate_timeout(None).unwrap(), Duration::from_secs(60));
+		assert_eq!(validate_timeout(Some(MAX_TIMEOUT_SECS)).unwrap(), Duration::from_secs(600));
+		let err = validate_timeout(Some(MAX_TIMEOUT_SECS + 1)).unwrap_err();
+		assert_eq!(err.validation_errors[0].field, "timeout_secs");
+	}
+
+	#[tokio::test]
+	async fn test_await_channel_ready() {
+		let (sender, _) = broadcast::channel(16);
+		let user_channel_id = UserChannelId(7);
+		let deadline = Instant::now() + Duration::from_secs(10);
+
+		let err = await_channel_ready(
+			&mut sender.subscribe(),
+			user_channel_id,
+			deadline,
+			|| None::<bool>,
+			|ready| *ready,
+		)
+		.await
+		.unwrap_err();
+		assert_eq!(err.error_code, NotFoundError);
+
+		// Ready channels are returned right away.
+		let ready = await_channel_ready(
+			&mut sender.subscribe(),
+			user_channel_id,
+			deadline,
+			|| Some(true),
+			|r| *r,
+		)
+		.await;
+		assert!(ready.unwrap());
+
+		// Pending channels are returned once their `ChannelReady` event arrives, while events of
+		// other channels are skipped.
+		let is_ready = Arc::new(AtomicBool::new(false));
+		let mut receiver = sender.subscribe();
+		sender.send(UserChannelId(8)).unwrap();
+		let ready_flag = Arc::clone(&is_ready);
+		let notifier = sender.clone();
+		tokio::spawn(async move {
+			tokio::time::sleep(Duration::from_millis(10)).await;
+			ready_flag.store(true, Ordering::Release);
+			notifier.send(UserChannelId(7)).unwrap();
+		});
+		let ready = await_channel_ready(
+			&mut receiver,
+			user_channel_id,
+			deadline,
+			|| Some(is_ready.load(Ordering::Acquire)),
+			|ready| *ready,
+		)
+		.await;
+		assert!(ready.unwrap());
+
+		// Channels which never become ready time out.
+		let deadline = Instant::now() + Duration::from_millis(10);
+		let err = await_channel_ready(
+			&mut sender.subscribe(),
+			user_channel_id,
+			deadline,
+			|| Some(false),
+			|ready| *ready,
+		)
+		.await
+		.unwrap_err();
+		assert_eq!(err.error_code, TimeoutError);
+	}
+}
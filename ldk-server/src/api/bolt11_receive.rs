@@ -1,21 +1,199 @@
-use ldk_node::Node;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, InvalidRequestError};
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::inbound_invoices::{write_inbound_invoice_record, InboundInvoiceRecord};
+use crate::service::Context;
+use crate::util::amount::{msat_to_sats_ceil, validate_amount_msat};
+use hex::DisplayHex;
+use ldk_node::bitcoin::hashes::{sha256, Hash};
+use ldk_node::lightning::ln::PaymentHash;
 use ldk_server_protos::api::{Bolt11ReceiveRequest, Bolt11ReceiveResponse};
-use std::sync::Arc;
+use rand::RngCore;
 
 pub(crate) const BOLT11_RECEIVE_PATH: &str = "Bolt11Receive";
 
+/// The maximum length in bytes of the scope invoices and offers may be issued for.
+const MAX_SCOPE_LEN: usize = 64;
+
 pub(crate) fn handle_bolt11_receive_request(
-	node: Arc<Node>, request: Bolt11ReceiveRequest,
-) -> Result<Bolt11ReceiveResponse, ldk_node::NodeError> {
+	context: Context, request: Bolt11ReceiveRequest,
+) -> Result<Bolt11ReceiveResponse, LdkServerError> {
+	if let Some(amount_msat) = request.amount_msat {
+		validate_amount_msat("amount_msat", amount_msat)?;
+	}
+	let (max_description_len, expiry_secs) = {
+		let config = context.config.read().unwrap();
+		let expiry_secs = request.expiry_secs.unwrap_or(config.default_invoice_expiry_secs);
+		(config.max_invoice_description_len, expiry_secs)
+	};
+	validate_invoice_description(&request.description, max_description_len)?;
+	if let Some(scope) = request.scope.as_deref() {
+		validate_scope(scope)?;
+	}
+	if request.require_exact_amount && request.amount_msat.is_none() {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			"An exact amount can only be required if amount_msat is set.",
+		));
+	}
+	let warnings = match request.amount_msat {
+		Some(amount_msat) => {
+			let inbound_capacity_msat = context
+				.node
+				.list_channels()
+				.iter()
+				.filter(|channel| channel.is_usable)
+				.map(|channel| channel.inbound_capacity_msat)
+				.sum();
+			check_inbound_capacity(
+				amount_msat,
+				inbound_capacity_msat,
+				request.require_inbound_capacity,
+			)?
+		},
+		None => Vec::new(),
+	};
+
+	let mut record = InboundInvoiceRecord {
+		expected_amount_msat: request.amount_msat,
+		preimage: None,
+		require_exact_amount: request.require_exact_amount,
+		claimable_amount_msat: None,
+		scope: request.scope,
+		expires_at: None,
+	};
+
 	let invoice = match request.amount_msat {
+		// Exact-amount invoices are registered by payment hash, so that we get to check the
+		// claimable amount before claiming the payment ourselves.
+		Some(amount_msat) if request.require_exact_amount => {
+			let mut preimage = [0u8; 32];
+			rand::thread_rng().fill_bytes(&mut preimage);
+			let payment_hash = PaymentHash(sha256::Hash::hash(&preimage).to_byte_array());
+			record.preimage = Some(preimage.to_lower_hex_string());
+			context.node.bolt11_payment().receive_for_hash(
+				amount_msat,
+				&request.description,
+				expiry_secs,
+				payment_hash,
+			)?
+		},
 		Some(amount_msat) => {
-			node.bolt11_payment().receive(amount_msat, &request.description, request.expiry_secs)?
+			context.node.bolt11_payment().receive(amount_msat, &request.description, expiry_secs)?
 		},
-		None => node
+		None => context
+			.node
 			.bolt11_payment()
-			.receive_variable_amount(&request.description, request.expiry_secs)?,
+			.receive_variable_amount(&request.description, expiry_secs)?,
 	};
+	record.expires_at = invoice.expires_at().map(|expires_at| expires_at.as_secs());
+
+	let store = context.paginated_kv_store.as_ref();
+	write_inbound_invoice_record(store, &invoice.payment_hash().to_string(), &record).map_err(
+		|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to persist inbound invoice record: {}", e),
+			)
+		},
+	)?;
 
-	let response = Bolt11ReceiveResponse { invoice: invoice.to_string() };
+	let response = Bolt11ReceiveResponse { invoice: invoice.to_string(), warnings };
 	Ok(response)
 }
+
+// Checks whether receiving `amount_msat` fits the node's `inbound_capacity_msat`, failing if it
+// doesn't and the capacity is `required`, and returning a warning otherwise.
+//
+// LDK Node issues invoices regardless of the capacity, but payments to them fail unless the
+// capacity is increased before they are paid.
+fn check_inbound_capacity(
+	amount_msat: u64, inbound_capacity_msat: u64, required: bool,
+) -> Result<Vec<String>, LdkServerError> {
+	if amount_msat <= inbound_capacity_msat {
+		return Ok(Vec::new());
+	}
+	let needed_sats = msat_to_sats_ceil(amount_msat);
+	let available_sats = inbound_capacity_msat / 1000;
+	if required {
+		return Err(LdkServerError::insufficient_inbound_capacity(needed_sats, available_sats));
+	}
+	Ok(vec![format!(
+		"The invoice amount of {} sats exceeds the current inbound capacity of {} sats, payments to it will fail unless more inbound capacity is obtained.",
+		needed_sats, available_sats
+	)])
+}
+
+/// Fails if `description` exceeds `max_len` bytes or contains control characters, which could
+/// garble the display of invoices.
+pub(crate) fn validate_invoice_description(
+	description: &str, max_len: usize,
+) -> Result<(), LdkServerError> {
+	let reason = if description.len() > max_len {
+		format!("must be no longer than {} bytes", max_len)
+	} else if description.chars().any(char::is_control) {
+		"must not contain control characters".to_string()
+	} else {
+		return Ok(());
+	};
+	Err(LdkServerError::invalid_fields(vec![ValidationError::new("description", reason)]))
+}
+
+/// Fails if `scope` is empty or exceeds [`MAX_SCOPE_LEN`] bytes.
+pub(crate) fn validate_scope(scope: &str) -> Result<(), LdkServerError> {
+	let reason = if scope.is_empty() {
+		"must not be empty".to_string()
+	} else if scope.len() > MAX_SCOPE_LEN {
+		format!("must be no longer than {} bytes", MAX_SCOPE_LEN)
+	} else {
+		return Ok(());
+	};
+	Err(LdkServerError::invalid_fields(vec![ValidationError::new("scope", reason)]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InsufficientInboundCapacityError;
+
+	#[test]
+	fn test_amount_exceeding_inbound_capacity() {
+		assert!(check_inbound_capacity(500_000, 500_000, true).unwrap().is_empty());
+
+		let warnings = check_inbound_capacity(500_001, 400_000, false).unwrap();
+		assert_eq!(warnings.len(), 1);
+		assert!(warnings[0].contains("exceeds the current inbound capacity of 400 sats"));
+
+		let err = check_inbound_capacity(500_001, 400_000, true).unwrap_err();
+		assert_eq!(err.error_code, InsufficientInboundCapacityError);
+		assert_eq!(err.needed_sats, Some(501));
+		assert_eq!(err.available_sats, Some(400));
+	}
+
+	#[test]
+	fn test_validate_invoice_description() {
+		assert!(validate_invoice_description("coffee ☕", 10).is_ok());
+		assert!(validate_invoice_description(&"a".repeat(10), 10).is_ok());
+
+		let err = validate_invoice_description(&"a".repeat(11), 10).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert_eq!(err.validation_errors[0].field, "description");
+		// Lengths are in bytes rather than characters.
+		assert!(validate_invoice_description(&"☕".repeat(4), 10).is_err());
+
+		for description in ["coffee\n", "coffee\u{0}", "\u{1b}[31mcoffee"] {
+			let err = validate_invoice_description(description, 639).unwrap_err();
+			assert_eq!(err.error_code, InvalidRequestError);
+			assert!(err.message.contains("control characters"));
+		}
+	}
+
+	#[test]
+	fn test_validate_scope() {
+		assert!(validate_scope("tenant-1").is_ok());
+		assert!(validate_scope(&"a".repeat(MAX_SCOPE_LEN)).is_ok());
+		for scope in [String::new(), "a".repeat(MAX_SCOPE_LEN + 1)] {
+			let err = validate_scope(&scope).unwrap_err();
+			assert_eq!(err.validation_errors[0].field, "scope");
+		}
+	}
+}
@@ -1,23 +1,416 @@
+use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::payment_diagnostics::read_payment_diagnostics_record;
+use crate::service::Context;
+use crate::util::amount::{msat_to_sats_ceil, validate_amounts_msat, validate_invoice_amount};
+use crate::util::fee_limit::{fee_limit_warning, routing_fee_limit};
+use crate::util::invoice_fields::invoice_from_fields;
+use crate::util::payment_invoice::record_bolt11_invoice;
+use crate::util::payment_timeout::{payment_timeout, schedule_payment_timeout};
+use crate::util::request_nonce::check_request_nonce;
+use crate::util::route_randomization::randomize_route;
 use bytes::Bytes;
+use ldk_node::bitcoin::hex::DisplayHex;
+use ldk_node::lightning::ln::channelmanager::PaymentId;
 use ldk_node::lightning_invoice::Bolt11Invoice;
-use ldk_node::Node;
+use ldk_node::payment::{PaymentStatus, SendingParameters};
+use ldk_node::NodeError;
 use ldk_server_protos::api::{Bolt11SendRequest, Bolt11SendResponse};
+use ldk_server_protos::types::{PaymentFailureReason, PaymentRail};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::time::Instant;
 
 pub(crate) const BOLT11_SEND_PATH: &str = "Bolt11Send";
 
+// The base of LDK's default routing fee limit, which additionally allows 1% of the amount.
+const DEFAULT_BASE_FEE_LIMIT_MSAT: u64 = 50_000;
+
+/// The maximum number of attempts a request may ask for.
+const MAX_PAYMENT_ATTEMPTS: u32 = 10;
+
+/// The time we wait for a payment attempt to resolve before giving up on retrying it.
+const PAYMENT_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The interval at which we check whether a payment attempt resolved.
+const PAYMENT_ATTEMPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 pub(crate) fn handle_bolt11_send_request(
-	node: Arc<Node>, request: Bolt11SendRequest,
-) -> Result<Bolt11SendResponse, ldk_node::NodeError> {
-	let invoice = Bolt11Invoice::from_str(&request.invoice.as_str())
-		.map_err(|_| ldk_node::NodeError::InvalidInvoice)?;
+	context: Context, request: Bolt11SendRequest,
+) -> Result<Bolt11SendResponse, LdkServerError> {
+	check_request_nonce(&context, request.request_nonce.as_deref())?;
+	let invoice = validate_request(&request)?;
+
+	let (configured_max_msat, configured_timeout) = {
+		let config = context.config.read().unwrap();
+		(config.max_routing_fee_msat, config.outbound_payment_timeout)
+	};
+	let timeout = payment_timeout(configured_timeout, request.timeout_secs)?;
+	let mut fee_limit_msat = routing_fee_limit(
+		configured_max_msat,
+		request.max_routing_fee_msat,
+		request.override_fee_limit,
+	)?;
+
+	let mut amount_msat = request.amount_msat;
+	if request.send_max {
+		if request.amount_msat.is_some() || invoice.amount_milli_satoshis().is_some() {
+			return Err(LdkServerError::new(
+				InvalidRequestError,
+				"send_max requires a zero-amount invoice and amount_msat to be unset.",
+			));
+		}
+		let max_sendable_msat = context
+			.node
+			.list_channels()
+			.iter()
+			.filter(|channel| channel.is_usable)
+			.map(|channel| channel.next_outbound_htlc_limit_msat)
+			.sum();
+		let (max_amount_msat, max_fee_msat) =
+			max_sendable_amount_msat(max_sendable_msat, fee_limit_msat)?;
+		amount_msat = Some(max_amount_msat);
+		fee_limit_msat = Some(max_fee_msat);
+	}
 
-	let payment_id = match request.amount_msat {
-		None => node.bolt11_payment().send(&invoice, None),
-		Some(amount_msat) => node.bolt11_payment().send_using_amount(&invoice, amount_msat, None),
-	}?;
+	// LDK Node only reports a generic sending failure if routing fails for lack of outbound
+	// liquidity, so we check for it upfront to report the amounts involved.
+	if let Some(amount_msat) = amount_msat.or(invoice.amount_milli_satoshis()) {
+		let outbound_capacity_msat = context
+			.node
+			.list_channels()
+			.iter()
+			.filter(|channel| channel.is_usable)
+			.map(|channel| channel.outbound_capacity_msat)
+			.sum();
+		check_outbound_capacity(amount_msat, outbound_capacity_msat)?;
+	}
 
-	let response = Bolt11SendResponse { payment_id: Bytes::from(payment_id.0.to_vec()) };
+	let sending_parameters = fee_limit_msat.map(|limit_msat| SendingParameters {
+		max_total_routing_fee_msat: Some(Some(limit_msat)),
+		max_total_cltv_expiry_delta: None,
+		max_path_count: None,
+		max_channel_saturation_power_of_half: None,
+	});
+	let sending_parameters = if request.randomize_route {
+		Some(randomize_route(sending_parameters, &mut rand::thread_rng()))
+	} else {
+		sending_parameters
+	};
+
+	let send = || match amount_msat {
+		None => context.node.bolt11_payment().send(&invoice, sending_parameters.clone()),
+		Some(amount_msat) => context.node.bolt11_payment().send_using_amount(
+			&invoice,
+			amount_msat,
+			sending_parameters.clone(),
+		),
+	};
+
+	let (payment_id, attempts) = match request.max_attempts {
+		None => (send()?, None),
+		Some(max_attempts) => {
+			let (payment_id, attempts) = send_with_retries(max_attempts, || {
+				let payment_id = send()?;
+				Ok((payment_id, await_attempt_outcome(&context, &payment_id)))
+			})?;
+			(payment_id, Some(attempts))
+		},
+	};
+	record_bolt11_invoice(context.paginated_kv_store.as_ref(), &payment_id, &invoice);
+	if let Some(timeout) = timeout {
+		schedule_payment_timeout(
+			context.paginated_kv_store.as_ref(),
+			&payment_id,
+			timeout,
+			SystemTime::now(),
+		);
+	}
+	context
+		.payment_metrics
+		.record_payment(PaymentRail::Bolt11, amount_msat.or(invoice.amount_milli_satoshis()));
+
+	let response = Bolt11SendResponse {
+		payment_id: Bytes::from(payment_id.0.to_vec()),
+		amount_msat: if request.send_max { amount_msat } else { None },
+		attempts,
+		warnings: fee_limit_warning(configured_max_msat, fee_limit_msat).into_iter().collect(),
+	};
 	Ok(response)
 }
+
+// How a payment attempt turned out.
+#[derive(Debug)]
+enum AttemptOutcome {
+	Succeeded,
+	Failed { retryable: bool },
+	// The attempt didn't resolve in time.
+	Pending,
+}
+
+// Makes up to `max_attempts` payment `attempt`s, retrying those that failed for lack of a route.
+// Returns the id of the last attempt's payment along with the number of attempts made.
+fn send_with_retries<F: FnMut() -> Result<(PaymentId, AttemptOutcome), NodeError>>(
+	max_attempts: u32, mut attempt: F,
+) -> Result<(PaymentId, u32), LdkServerError> {
+	let mut attempts = 0;
+	loop {
+		attempts += 1;
+		match attempt() {
+			// LDK Node fails right away if no route could be found to begin with.
+			Err(NodeError::PaymentSendingFailed) if attempts < max_attempts => {},
+			Ok((_, AttemptOutcome::Failed { retryable: true })) if attempts < max_attempts => {},
+			Ok((payment_id, _)) => return Ok((payment_id, attempts)),
+			Err(e) => return Err(e.into()),
+		}
+	}
+}
+
+// Waits for the payment with the given id to resolve, for at most `PAYMENT_ATTEMPT_TIMEOUT`.
+//
+// Failed payments are retryable if they failed for lack of a route, as recorded in the payment's
+// diagnostics once its `PaymentFailed` event was handled.
+fn await_attempt_outcome(context: &Context, payment_id: &PaymentId) -> AttemptOutcome {
+	let payment_id_hex = payment_id.0.to_lower_hex_string();
+	let deadline = Instant::now() + PAYMENT_ATTEMPT_TIMEOUT;
+	// Handlers are synchronous, so we block this worker thread rather than the runtime.
+	tokio::task::block_in_place(|| {
+		tokio::runtime::Handle::current().block_on(async {
+			while Instant::now() < deadline {
+				match context.node.payment(payment_id).map(|payment| payment.status) {
+					Some(PaymentStatus::Succeeded) => return AttemptOutcome::Succeeded,
+					Some(PaymentStatus::Failed) => {
+						let record = read_payment_diagnostics_record(
+							context.paginated_kv_store.as_ref(),
+							&payment_id_hex,
+						);
+						if let Ok(Some(record)) = record {
+							let retryable = record.failure_reason.map_or(false, |reason| {
+								reason == PaymentFailureReason::RouteNotFound as i32
+									|| reason == PaymentFailureReason::RetriesExhausted as i32
+							});
+							return AttemptOutcome::Failed { retryable };
+						}
+					},
+					Some(PaymentStatus::Pending) | None => {},
+				}
+				tokio::time::sleep(PAYMENT_ATTEMPT_POLL_INTERVAL).await;
+			}
+			AttemptOutcome::Pending
+		})
+	})
+}
+
+// Validates the fields of `request`, returning the invoice to pay.
+fn validate_request(request: &Bolt11SendRequest) -> Result<Bolt11Invoice, LdkServerError> {
+	validate_amounts_msat(&[
+		("amount_msat", request.amount_msat),
+		("max_routing_fee_msat", request.max_routing_fee_msat),
+	])?;
+	if let Some(max_attempts) = request.max_attempts {
+		if max_attempts == 0 || max_attempts > MAX_PAYMENT_ATTEMPTS {
+			return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+				"max_attempts",
+				format!("must be between 1 and {}", MAX_PAYMENT_ATTEMPTS),
+			)]));
+		}
+	}
+
+	let invoice = match &request.invoice_fields {
+		None => Bolt11Invoice::from_str(request.invoice.as_str())
+			.map_err(|_| ldk_node::NodeError::InvalidInvoice)?,
+		Some(invoice_fields) if request.invoice.is_empty() => invoice_from_fields(invoice_fields)?,
+		Some(_) => {
+			return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+				"invoice_fields",
+				"can't be set along with invoice".to_string(),
+			)]));
+		},
+	};
+	validate_invoice_amount("invoice", &invoice)?;
+	Ok(invoice)
+}
+
+// Fails with an insufficient funds error if `amount_msat` exceeds the `outbound_capacity_msat` of
+// our usable channels.
+fn check_outbound_capacity(
+	amount_msat: u64, outbound_capacity_msat: u64,
+) -> Result<(), LdkServerError> {
+	if amount_msat > outbound_capacity_msat {
+		return Err(LdkServerError::insufficient_funds(
+			msat_to_sats_ceil(amount_msat),
+			outbound_capacity_msat / 1000,
+		));
+	}
+	Ok(())
+}
+
+// Returns the largest amount we can send given `max_sendable_msat` while leaving room for routing
+// fees, along with the fee limit to apply. LDK's default fee limit of 1% plus 50 satoshis is used
+// unless a `fee_limit_msat` is given.
+fn max_sendable_amount_msat(
+	max_sendable_msat: u64, fee_limit_msat: Option<u64>,
+) -> Result<(u64, u64), LdkServerError> {
+	let amount_msat = match fee_limit_msat {
+		Some(fee_limit_msat) => max_sendable_msat.saturating_sub(fee_limit_msat),
+		None => {
+			let available_msat = max_sendable_msat.saturating_sub(DEFAULT_BASE_FEE_LIMIT_MSAT);
+			let mut amount_msat = available_msat / 101 * 100;
+			while amount_msat + 1 + (amount_msat + 1) / 100 <= available_msat {
+				amount_msat += 1;
+			}
+			amount_msat
+		},
+	};
+	if amount_msat == 0 {
+		return Err(LdkServerError::insufficient_funds(1, max_sendable_msat / 1000));
+	}
+	Ok((amount_msat, max_sendable_msat - amount_msat))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode;
+	use crate::util::amount::tests::overflowing_invoice;
+	use crate::util::invoice_fields::tests::invoice_fields;
+	use ldk_node::bitcoin::hashes::{sha256, Hash};
+	use ldk_node::bitcoin::secp256k1::{Secp256k1, SecretKey};
+	use ldk_node::lightning::ln::types::PaymentSecret;
+	use ldk_node::lightning_invoice::{Currency, InvoiceBuilder};
+
+	fn invoice(amount_msat: u64) -> String {
+		let secp_ctx = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&[42; 32]).unwrap();
+		InvoiceBuilder::new(Currency::Regtest)
+			.description("test".to_string())
+			.payment_hash(sha256::Hash::hash(&[1; 32]))
+			.payment_secret(PaymentSecret([1; 32]))
+			.current_timestamp()
+			.min_final_cltv_expiry_delta(144)
+			.amount_milli_satoshis(amount_msat)
+			.build_signed(|hash| secp_ctx.sign_ecdsa_recoverable(hash, &secret_key))
+			.unwrap()
+			.to_string()
+	}
+
+	#[test]
+	fn test_u64_max_amounts_are_rejected() {
+		let request = Bolt11SendRequest { invoice: invoice(1_000), ..Default::default() };
+		assert!(validate_request(&request).is_ok());
+
+		let request = Bolt11SendRequest {
+			amount_msat: Some(u64::MAX),
+			max_routing_fee_msat: Some(u64::MAX),
+			..request
+		};
+		let err = validate_request(&request).unwrap_err();
+		assert_eq!(err.error_code, LdkServerErrorCode::InvalidRequestError);
+		let fields: Vec<_> = err.validation_errors.iter().map(|e| e.field.as_str()).collect();
+		assert_eq!(fields, ["amount_msat", "max_routing_fee_msat"]);
+
+		// The amount overflows when converted, making it look like a zero-amount invoice.
+		let request =
+			Bolt11SendRequest { invoice: overflowing_invoice().to_string(), ..Default::default() };
+		let err = validate_request(&request).unwrap_err();
+		assert_eq!(err.validation_errors[0].field, "invoice");
+
+		// Reporting the amounts involved doesn't overflow either.
+		let err = check_outbound_capacity(u64::MAX, 0).unwrap_err();
+		assert_eq!(err.needed_sats, Some(u64::MAX / 1000 + 1));
+	}
+
+	#[test]
+	fn test_invoice_fields_are_paid_like_invoice() {
+		let invoice_string = invoice(1_000);
+		let request = Bolt11SendRequest { invoice: invoice_string.clone(), ..Default::default() };
+		let invoice = validate_request(&request).unwrap();
+
+		let fields_request = Bolt11SendRequest {
+			invoice_fields: Some(invoice_fields(&invoice)),
+			..Default::default()
+		};
+		let fields_invoice = validate_request(&fields_request).unwrap();
+		assert_eq!(fields_invoice, invoice);
+		assert_eq!(fields_invoice.to_string(), invoice_string);
+
+		let request = Bolt11SendRequest { invoice: invoice_string, ..fields_request };
+		let err = validate_request(&request).unwrap_err();
+		assert_eq!(err.validation_errors[0].field, "invoice_fields");
+	}
+
+	#[test]
+	fn test_check_outbound_capacity() {
+		assert!(check_outbound_capacity(1_000_000, 1_000_000).is_ok());
+
+		let err = check_outbound_capacity(1_000_001, 500_999).unwrap_err();
+		assert_eq!(err.error_code, LdkServerErrorCode::InsufficientFundsError);
+		assert_eq!(err.needed_sats, Some(1001));
+		assert_eq!(err.available_sats, Some(500));
+	}
+
+	#[test]
+	fn test_send_with_retries() {
+		let payment_id = PaymentId([1; 32]);
+
+		// A retry over another route succeeds after the first attempt failed for lack of a route.
+		let mut outcomes = vec![
+			Ok((payment_id, AttemptOutcome::Succeeded)),
+			Ok((payment_id, AttemptOutcome::Failed { retryable: true })),
+		];
+		let res = send_with_retries(3, || outcomes.pop().unwrap());
+		assert_eq!(res.unwrap(), (payment_id, 2));
+		assert!(outcomes.is_empty());
+
+		// Payments failing to find a route right away are retried as well.
+		let mut outcomes =
+			vec![Ok((payment_id, AttemptOutcome::Succeeded)), Err(NodeError::PaymentSendingFailed)];
+		assert_eq!(send_with_retries(3, || outcomes.pop().unwrap()).unwrap(), (payment_id, 2));
+
+		// Succeeded, pending and permanently failed payments are never retried.
+		let mut attempts = 0;
+		let res = send_with_retries(3, || {
+			attempts += 1;
+			Ok((payment_id, AttemptOutcome::Succeeded))
+		});
+		assert_eq!(res.unwrap(), (payment_id, 1));
+		let res = send_with_retries(3, || Ok((payment_id, AttemptOutcome::Pending)));
+		assert_eq!(res.unwrap(), (payment_id, 1));
+		let res =
+			send_with_retries(3, || Ok((payment_id, AttemptOutcome::Failed { retryable: false })));
+		assert_eq!(res.unwrap(), (payment_id, 1));
+		assert_eq!(attempts, 1);
+
+		// The attempt budget is respected, surfacing the last failure.
+		let mut attempts = 0;
+		let res = send_with_retries(3, || {
+			attempts += 1;
+			Ok((payment_id, AttemptOutcome::Failed { retryable: true }))
+		});
+		assert_eq!(res.unwrap(), (payment_id, 3));
+		assert_eq!(attempts, 3);
+		let err = send_with_retries(2, || Err(NodeError::PaymentSendingFailed)).unwrap_err();
+		assert_eq!(err.error_code, LdkServerErrorCode::LightningError);
+	}
+
+	#[test]
+	fn test_max_sendable_amount_msat() {
+		// With a fee limit, exactly the limit is held back.
+		assert_eq!(max_sendable_amount_msat(1_000_000, Some(10_000)).unwrap(), (990_000, 10_000));
+
+		// Otherwise, the amount is maximized such that LDK's default fee limit is covered.
+		let (amount_msat, fee_limit_msat) = max_sendable_amount_msat(10_050_000, None).unwrap();
+		assert_eq!(amount_msat, 9_900_991);
+		assert_eq!(amount_msat + fee_limit_msat, 10_050_000);
+		assert!(fee_limit_msat >= amount_msat / 100 + DEFAULT_BASE_FEE_LIMIT_MSAT);
+		let larger_amount_msat = amount_msat + 1;
+		assert!(
+			larger_amount_msat + larger_amount_msat / 100 + DEFAULT_BASE_FEE_LIMIT_MSAT
+				> 10_050_000
+		);
+
+		let err = max_sendable_amount_msat(50_000, None).unwrap_err();
+		assert_eq!(err.error_code, LdkServerErrorCode::InsufficientFundsError);
+		assert!(max_sendable_amount_msat(10_000, Some(10_000)).is_err());
+	}
+}
@@ -1,24 +1,56 @@
-use ldk_node::Node;
+use crate::api::bolt11_receive::validate_scope;
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, InvalidRequestError};
+use crate::io::issued_offers::{write_issued_offer_record, IssuedOfferRecord};
+use crate::service::Context;
+use crate::util::amount::validate_amount_msat;
+use hex::DisplayHex;
 use ldk_server_protos::api::{Bolt12ReceiveRequest, Bolt12ReceiveResponse};
-use std::sync::Arc;
 
 pub(crate) const BOLT12_RECEIVE_PATH: &str = "Bolt12Receive";
 
 pub(crate) fn handle_bolt12_receive_request(
-	node: Arc<Node>, request: Bolt12ReceiveRequest,
-) -> Result<Bolt12ReceiveResponse, ldk_node::NodeError> {
+	context: Context, request: Bolt12ReceiveRequest,
+) -> Result<Bolt12ReceiveResponse, LdkServerError> {
+	if let Some(amount_msat) = request.amount_msat {
+		validate_amount_msat("amount_msat", amount_msat)?;
+	}
+	if let Some(scope) = request.scope.as_deref() {
+		validate_scope(scope)?;
+	}
+
+	if request.use_blinded_paths == Some(false) {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			"Offers without blinded paths are not supported.",
+		));
+	}
+
 	let offer = match request.amount_msat {
-		Some(amount_msat) => node.bolt12_payment().receive(
+		Some(amount_msat) => context.node.bolt12_payment().receive(
 			amount_msat,
 			&request.description,
 			request.expiry_secs,
 			request.quantity,
 		)?,
-		None => node
+		None => context
+			.node
 			.bolt12_payment()
 			.receive_variable_amount(&request.description, request.expiry_secs)?,
 	};
 
-	let response = Bolt12ReceiveResponse { offer: offer.to_string() };
+	// Keep track of the offers we issued, so that their usage can be queried via `GetOfferStatus`.
+	let record = IssuedOfferRecord { scope: request.scope };
+	write_issued_offer_record(
+		context.paginated_kv_store.as_ref(),
+		&offer.id().0.to_lower_hex_string(),
+		&record,
+	)
+	.map_err(|e| {
+		LdkServerError::new(InternalServerError, format!("Failed to record offer: {}", e))
+	})?;
+
+	let blinded_path_count = offer.paths().len() as u32;
+	let response = Bolt12ReceiveResponse { offer: offer.to_string(), blinded_path_count };
 	Ok(response)
 }
@@ -1,21 +1,53 @@
+use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use crate::util::amount::validate_amount_msat;
+use crate::util::payment_invoice::record_bolt12_offer;
+use crate::util::payment_timeout::{payment_timeout, schedule_payment_timeout};
+use crate::util::request_nonce::check_request_nonce;
 use bytes::Bytes;
-use ldk_node::lightning::offers::offer::Offer;
-use ldk_node::Node;
+use ldk_node::lightning::offers::offer::{Amount, Offer};
 use ldk_server_protos::api::{Bolt12SendRequest, Bolt12SendResponse};
+use ldk_server_protos::types::PaymentRail;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 pub(crate) const BOLT12_SEND_PATH: &str = "Bolt12Send";
 
 pub(crate) fn handle_bolt12_send_request(
-	node: Arc<Node>, request: Bolt12SendRequest,
-) -> Result<Bolt12SendResponse, ldk_node::NodeError> {
+	context: Context, request: Bolt12SendRequest,
+) -> Result<Bolt12SendResponse, LdkServerError> {
+	check_request_nonce(&context, request.request_nonce.as_deref())?;
+	if let Some(amount_msat) = request.amount_msat {
+		validate_amount_msat("amount_msat", amount_msat)?;
+	}
+
+	if request.randomize_route {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			"Route randomization isn't supported for BOLT12 payments.",
+		));
+	}
+
 	let offer =
-		Offer::from_str(&request.offer.as_str()).map_err(|_| ldk_node::NodeError::InvalidOffer)?;
+		Offer::from_str(request.offer.as_str()).map_err(|_| ldk_node::NodeError::InvalidOffer)?;
 
+	let (configured_max_msat, configured_timeout) = {
+		let config = context.config.read().unwrap();
+		(config.max_routing_fee_msat, config.outbound_payment_timeout)
+	};
+	let timeout = payment_timeout(configured_timeout, request.timeout_secs)?;
+	let timeout = with_invoice_timeout(timeout, request.invoice_timeout_secs)?;
+	check_fee_limit(configured_max_msat, request.override_fee_limit)?;
+
+	// Offers may be denominated in other currencies, in which case the amount paid is unknown.
+	let amount_msat = match request.amount_msat {
+		Some(amount_msat) => Some(amount_msat),
+		None => offer_amount_msat(&offer, request.quantity)?,
+	};
 	let payment_id = match request.amount_msat {
-		None => node.bolt12_payment().send(&offer, request.quantity, request.payer_note),
-		Some(amount_msat) => node.bolt12_payment().send_using_amount(
+		None => context.node.bolt12_payment().send(&offer, request.quantity, request.payer_note),
+		Some(amount_msat) => context.node.bolt12_payment().send_using_amount(
 			&offer,
 			amount_msat,
 			request.quantity,
@@ -23,6 +55,115 @@ pub(crate) fn handle_bolt12_send_request(
 		),
 	}?;
 
+	record_bolt12_offer(context.paginated_kv_store.as_ref(), &payment_id, &offer);
+	if let Some(timeout) = timeout {
+		schedule_payment_timeout(
+			context.paginated_kv_store.as_ref(),
+			&payment_id,
+			timeout,
+			SystemTime::now(),
+		);
+	}
+	context.payment_metrics.record_payment(PaymentRail::Bolt12, amount_msat);
+
 	let response = Bolt12SendResponse { payment_id: Bytes::from(payment_id.0.to_vec()) };
 	Ok(response)
 }
+
+// Fails if the configured routing fee limit applies to the payment, as LDK Node can't limit the
+// routing fee of BOLT12 payments.
+fn check_fee_limit(
+	configured_max_msat: Option<u64>, override_fee_limit: bool,
+) -> Result<(), LdkServerError> {
+	if configured_max_msat.is_some() && !override_fee_limit {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			"The routing fee of BOLT12 payments can't be limited to the configured max_routing_fee_msat, set override_fee_limit to send anyway.",
+		));
+	}
+	Ok(())
+}
+
+// Returns the timeout of a payment with the given `timeout`, shortened to the `invoice_timeout_secs`
+// if set.
+//
+// We can't tell whether the recipient responded with an invoice, so the invoice timeout is the
+// timeout of payments without HTLCs in flight, which is the only kind of payment ever abandoned.
+fn with_invoice_timeout(
+	timeout: Option<Duration>, invoice_timeout_secs: Option<u32>,
+) -> Result<Option<Duration>, LdkServerError> {
+	match invoice_timeout_secs {
+		Some(0) => Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"invoice_timeout_secs",
+			"must be greater than zero",
+		)])),
+		Some(invoice_timeout_secs) => {
+			let invoice_timeout = Duration::from_secs(invoice_timeout_secs as u64);
+			Ok(Some(timeout.map_or(invoice_timeout, |timeout| timeout.min(invoice_timeout))))
+		},
+		None => Ok(timeout),
+	}
+}
+
+// Returns the amount paid for `quantity` items of `offer`, if denominated in bitcoin, failing if it
+// exceeds the total supply of bitcoin rather than overflowing.
+fn offer_amount_msat(offer: &Offer, quantity: Option<u64>) -> Result<Option<u64>, LdkServerError> {
+	let amount_msats = match offer.amount() {
+		Some(Amount::Bitcoin { amount_msats }) => amount_msats,
+		_ => return Ok(None),
+	};
+	let amount_msat = amount_msats.checked_mul(quantity.unwrap_or(1)).ok_or_else(|| {
+		LdkServerError::invalid_fields(vec![ValidationError::new(
+			"quantity",
+			"makes the amount exceed the total supply of bitcoin",
+		)])
+	})?;
+	validate_amount_msat("quantity", amount_msat)?;
+	Ok(Some(amount_msat))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::bitcoin::secp256k1::PublicKey;
+	use ldk_node::lightning::offers::offer::{OfferBuilder, Quantity};
+
+	#[test]
+	fn test_configured_fee_limit_requires_override() {
+		let err = check_fee_limit(Some(1_000), false).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert!(check_fee_limit(Some(1_000), true).is_ok());
+		assert!(check_fee_limit(None, false).is_ok());
+	}
+
+	#[test]
+	fn test_amount_overflow_is_rejected() {
+		let signing_pubkey = PublicKey::from_str(
+			"02eadbd9e7557375161df8b646776a547c5cbc2e95b3071ec81553f8ec2cea3b8c",
+		)
+		.unwrap();
+		let offer = OfferBuilder::new(signing_pubkey)
+			.amount_msats(1_000)
+			.supported_quantity(Quantity::Unbounded)
+			.build()
+			.unwrap();
+		assert_eq!(offer_amount_msat(&offer, None).unwrap(), Some(1_000));
+		assert_eq!(offer_amount_msat(&offer, Some(3)).unwrap(), Some(3_000));
+		for quantity in [u64::MAX, u64::MAX / 1_000] {
+			let err = offer_amount_msat(&offer, Some(quantity)).unwrap_err();
+			assert_eq!(err.error_code, InvalidRequestError);
+			assert_eq!(err.validation_errors[0].field, "quantity");
+		}
+	}
+
+	#[test]
+	fn test_invoice_timeout() {
+		let timeout = Some(Duration::from_secs(300));
+		assert_eq!(with_invoice_timeout(timeout, Some(30)).unwrap(), Some(Duration::from_secs(30)));
+		assert_eq!(with_invoice_timeout(None, Some(30)).unwrap(), Some(Duration::from_secs(30)));
+		assert_eq!(with_invoice_timeout(timeout, Some(600)).unwrap(), timeout);
+		assert_eq!(with_invoice_timeout(timeout, None).unwrap(), timeout);
+		let err = with_invoice_timeout(timeout, Some(0)).unwrap_err();
+		assert_eq!(err.validation_errors[0].field, "invoice_timeout_secs");
+	}
+}
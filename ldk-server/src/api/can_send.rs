@@ -0,0 +1,54 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
+use crate::util::amount::validate_amount_msat;
+use ldk_node::bitcoin::secp256k1::PublicKey;
+use ldk_node::lightning::routing::gossip::NodeId;
+use ldk_server_protos::api::{CanSendRequest, CanSendResponse};
+use std::str::FromStr;
+
+pub(crate) const CAN_SEND_PATH: &str = "CanSend";
+
+pub(crate) fn handle_can_send_request(
+	context: Context, request: CanSendRequest,
+) -> Result<CanSendResponse, LdkServerError> {
+	validate_amount_msat("amount_msat", request.amount_msat)?;
+
+	let channels = context.node.list_channels();
+	let usable_channels = channels.iter().filter(|channel| channel.is_usable);
+	let max_sendable_msat =
+		usable_channels.clone().map(|channel| channel.next_outbound_htlc_limit_msat).sum();
+
+	let destination_known = match request.destination {
+		Some(destination) => {
+			let node_id = PublicKey::from_str(&destination)
+				.map_err(|_| ldk_node::NodeError::InvalidNodeId)?;
+			// Destinations are reachable if they are a direct peer or known from the network graph.
+			usable_channels.clone().any(|channel| channel.counterparty_node_id == node_id)
+				|| context.node.network_graph().node(&NodeId::from_pubkey(&node_id)).is_some()
+		},
+		None => true,
+	};
+
+	let sendable = destination_known && can_send(request.amount_msat, max_sendable_msat);
+	let response = CanSendResponse { sendable, max_sendable_msat };
+	Ok(response)
+}
+
+fn can_send(amount_msat: u64, max_sendable_msat: u64) -> bool {
+	amount_msat > 0 && amount_msat <= max_sendable_msat
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_can_send() {
+		let max_sendable_msat = 50_000;
+		assert!(can_send(1, max_sendable_msat));
+		assert!(can_send(50_000, max_sendable_msat));
+		assert!(!can_send(50_001, max_sendable_msat));
+		assert!(!can_send(0, max_sendable_msat));
+		assert!(!can_send(1, 0));
+	}
+}
@@ -0,0 +1,93 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::InternalServerError;
+use crate::io::inbound_invoices::{read_inbound_invoice_record, write_inbound_invoice_record};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::service::Context;
+use hex::{DisplayHex, FromHex};
+use ldk_node::lightning::ln::PaymentHash;
+use ldk_node::NodeError;
+use ldk_server_protos::api::{CancelHoldInvoiceRequest, CancelHoldInvoiceResponse};
+
+pub(crate) const CANCEL_HOLD_INVOICE_PATH: &str = "CancelHoldInvoice";
+
+pub(crate) fn handle_cancel_hold_invoice_request(
+	context: Context, request: CancelHoldInvoiceRequest,
+) -> Result<CancelHoldInvoiceResponse, LdkServerError> {
+	let payment_hash = <[u8; 32]>::from_hex(&request.payment_hash)
+		.map(PaymentHash)
+		.map_err(|_| NodeError::InvalidPaymentHash)?;
+
+	cancel_held_payment(context.paginated_kv_store.as_ref(), payment_hash, |payment_hash| {
+		context.node.bolt11_payment().fail_for_hash(payment_hash)
+	})?;
+
+	Ok(CancelHoldInvoiceResponse {})
+}
+
+// Fails back the payment to the invoice of `payment_hash` via `fail`, clearing any held amount
+// from its record, such that it can't be settled afterwards.
+fn cancel_held_payment<F: FnOnce(PaymentHash) -> Result<(), NodeError>>(
+	store: &dyn PaginatedKVStore, payment_hash: PaymentHash, fail: F,
+) -> Result<(), LdkServerError> {
+	let payment_hash_hex = payment_hash.0.to_lower_hex_string();
+	let mut record = read_inbound_invoice_record(store, &payment_hash_hex)
+		.map_err(|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to read inbound invoice record: {}", e),
+			)
+		})?
+		.ok_or(NodeError::InvalidPaymentHash)?;
+
+	fail(payment_hash)?;
+
+	if record.claimable_amount_msat.take().is_some() {
+		write_inbound_invoice_record(store, &payment_hash_hex, &record).map_err(|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to persist inbound invoice record: {}", e),
+			)
+		})?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+	use crate::io::inbound_invoices::InboundInvoiceRecord;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+
+	#[test]
+	fn test_cancel_fails_back_held_payment() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let payment_hash = PaymentHash([42; 32]);
+		let payment_hash_hex = payment_hash.0.to_lower_hex_string();
+
+		let err =
+			cancel_held_payment(&store, payment_hash, |_| panic!("unknown invoice")).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+
+		let record = InboundInvoiceRecord {
+			expected_amount_msat: Some(1000),
+			preimage: None,
+			require_exact_amount: false,
+			claimable_amount_msat: Some(1000),
+			scope: None,
+			expires_at: None,
+		};
+		write_inbound_invoice_record(&store, &payment_hash_hex, &record).unwrap();
+		let mut failed = Vec::new();
+		cancel_held_payment(&store, payment_hash, |hash| {
+			failed.push(hash);
+			Ok(())
+		})
+		.unwrap();
+		assert_eq!(failed, vec![payment_hash]);
+
+		let record = read_inbound_invoice_record(&store, &payment_hash_hex).unwrap().unwrap();
+		assert_eq!(record.claimable_amount_msat, None);
+	}
+}
@@ -0,0 +1,246 @@
+use crate::api::error::LdkServerErrorCode::{InternalServerError, LightningError};
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::inbound_invoices::{write_inbound_invoice_record, InboundInvoiceRecord};
+use crate::service::Context;
+use crate::util::amount::validate_amount_msat;
+use crate::util::lnurl::{decode_lnurl, lnurl_client, lnurl_get};
+use ldk_node::lightning_invoice::Bolt11Invoice;
+use ldk_server_protos::api::{ClaimLnurlWithdrawRequest, ClaimLnurlWithdrawResponse};
+use serde::Deserialize;
+
+pub(crate) const CLAIM_LNURL_WITHDRAW_PATH: &str = "ClaimLnurlWithdraw";
+
+/// The parameters of an LNURL-withdraw request, as returned by the LNURL service (LUD-03).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WithdrawRequest {
+	tag: String,
+	callback: String,
+	k1: String,
+	min_withdrawable: u64,
+	max_withdrawable: u64,
+	#[serde(default)]
+	default_description: String,
+}
+
+pub(crate) fn handle_claim_lnurl_withdraw_request(
+	context: Context, request: ClaimLnurlWithdrawRequest,
+) -> Result<ClaimLnurlWithdrawResponse, LdkServerError> {
+	let url = decode_lnurl("lnurl", &request.lnurl)?;
+	if let Some(amount_msat) = request.amount_msat {
+		validate_amount_msat("amount_msat", amount_msat)?;
+	}
+	let expiry_secs = context.config.read().unwrap().default_invoice_expiry_secs;
+
+	let client = lnurl_client()?;
+	// Handlers are synchronous, so we block this worker thread rather than the runtime.
+	let invoice = tokio::task::block_in_place(|| {
+		tokio::runtime::Handle::current().block_on(claim_lnurl_withdraw(
+			&client,
+			&url,
+			request.amount_msat,
+			|amount_msat, description| {
+				let invoice =
+					context.node.bolt11_payment().receive(amount_msat, description, expiry_secs)?;
+				let record = InboundInvoiceRecord {
+					expected_amount_msat: Some(amount_msat),
+					preimage: None,
+					require_exact_amount: false,
+					claimable_amount_msat: None,
+					scope: None,
+					expires_at: invoice.expires_at().map(|expires_at| expires_at.as_secs()),
+				};
+				let store = context.paginated_kv_store.as_ref();
+				write_inbound_invoice_record(store, &invoice.payment_hash().to_string(), &record)
+					.map_err(|e| {
+					LdkServerError::new(
+						InternalServerError,
+						format!("Failed to persist inbound invoice record: {}", e),
+					)
+				})?;
+				Ok(invoice)
+			},
+		))
+	})?;
+
+	let response = ClaimLnurlWithdrawResponse {
+		invoice: invoice.to_string(),
+		// unwrap safety: the invoice is always issued for an amount.
+		amount_msat: invoice.amount_milli_satoshis().unwrap(),
+	};
+	Ok(response)
+}
+
+// Resolves the LNURL-withdraw request at `url`, issues an invoice for `amount_msat`, or the most
+// the service allows if unset, via `receive`, and submits it to the service to be paid.
+async fn claim_lnurl_withdraw<F>(
+	client: &reqwest::Client, url: &str, amount_msat: Option<u64>, receive: F,
+) -> Result<Bolt11Invoice, LdkServerError>
+where
+	F: FnOnce(u64, &str) -> Result<Bolt11Invoice, LdkServerError>,
+{
+	let withdraw_request: WithdrawRequest = lnurl_get(client.get(url)).await?;
+	let amount_msat = withdraw_amount(&withdraw_request, amount_msat)?;
+
+	let invoice = receive(amount_msat, &withdraw_request.default_description)?;
+	let callback = client
+		.get(&withdraw_request.callback)
+		.query(&[("k1", withdraw_request.k1.as_str()), ("pr", &invoice.to_string())]);
+	// Any response other than an error acknowledges the invoice, which the service then pays.
+	let _: serde_json::Value = lnurl_get(callback).await?;
+	Ok(invoice)
+}
+
+// Returns the amount to withdraw given the `requested_amount_msat`, if any, failing if it's outside
+// the bounds of `withdraw_request`.
+fn withdraw_amount(
+	withdraw_request: &WithdrawRequest, requested_amount_msat: Option<u64>,
+) -> Result<u64, LdkServerError> {
+	if withdraw_request.tag != "withdrawRequest" {
+		return Err(LdkServerError::new(
+			LightningError,
+			format!("LNURL service returned unexpected tag {}.", withdraw_request.tag),
+		));
+	}
+	let (min_msat, max_msat) =
+		(withdraw_request.min_withdrawable, withdraw_request.max_withdrawable);
+	// Invoices can only be issued for whole millisatoshis above zero.
+	if max_msat == 0 || max_msat < min_msat {
+		return Err(LdkServerError::new(
+			LightningError,
+			format!(
+				"LNURL service allows no withdrawal, between {} and {} msat.",
+				min_msat, max_msat
+			),
+		));
+	}
+	match requested_amount_msat {
+		Some(amount_msat) if amount_msat < min_msat || amount_msat > max_msat => {
+			Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+				"amount_msat",
+				format!("must be between {} and {} msat for this withdrawal", min_msat, max_msat),
+			)]))
+		},
+		Some(amount_msat) => Ok(amount_msat),
+		None => Ok(max_msat),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+	use crate::util::lnurl::tests::mock_lnurl_server;
+	use ldk_node::bitcoin::hashes::{sha256, Hash};
+	use ldk_node::bitcoin::secp256k1::{Secp256k1, SecretKey};
+	use ldk_node::lightning::ln::types::PaymentSecret;
+	use ldk_node::lightning_invoice::{Currency, InvoiceBuilder};
+	use std::str::FromStr;
+	use std::sync::{Arc, Mutex};
+
+	const K1: &str = "e2af6254a8df433264fa23f67eb8188635d15ce883e8fc020989d5f82ae6f11e";
+
+	fn test_invoice(amount_msat: u64, description: &str) -> Bolt11Invoice {
+		let secp_ctx = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&[42; 32]).unwrap();
+		InvoiceBuilder::new(Currency::Regtest)
+			.description(description.to_string())
+			.payment_hash(sha256::Hash::hash(&[1; 32]))
+			.payment_secret(PaymentSecret([1; 32]))
+			.current_timestamp()
+			.min_final_cltv_expiry_delta(144)
+			.amount_milli_satoshis(amount_msat)
+			.build_signed(|hash| secp_ctx.sign_ecdsa_recoverable(hash, &secret_key))
+			.unwrap()
+	}
+
+	// Serves an LNURL-withdraw request on localhost, returning its URL along with the invoices
+	// submitted to it.
+	async fn mock_withdraw_server() -> (String, Arc<Mutex<Vec<Bolt11Invoice>>>) {
+		let submitted_invoices = Arc::new(Mutex::new(Vec::new()));
+		let invoices = submitted_invoices.clone();
+		let base_url = mock_lnurl_server(move |base_url, target| {
+			if target == "/withdraw" {
+				return serde_json::json!({
+					"tag": "withdrawRequest",
+					"callback": format!("{}/callback?session=1", base_url),
+					"k1": K1,
+					"minWithdrawable": 1_000,
+					"maxWithdrawable": 50_000,
+					"defaultDescription": "Voucher",
+				});
+			}
+			let query = target.strip_prefix("/callback?session=1&").unwrap();
+			let params: Vec<(&str, &str)> =
+				query.split('&').map(|param| param.split_once('=').unwrap()).collect();
+			if params[0] != ("k1", K1) {
+				return serde_json::json!({ "status": "ERROR", "reason": "Invalid k1" });
+			}
+			invoices.lock().unwrap().push(Bolt11Invoice::from_str(params[1].1).unwrap());
+			serde_json::json!({ "status": "OK" })
+		})
+		.await;
+		(format!("{}/withdraw", base_url), submitted_invoices)
+	}
+
+	#[tokio::test]
+	async fn test_claim_withdraw() {
+		let (url, submitted_invoices) = mock_withdraw_server().await;
+		let client = reqwest::Client::new();
+
+		// Without an amount, the most the service allows is claimed.
+		let invoice = claim_lnurl_withdraw(&client, &url, None, |amount_msat, description| {
+			assert_eq!(description, "Voucher");
+			Ok(test_invoice(amount_msat, description))
+		})
+		.await
+		.unwrap();
+		assert_eq!(invoice.amount_milli_satoshis(), Some(50_000));
+		assert_eq!(*submitted_invoices.lock().unwrap(), [invoice]);
+
+		let invoice =
+			claim_lnurl_withdraw(&client, &url, Some(2_000), |amount_msat, description| {
+				Ok(test_invoice(amount_msat, description))
+			})
+			.await
+			.unwrap();
+		assert_eq!(invoice.amount_milli_satoshis(), Some(2_000));
+		assert_eq!(submitted_invoices.lock().unwrap().len(), 2);
+
+		// Amounts outside the allowed bounds fail before an invoice is issued.
+		let receive = |_: u64, _: &str| -> Result<Bolt11Invoice, LdkServerError> { unreachable!() };
+		let err = claim_lnurl_withdraw(&client, &url, Some(60_000), receive).await.unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert_eq!(err.validation_errors[0].field, "amount_msat");
+		assert!(err.validation_errors[0].reason.contains("between 1000 and 50000 msat"));
+		assert_eq!(submitted_invoices.lock().unwrap().len(), 2);
+	}
+
+	#[test]
+	fn test_withdraw_amount_bounds() {
+		let mut withdraw_request = WithdrawRequest {
+			tag: "withdrawRequest".to_string(),
+			callback: "https://example.com/callback".to_string(),
+			k1: K1.to_string(),
+			min_withdrawable: 1_000,
+			max_withdrawable: 1_000,
+			default_description: String::new(),
+		};
+		assert_eq!(withdraw_amount(&withdraw_request, None).unwrap(), 1_000);
+		assert_eq!(withdraw_amount(&withdraw_request, Some(1_000)).unwrap(), 1_000);
+		assert!(withdraw_amount(&withdraw_request, Some(999)).is_err());
+
+		withdraw_request.max_withdrawable = 0;
+		withdraw_request.min_withdrawable = 0;
+		assert_eq!(
+			withdraw_amount(&withdraw_request, None).unwrap_err().error_code,
+			LightningError
+		);
+
+		withdraw_request.tag = "payRequest".to_string();
+		assert_eq!(
+			withdraw_amount(&withdraw_request, None).unwrap_err().error_code,
+			LightningError
+		);
+	}
+}
@@ -1,14 +1,18 @@
+use crate::api::error::LdkServerErrorCode::InternalServerError;
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use crate::util::close_timeout::{cancel_close_timeout, close_timeout, schedule_close_timeout};
 use ldk_node::bitcoin::secp256k1::PublicKey;
-use ldk_node::{Node, UserChannelId};
+use ldk_node::UserChannelId;
 use ldk_server_protos::api::{CloseChannelRequest, CloseChannelResponse};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::time::SystemTime;
 
 pub(crate) const CLOSE_CHANNEL_PATH: &str = "CloseChannel";
 
 pub(crate) fn handle_close_channel_request(
-	node: Arc<Node>, request: CloseChannelRequest,
-) -> Result<CloseChannelResponse, ldk_node::NodeError> {
+	context: Context, request: CloseChannelRequest,
+) -> Result<CloseChannelResponse, LdkServerError> {
 	//TODO: Should this be string?
 	let mut user_channel_id_bytes = [0u8; 16];
 	user_channel_id_bytes.copy_from_slice(&request.user_channel_id);
@@ -16,13 +20,48 @@ pub(crate) fn handle_close_channel_request(
 	let counterparty_node_id = PublicKey::from_str(&request.counterparty_node_id)
 		.map_err(|_| ldk_node::NodeError::InvalidPublicKey)?;
 
+	let timeout = close_timeout(request.cooperative_timeout_secs, request.force_close_on_timeout)?;
+
 	match request.force_close {
-		Some(true) => node.force_close_channel(
-			&user_channel_id,
-			counterparty_node_id,
-			request.force_close_reason,
-		)?,
-		_ => node.close_channel(&user_channel_id, counterparty_node_id)?,
+		Some(true) => {
+			if timeout.is_some() {
+				return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+					"cooperative_timeout_secs",
+					"can only be set while closing a channel cooperatively".to_string(),
+				)]));
+			}
+			context.node.force_close_channel(
+				&user_channel_id,
+				counterparty_node_id,
+				request.force_close_reason,
+			)?
+		},
+		_ => {
+			let store = context.paginated_kv_store.as_ref();
+			// The timeout is in place before the close starts, so that no close goes without it.
+			if let Some(timeout) = timeout {
+				schedule_close_timeout(
+					store,
+					&user_channel_id,
+					counterparty_node_id,
+					timeout,
+					request.force_close_on_timeout,
+					SystemTime::now(),
+				)
+				.map_err(|e| {
+					LdkServerError::new(
+						InternalServerError,
+						format!("Failed to persist close timeout: {}", e),
+					)
+				})?;
+			}
+			if let Err(e) = context.node.close_channel(&user_channel_id, counterparty_node_id) {
+				if timeout.is_some() {
+					cancel_close_timeout(store, &user_channel_id);
+				}
+				return Err(e.into());
+			}
+		},
 	};
 
 	let response = CloseChannelResponse {};
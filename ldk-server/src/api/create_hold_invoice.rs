@@ -0,0 +1,143 @@
+use crate::api::bolt11_receive::{validate_invoice_description, validate_scope};
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::InternalServerError;
+use crate::io::inbound_invoices::{
+	read_inbound_invoice_record, write_inbound_invoice_record, InboundInvoiceRecord,
+};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::service::Context;
+use crate::util::amount::validate_amount_msat;
+use hex::{DisplayHex, FromHex};
+use ldk_node::lightning::ln::PaymentHash;
+use ldk_node::lightning_invoice::Bolt11Invoice;
+use ldk_node::NodeError;
+use ldk_server_protos::api::{CreateHoldInvoiceRequest, CreateHoldInvoiceResponse};
+
+pub(crate) const CREATE_HOLD_INVOICE_PATH: &str = "CreateHoldInvoice";
+
+pub(crate) fn handle_create_hold_invoice_request(
+	context: Context, request: CreateHoldInvoiceRequest,
+) -> Result<CreateHoldInvoiceResponse, LdkServerError> {
+	if let Some(amount_msat) = request.amount_msat {
+		validate_amount_msat("amount_msat", amount_msat)?;
+	}
+
+	let max_description_len = context.config.read().unwrap().max_invoice_description_len;
+	validate_invoice_description(&request.description, max_description_len)?;
+	if let Some(scope) = request.scope.as_deref() {
+		validate_scope(scope)?;
+	}
+
+	let payment_hash = <[u8; 32]>::from_hex(&request.payment_hash)
+		.map(PaymentHash)
+		.map_err(|_| NodeError::InvalidPaymentHash)?;
+
+	let record = InboundInvoiceRecord {
+		expected_amount_msat: request.amount_msat,
+		preimage: None,
+		require_exact_amount: false,
+		claimable_amount_msat: None,
+		scope: request.scope,
+		expires_at: None,
+	};
+	let invoice =
+		create_hold_invoice(context.paginated_kv_store.as_ref(), &payment_hash, record, || {
+			match request.amount_msat {
+				Some(amount_msat) => context.node.bolt11_payment().receive_for_hash(
+					amount_msat,
+					&request.description,
+					request.expiry_secs,
+					payment_hash,
+				),
+				None => context.node.bolt11_payment().receive_variable_amount_for_hash(
+					&request.description,
+					request.expiry_secs,
+					payment_hash,
+				),
+			}
+		})?;
+
+	let response = CreateHoldInvoiceResponse { invoice: invoice.to_string() };
+	Ok(response)
+}
+
+// Creates the invoice for `payment_hash` via `create_invoice`, failing if one was issued for it
+// before. The `record` is only persisted once the invoice was created, such that failed attempts
+// can be retried, and completed with the expiry of the created invoice.
+fn create_hold_invoice<F: FnOnce() -> Result<Bolt11Invoice, NodeError>>(
+	store: &dyn PaginatedKVStore, payment_hash: &PaymentHash, mut record: InboundInvoiceRecord,
+	create_invoice: F,
+) -> Result<Bolt11Invoice, LdkServerError> {
+	let payment_hash_hex = payment_hash.0.to_lower_hex_string();
+	let existing_record = read_inbound_invoice_record(store, &payment_hash_hex).map_err(|e| {
+		LdkServerError::new(
+			InternalServerError,
+			format!("Failed to read inbound invoice record: {}", e),
+		)
+	})?;
+	if existing_record.is_some() {
+		return Err(NodeError::DuplicatePayment.into());
+	}
+
+	let invoice = create_invoice()?;
+	record.expires_at = invoice.expires_at().map(|expires_at| expires_at.as_secs());
+
+	// The record needs to be in place before the invoice is handed out, as without a known
+	// preimage claimable payments will be held until settled or cancelled.
+	write_inbound_invoice_record(store, &payment_hash_hex, &record).map_err(|e| {
+		LdkServerError::new(
+			InternalServerError,
+			format!("Failed to persist inbound invoice record: {}", e),
+		)
+	})?;
+	Ok(invoice)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::{InvalidRequestError, LightningError};
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use crate::util::amount::tests::overflowing_invoice;
+
+	#[test]
+	fn test_record_is_only_persisted_for_created_invoices() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let payment_hash = PaymentHash([42; 32]);
+		let record = InboundInvoiceRecord {
+			expected_amount_msat: Some(1000),
+			preimage: None,
+			require_exact_amount: false,
+			claimable_amount_msat: None,
+			scope: None,
+			expires_at: None,
+		};
+
+		let err = create_hold_invoice(&store, &payment_hash, record.clone(), || {
+			Err(NodeError::InvoiceCreationFailed)
+		})
+		.unwrap_err();
+		assert_eq!(err.error_code, LightningError);
+		let payment_hash_hex = payment_hash.0.to_lower_hex_string();
+		assert_eq!(read_inbound_invoice_record(&store, &payment_hash_hex).unwrap(), None);
+
+		// Retrying after a failure succeeds, reusing the payment hash afterwards doesn't.
+		let invoice =
+			create_hold_invoice(
+				&store,
+				&payment_hash,
+				record.clone(),
+				|| Ok(overflowing_invoice()),
+			)
+			.unwrap();
+		let expires_at = invoice.expires_at().map(|expires_at| expires_at.as_secs());
+		assert_eq!(
+			read_inbound_invoice_record(&store, &payment_hash_hex).unwrap(),
+			Some(InboundInvoiceRecord { expires_at, ..record.clone() })
+		);
+		let err = create_hold_invoice(&store, &payment_hash, record, || Ok(overflowing_invoice()))
+			.unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+	}
+}
@@ -0,0 +1,207 @@
+use crate::api::bolt11_receive::{validate_invoice_description, validate_scope};
+use crate::api::error::LdkServerErrorCode::InternalServerError;
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::inbound_invoices::{
+	remove_inbound_invoice_record, write_inbound_invoice_record, InboundInvoiceRecord,
+};
+use crate::io::invoice_groups::{write_invoice_group_record, InvoiceGroupRecord};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::service::Context;
+use crate::util::amount::validate_amount_msat;
+use hex::DisplayHex;
+use ldk_node::bitcoin::hashes::{sha256, Hash};
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_node::lightning::ln::PaymentHash;
+use ldk_node::lightning_invoice::Bolt11Invoice;
+use ldk_node::NodeError;
+use ldk_server_protos::api::{CreateInvoiceGroupRequest, CreateInvoiceGroupResponse};
+use rand::RngCore;
+use std::ops::RangeInclusive;
+
+pub(crate) const CREATE_INVOICE_GROUP_PATH: &str = "CreateInvoiceGroup";
+
+/// The number of invoices an order may be split into.
+const INVOICE_GROUP_PARTS: RangeInclusive<u32> = 2..=10;
+
+/// The smallest amount a single invoice of a group may be issued for.
+const MIN_PART_AMOUNT_MSAT: u64 = 1000;
+
+pub(crate) fn handle_create_invoice_group_request(
+	context: Context, request: CreateInvoiceGroupRequest,
+) -> Result<CreateInvoiceGroupResponse, LdkServerError> {
+	validate_amount_msat("total_amount_msat", request.total_amount_msat)?;
+	if !INVOICE_GROUP_PARTS.contains(&request.parts) {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"parts",
+			format!(
+				"must be between {} and {}",
+				INVOICE_GROUP_PARTS.start(),
+				INVOICE_GROUP_PARTS.end()
+			),
+		)]));
+	}
+	if request.total_amount_msat < request.parts as u64 * MIN_PART_AMOUNT_MSAT {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"total_amount_msat",
+			format!("must be at least {} msat per part", MIN_PART_AMOUNT_MSAT),
+		)]));
+	}
+	let (max_description_len, expiry_secs) = {
+		let config = context.config.read().unwrap();
+		let expiry_secs = request.expiry_secs.unwrap_or(config.default_invoice_expiry_secs);
+		(config.max_invoice_description_len, expiry_secs)
+	};
+	validate_invoice_description(&request.description, max_description_len)?;
+	if let Some(scope) = request.scope.as_deref() {
+		validate_scope(scope)?;
+	}
+
+	let amounts_msat = split_amount_msat(request.total_amount_msat, request.parts);
+	let (group_id, invoices) = create_invoice_group(
+		context.paginated_kv_store.as_ref(),
+		&amounts_msat,
+		request.scope,
+		|amount_msat, payment_hash| {
+			context.node.bolt11_payment().receive_for_hash(
+				amount_msat,
+				&request.description,
+				expiry_secs,
+				payment_hash,
+			)
+		},
+		|payment_hash| {
+			if let Err(e) = context.node.remove_payment(&PaymentId(payment_hash.0)) {
+				eprintln!("Failed to remove orphaned invoice {}: {}", payment_hash, e);
+			}
+		},
+	)?;
+
+	Ok(CreateInvoiceGroupResponse { group_id, invoices })
+}
+
+// Creates an invoice via `create_invoice` for each of the `amounts_msat`, returning the id of the
+// group along with the invoices.
+//
+// The invoices are created for payment hashes of our own preimages, such that payments are only
+// claimed while the invoice's record exists. If creating any part fails, the records of the parts
+// created so far are removed again and their payments dropped via `remove_payment`, rendering the
+// orphaned invoices unpayable.
+pub(crate) fn create_invoice_group<C, R>(
+	store: &dyn PaginatedKVStore, amounts_msat: &[u64], scope: Option<String>,
+	mut create_invoice: C, remove_payment: R,
+) -> Result<(String, Vec<String>), LdkServerError>
+where
+	C: FnMut(u64, PaymentHash) -> Result<Bolt11Invoice, NodeError>,
+	R: Fn(PaymentHash),
+{
+	let mut invoices = Vec::new();
+	let mut payment_hashes = Vec::new();
+	let res = amounts_msat.iter().try_for_each(|&amount_msat| {
+		let mut preimage = [0u8; 32];
+		rand::thread_rng().fill_bytes(&mut preimage);
+		let payment_hash = PaymentHash(sha256::Hash::hash(&preimage).to_byte_array());
+		let invoice = create_invoice(amount_msat, payment_hash)?;
+		payment_hashes.push(payment_hash);
+
+		let record = InboundInvoiceRecord {
+			expected_amount_msat: Some(amount_msat),
+			preimage: Some(preimage.to_lower_hex_string()),
+			require_exact_amount: false,
+			claimable_amount_msat: None,
+			scope: scope.clone(),
+			expires_at: invoice.expires_at().map(|expires_at| expires_at.as_secs()),
+		};
+		write_inbound_invoice_record(store, &payment_hash.to_string(), &record).map_err(|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to persist inbound invoice record: {}", e),
+			)
+		})?;
+		invoices.push(invoice.to_string());
+		Ok(())
+	});
+
+	let res = res.and_then(|()| {
+		let mut group_id = [0u8; 16];
+		rand::thread_rng().fill_bytes(&mut group_id);
+		let group_id = group_id.to_lower_hex_string();
+		let total_amount_msat = amounts_msat.iter().sum();
+		let payment_hashes = payment_hashes.iter().map(PaymentHash::to_string).collect();
+		let record = InvoiceGroupRecord { total_amount_msat, payment_hashes, scope };
+		write_invoice_group_record(store, &group_id, &record).map_err(|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to persist invoice group record: {}", e),
+			)
+		})?;
+		Ok(group_id)
+	});
+
+	match res {
+		Ok(group_id) => Ok((group_id, invoices)),
+		Err(e) => {
+			for payment_hash in payment_hashes {
+				if let Err(e) = remove_inbound_invoice_record(store, &payment_hash.to_string()) {
+					eprintln!("Failed to remove inbound invoice record {}: {}", payment_hash, e);
+				}
+				remove_payment(payment_hash);
+			}
+			Err(e)
+		},
+	}
+}
+
+// Splits `total_amount_msat` into `parts` amounts differing by at most a millisatoshi.
+pub(crate) fn split_amount_msat(total_amount_msat: u64, parts: u32) -> Vec<u64> {
+	let parts = parts as u64;
+	(0..parts)
+		.map(|i| total_amount_msat / parts + if i < total_amount_msat % parts { 1 } else { 0 })
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::LightningError;
+	use crate::io::inbound_invoices::INBOUND_INVOICES_PRIMARY_NAMESPACE;
+	use crate::io::invoice_groups::INVOICE_GROUPS_PRIMARY_NAMESPACE;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use crate::util::amount::tests::overflowing_invoice;
+	use std::cell::RefCell;
+
+	#[test]
+	fn test_failed_part_removes_created_parts() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let created = RefCell::new(Vec::new());
+		let removed = RefCell::new(Vec::new());
+		let err = create_invoice_group(
+			&store,
+			&[1_000, 1_000, 1_000],
+			None,
+			|_, payment_hash| {
+				if created.borrow().len() == 2 {
+					return Err(NodeError::InvoiceCreationFailed);
+				}
+				created.borrow_mut().push(payment_hash);
+				Ok(overflowing_invoice())
+			},
+			|payment_hash| removed.borrow_mut().push(payment_hash),
+		)
+		.unwrap_err();
+		assert_eq!(err.error_code, LightningError);
+
+		// Neither the parts created before the failure nor the group are left behind.
+		assert_eq!(removed.into_inner(), created.into_inner());
+		for namespace in [INBOUND_INVOICES_PRIMARY_NAMESPACE, INVOICE_GROUPS_PRIMARY_NAMESPACE] {
+			assert!(store.list(namespace, "", None).unwrap().keys.is_empty());
+		}
+	}
+
+	#[test]
+	fn test_split_amount_msat() {
+		assert_eq!(split_amount_msat(10_000, 2), [5_000, 5_000]);
+		assert_eq!(split_amount_msat(10_001, 3), [3_334, 3_334, 3_333]);
+		assert_eq!(split_amount_msat(10_001, 3).iter().sum::<u64>(), 10_001);
+	}
+}
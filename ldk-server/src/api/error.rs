@@ -1,3 +1,7 @@
+use ldk_node::NodeError;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct LdkServerError {
 	// The error message containing a generic description of the error condition in English.
 	// It is intended for a human audience only and should not be parsed to extract any information
@@ -8,8 +12,98 @@ pub(crate) struct LdkServerError {
 	// It is meant to be read and understood programmatically by code that detects/handles errors by
 	// type.
 	pub(crate) error_code: LdkServerErrorCode,
+
+	// The amounts in satoshis needed and available, if known, for `InsufficientFundsError`s.
+	pub(crate) needed_sats: Option<u64>,
+	pub(crate) available_sats: Option<u64>,
+
+	// The request fields that failed validation, for `InvalidRequestError`s.
+	pub(crate) validation_errors: Vec<ValidationError>,
+
+	// The paths of the supported endpoints, for `NotFoundError`s of requests to unknown endpoints.
+	pub(crate) supported_endpoints: Vec<String>,
+}
+
+/// A request field that failed validation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ValidationError {
+	pub(crate) field: String,
+	pub(crate) reason: String,
+}
+
+impl ValidationError {
+	pub(crate) fn new(field: impl Into<String>, reason: impl Into<String>) -> Self {
+		Self { field: field.into(), reason: reason.into() }
+	}
+}
+
+impl LdkServerError {
+	pub(crate) fn new(error_code: LdkServerErrorCode, message: impl Into<String>) -> Self {
+		Self {
+			error_code,
+			message: message.into(),
+			needed_sats: None,
+			available_sats: None,
+			validation_errors: Vec::new(),
+			supported_endpoints: Vec::new(),
+		}
+	}
+
+	/// Creates an [`LdkServerErrorCode::InvalidRequestError`] reporting the given invalid fields.
+	pub(crate) fn invalid_fields(validation_errors: Vec<ValidationError>) -> Self {
+		let reasons = validation_errors
+			.iter()
+			.map(|e| format!("{}: {}", e.field, e.reason))
+			.collect::<Vec<_>>()
+			.join("; ");
+		Self {
+			validation_errors,
+			..Self::new(
+				LdkServerErrorCode::InvalidRequestError,
+				format!("Invalid request: {}", reasons),
+			)
+		}
+	}
+
+	/// Creates an [`LdkServerErrorCode::InsufficientFundsError`] with the given amounts attached.
+	pub(crate) fn insufficient_funds(needed_sats: u64, available_sats: u64) -> Self {
+		Self {
+			error_code: LdkServerErrorCode::InsufficientFundsError,
+			message: format!(
+				"Insufficient funds: {} sats needed, {} sats available.",
+				needed_sats, available_sats
+			),
+			needed_sats: Some(needed_sats),
+			available_sats: Some(available_sats),
+			validation_errors: Vec::new(),
+			supported_endpoints: Vec::new(),
+		}
+	}
+
+	/// Creates an [`LdkServerErrorCode::InsufficientInboundCapacityError`] for receiving
+	/// `needed_sats` with only `available_sats` of inbound capacity.
+	pub(crate) fn insufficient_inbound_capacity(needed_sats: u64, available_sats: u64) -> Self {
+		Self {
+			error_code: LdkServerErrorCode::InsufficientInboundCapacityError,
+			message: format!(
+				"Insufficient inbound capacity: {} sats to receive, {} sats available.",
+				needed_sats, available_sats
+			),
+			..Self::insufficient_funds(needed_sats, available_sats)
+		}
+	}
+}
+
+impl std::error::Error for LdkServerError {}
+
+impl fmt::Display for LdkServerError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Error: [{}]: {}", self.error_code, self.message)
+	}
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum LdkServerErrorCode {
 	/// Please refer to [`protos::error::ErrorCode::InvalidRequestError`].
 	InvalidRequestError,
@@ -22,4 +116,116 @@ pub(crate) enum LdkServerErrorCode {
 
 	/// Please refer to [`protos::error::ErrorCode::InternalServerError`].
 	InternalServerError,
+
+	/// Please refer to [`protos::error::ErrorCode::NotFoundError`].
+	NotFoundError,
+
+	/// Please refer to [`protos::error::ErrorCode::InsufficientFundsError`].
+	InsufficientFundsError,
+
+	/// Please refer to [`protos::error::ErrorCode::ServiceUnavailableError`].
+	ServiceUnavailableError,
+
+	/// Please refer to [`protos::error::ErrorCode::TimeoutError`].
+	TimeoutError,
+
+	/// Please refer to [`protos::error::ErrorCode::ConflictError`].
+	ConflictError,
+
+	/// Please refer to [`protos::error::ErrorCode::InsufficientStorageError`].
+	InsufficientStorageError,
+
+	/// Please refer to [`protos::error::ErrorCode::EndpointDisabledError`].
+	EndpointDisabledError,
+
+	/// Please refer to [`protos::error::ErrorCode::InsufficientInboundCapacityError`].
+	InsufficientInboundCapacityError,
+}
+
+impl fmt::Display for LdkServerErrorCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			LdkServerErrorCode::InvalidRequestError => write!(f, "InvalidRequestError"),
+			LdkServerErrorCode::AuthError => write!(f, "AuthError"),
+			LdkServerErrorCode::LightningError => write!(f, "LightningError"),
+			LdkServerErrorCode::InternalServerError => write!(f, "InternalServerError"),
+			LdkServerErrorCode::NotFoundError => write!(f, "NotFoundError"),
+			LdkServerErrorCode::InsufficientFundsError => write!(f, "InsufficientFundsError"),
+			LdkServerErrorCode::ServiceUnavailableError => write!(f, "ServiceUnavailableError"),
+			LdkServerErrorCode::TimeoutError => write!(f, "TimeoutError"),
+			LdkServerErrorCode::ConflictError => write!(f, "ConflictError"),
+			LdkServerErrorCode::InsufficientStorageError => write!(f, "InsufficientStorageError"),
+			LdkServerErrorCode::EndpointDisabledError => write!(f, "EndpointDisabledError"),
+			LdkServerErrorCode::InsufficientInboundCapacityError => {
+				write!(f, "InsufficientInboundCapacityError")
+			},
+		}
+	}
+}
+
+impl From<NodeError> for LdkServerError {
+	fn from(error: NodeError) -> Self {
+		let (message, error_code) = match error {
+			NodeError::InvalidAddress
+			| NodeError::InvalidSocketAddress
+			| NodeError::InvalidPublicKey
+			| NodeError::InvalidSecretKey
+			| NodeError::InvalidOfferId
+			| NodeError::InvalidNodeId
+			| NodeError::InvalidPaymentId
+			| NodeError::InvalidPaymentHash
+			| NodeError::InvalidPaymentPreimage
+			| NodeError::InvalidPaymentSecret
+			| NodeError::InvalidAmount
+			| NodeError::InvalidInvoice
+			| NodeError::InvalidOffer
+			| NodeError::InvalidRefund
+			| NodeError::InvalidChannelId
+			| NodeError::InvalidNetwork
+			| NodeError::InvalidUri
+			| NodeError::InvalidQuantity
+			| NodeError::InvalidNodeAlias
+			| NodeError::DuplicatePayment
+			| NodeError::UnsupportedCurrency
+			| NodeError::UriParameterParsingFailed => {
+				(error.to_string(), LdkServerErrorCode::InvalidRequestError)
+			},
+
+			NodeError::ConnectionFailed
+			| NodeError::InvoiceCreationFailed
+			| NodeError::InvoiceRequestCreationFailed
+			| NodeError::OfferCreationFailed
+			| NodeError::RefundCreationFailed
+			| NodeError::PaymentSendingFailed
+			| NodeError::ProbeSendingFailed
+			| NodeError::ChannelCreationFailed
+			| NodeError::ChannelClosingFailed
+			| NodeError::ChannelConfigUpdateFailed
+			| NodeError::LiquiditySourceUnavailable
+			| NodeError::LiquidityFeeTooHigh
+			| NodeError::LiquidityRequestFailed => (error.to_string(), LdkServerErrorCode::LightningError),
+
+			NodeError::InsufficientFunds => {
+				(error.to_string(), LdkServerErrorCode::InsufficientFundsError)
+			},
+
+			NodeError::FeerateEstimationUpdateFailed
+			| NodeError::FeerateEstimationUpdateTimeout
+			| NodeError::WalletOperationFailed
+			| NodeError::WalletOperationTimeout
+			| NodeError::TxSyncFailed
+			| NodeError::TxSyncTimeout => (error.to_string(), LdkServerErrorCode::ServiceUnavailableError),
+
+			NodeError::AlreadyRunning
+			| NodeError::NotRunning
+			| NodeError::PersistenceFailed
+			| NodeError::GossipUpdateFailed
+			| NodeError::GossipUpdateTimeout
+			| NodeError::OnchainTxCreationFailed
+			| NodeError::OnchainTxSigningFailed => {
+				(error.to_string(), LdkServerErrorCode::InternalServerError)
+			},
+		};
+		LdkServerError::new(error_code, message)
+	}
 }
@@ -0,0 +1,207 @@
+use crate::api::error::LdkServerErrorCode::{NotFoundError, ServiceUnavailableError};
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use crate::util::bitcoind_rpc::{
+	btc_per_kvb_to_sat_per_kw, call_bitcoind_rpc, rpc_error_code, RPC_INVALID_ADDRESS_OR_KEY,
+};
+use ldk_node::bitcoin::Txid;
+use ldk_server_protos::api::{EstimateConfirmationRequest, EstimateConfirmationResponse};
+use serde_json::json;
+use std::str::FromStr;
+
+pub(crate) const ESTIMATE_CONFIRMATION_PATH: &str = "EstimateConfirmation";
+
+/// The confirmation targets, in blocks, we estimate the required feerates for, in ascending order.
+const CONFIRMATION_TARGETS: [u32; 10] = [1, 2, 3, 6, 12, 24, 48, 144, 504, 1008];
+
+/// The average time between blocks.
+const MINUTES_PER_BLOCK: u32 = 10;
+
+pub(crate) fn handle_estimate_confirmation_request(
+	context: Context, request: EstimateConfirmationRequest,
+) -> Result<EstimateConfirmationResponse, LdkServerError> {
+	let txid = Txid::from_str(&request.txid).map_err(|e| {
+		LdkServerError::invalid_fields(vec![ValidationError::new("txid", e.to_string())])
+	})?;
+	let config = context.config.read().unwrap().clone();
+	let not_found = || {
+		LdkServerError::new(
+			NotFoundError,
+			format!(
+				"Transaction {} not found. Confirmed transactions unrelated to bitcoind's wallet are only found with -txindex.",
+				txid
+			),
+		)
+	};
+	// Failures to reach bitcoind are reported as `ServiceUnavailableError`, such that they count
+	// towards the chain source circuit breaker this handler is served behind.
+	let rpc = |method: &str, params: &[serde_json::Value]| {
+		call_bitcoind_rpc(&config, method, params).map_err(|e| {
+			if rpc_error_code(&e) == Some(RPC_INVALID_ADDRESS_OR_KEY) {
+				not_found()
+			} else {
+				chain_source_error(e)
+			}
+		})
+	};
+
+	match rpc("getmempoolentry", &[json!(txid.to_string())]) {
+		Ok(mempool_entry) => {
+			let feerate = mempool_entry_feerate(&mempool_entry)?;
+			let estimated_blocks = estimate_blocks(feerate, |target| {
+				let estimate = rpc("estimatesmartfee", &[json!(target)])?;
+				Ok(estimate["feerate"].as_f64().map(btc_per_kvb_to_sat_per_kw))
+			})?;
+			Ok(unconfirmed_response(feerate, estimated_blocks))
+		},
+		// The transaction might have left the mempool by being confirmed.
+		Err(e) if e.error_code == NotFoundError => {
+			// bitcoind's wallet knows its transactions without `-txindex`. Any error it responds
+			// with, e.g., as the transaction isn't related to it or no wallet is loaded, means we
+			// have to look the transaction up in the transaction index instead.
+			let tx = match call_bitcoind_rpc(&config, "gettransaction", &[json!(txid.to_string())])
+			{
+				Ok(tx) => tx,
+				Err(e) if rpc_error_code(&e).is_some() => {
+					rpc("getrawtransaction", &[json!(txid.to_string()), json!(true)])?
+				},
+				Err(e) => return Err(chain_source_error(e)),
+			};
+			confirmed_response(&tx).ok_or_else(not_found)
+		},
+		Err(e) => Err(e),
+	}
+}
+
+fn chain_source_error(e: std::io::Error) -> LdkServerError {
+	LdkServerError::new(
+		ServiceUnavailableError,
+		format!("Failed to estimate confirmation via chain source: {}", e),
+	)
+}
+
+// Returns the feerate in satoshis per 1000 weight units at which bitcoind's `getmempoolentry`
+// result is mined, i.e., the lower of its own and its ancestor package's feerate.
+fn mempool_entry_feerate(mempool_entry: &serde_json::Value) -> Result<u64, LdkServerError> {
+	let field = |name: &str, value: Option<f64>| {
+		value.filter(|value| *value > 0.0).ok_or_else(|| {
+			LdkServerError::new(
+				ServiceUnavailableError,
+				format!("Chain source returned invalid mempool entry: missing {}", name),
+			)
+		})
+	};
+	let fee_sat =
+		(field("fees.base", mempool_entry["fees"]["base"].as_f64())? * 100_000_000.0).round();
+	let weight = field("weight", mempool_entry["weight"].as_f64())?;
+	let ancestor_fee_sat = (field("fees.ancestor", mempool_entry["fees"]["ancestor"].as_f64())?
+		* 100_000_000.0)
+		.round();
+	let ancestor_vsize = field("ancestorsize", mempool_entry["ancestorsize"].as_f64())?;
+
+	let feerate = fee_sat * 1000.0 / weight;
+	let ancestor_feerate = ancestor_fee_sat * 1000.0 / (ancestor_vsize * 4.0);
+	Ok(feerate.min(ancestor_feerate).floor() as u64)
+}
+
+// Returns the smallest of the `CONFIRMATION_TARGETS` a transaction paying `feerate_sat_per_kw` is
+// estimated to confirm within, given the feerate `estimate`d to be required for each target, if
+// any. Targets the estimator has no data for are skipped.
+fn estimate_blocks<F: FnMut(u32) -> Result<Option<u64>, LdkServerError>>(
+	feerate_sat_per_kw: u64, mut estimate: F,
+) -> Result<Option<u32>, LdkServerError> {
+	for target in CONFIRMATION_TARGETS {
+		if estimate(target)?.map_or(false, |required| feerate_sat_per_kw >= required) {
+			return Ok(Some(target));
+		}
+	}
+	Ok(None)
+}
+
+fn unconfirmed_response(
+	feerate_sat_per_kw: u64, estimated_blocks: Option<u32>,
+) -> EstimateConfirmationResponse {
+	EstimateConfirmationResponse {
+		confirmed: false,
+		confirmations: None,
+		feerate_sat_per_1000_weight: Some(feerate_sat_per_kw),
+		estimated_blocks,
+		estimated_minutes: estimated_blocks.map(|blocks| blocks * MINUTES_PER_BLOCK),
+	}
+}
+
+// Returns the response for a transaction bitcoind's `gettransaction` or `getrawtransaction` result
+// shows to be confirmed, if it is. Conflicted wallet transactions have negative confirmations.
+fn confirmed_response(tx: &serde_json::Value) -> Option<EstimateConfirmationResponse> {
+	let confirmations = tx["confirmations"].as_i64().filter(|confirmations| *confirmations > 0)?;
+	Some(EstimateConfirmationResponse {
+		confirmed: true,
+		confirmations: Some(confirmations as u32),
+		..Default::default()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_estimate_with_stubbed_estimator() {
+		// As returned by a regtest bitcoind for a transaction paying 10 sat/vB without ancestors.
+		let mempool_entry = json!({
+			"vsize": 141,
+			"weight": 561,
+			"time": 1700000000,
+			"height": 101,
+			"descendantcount": 1,
+			"descendantsize": 141,
+			"ancestorcount": 1,
+			"ancestorsize": 141,
+			"fees": {
+				"base": 0.00001410,
+				"modified": 0.00001410,
+				"ancestor": 0.00001410,
+				"descendant": 0.00001410,
+			},
+			"bip125-replaceable": true,
+		});
+		let feerate = mempool_entry_feerate(&mempool_entry).unwrap();
+		assert_eq!(feerate, 2500);
+
+		// The next blocks require more, but within 6 blocks it's estimated to confirm.
+		let required_feerates = [(1, 5000), (2, 4000), (3, 3000), (6, 2500), (12, 1000)];
+		let stub_estimator = |target: u32| {
+			Ok(required_feerates.iter().find(|(t, _)| *t == target).map(|(_, feerate)| *feerate))
+		};
+		let estimated_blocks = estimate_blocks(feerate, stub_estimator).unwrap();
+		let response = unconfirmed_response(feerate, estimated_blocks);
+		assert!(!response.confirmed);
+		assert_eq!(response.estimated_blocks, Some(6));
+		assert_eq!(response.estimated_minutes, Some(60));
+
+		// Regtest usually lacks the data to estimate anything.
+		assert_eq!(estimate_blocks(feerate, |_| Ok(None)).unwrap(), None);
+		assert_eq!(estimate_blocks(100, stub_estimator).unwrap(), None);
+
+		// Low-fee ancestors hold back the transaction.
+		let mut child = mempool_entry.clone();
+		child["ancestorsize"] = json!(282);
+		child["fees"]["ancestor"] = json!(0.00001551);
+		assert_eq!(mempool_entry_feerate(&child).unwrap(), 1375);
+
+		let err = mempool_entry_feerate(&json!({ "weight": 561 })).unwrap_err();
+		assert_eq!(err.error_code, ServiceUnavailableError);
+	}
+
+	#[test]
+	fn test_confirmed_response() {
+		let response = confirmed_response(&json!({ "confirmations": 3 })).unwrap();
+		assert!(response.confirmed);
+		assert_eq!(response.confirmations, Some(3));
+
+		// Unconfirmed, and conflicted wallet transactions aren't confirmed.
+		assert!(confirmed_response(&json!({ "confirmations": 0 })).is_none());
+		assert!(confirmed_response(&json!({ "confirmations": -2 })).is_none());
+		assert!(confirmed_response(&json!({ "txid": "00" })).is_none());
+	}
+}
@@ -0,0 +1,90 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::InternalServerError;
+use crate::service::Context;
+use crate::util::encryption::encrypt;
+use ldk_node::lightning::util::persist::{
+	KVStore, CHANNEL_MONITOR_PERSISTENCE_PRIMARY_NAMESPACE,
+	CHANNEL_MONITOR_PERSISTENCE_SECONDARY_NAMESPACE,
+};
+use ldk_server_protos::api::{ExportChannelMonitorsRequest, ExportChannelMonitorsResponse};
+use ldk_server_protos::types::{ChannelMonitorBackup, ChannelMonitorBackupEntry};
+use prost::Message;
+
+pub(crate) const EXPORT_CHANNEL_MONITORS_PATH: &str = "ExportChannelMonitors";
+
+pub(crate) fn handle_export_channel_monitors_request(
+	context: Context, request: ExportChannelMonitorsRequest,
+) -> Result<ExportChannelMonitorsResponse, LdkServerError> {
+	export_channel_monitors(context.node_kv_store.as_ref(), &request.encryption_key)
+}
+
+fn export_channel_monitors(
+	node_kv_store: &dyn KVStore, encryption_key: &[u8],
+) -> Result<ExportChannelMonitorsResponse, LdkServerError> {
+	let read_failed = |e| {
+		LdkServerError::new(InternalServerError, format!("Failed to read channel monitors: {}", e))
+	};
+
+	let keys = node_kv_store
+		.list(
+			CHANNEL_MONITOR_PERSISTENCE_PRIMARY_NAMESPACE,
+			CHANNEL_MONITOR_PERSISTENCE_SECONDARY_NAMESPACE,
+		)
+		.map_err(read_failed)?;
+	let mut monitors = Vec::with_capacity(keys.len());
+	for key in keys {
+		let monitor = node_kv_store
+			.read(
+				CHANNEL_MONITOR_PERSISTENCE_PRIMARY_NAMESPACE,
+				CHANNEL_MONITOR_PERSISTENCE_SECONDARY_NAMESPACE,
+				&key,
+			)
+			.map_err(read_failed)?;
+		monitors.push(ChannelMonitorBackupEntry { key, monitor: monitor.into() });
+	}
+
+	let monitor_count = monitors.len() as u32;
+	let backup = ChannelMonitorBackup { monitors }.encode_to_vec();
+	let encrypted_backup = encrypt(encryption_key, &backup)?;
+
+	let response =
+		ExportChannelMonitorsResponse { encrypted_backup: encrypted_backup.into(), monitor_count };
+	Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::util::encryption::decrypt;
+	use ldk_node::io::sqlite_store::SqliteStore as NodeSqliteStore;
+
+	#[test]
+	fn test_export_is_encrypted_backup_of_all_monitors() {
+		let store = NodeSqliteStore::new(random_storage_path(), None, None).unwrap();
+		let monitor = vec![0x42; 128];
+		let key = format!("{}_{}", "ab".repeat(32), 1);
+		store
+			.write(
+				CHANNEL_MONITOR_PERSISTENCE_PRIMARY_NAMESPACE,
+				CHANNEL_MONITOR_PERSISTENCE_SECONDARY_NAMESPACE,
+				&key,
+				&monitor,
+			)
+			.unwrap();
+
+		let encryption_key = [7u8; 32];
+		let response = export_channel_monitors(&store, &encryption_key).unwrap();
+		assert_eq!(response.monitor_count, 1);
+		assert!(!response.encrypted_backup.is_empty());
+		assert!(!response.encrypted_backup.windows(monitor.len()).any(|w| w == &monitor[..]));
+
+		assert!(decrypt(&[8u8; 32], &response.encrypted_backup).is_err());
+		let backup = decrypt(&encryption_key, &response.encrypted_backup).unwrap();
+		let backup = ChannelMonitorBackup::decode(&backup[..]).unwrap();
+		assert_eq!(
+			backup.monitors,
+			vec![ChannelMonitorBackupEntry { key, monitor: monitor.into() }]
+		);
+	}
+}
@@ -0,0 +1,27 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, NotFoundError};
+use crate::io::issued_addresses::read_issued_address_record;
+use crate::service::Context;
+use crate::util::address::parse_address_checked;
+use ldk_server_protos::api::{GetAddressScopeRequest, GetAddressScopeResponse};
+
+pub(crate) const GET_ADDRESS_SCOPE_PATH: &str = "GetAddressScope";
+
+pub(crate) fn handle_get_address_scope_request(
+	context: Context, request: GetAddressScopeRequest,
+) -> Result<GetAddressScopeResponse, LdkServerError> {
+	let address =
+		parse_address_checked("address", &request.address, context.node.config().network)?;
+
+	let record =
+		read_issued_address_record(context.paginated_kv_store.as_ref(), &address.to_string())
+			.map_err(|e| {
+				LdkServerError::new(InternalServerError, format!("Failed to read address: {}", e))
+			})?
+			.ok_or_else(|| {
+				LdkServerError::new(NotFoundError, "The address wasn't handed out by this node.")
+			})?;
+
+	let response = GetAddressScopeResponse { scope: record.scope };
+	Ok(response)
+}
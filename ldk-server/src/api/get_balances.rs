@@ -1,14 +1,23 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
 use crate::util::proto_adapter::{lightning_balance_to_proto, pending_sweep_balance_to_proto};
-use ldk_node::Node;
+use crate::util::wallet_sync::{data_age_ms, sync_wallets_for_read};
 use ldk_server_protos::api::{GetBalancesRequest, GetBalancesResponse};
-use std::sync::Arc;
+use std::time::SystemTime;
 
 pub(crate) const GET_BALANCES: &str = "GetBalances";
 
 pub(crate) fn handle_get_balances_request(
-	node: Arc<Node>, _request: GetBalancesRequest,
-) -> Result<GetBalancesResponse, ldk_node::NodeError> {
-	let balance_details = node.list_balances();
+	context: Context, request: GetBalancesRequest,
+) -> Result<GetBalancesResponse, LdkServerError> {
+	let data_freshness = sync_wallets_for_read(&context, request.sync_first)?;
+	let balance_details = context.node.list_balances();
+	// The balances are only as recent as the older of the two wallets.
+	let status = context.node.status();
+	let latest_sync_timestamp = status
+		.latest_onchain_wallet_sync_timestamp
+		.zip(status.latest_lightning_wallet_sync_timestamp)
+		.map(|(onchain, lightning)| onchain.min(lightning));
 
 	let response = GetBalancesResponse {
 		total_onchain_balance_sats: balance_details.total_onchain_balance_sats,
@@ -25,6 +34,8 @@ pub(crate) fn handle_get_balances_request(
 			.into_iter()
 			.map(pending_sweep_balance_to_proto)
 			.collect(),
+		data_freshness: data_freshness.into(),
+		data_age_ms: data_age_ms(latest_sync_timestamp, SystemTime::now()),
 	};
 	Ok(response)
 }
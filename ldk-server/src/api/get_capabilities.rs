@@ -0,0 +1,48 @@
+use crate::api::error::LdkServerError;
+use crate::service::{supported_endpoints, Context};
+use ldk_server_protos::api::{GetCapabilitiesRequest, GetCapabilitiesResponse};
+use std::collections::HashSet;
+
+pub(crate) const GET_CAPABILITIES_PATH: &str = "GetCapabilities";
+
+pub(crate) fn handle_get_capabilities_request(
+	context: Context, _request: GetCapabilitiesRequest,
+) -> Result<GetCapabilitiesResponse, LdkServerError> {
+	let config = context.config.read().unwrap();
+	Ok(capabilities(&config.disabled_endpoints, config.require_request_nonce))
+}
+
+fn capabilities(
+	disabled_endpoints: &HashSet<String>, require_request_nonce: bool,
+) -> GetCapabilitiesResponse {
+	GetCapabilitiesResponse {
+		endpoints: supported_endpoints(disabled_endpoints),
+		length_delimited_framing: true,
+		request_nonce_required: require_request_nonce,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::bolt11_send::BOLT11_SEND_PATH;
+	use crate::api::bolt12_send::BOLT12_SEND_PATH;
+	use crate::util::metrics::METRICS_PATH;
+
+	#[test]
+	fn test_capabilities() {
+		let all = capabilities(&HashSet::new(), false);
+		assert!(all.endpoints.iter().any(|path| path == BOLT11_SEND_PATH));
+		assert!(all.endpoints.iter().any(|path| path == GET_CAPABILITIES_PATH));
+		assert!(all.length_delimited_framing);
+		assert!(!all.request_nonce_required);
+
+		let disabled_endpoints =
+			HashSet::from([BOLT11_SEND_PATH.to_string(), METRICS_PATH.to_string()]);
+		let restricted = capabilities(&disabled_endpoints, true);
+		assert!(!restricted.endpoints.iter().any(|path| disabled_endpoints.contains(path)));
+		assert!(restricted.endpoints.iter().any(|path| path == BOLT12_SEND_PATH));
+		assert_eq!(restricted.endpoints.len(), all.endpoints.len() - 2);
+		assert!(restricted.request_nonce_required);
+	}
+}
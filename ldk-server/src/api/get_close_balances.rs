@@ -0,0 +1,83 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
+use ldk_node::bitcoin::hex::DisplayHex;
+use ldk_node::LightningBalance;
+use ldk_server_protos::api::{GetCloseBalancesRequest, GetCloseBalancesResponse};
+use ldk_server_protos::types::ChannelCloseBalance;
+
+pub(crate) const GET_CLOSE_BALANCES_PATH: &str = "GetCloseBalances";
+
+// An upper bound on the weight of a cooperative closing transaction, spending the 2-of-2 funding
+// output to two outputs of up to 34 bytes of script each (e.g., P2WSH or P2TR).
+const CLOSING_TX_WEIGHT: u64 = 772;
+
+pub(crate) fn handle_get_close_balances_request(
+	context: Context, _request: GetCloseBalancesRequest,
+) -> Result<GetCloseBalancesResponse, LdkServerError> {
+	let channels = context.node.list_channels();
+	let close_balances = context
+		.node
+		.list_balances()
+		.lightning_balances
+		.into_iter()
+		.filter_map(|balance| match balance {
+			LightningBalance::ClaimableOnChannelClose {
+				channel_id,
+				counterparty_node_id,
+				amount_satoshis,
+				transaction_fee_satoshis,
+				..
+			} => {
+				let channel = channels.iter().find(|channel| channel.channel_id == channel_id)?;
+				let (estimated_closing_fee_sats, estimated_close_output_sats) =
+					estimate_close_output(
+						amount_satoshis,
+						transaction_fee_satoshis,
+						channel.is_outbound,
+						channel.feerate_sat_per_1000_weight,
+					);
+				Some(ChannelCloseBalance {
+					channel_id: channel_id.0.to_lower_hex_string(),
+					counterparty_node_id: counterparty_node_id.to_string(),
+					balance_sats: amount_satoshis,
+					estimated_closing_fee_sats,
+					estimated_close_output_sats,
+				})
+			},
+			_ => None,
+		})
+		.collect();
+
+	let response = GetCloseBalancesResponse { close_balances };
+	Ok(response)
+}
+
+// Returns the estimated closing transaction fee we pay along with our resulting output, given our
+// `balance_sats` and the `commitment_fee_sats` LDK holds back from it. The channel funder pays the
+// closing fee, which replaces the commitment transaction fee it would otherwise pay.
+fn estimate_close_output(
+	balance_sats: u64, commitment_fee_sats: u64, is_outbound: bool,
+	feerate_sat_per_1000_weight: u32,
+) -> (u64, u64) {
+	if !is_outbound {
+		return (0, balance_sats);
+	}
+	let closing_fee_sats = (feerate_sat_per_1000_weight as u64 * CLOSING_TX_WEIGHT + 999) / 1000;
+	let close_output_sats = (balance_sats + commitment_fee_sats).saturating_sub(closing_fee_sats);
+	(closing_fee_sats, close_output_sats)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_estimate_close_output() {
+		// The funder pays the closing fee, getting back the commitment fee held back from its balance.
+		assert_eq!(estimate_close_output(100_000, 2_000, true, 2_500), (1_930, 100_070));
+		assert_eq!(estimate_close_output(1_000, 0, true, 2_500), (1_930, 0));
+
+		// The other party keeps its full balance.
+		assert_eq!(estimate_close_output(100_000, 0, false, 2_500), (0, 100_000));
+	}
+}
@@ -0,0 +1,14 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
+use crate::util::proto_adapter::channel_dust_exposure_to_proto;
+use ldk_server_protos::api::{GetDustExposureRequest, GetDustExposureResponse};
+
+pub(crate) const GET_DUST_EXPOSURE_PATH: &str = "GetDustExposure";
+
+pub(crate) fn handle_get_dust_exposure_request(
+	context: Context, _request: GetDustExposureRequest,
+) -> Result<GetDustExposureResponse, LdkServerError> {
+	let channels =
+		context.node.list_channels().iter().map(channel_dust_exposure_to_proto).collect();
+	Ok(GetDustExposureResponse { channels })
+}
@@ -0,0 +1,207 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, NotFoundError};
+use crate::io::inbound_invoices::{read_inbound_invoice_record, InboundInvoiceRecord};
+use crate::io::issued_offers::read_issued_offer_record;
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::service::Context;
+use hex::{DisplayHex, FromHex};
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_node::payment::{PaymentDirection, PaymentKind, PaymentStatus};
+use ldk_server_protos::api::{GetInboundPaymentStatusRequest, GetInboundPaymentStatusResponse};
+use ldk_server_protos::types::InboundPaymentStatus;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) const GET_INBOUND_PAYMENT_STATUS_PATH: &str = "GetInboundPaymentStatus";
+
+pub(crate) fn handle_get_inbound_payment_status_request(
+	context: Context, request: GetInboundPaymentStatusRequest,
+) -> Result<GetInboundPaymentStatusResponse, LdkServerError> {
+	let payment_hash_bytes = <[u8; 32]>::from_hex(&request.payment_hash)
+		.map_err(|_| ldk_node::NodeError::InvalidPaymentHash)?;
+
+	// Inbound payments are tracked using their payment hash as the payment id.
+	let payment_details = context
+		.node
+		.payment(&PaymentId(payment_hash_bytes))
+		.filter(|payment| payment.direction == PaymentDirection::Inbound)
+		.ok_or_else(|| {
+			LdkServerError::new(
+				NotFoundError,
+				format!("No inbound payment found for payment hash {}", request.payment_hash),
+			)
+		})?;
+
+	// Once claimed, the payment details only reflect the claimed amount, so we rely on our own
+	// record of the invoice to surface the amount it was issued for.
+	let invoice_record = read_inbound_invoice_record(
+		context.paginated_kv_store.as_ref(),
+		&payment_hash_bytes.to_lower_hex_string(),
+	)
+	.map_err(|e| {
+		LdkServerError::new(
+			InternalServerError,
+			format!("Failed to read inbound invoice record: {}", e),
+		)
+	})?;
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+	let status = inbound_payment_status(&payment_details.status, invoice_record.as_ref(), now);
+	let claimed_amount_msat = match status {
+		InboundPaymentStatus::Claimed => payment_details.amount_msat,
+		_ => None,
+	};
+	let (expected_amount_msat, invoice_scope) = match invoice_record {
+		Some(record) => (record.expected_amount_msat, record.scope),
+		None if payment_details.status == PaymentStatus::Pending => {
+			(payment_details.amount_msat, None)
+		},
+		None => (None, None),
+	};
+
+	let scope =
+		payment_scope(context.paginated_kv_store.as_ref(), &payment_details.kind, invoice_scope)
+			.map_err(|e| {
+				LdkServerError::new(
+					InternalServerError,
+					format!("Failed to read issued offer record: {}", e),
+				)
+			})?;
+
+	let payer_note = match payment_details.kind {
+		PaymentKind::Bolt12Offer { payer_note, .. } => payer_note.map(|s| s.to_string()),
+		_ => None,
+	};
+
+	let response = GetInboundPaymentStatusResponse {
+		status: status.into(),
+		claimed_amount_msat,
+		expected_amount_msat,
+		payer_note,
+		scope,
+	};
+	Ok(response)
+}
+
+// Returns the status of an inbound payment in the given `status`. Payments still pending once the
+// invoice `record` expired are reported as expired, unless a payment is being held for it.
+fn inbound_payment_status(
+	status: &PaymentStatus, record: Option<&InboundInvoiceRecord>, now: u64,
+) -> InboundPaymentStatus {
+	match status {
+		PaymentStatus::Pending => match record {
+			Some(InboundInvoiceRecord {
+				expires_at: Some(expires_at),
+				claimable_amount_msat: None,
+				..
+			}) if *expires_at <= now => InboundPaymentStatus::Expired,
+			_ => InboundPaymentStatus::Unclaimed,
+		},
+		PaymentStatus::Succeeded => InboundPaymentStatus::Claimed,
+		PaymentStatus::Failed => InboundPaymentStatus::Expired,
+	}
+}
+
+// Returns the scope a payment of the given `kind` is attributed to. BOLT12 payments are attributed
+// to the scope of the offer they paid, all others to the one of the invoice they paid.
+fn payment_scope(
+	store: &dyn PaginatedKVStore, kind: &PaymentKind, invoice_scope: Option<String>,
+) -> io::Result<Option<String>> {
+	match kind {
+		PaymentKind::Bolt12Offer { offer_id, .. } => {
+			let record = read_issued_offer_record(store, &offer_id.0.to_lower_hex_string())?;
+			Ok(record.and_then(|record| record.scope))
+		},
+		_ => Ok(invoice_scope),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::issued_offers::{write_issued_offer_record, IssuedOfferRecord};
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use ldk_node::lightning::ln::PaymentHash;
+	use ldk_node::lightning::offers::offer::OfferId;
+
+	fn offer_payment(offer_id: OfferId) -> PaymentKind {
+		PaymentKind::Bolt12Offer {
+			hash: None,
+			preimage: None,
+			secret: None,
+			offer_id,
+			payer_note: None,
+			quantity: None,
+		}
+	}
+
+	#[test]
+	fn test_unpaid_invoice_expires() {
+		let record = InboundInvoiceRecord {
+			expected_amount_msat: Some(1000),
+			preimage: None,
+			require_exact_amount: false,
+			claimable_amount_msat: None,
+			scope: None,
+			expires_at: Some(1_000),
+		};
+		let status = |status, record, now| inbound_payment_status(&status, record, now);
+		assert_eq!(
+			status(PaymentStatus::Pending, Some(&record), 999),
+			InboundPaymentStatus::Unclaimed
+		);
+		assert_eq!(
+			status(PaymentStatus::Pending, Some(&record), 1_000),
+			InboundPaymentStatus::Expired
+		);
+		assert_eq!(
+			status(PaymentStatus::Succeeded, Some(&record), 1_000),
+			InboundPaymentStatus::Claimed
+		);
+		assert_eq!(
+			status(PaymentStatus::Failed, Some(&record), 999),
+			InboundPaymentStatus::Expired
+		);
+
+		// Payments held past the expiry of their invoice can still be claimed.
+		let held_record =
+			InboundInvoiceRecord { claimable_amount_msat: Some(1000), ..record.clone() };
+		assert_eq!(
+			status(PaymentStatus::Pending, Some(&held_record), 1_000),
+			InboundPaymentStatus::Unclaimed
+		);
+
+		// Without a known expiry, unpaid invoices are never considered expired.
+		let unknown_expiry = InboundInvoiceRecord { expires_at: None, ..record.clone() };
+		assert_eq!(
+			status(PaymentStatus::Pending, Some(&unknown_expiry), u64::MAX),
+			InboundPaymentStatus::Unclaimed
+		);
+		assert_eq!(status(PaymentStatus::Pending, None, u64::MAX), InboundPaymentStatus::Unclaimed);
+	}
+
+	#[test]
+	fn test_payment_scope() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let (alice_offer, bob_offer, unscoped_offer) =
+			(OfferId([1; 32]), OfferId([2; 32]), OfferId([3; 32]));
+		for (offer_id, scope) in
+			[(alice_offer, Some("alice")), (bob_offer, Some("bob")), (unscoped_offer, None)]
+		{
+			let record = IssuedOfferRecord { scope: scope.map(String::from) };
+			write_issued_offer_record(&store, &offer_id.0.to_lower_hex_string(), &record).unwrap();
+		}
+
+		let scope = |kind: &PaymentKind| payment_scope(&store, kind, None).unwrap();
+		assert_eq!(scope(&offer_payment(alice_offer)), Some("alice".to_string()));
+		assert_eq!(scope(&offer_payment(bob_offer)), Some("bob".to_string()));
+		assert_eq!(scope(&offer_payment(unscoped_offer)), None);
+		assert_eq!(scope(&offer_payment(OfferId([4; 32]))), None);
+
+		// BOLT11 payments are attributed to the scope of the invoice they paid.
+		let bolt11 =
+			PaymentKind::Bolt11 { hash: PaymentHash([5; 32]), preimage: None, secret: None };
+		let invoice_scope = Some("alice".to_string());
+		assert_eq!(payment_scope(&store, &bolt11, invoice_scope.clone()).unwrap(), invoice_scope);
+	}
+}
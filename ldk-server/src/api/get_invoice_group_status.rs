@@ -0,0 +1,140 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, NotFoundError};
+use crate::io::invoice_groups::read_invoice_group_record;
+use crate::service::Context;
+use hex::FromHex;
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_node::payment::PaymentStatus;
+use ldk_server_protos::api::{GetInvoiceGroupStatusRequest, GetInvoiceGroupStatusResponse};
+use ldk_server_protos::types::InvoiceGroupStatus;
+
+pub(crate) const GET_INVOICE_GROUP_STATUS_PATH: &str = "GetInvoiceGroupStatus";
+
+pub(crate) fn handle_get_invoice_group_status_request(
+	context: Context, request: GetInvoiceGroupStatusRequest,
+) -> Result<GetInvoiceGroupStatusResponse, LdkServerError> {
+	let record = read_invoice_group_record(context.paginated_kv_store.as_ref(), &request.group_id)
+		.map_err(|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to read invoice group record: {}", e),
+			)
+		})?
+		.ok_or_else(|| {
+			LdkServerError::new(
+				NotFoundError,
+				format!("No invoice group found for id {}", request.group_id),
+			)
+		})?;
+
+	// Inbound payments are tracked using their payment hash as the payment id.
+	let payments: Vec<_> = record
+		.payment_hashes
+		.iter()
+		.map(|payment_hash| {
+			<[u8; 32]>::from_hex(payment_hash)
+				.ok()
+				.and_then(|payment_hash| context.node.payment(&PaymentId(payment_hash)))
+				.map(|payment| (payment.status, payment.amount_msat))
+		})
+		.collect();
+	let status = invoice_group_status(record.total_amount_msat, &payments);
+	Ok(GetInvoiceGroupStatusResponse { scope: record.scope, ..status })
+}
+
+// Returns the progress of a group towards its `total_amount_msat`, given the status and amount of
+// the payment made for each of its invoices, if any.
+fn invoice_group_status(
+	total_amount_msat: u64, payments: &[Option<(PaymentStatus, Option<u64>)>],
+) -> GetInvoiceGroupStatusResponse {
+	let mut paid_parts = 0;
+	let mut received_amount_msat = 0;
+	let mut any_expired = false;
+	for payment in payments {
+		match payment {
+			Some((PaymentStatus::Succeeded, amount_msat)) => {
+				paid_parts += 1;
+				received_amount_msat += amount_msat.unwrap_or(0);
+			},
+			Some((PaymentStatus::Failed, _)) => any_expired = true,
+			Some((PaymentStatus::Pending, _)) | None => {},
+		}
+	}
+
+	let status = if paid_parts == payments.len() {
+		InvoiceGroupStatus::Completed
+	} else if any_expired {
+		InvoiceGroupStatus::Expired
+	} else if paid_parts > 0 {
+		InvoiceGroupStatus::PartiallyPaid
+	} else {
+		InvoiceGroupStatus::Unpaid
+	};
+	GetInvoiceGroupStatusResponse {
+		status: status.into(),
+		total_amount_msat,
+		received_amount_msat,
+		parts: payments.len() as u32,
+		paid_parts: paid_parts as u32,
+		scope: None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::create_invoice_group::{create_invoice_group, split_amount_msat};
+	use crate::io::inbound_invoices::read_inbound_invoice_record;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use crate::util::amount::tests::overflowing_invoice;
+
+	#[test]
+	fn test_group_completes_once_all_parts_are_paid() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let amounts = split_amount_msat(250_000_001, 2);
+		let (group_id, invoices) = create_invoice_group(
+			&store,
+			&amounts,
+			None,
+			|_, _| Ok(overflowing_invoice()),
+			|_| panic!("no part failed"),
+		)
+		.unwrap();
+		assert_eq!(invoices.len(), 2);
+		let record = read_invoice_group_record(&store, &group_id).unwrap().unwrap();
+		assert_eq!(record.total_amount_msat, 250_000_001);
+		assert_eq!(record.payment_hashes.len(), 2);
+		// Each part is claimed for the amount it was issued for.
+		for (payment_hash, amount_msat) in record.payment_hashes.iter().zip(&amounts) {
+			let part = read_inbound_invoice_record(&store, payment_hash).unwrap().unwrap();
+			assert_eq!(part.expected_amount_msat, Some(*amount_msat));
+			assert!(part.preimage.is_some());
+		}
+
+		let status = invoice_group_status(record.total_amount_msat, &[None, None]);
+		assert_eq!(status.status, InvoiceGroupStatus::Unpaid as i32);
+		assert_eq!(status.parts, 2);
+
+		// The first part is paid, while the second is still in-flight.
+		let mut payments = vec![
+			Some((PaymentStatus::Succeeded, Some(amounts[0]))),
+			Some((PaymentStatus::Pending, Some(amounts[1]))),
+		];
+		let status = invoice_group_status(record.total_amount_msat, &payments);
+		assert_eq!(status.status, InvoiceGroupStatus::PartiallyPaid as i32);
+		assert_eq!(status.paid_parts, 1);
+		assert_eq!(status.received_amount_msat, amounts[0]);
+
+		payments[1] = Some((PaymentStatus::Succeeded, Some(amounts[1])));
+		let status = invoice_group_status(record.total_amount_msat, &payments);
+		assert_eq!(status.status, InvoiceGroupStatus::Completed as i32);
+		assert_eq!(status.paid_parts, 2);
+		assert_eq!(status.received_amount_msat, 250_000_001);
+
+		// An unpaid part expiring renders the order unfulfillable.
+		payments[1] = Some((PaymentStatus::Failed, None));
+		let status = invoice_group_status(record.total_amount_msat, &payments);
+		assert_eq!(status.status, InvoiceGroupStatus::Expired as i32);
+	}
+}
@@ -0,0 +1,89 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
+use ldk_node::ChannelDetails;
+use ldk_server_protos::api::{GetLiquiditySummaryRequest, GetLiquiditySummaryResponse};
+
+pub(crate) const GET_LIQUIDITY_SUMMARY_PATH: &str = "GetLiquiditySummary";
+
+pub(crate) fn handle_get_liquidity_summary_request(
+	context: Context, _request: GetLiquiditySummaryRequest,
+) -> Result<GetLiquiditySummaryResponse, LdkServerError> {
+	Ok(liquidity_summary(&context.node.list_channels()))
+}
+
+fn liquidity_summary(channels: &[ChannelDetails]) -> GetLiquiditySummaryResponse {
+	let mut summary = GetLiquiditySummaryResponse::default();
+	for channel in channels {
+		summary.total_inbound_capacity_msat += channel.inbound_capacity_msat;
+		summary.total_outbound_capacity_msat += channel.outbound_capacity_msat;
+		summary.max_channel_inbound_capacity_msat =
+			summary.max_channel_inbound_capacity_msat.max(channel.inbound_capacity_msat);
+		summary.max_channel_outbound_capacity_msat =
+			summary.max_channel_outbound_capacity_msat.max(channel.outbound_capacity_msat);
+		if channel.is_usable {
+			summary.usable_channel_count += 1;
+		}
+	}
+	summary
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::bitcoin::secp256k1::PublicKey;
+	use ldk_node::config::ChannelConfig;
+	use ldk_node::lightning::ln::types::ChannelId;
+	use ldk_node::UserChannelId;
+	use std::str::FromStr;
+
+	fn channel(
+		id: u8, inbound_capacity_msat: u64, outbound_capacity_msat: u64, is_usable: bool,
+	) -> ChannelDetails {
+		ChannelDetails {
+			channel_id: ChannelId([id; 32]),
+			counterparty_node_id: PublicKey::from_str(
+				"02eec7245d6b7d2ccb30380bfbe2a3648cd7a942653f5aa340edcea1f283686619",
+			)
+			.unwrap(),
+			funding_txo: None,
+			channel_value_sats: (inbound_capacity_msat + outbound_capacity_msat) / 1000,
+			unspendable_punishment_reserve: None,
+			user_channel_id: UserChannelId(id as u128),
+			feerate_sat_per_1000_weight: 253,
+			outbound_capacity_msat,
+			inbound_capacity_msat,
+			confirmations_required: None,
+			confirmations: None,
+			is_outbound: true,
+			is_channel_ready: is_usable,
+			is_usable,
+			is_announced: false,
+			cltv_expiry_delta: None,
+			counterparty_unspendable_punishment_reserve: 0,
+			counterparty_outbound_htlc_minimum_msat: None,
+			counterparty_outbound_htlc_maximum_msat: None,
+			counterparty_forwarding_info_fee_base_msat: None,
+			counterparty_forwarding_info_fee_proportional_millionths: None,
+			counterparty_forwarding_info_cltv_expiry_delta: None,
+			next_outbound_htlc_limit_msat: outbound_capacity_msat,
+			next_outbound_htlc_minimum_msat: 0,
+			force_close_spend_delay: None,
+			inbound_htlc_minimum_msat: 0,
+			inbound_htlc_maximum_msat: None,
+			config: ChannelConfig::default(),
+		}
+	}
+
+	#[test]
+	fn test_liquidity_summary() {
+		let channels = [channel(1, 300_000, 700_000, true), channel(2, 500_000, 100_000, false)];
+		let summary = liquidity_summary(&channels);
+		assert_eq!(summary.total_inbound_capacity_msat, 800_000);
+		assert_eq!(summary.total_outbound_capacity_msat, 800_000);
+		assert_eq!(summary.usable_channel_count, 1);
+		assert_eq!(summary.max_channel_inbound_capacity_msat, 500_000);
+		assert_eq!(summary.max_channel_outbound_capacity_msat, 700_000);
+
+		assert_eq!(liquidity_summary(&[]), GetLiquiditySummaryResponse::default());
+	}
+}
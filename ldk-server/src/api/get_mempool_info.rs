@@ -0,0 +1,77 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::ServiceUnavailableError;
+use crate::service::Context;
+use crate::util::bitcoind_rpc::{btc_per_kvb_to_sat_per_kw, call_bitcoind_rpc};
+use ldk_server_protos::api::{GetMempoolInfoRequest, GetMempoolInfoResponse};
+
+pub(crate) const GET_MEMPOOL_INFO_PATH: &str = "GetMempoolInfo";
+
+pub(crate) fn handle_get_mempool_info_request(
+	context: Context, _request: GetMempoolInfoRequest,
+) -> Result<GetMempoolInfoResponse, LdkServerError> {
+	let config = context.config.read().unwrap().clone();
+	let mempool_info =
+		call_bitcoind_rpc(&config, "getmempoolinfo", &[]).map_err(chain_source_error)?;
+	mempool_info_response(&mempool_info)
+}
+
+fn chain_source_error(e: std::io::Error) -> LdkServerError {
+	LdkServerError::new(
+		ServiceUnavailableError,
+		format!("Failed to retrieve mempool info from chain source: {}", e),
+	)
+}
+
+// Converts bitcoind's `getmempoolinfo` result, which gives feerates in BTC per 1000 virtual bytes.
+fn mempool_info_response(
+	mempool_info: &serde_json::Value,
+) -> Result<GetMempoolInfoResponse, LdkServerError> {
+	let feerate = |field: &str| {
+		mempool_info[field].as_f64().map(btc_per_kvb_to_sat_per_kw).ok_or_else(|| {
+			LdkServerError::new(
+				ServiceUnavailableError,
+				format!("Chain source returned invalid mempool info: missing {}", field),
+			)
+		})
+	};
+	Ok(GetMempoolInfoResponse {
+		mempool_min_feerate_sat_per_1000_weight: feerate("mempoolminfee")?,
+		min_relay_feerate_sat_per_1000_weight: feerate("minrelaytxfee")?,
+		// Only reported by Bitcoin Core v24 and later, which defaults to it from v28 on.
+		full_rbf: mempool_info["fullrbf"].as_bool().unwrap_or(false),
+		signals_rbf: true,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_mempool_info_response() {
+		// As returned by a regtest bitcoind with default settings.
+		let mempool_info = serde_json::json!({
+			"loaded": true,
+			"size": 0,
+			"mempoolminfee": 0.00001000,
+			"minrelaytxfee": 0.00001000,
+			"fullrbf": false,
+		});
+		let response = mempool_info_response(&mempool_info).unwrap();
+		assert_eq!(response.mempool_min_feerate_sat_per_1000_weight, 250);
+		assert_eq!(response.min_relay_feerate_sat_per_1000_weight, 250);
+		assert!(!response.full_rbf);
+		assert!(response.signals_rbf);
+
+		let mempool_info = serde_json::json!({
+			"mempoolminfee": 0.00001001,
+			"minrelaytxfee": 0.00000100,
+		});
+		let response = mempool_info_response(&mempool_info).unwrap();
+		assert_eq!(response.mempool_min_feerate_sat_per_1000_weight, 251);
+		assert_eq!(response.min_relay_feerate_sat_per_1000_weight, 25);
+
+		let err = mempool_info_response(&serde_json::json!({})).unwrap_err();
+		assert_eq!(err.error_code, ServiceUnavailableError);
+	}
+}
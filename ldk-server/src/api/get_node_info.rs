@@ -1,14 +1,14 @@
-use ldk_node::Node;
+use crate::api::error::LdkServerError;
+use crate::service::Context;
 use ldk_server_protos::api::{GetNodeInfoRequest, GetNodeInfoResponse};
 use ldk_server_protos::types::BestBlock;
-use std::sync::Arc;
 
 pub(crate) const GET_NODE_INFO: &str = "GetNodeInfo";
 
 pub(crate) fn handle_get_node_info_request(
-	node: Arc<Node>, _request: GetNodeInfoRequest,
-) -> Result<GetNodeInfoResponse, ldk_node::NodeError> {
-	let node_status = node.status();
+	context: Context, _request: GetNodeInfoRequest,
+) -> Result<GetNodeInfoResponse, LdkServerError> {
+	let node_status = context.node.status();
 
 	let best_block = BestBlock {
 		block_hash: node_status.current_best_block.block_hash.to_string(),
@@ -16,7 +16,7 @@ pub(crate) fn handle_get_node_info_request(
 	};
 
 	let response = GetNodeInfoResponse {
-		node_id: node.node_id().to_string(),
+		node_id: context.node.node_id().to_string(),
 		current_best_block: Some(best_block),
 		latest_lightning_wallet_sync_timestamp: node_status.latest_lightning_wallet_sync_timestamp,
 		latest_onchain_wallet_sync_timestamp: node_status.latest_onchain_wallet_sync_timestamp,
@@ -24,6 +24,7 @@ pub(crate) fn handle_get_node_info_request(
 		latest_rgs_snapshot_timestamp: node_status.latest_rgs_snapshot_timestamp,
 		latest_node_announcement_broadcast_timestamp: node_status
 			.latest_node_announcement_broadcast_timestamp,
+		node_alias: context.node.node_alias().map(|alias| alias.to_string()),
 	};
 	Ok(response)
 }
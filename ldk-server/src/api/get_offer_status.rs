@@ -0,0 +1,90 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, NotFoundError};
+use crate::io::issued_offers::read_issued_offer_record;
+use crate::service::Context;
+use hex::DisplayHex;
+use ldk_node::lightning::offers::offer::{Offer, OfferId};
+use ldk_node::payment::{PaymentDetails, PaymentDirection, PaymentKind, PaymentStatus};
+use ldk_server_protos::api::{GetOfferStatusRequest, GetOfferStatusResponse};
+use std::str::FromStr;
+
+pub(crate) const GET_OFFER_STATUS_PATH: &str = "GetOfferStatus";
+
+pub(crate) fn handle_get_offer_status_request(
+	context: Context, request: GetOfferStatusRequest,
+) -> Result<GetOfferStatusResponse, LdkServerError> {
+	let offer = Offer::from_str(&request.offer).map_err(|_| ldk_node::NodeError::InvalidOffer)?;
+	let offer_id = offer.id();
+
+	let record = read_issued_offer_record(
+		context.paginated_kv_store.as_ref(),
+		&offer_id.0.to_lower_hex_string(),
+	)
+	.map_err(|e| LdkServerError::new(InternalServerError, format!("Failed to read offer: {}", e)))?
+	.ok_or_else(|| LdkServerError::new(NotFoundError, "The offer wasn't issued by this node."))?;
+
+	let (payment_count, total_received_msat) = offer_usage(&context.node.list_payments(), offer_id);
+	let response =
+		GetOfferStatusResponse { payment_count, total_received_msat, scope: record.scope };
+	Ok(response)
+}
+
+// Returns the number of payments received for the offer with the given `offer_id`, along with the
+// total amount received.
+fn offer_usage(payments: &[PaymentDetails], offer_id: OfferId) -> (u64, u64) {
+	payments
+		.iter()
+		.filter(|payment| {
+			payment.direction == PaymentDirection::Inbound
+				&& payment.status == PaymentStatus::Succeeded
+				&& matches!(payment.kind, PaymentKind::Bolt12Offer { offer_id: id, .. } if id == offer_id)
+		})
+		.fold((0, 0), |(count, total_msat), payment| {
+			(count + 1, total_msat + payment.amount_msat.unwrap_or(0))
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::lightning::ln::channelmanager::PaymentId;
+
+	fn payment(
+		id: u8, offer_id: OfferId, amount_msat: u64, status: PaymentStatus,
+	) -> PaymentDetails {
+		PaymentDetails {
+			id: PaymentId([id; 32]),
+			kind: PaymentKind::Bolt12Offer {
+				hash: None,
+				preimage: None,
+				secret: None,
+				offer_id,
+				payer_note: None,
+				quantity: None,
+			},
+			amount_msat: Some(amount_msat),
+			direction: PaymentDirection::Inbound,
+			status,
+			latest_update_timestamp: 0,
+		}
+	}
+
+	#[test]
+	fn test_offer_usage() {
+		let offer_id = OfferId([1; 32]);
+		let other_offer_id = OfferId([2; 32]);
+		let mut payments = vec![
+			payment(1, offer_id, 10_000, PaymentStatus::Succeeded),
+			payment(2, offer_id, 25_000, PaymentStatus::Succeeded),
+			payment(3, offer_id, 5_000, PaymentStatus::Pending),
+			payment(4, other_offer_id, 7_000, PaymentStatus::Succeeded),
+		];
+		let mut outbound = payment(5, offer_id, 3_000, PaymentStatus::Succeeded);
+		outbound.direction = PaymentDirection::Outbound;
+		payments.push(outbound);
+
+		assert_eq!(offer_usage(&payments, offer_id), (2, 35_000));
+		assert_eq!(offer_usage(&payments, other_offer_id), (1, 7_000));
+		assert_eq!(offer_usage(&payments, OfferId([3; 32])), (0, 0));
+	}
+}
@@ -1,23 +1,109 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::InternalServerError;
+use crate::io::payment_diagnostics::read_payment_diagnostics_record;
+use crate::service::Context;
+use crate::util::payment_timeout::reported_payment;
 use crate::util::proto_adapter::payment_to_proto;
 use hex::FromHex;
+use ldk_node::bitcoin::hex::DisplayHex;
 use ldk_node::lightning::ln::channelmanager::PaymentId;
-use ldk_node::Node;
+use ldk_node::lightning::ln::PaymentPreimage;
+use ldk_node::payment::{PaymentDetails, PaymentDirection, PaymentKind, PaymentStatus};
 use ldk_server_protos::api::{GetPaymentDetailsRequest, GetPaymentDetailsResponse};
-use std::sync::Arc;
+use ldk_server_protos::types::PaymentDiagnostics;
 
 pub(crate) const GET_PAYMENT_DETAILS_PATH: &str = "GetPaymentDetails";
 
 pub(crate) fn handle_get_payment_details_request(
-	node: Arc<Node>, request: GetPaymentDetailsRequest,
-) -> Result<GetPaymentDetailsResponse, ldk_node::NodeError> {
+	context: Context, request: GetPaymentDetailsRequest,
+) -> Result<GetPaymentDetailsResponse, LdkServerError> {
 	let payment_id_bytes = <[u8; PaymentId::LENGTH]>::from_hex(&request.payment_id)
 		.map_err(|_| ldk_node::NodeError::InvalidPaymentId)?;
 
-	let payment_details = node.payment(&PaymentId(payment_id_bytes));
+	let payment_details = context
+		.node
+		.payment(&PaymentId(payment_id_bytes))
+		.map(|payment| reported_payment(context.paginated_kv_store.as_ref(), payment));
 
+	let diagnostics = if request.verbose && payment_details.is_some() {
+		let record = read_payment_diagnostics_record(
+			context.paginated_kv_store.as_ref(),
+			&request.payment_id.to_lowercase(),
+		)
+		.map_err(|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to read payment diagnostics: {}", e),
+			)
+		})?
+		.unwrap_or_default();
+		Some(PaymentDiagnostics {
+			fee_paid_msat: record.fee_paid_msat,
+			failure_reason: record.failure_reason,
+		})
+	} else {
+		None
+	};
+
+	let payment_preimage = payment_details
+		.as_ref()
+		.and_then(proof_of_payment)
+		.map(|preimage| preimage.0.to_lower_hex_string());
 	let response = GetPaymentDetailsResponse {
-		payment: payment_details.map(|payment| payment_to_proto(payment)),
+		payment: payment_details.map(payment_to_proto),
+		diagnostics,
+		payment_preimage,
 	};
 
 	Ok(response)
 }
+
+// Returns the preimage proving the given payment was made, if it is a succeeded outbound payment.
+fn proof_of_payment(payment: &PaymentDetails) -> Option<PaymentPreimage> {
+	if payment.direction != PaymentDirection::Outbound || payment.status != PaymentStatus::Succeeded
+	{
+		return None;
+	}
+	match payment.kind {
+		PaymentKind::Bolt11 { preimage, .. }
+		| PaymentKind::Bolt11Jit { preimage, .. }
+		| PaymentKind::Bolt12Offer { preimage, .. }
+		| PaymentKind::Bolt12Refund { preimage, .. }
+		| PaymentKind::Spontaneous { preimage, .. } => preimage,
+		PaymentKind::Onchain => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::bitcoin::hashes::sha256::Hash as Sha256;
+	use ldk_node::bitcoin::hashes::Hash;
+	use ldk_node::lightning::ln::PaymentHash;
+
+	#[test]
+	fn test_proof_of_payment() {
+		let preimage = PaymentPreimage([42; 32]);
+		let payment_hash = PaymentHash(Sha256::hash(&preimage.0).to_byte_array());
+		let mut payment = PaymentDetails {
+			id: PaymentId(payment_hash.0),
+			kind: PaymentKind::Bolt11 { hash: payment_hash, preimage: None, secret: None },
+			amount_msat: Some(1_000),
+			direction: PaymentDirection::Outbound,
+			status: PaymentStatus::Pending,
+			latest_update_timestamp: 0,
+		};
+		assert_eq!(proof_of_payment(&payment), None);
+
+		// LDK Node learns the preimage once the payment succeeded.
+		payment.kind =
+			PaymentKind::Bolt11 { hash: payment_hash, preimage: Some(preimage), secret: None };
+		payment.status = PaymentStatus::Succeeded;
+		let proof = proof_of_payment(&payment).unwrap();
+		assert_eq!(PaymentHash(Sha256::hash(&proof.0).to_byte_array()), payment_hash);
+
+		// Inbound payments know their preimage upfront, which proves nothing.
+		payment.direction = PaymentDirection::Inbound;
+		assert_eq!(proof_of_payment(&payment), None);
+	}
+}
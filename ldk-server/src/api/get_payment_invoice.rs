@@ -0,0 +1,82 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, NotFoundError};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::io::payment_invoices::read_payment_invoice_record;
+use crate::service::Context;
+use hex::FromHex;
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_server_protos::api::{GetPaymentInvoiceRequest, GetPaymentInvoiceResponse};
+
+pub(crate) const GET_PAYMENT_INVOICE_PATH: &str = "GetPaymentInvoice";
+
+pub(crate) fn handle_get_payment_invoice_request(
+	context: Context, request: GetPaymentInvoiceRequest,
+) -> Result<GetPaymentInvoiceResponse, LdkServerError> {
+	<[u8; PaymentId::LENGTH]>::from_hex(&request.payment_id)
+		.map_err(|_| ldk_node::NodeError::InvalidPaymentId)?;
+	payment_invoice(context.paginated_kv_store.as_ref(), &request.payment_id.to_lowercase())
+}
+
+// Returns the invoice or offer recorded for the payment with the given hex-encoded id.
+fn payment_invoice(
+	store: &dyn PaginatedKVStore, payment_id: &str,
+) -> Result<GetPaymentInvoiceResponse, LdkServerError> {
+	let record = read_payment_invoice_record(store, payment_id)
+		.map_err(|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to read payment invoice: {}", e),
+			)
+		})?
+		.ok_or_else(|| {
+			LdkServerError::new(
+				NotFoundError,
+				format!("No invoice or offer is known for payment {}.", payment_id),
+			)
+		})?;
+	Ok(GetPaymentInvoiceResponse {
+		bolt11_invoice: record.bolt11_invoice,
+		bolt12_offer: record.bolt12_offer,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use crate::util::payment_invoice::record_bolt11_invoice;
+	use ldk_node::bitcoin::hashes::{sha256, Hash};
+	use ldk_node::bitcoin::hex::DisplayHex;
+	use ldk_node::bitcoin::secp256k1::{Secp256k1, SecretKey};
+	use ldk_node::lightning::ln::types::PaymentSecret;
+	use ldk_node::lightning_invoice::{Currency, InvoiceBuilder};
+
+	#[test]
+	fn test_invoice_of_sent_bolt11_payment() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let secp_ctx = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&[42; 32]).unwrap();
+		let invoice = InvoiceBuilder::new(Currency::Regtest)
+			.description("coffee".to_string())
+			.payment_hash(sha256::Hash::hash(&[1; 32]))
+			.payment_secret(PaymentSecret([1; 32]))
+			.current_timestamp()
+			.min_final_cltv_expiry_delta(144)
+			.amount_milli_satoshis(100_000)
+			.build_signed(|hash| secp_ctx.sign_ecdsa_recoverable(hash, &secret_key))
+			.unwrap();
+
+		// LDK Node identifies BOLT11 payments by their payment hash.
+		let payment_id = PaymentId(*invoice.payment_hash().as_byte_array());
+		record_bolt11_invoice(&store, &payment_id, &invoice);
+
+		let response = payment_invoice(&store, &payment_id.0.to_lower_hex_string()).unwrap();
+		assert_eq!(response.bolt11_invoice, Some(invoice.to_string()));
+		assert_eq!(response.bolt12_offer, None);
+
+		let unknown_payment_id = PaymentId([2; 32]).0.to_lower_hex_string();
+		let err = payment_invoice(&store, &unknown_payment_id).unwrap_err();
+		assert_eq!(err.error_code, NotFoundError);
+	}
+}
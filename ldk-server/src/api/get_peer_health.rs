@@ -0,0 +1,33 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::NotFoundError;
+use crate::service::Context;
+use ldk_node::bitcoin::secp256k1::PublicKey;
+use ldk_server_protos::api::{GetPeerHealthRequest, GetPeerHealthResponse};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) const GET_PEER_HEALTH_PATH: &str = "GetPeerHealth";
+
+pub(crate) fn handle_get_peer_health_request(
+	context: Context, request: GetPeerHealthRequest,
+) -> Result<GetPeerHealthResponse, LdkServerError> {
+	let node_id =
+		PublicKey::from_str(&request.node_id).map_err(|_| ldk_node::NodeError::InvalidNodeId)?;
+
+	let health = context.peer_health.get(&node_id).ok_or_else(|| {
+		LdkServerError::new(NotFoundError, format!("Peer {} was never seen.", node_id))
+	})?;
+
+	let now = SystemTime::now();
+	let response = GetPeerHealthResponse {
+		is_connected: health.connected_since.is_some(),
+		connection_uptime_secs: health
+			.connected_since
+			.map(|since| now.duration_since(since).unwrap_or_default().as_secs()),
+		last_disconnected_at: health
+			.last_disconnected_at
+			.map(|at| at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()),
+		reconnect_count: health.reconnect_count,
+	};
+	Ok(response)
+}
@@ -0,0 +1,103 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{AuthError, InternalServerError};
+use crate::service::Context;
+use ldk_node::bitcoin::bip32::{self, ChildNumber, DerivationPath, Xpriv, Xpub};
+use ldk_node::bitcoin::secp256k1::Secp256k1;
+use ldk_node::bitcoin::{Network, NetworkKind};
+use ldk_server_protos::api::{GetWalletDescriptorRequest, GetWalletDescriptorResponse};
+use miniscript::descriptor::{DescriptorPublicKey, DescriptorXKey, Wildcard};
+use miniscript::Descriptor;
+use std::path::Path;
+
+pub(crate) const GET_WALLET_DESCRIPTOR_PATH: &str = "GetWalletDescriptor";
+
+/// The name of the file under the storage directory LDK Node keeps its entropy seed in.
+const KEYS_SEED_FILE_NAME: &str = "keys_seed";
+
+/// The length of LDK Node's entropy seed in bytes.
+const KEYS_SEED_LEN: usize = 64;
+
+pub(crate) fn handle_get_wallet_descriptor_request(
+	context: Context, _request: GetWalletDescriptorRequest,
+) -> Result<GetWalletDescriptorResponse, LdkServerError> {
+	let storage_dir_path = {
+		let config = context.config.read().unwrap();
+		if config.auth_token.is_none() {
+			return Err(LdkServerError::new(
+				AuthError,
+				"The wallet descriptor is only served if the server is configured with an auth_token.",
+			));
+		}
+		config.storage_dir_path.clone()
+	};
+
+	let seed = std::fs::read(Path::new(&storage_dir_path).join(KEYS_SEED_FILE_NAME))
+		.ok()
+		.filter(|seed| seed.len() == KEYS_SEED_LEN)
+		.ok_or_else(|| LdkServerError::new(InternalServerError, "Failed to read the keys seed."))?;
+	let (descriptor, change_descriptor) = wallet_descriptors(&seed, context.node.config().network)
+		.map_err(|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to derive the wallet descriptor: {}", e),
+			)
+		})?;
+	Ok(GetWalletDescriptorResponse { descriptor, change_descriptor })
+}
+
+// Returns the public counterparts of the BIP84 descriptors LDK Node derives its on-chain wallet from
+// the given `seed`, for receiving and change outputs, respectively.
+fn wallet_descriptors(seed: &[u8], network: Network) -> Result<(String, String), bip32::Error> {
+	let secp = Secp256k1::new();
+	let master_xprv = Xpriv::new_master(network, seed)?;
+	let coin_type = if NetworkKind::from(network).is_mainnet() { 0 } else { 1 };
+	let account_path = DerivationPath::from(vec![
+		ChildNumber::from_hardened_idx(84)?,
+		ChildNumber::from_hardened_idx(coin_type)?,
+		ChildNumber::from_hardened_idx(0)?,
+	]);
+	let account_xpub = Xpub::from_priv(&secp, &master_xprv.derive_priv(&secp, &account_path)?);
+
+	let descriptor = |keychain: u32| -> Result<String, bip32::Error> {
+		let key = DescriptorPublicKey::XPub(DescriptorXKey {
+			origin: Some((master_xprv.fingerprint(&secp), account_path.clone())),
+			xkey: account_xpub,
+			derivation_path: DerivationPath::from(vec![ChildNumber::from_normal_idx(keychain)?]),
+			wildcard: Wildcard::Unhardened,
+		});
+		// unwrap safety: compressed keys are always valid in wpkh descriptors.
+		Ok(Descriptor::new_wpkh(key).unwrap().to_string())
+	};
+	Ok((descriptor(0)?, descriptor(1)?))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_wallet_descriptors_are_public() {
+		let secp = Secp256k1::new();
+		let (descriptor, change_descriptor) =
+			wallet_descriptors(&[42; KEYS_SEED_LEN], Network::Regtest).unwrap();
+		assert_ne!(descriptor, change_descriptor);
+
+		for (descriptor, keychain) in [(descriptor, "/0/*"), (change_descriptor, "/1/*")] {
+			assert!(descriptor.starts_with("wpkh(["));
+			assert!(descriptor.contains("/84'/1'/0']tpub"));
+			assert!(descriptor.contains(keychain));
+			assert!(!descriptor.contains("prv"));
+			assert!(descriptor.contains('#'));
+
+			// Parsing yields no secret keys, and the checksum matches.
+			let (parsed, secret_keys) =
+				Descriptor::<DescriptorPublicKey>::parse_descriptor(&secp, &descriptor).unwrap();
+			assert!(secret_keys.is_empty());
+			assert_eq!(parsed.to_string(), descriptor);
+		}
+
+		let (mainnet_descriptor, _) =
+			wallet_descriptors(&[42; KEYS_SEED_LEN], Network::Bitcoin).unwrap();
+		assert!(mainnet_descriptor.contains("/84'/0'/0']xpub"));
+	}
+}
@@ -0,0 +1,159 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, InvalidRequestError};
+use crate::io::imported_monitors::write_imported_monitor_record;
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::service::Context;
+use crate::util::encryption::decrypt;
+use ldk_node::bitcoin::BlockHash;
+use ldk_node::lightning::chain::channelmonitor::ChannelMonitor;
+use ldk_node::lightning::sign::{InMemorySigner, KeysManager};
+use ldk_node::lightning::util::persist::{
+	KVStore, CHANNEL_MONITOR_PERSISTENCE_PRIMARY_NAMESPACE,
+	CHANNEL_MONITOR_PERSISTENCE_SECONDARY_NAMESPACE,
+};
+use ldk_node::lightning::util::ser::ReadableArgs;
+use ldk_server_protos::api::{ImportChannelMonitorsRequest, ImportChannelMonitorsResponse};
+use ldk_server_protos::types::{ChannelMonitorBackup, ChannelMonitorBackupEntry};
+use prost::Message;
+use rand::RngCore;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) const IMPORT_CHANNEL_MONITORS_PATH: &str = "ImportChannelMonitors";
+
+pub(crate) fn handle_import_channel_monitors_request(
+	context: Context, request: ImportChannelMonitorsRequest,
+) -> Result<ImportChannelMonitorsResponse, LdkServerError> {
+	// A running node would never see the imported monitors, while their channels may conflict with
+	// the ones it operates on.
+	if context.node.status().is_running {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			"Channel monitors can only be imported while the server runs in recovery mode.",
+		));
+	}
+
+	let backup = decrypt(&request.encryption_key, &request.encrypted_backup)?;
+	let backup = ChannelMonitorBackup::decode(&backup[..]).map_err(|_| {
+		LdkServerError::new(InvalidRequestError, "Backup does not contain channel monitors.")
+	})?;
+
+	import_channel_monitors(
+		context.node_kv_store.as_ref(),
+		context.paginated_kv_store.as_ref(),
+		backup,
+	)
+}
+
+fn import_channel_monitors(
+	node_kv_store: &dyn KVStore, paginated_kv_store: &dyn PaginatedKVStore,
+	backup: ChannelMonitorBackup,
+) -> Result<ImportChannelMonitorsResponse, LdkServerError> {
+	// Validate the whole backup upfront, as a monitor failing to load keeps the node from starting.
+	for entry in &backup.monitors {
+		validate_monitor(entry)?;
+	}
+
+	let store_failed = |e| {
+		LdkServerError::new(
+			InternalServerError,
+			format!("Failed to import channel monitors: {}", e),
+		)
+	};
+
+	let existing_keys = node_kv_store
+		.list(
+			CHANNEL_MONITOR_PERSISTENCE_PRIMARY_NAMESPACE,
+			CHANNEL_MONITOR_PERSISTENCE_SECONDARY_NAMESPACE,
+		)
+		.map_err(store_failed)?;
+
+	let mut imported_count = 0;
+	let mut skipped_count = 0;
+	for entry in backup.monitors {
+		// Never overwrite a monitor the node knows about, as it may be more recent than the backup.
+		if existing_keys.contains(&entry.key) {
+			skipped_count += 1;
+			continue;
+		}
+		node_kv_store
+			.write(
+				CHANNEL_MONITOR_PERSISTENCE_PRIMARY_NAMESPACE,
+				CHANNEL_MONITOR_PERSISTENCE_SECONDARY_NAMESPACE,
+				&entry.key,
+				&entry.monitor,
+			)
+			.map_err(store_failed)?;
+		// Remember the import, such that `RecoverChannels` knows which channels to recover.
+		write_imported_monitor_record(paginated_kv_store, &entry.key)
+			.map_err(|e| store_failed(e.into()))?;
+		imported_count += 1;
+	}
+
+	let response = ImportChannelMonitorsResponse { imported_count, skipped_count };
+	Ok(response)
+}
+
+// Checks that the entry deserializes the way LDK loads monitors on startup, and that it is stored
+// under the key derived from its funding outpoint.
+fn validate_monitor(entry: &ChannelMonitorBackupEntry) -> Result<(), LdkServerError> {
+	// Deserializing only derives the channel signer, which is never used here, so any keys do.
+	let mut seed = [0u8; 32];
+	rand::thread_rng().fill_bytes(&mut seed);
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+	let keys_manager = KeysManager::new(&seed, now.as_secs(), now.subsec_nanos());
+
+	let (_, monitor) = <(BlockHash, ChannelMonitor<InMemorySigner>)>::read(
+		&mut &entry.monitor[..],
+		(&keys_manager, &keys_manager),
+	)
+	.map_err(|e| {
+		LdkServerError::new(
+			InvalidRequestError,
+			format!("Invalid channel monitor with key {}: {:?}", entry.key, e),
+		)
+	})?;
+
+	let (funding_txo, _) = monitor.get_funding_txo();
+	let expected_key = format!("{}_{}", funding_txo.txid, funding_txo.index);
+	if entry.key != expected_key {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			format!(
+				"Channel monitor with key {} doesn't match its funding outpoint {}.",
+				entry.key, expected_key
+			),
+		));
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use ldk_node::io::sqlite_store::SqliteStore as NodeSqliteStore;
+
+	#[test]
+	fn test_invalid_monitor_rejects_whole_backup() {
+		let store = NodeSqliteStore::new(random_storage_path(), None, None).unwrap();
+		let entry = |key: &str| ChannelMonitorBackupEntry {
+			key: key.to_string(),
+			monitor: vec![0xff; 64].into(),
+		};
+		let backup = ChannelMonitorBackup { monitors: vec![entry("a_0"), entry("b_1")] };
+
+		let paginated_store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let err = import_channel_monitors(&store, &paginated_store, backup).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert!(err.message.contains("a_0"));
+
+		let keys = store
+			.list(
+				CHANNEL_MONITOR_PERSISTENCE_PRIMARY_NAMESPACE,
+				CHANNEL_MONITOR_PERSISTENCE_SECONDARY_NAMESPACE,
+			)
+			.unwrap();
+		assert!(keys.is_empty());
+	}
+}
@@ -1,15 +1,78 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
 use crate::util::proto_adapter::channel_to_proto;
-use ldk_node::Node;
+use crate::util::response_body::{RepeatedFieldBody, ResponseBody};
+use crate::util::wallet_sync::{data_age_ms, sync_wallets_for_read};
+use http_body_util::BodyExt;
+use ldk_node::lightning::routing::gossip::{NodeAnnouncementInfo, NodeId};
 use ldk_server_protos::api::{ListChannelsRequest, ListChannelsResponse};
-use std::sync::Arc;
+use std::time::SystemTime;
 
 pub(crate) const LIST_CHANNELS_PATH: &str = "ListChannels";
 
+/// The field number of `ListChannelsResponse.channels`.
+const CHANNELS_FIELD_NUMBER: u32 = 1;
+
 pub(crate) fn handle_list_channels_request(
-	node: Arc<Node>, _request: ListChannelsRequest,
-) -> Result<ListChannelsResponse, ldk_node::NodeError> {
-	let channels = node.list_channels().into_iter().map(|c| channel_to_proto(c)).collect();
+	context: Context, request: ListChannelsRequest,
+) -> Result<ListChannelsResponse, LdkServerError> {
+	let data_freshness = sync_wallets_for_read(&context, request.sync_first)?;
+	let latest_sync_timestamp = context.node.status().latest_lightning_wallet_sync_timestamp;
+	let network_graph = context.node.network_graph();
+	let channels = context
+		.node
+		.list_channels()
+		.into_iter()
+		.map(|channel| {
+			let node_info = network_graph.node(&NodeId::from_pubkey(&channel.counterparty_node_id));
+			let counterparty_alias = announced_alias(
+				node_info.as_ref().and_then(|info| info.announcement_info.as_ref()),
+			);
+			channel_to_proto(channel, counterparty_alias)
+		})
+		.collect();
 
-	let response = ListChannelsResponse { channels };
+	let response = ListChannelsResponse {
+		channels,
+		data_freshness: data_freshness.into(),
+		data_age_ms: data_age_ms(latest_sync_timestamp, SystemTime::now()),
+	};
 	Ok(response)
 }
+
+/// Returns a body streaming `response` one channel at a time, such that nodes with many channels
+/// never encode the response as a whole.
+pub(crate) fn list_channels_body(
+	mut response: ListChannelsResponse, length_delimited: bool,
+) -> ResponseBody {
+	let channels = std::mem::take(&mut response.channels);
+	RepeatedFieldBody::new(&response, CHANNELS_FIELD_NUMBER, channels, length_delimited).boxed()
+}
+
+// Returns the alias from the node's latest announcement, or an empty string if it didn't announce
+// itself yet.
+fn announced_alias(announcement_info: Option<&NodeAnnouncementInfo>) -> String {
+	announcement_info.map(|info| info.alias().to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::lightning::ln::features::NodeFeatures;
+	use ldk_node::lightning::routing::gossip::{NodeAlias, NodeAnnouncementDetails};
+
+	#[test]
+	fn test_announced_alias() {
+		let mut alias = [0u8; 32];
+		alias[..10].copy_from_slice(b"ldk-server");
+		let announcement_info = NodeAnnouncementInfo::Local(NodeAnnouncementDetails {
+			features: NodeFeatures::empty(),
+			last_update: 0,
+			rgb: [0; 3],
+			alias: NodeAlias(alias),
+			addresses: vec![],
+		});
+		assert_eq!(announced_alias(Some(&announcement_info)), "ldk-server");
+		assert_eq!(announced_alias(None), "");
+	}
+}
@@ -1,15 +1,321 @@
+use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use crate::util::payment_timeout::reported_payment;
 use crate::util::proto_adapter::payment_to_proto;
-use ldk_node::Node;
+use ldk_node::payment::{PaymentDetails, PaymentDirection, PaymentStatus};
 use ldk_server_protos::api::{ListPaymentsRequest, ListPaymentsResponse};
-use std::sync::Arc;
+use prost::Message;
 
 pub(crate) const LIST_PAYMENTS_PATH: &str = "ListPayments";
 
+/// The maximum number of payments returned in a single page.
+const LIST_PAYMENTS_MAX_PAGE_SIZE: usize = 100;
+
 pub(crate) fn handle_list_payments_request(
-	node: Arc<Node>, _request: ListPaymentsRequest,
-) -> Result<ListPaymentsResponse, ldk_node::NodeError> {
-	let payments = node.list_payments().into_iter().map(|p| payment_to_proto(p)).collect();
+	context: Context, request: ListPaymentsRequest,
+) -> Result<ListPaymentsResponse, LdkServerError> {
+	let filter = PaymentFilter::try_from(&request)?;
+	let query = filter_query(&request);
+	let page_size = requested_page_size(&request);
+	let after = match request.page_token {
+		Some(page_token) => {
+			let position =
+				context.page_token_codec.decode(&page_token, LIST_PAYMENTS_PATH, &query)?;
+			let after = <[u8; 32]>::try_from(&position[..])
+				.map_err(|_| LdkServerError::new(InvalidRequestError, "Invalid page token."))?;
+			Some(after)
+		},
+		None => None,
+	};
+
+	let payments = context.node.list_payments().into_iter();
+	let payments = payments
+		.map(|payment| reported_payment(context.paginated_kv_store.as_ref(), payment))
+		.collect();
+	let (payments, next_after) = list_payments_page(payments, &filter, after, page_size);
+	let next_page_token =
+		next_after.map(|after| context.page_token_codec.encode(LIST_PAYMENTS_PATH, &query, &after));
+
+	let payments = payments.into_iter().map(payment_to_proto).collect();
 
-	let response = ListPaymentsResponse { payments };
+	let response = ListPaymentsResponse { payments, next_page_token };
 	Ok(response)
 }
+
+// Returns the number of payments to return for `request`, or `None` if all payments are to be
+// returned at once. Payments are only paged if `count` or `page_token` is set, as clients predating
+// pagination expect all payments in a single response.
+fn requested_page_size(request: &ListPaymentsRequest) -> Option<usize> {
+	if request.count.is_none() && request.page_token.is_none() {
+		return None;
+	}
+	let page_size = request
+		.count
+		.filter(|count| *count > 0)
+		.map_or(LIST_PAYMENTS_MAX_PAGE_SIZE, |count| count as usize)
+		.min(LIST_PAYMENTS_MAX_PAGE_SIZE);
+	Some(page_size)
+}
+
+// Returns the filters of `request` serialized, to which page tokens are bound, as positions are
+// only meaningful for the filters they were issued for.
+fn filter_query(request: &ListPaymentsRequest) -> Vec<u8> {
+	ListPaymentsRequest { page_token: None, count: None, ..request.clone() }.encode_to_vec()
+}
+
+// The filters of a `ListPaymentsRequest`.
+#[derive(Debug, Default)]
+struct PaymentFilter {
+	status: Option<PaymentStatus>,
+	direction: Option<PaymentDirection>,
+	start_ms: Option<u64>,
+	end_ms: Option<u64>,
+}
+
+impl PaymentFilter {
+	fn matches(&self, payment: &PaymentDetails) -> bool {
+		let updated_at_ms = payment.latest_update_timestamp.saturating_mul(1000);
+		self.status.map_or(true, |status| payment.status == status)
+			&& self.direction.map_or(true, |direction| payment.direction == direction)
+			&& self.start_ms.map_or(true, |start_ms| updated_at_ms >= start_ms)
+			&& self.end_ms.map_or(true, |end_ms| updated_at_ms < end_ms)
+	}
+}
+
+impl TryFrom<&ListPaymentsRequest> for PaymentFilter {
+	type Error = LdkServerError;
+
+	fn try_from(request: &ListPaymentsRequest) -> Result<Self, LdkServerError> {
+		let mut validation_errors = Vec::new();
+
+		let status = match request.status.map(ldk_server_protos::types::PaymentStatus::from_i32) {
+			None => None,
+			Some(Some(ldk_server_protos::types::PaymentStatus::Pending)) => {
+				Some(PaymentStatus::Pending)
+			},
+			Some(Some(ldk_server_protos::types::PaymentStatus::Succeeded)) => {
+				Some(PaymentStatus::Succeeded)
+			},
+			Some(Some(ldk_server_protos::types::PaymentStatus::Failed)) => {
+				Some(PaymentStatus::Failed)
+			},
+			Some(None) => {
+				validation_errors.push(ValidationError::new("status", "unknown payment status"));
+				None
+			},
+		};
+		let direction =
+			match request.direction.map(ldk_server_protos::types::PaymentDirection::from_i32) {
+				None => None,
+				Some(Some(ldk_server_protos::types::PaymentDirection::Inbound)) => {
+					Some(PaymentDirection::Inbound)
+				},
+				Some(Some(ldk_server_protos::types::PaymentDirection::Outbound)) => {
+					Some(PaymentDirection::Outbound)
+				},
+				Some(None) => {
+					validation_errors
+						.push(ValidationError::new("direction", "unknown payment direction"));
+					None
+				},
+			};
+		if let (Some(start_ms), Some(end_ms)) = (request.start_ms, request.end_ms) {
+			if start_ms > end_ms {
+				validation_errors.push(ValidationError::new("end_ms", "must not precede start_ms"));
+			}
+		}
+
+		if !validation_errors.is_empty() {
+			return Err(LdkServerError::invalid_fields(validation_errors));
+		}
+		Ok(PaymentFilter { status, direction, start_ms: request.start_ms, end_ms: request.end_ms })
+	}
+}
+
+// Returns the page of `payments` matching `filter` that follows the payment with id `after`, along
+// with the id the next page follows, if any. All matching payments are returned if `page_size` is
+// `None`. Pages are ordered by payment id and continue after the
+// last payment returned rather than at an offset, so that payments created while paging don't
+// shift later pages.
+fn list_payments_page(
+	mut payments: Vec<PaymentDetails>, filter: &PaymentFilter, after: Option<[u8; 32]>,
+	page_size: Option<usize>,
+) -> (Vec<PaymentDetails>, Option<[u8; 32]>) {
+	payments.retain(|payment| {
+		filter.matches(payment) && after.map_or(true, |after| payment.id.0 > after)
+	});
+	payments.sort_unstable_by_key(|payment| payment.id.0);
+
+	let next_after = match page_size {
+		Some(page_size) if payments.len() > page_size => {
+			let next_after = payments[page_size - 1].id.0;
+			payments.truncate(page_size);
+			Some(next_after)
+		},
+		_ => None,
+	};
+	(payments, next_after)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode;
+	use crate::util::page_token::PageTokenCodec;
+	use ldk_node::lightning::ln::channelmanager::PaymentId;
+	use ldk_node::lightning::ln::PaymentHash;
+	use ldk_node::payment::PaymentKind;
+
+	fn payment(
+		id: u8, direction: PaymentDirection, status: PaymentStatus, latest_update_timestamp: u64,
+	) -> PaymentDetails {
+		PaymentDetails {
+			id: PaymentId([id; 32]),
+			kind: PaymentKind::Spontaneous { hash: PaymentHash([id; 32]), preimage: None },
+			amount_msat: Some(1_000),
+			direction,
+			status,
+			latest_update_timestamp,
+		}
+	}
+
+	fn test_payments() -> Vec<PaymentDetails> {
+		vec![
+			payment(4, PaymentDirection::Outbound, PaymentStatus::Failed, 40),
+			payment(1, PaymentDirection::Inbound, PaymentStatus::Succeeded, 10),
+			payment(3, PaymentDirection::Inbound, PaymentStatus::Pending, 30),
+			payment(2, PaymentDirection::Outbound, PaymentStatus::Succeeded, 20),
+			payment(5, PaymentDirection::Inbound, PaymentStatus::Succeeded, 50),
+		]
+	}
+
+	fn filtered_ids(filter: &PaymentFilter) -> Vec<u8> {
+		let (payments, next_after) = list_payments_page(test_payments(), filter, None, Some(100));
+		assert_eq!(next_after, None);
+		payments.iter().map(|payment| payment.id.0[0]).collect()
+	}
+
+	#[test]
+	fn test_filter_by_status() {
+		let filter = PaymentFilter { status: Some(PaymentStatus::Succeeded), ..Default::default() };
+		assert_eq!(filtered_ids(&filter), vec![1, 2, 5]);
+	}
+
+	#[test]
+	fn test_filter_by_direction() {
+		let filter =
+			PaymentFilter { direction: Some(PaymentDirection::Outbound), ..Default::default() };
+		assert_eq!(filtered_ids(&filter), vec![2, 4]);
+	}
+
+	#[test]
+	fn test_filter_by_time_range() {
+		let filter =
+			PaymentFilter { start_ms: Some(20_000), end_ms: Some(40_000), ..Default::default() };
+		assert_eq!(filtered_ids(&filter), vec![2, 3]);
+
+		let filter = PaymentFilter { start_ms: Some(30_001), ..Default::default() };
+		assert_eq!(filtered_ids(&filter), vec![4, 5]);
+	}
+
+	#[test]
+	fn test_combined_filter_with_pagination() {
+		let filter = PaymentFilter {
+			status: Some(PaymentStatus::Succeeded),
+			direction: Some(PaymentDirection::Inbound),
+			start_ms: Some(5_000),
+			end_ms: None,
+		};
+		assert_eq!(filtered_ids(&filter), vec![1, 5]);
+
+		// Pages only contain matching payments.
+		let (page, next_after) = list_payments_page(test_payments(), &filter, None, Some(1));
+		assert_eq!(page.iter().map(|payment| payment.id.0[0]).collect::<Vec<_>>(), vec![1]);
+		assert_eq!(next_after, Some([1; 32]));
+		let (page, next_after) = list_payments_page(test_payments(), &filter, next_after, Some(1));
+		assert_eq!(page.iter().map(|payment| payment.id.0[0]).collect::<Vec<_>>(), vec![5]);
+		assert_eq!(next_after, None);
+	}
+
+	#[test]
+	fn test_new_payments_dont_shift_pages() {
+		let filter = PaymentFilter::default();
+		let (page, next_after) = list_payments_page(test_payments(), &filter, None, Some(2));
+		assert_eq!(page.iter().map(|payment| payment.id.0[0]).collect::<Vec<_>>(), vec![1, 2]);
+
+		// A payment sorting before the current position is created before the next page is fetched.
+		let mut payments = test_payments();
+		payments.push(payment(0, PaymentDirection::Inbound, PaymentStatus::Pending, 60));
+		let (page, next_after) = list_payments_page(payments.clone(), &filter, next_after, Some(2));
+		assert_eq!(page.iter().map(|payment| payment.id.0[0]).collect::<Vec<_>>(), vec![3, 4]);
+		let (page, next_after) = list_payments_page(payments, &filter, next_after, Some(2));
+		assert_eq!(page.iter().map(|payment| payment.id.0[0]).collect::<Vec<_>>(), vec![5]);
+		assert_eq!(next_after, None);
+	}
+
+	#[test]
+	fn test_payments_are_only_paged_on_request() {
+		let request = ListPaymentsRequest::default();
+		assert_eq!(requested_page_size(&request), None);
+		let payments: Vec<_> = (0..=LIST_PAYMENTS_MAX_PAGE_SIZE as u8)
+			.map(|id| payment(id, PaymentDirection::Inbound, PaymentStatus::Succeeded, 10))
+			.collect();
+		let (page, next_after) =
+			list_payments_page(payments, &PaymentFilter::default(), None, None);
+		assert_eq!(page.len(), LIST_PAYMENTS_MAX_PAGE_SIZE + 1);
+		assert_eq!(next_after, None);
+
+		let request = ListPaymentsRequest { count: Some(10), ..Default::default() };
+		assert_eq!(requested_page_size(&request), Some(10));
+		let request = ListPaymentsRequest { count: Some(1_000), ..Default::default() };
+		assert_eq!(requested_page_size(&request), Some(LIST_PAYMENTS_MAX_PAGE_SIZE));
+		let request =
+			ListPaymentsRequest { page_token: Some("token".to_string()), ..Default::default() };
+		assert_eq!(requested_page_size(&request), Some(LIST_PAYMENTS_MAX_PAGE_SIZE));
+	}
+
+	#[test]
+	fn test_page_token_is_bound_to_filters() {
+		let codec = PageTokenCodec::new([42; 32]);
+		let request = ListPaymentsRequest {
+			page_token: None,
+			count: Some(1),
+			status: Some(ldk_server_protos::types::PaymentStatus::Succeeded as i32),
+			direction: None,
+			start_ms: None,
+			end_ms: None,
+		};
+		let token = codec.encode(LIST_PAYMENTS_PATH, &filter_query(&request), &[1; 32]);
+
+		// The page size may change between pages, the filters may not.
+		let next_request =
+			ListPaymentsRequest { page_token: Some(token.clone()), count: Some(10), ..request };
+		let query = filter_query(&next_request);
+		assert_eq!(codec.decode(&token, LIST_PAYMENTS_PATH, &query).unwrap(), vec![1; 32]);
+
+		let other_filters = ListPaymentsRequest { status: None, ..next_request.clone() };
+		let query = filter_query(&other_filters);
+		let err = codec.decode(&token, LIST_PAYMENTS_PATH, &query).unwrap_err();
+		assert_eq!(err.error_code, LdkServerErrorCode::InvalidRequestError);
+		let other_filters = ListPaymentsRequest { start_ms: Some(0), ..next_request };
+		assert!(codec.decode(&token, LIST_PAYMENTS_PATH, &filter_query(&other_filters)).is_err());
+	}
+
+	#[test]
+	fn test_invalid_filters_are_rejected() {
+		let request = ListPaymentsRequest {
+			page_token: None,
+			count: None,
+			status: Some(42),
+			direction: None,
+			start_ms: Some(2_000),
+			end_ms: Some(1_000),
+		};
+		let err = PaymentFilter::try_from(&request).unwrap_err();
+		assert_eq!(err.error_code, LdkServerErrorCode::InvalidRequestError);
+		let fields: Vec<&str> =
+			err.validation_errors.iter().map(|error| error.field.as_str()).collect();
+		assert_eq!(fields, vec!["status", "end_ms"]);
+	}
+}
@@ -0,0 +1,95 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
+use crate::util::proto_adapter::peer_to_proto;
+use ldk_node::PeerDetails;
+use ldk_server_protos::api::{ListPeersRequest, ListPeersResponse};
+
+pub(crate) const LIST_PEERS_PATH: &str = "ListPeers";
+
+/// The maximum number of peers returned in a single page.
+const LIST_PEERS_MAX_PAGE_SIZE: usize = 100;
+
+pub(crate) fn handle_list_peers_request(
+	context: Context, request: ListPeersRequest,
+) -> Result<ListPeersResponse, LdkServerError> {
+	let offset = match request.page_token {
+		Some(page_token) => {
+			context.page_token_codec.decode_offset(&page_token, LIST_PEERS_PATH)? as usize
+		},
+		None => 0,
+	};
+	let page_size = request
+		.count
+		.filter(|count| *count > 0)
+		.map_or(LIST_PEERS_MAX_PAGE_SIZE, |count| count as usize)
+		.min(LIST_PEERS_MAX_PAGE_SIZE);
+
+	let (peers, next_offset) = list_peers_page(context.node.list_peers(), offset, page_size);
+	let next_page_token = next_offset.map(|next_offset| {
+		context.page_token_codec.encode_offset(LIST_PEERS_PATH, next_offset as u64)
+	});
+
+	let peers = peers.into_iter().map(peer_to_proto).collect();
+
+	let response = ListPeersResponse { peers, next_page_token };
+	Ok(response)
+}
+
+// Returns the page of `peers` starting at `offset`, along with the offset of the next page, if any.
+fn list_peers_page(
+	mut peers: Vec<PeerDetails>, offset: usize, page_size: usize,
+) -> (Vec<PeerDetails>, Option<usize>) {
+	// Sort by node id so that offsets remain stable across requests.
+	peers.sort_unstable_by_key(|peer| peer.node_id);
+
+	let next_offset = offset.saturating_add(page_size);
+	let next_offset = if next_offset < peers.len() { Some(next_offset) } else { None };
+
+	let peers = peers.into_iter().skip(offset).take(page_size).collect();
+	(peers, next_offset)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+	use ldk_node::lightning::ln::msgs::SocketAddress;
+	use std::str::FromStr;
+
+	fn peer(secret_key_byte: u8) -> PeerDetails {
+		let secret_key = SecretKey::from_slice(&[secret_key_byte; 32]).unwrap();
+		PeerDetails {
+			node_id: PublicKey::from_secret_key(&Secp256k1::new(), &secret_key),
+			address: SocketAddress::from_str("127.0.0.1:9735").unwrap(),
+			is_persisted: false,
+			is_connected: true,
+		}
+	}
+
+	#[test]
+	fn test_list_peers_page() {
+		let peers: Vec<PeerDetails> = (1..=5).map(peer).collect();
+		let mut expected: Vec<PublicKey> = peers.iter().map(|peer| peer.node_id).collect();
+		expected.sort_unstable();
+
+		let mut listed = Vec::new();
+		let mut offset = 0;
+		let mut pages = 0;
+		loop {
+			let (page, next_offset) = list_peers_page(peers.clone(), offset, 2);
+			assert!(page.len() <= 2);
+			listed.extend(page.into_iter().map(|peer| peer.node_id));
+			pages += 1;
+			match next_offset {
+				Some(next_offset) => offset = next_offset,
+				None => break,
+			}
+		}
+		assert_eq!(pages, 3);
+		assert_eq!(listed, expected);
+
+		let (page, next_offset) = list_peers_page(peers, 10, 2);
+		assert!(page.is_empty());
+		assert_eq!(next_offset, None);
+	}
+}
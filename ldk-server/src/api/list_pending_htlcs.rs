@@ -0,0 +1,136 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
+use hex::DisplayHex;
+use ldk_node::lightning::ln::types::ChannelId;
+use ldk_node::LightningBalance;
+use ldk_server_protos::api::{ListPendingHtlcsRequest, ListPendingHtlcsResponse};
+use ldk_server_protos::types::{PaymentDirection, PendingHtlc};
+use std::collections::HashSet;
+
+pub(crate) const LIST_PENDING_HTLCS_PATH: &str = "ListPendingHtlcs";
+
+pub(crate) fn handle_list_pending_htlcs_request(
+	context: Context, _request: ListPendingHtlcsRequest,
+) -> Result<ListPendingHtlcsResponse, LdkServerError> {
+	let open_channel_ids =
+		context.node.list_channels().into_iter().map(|channel| channel.channel_id).collect();
+	let htlcs = pending_htlcs(&context.node.list_balances().lightning_balances, &open_channel_ids);
+	Ok(ListPendingHtlcsResponse { htlcs })
+}
+
+// Returns the HTLCs in-flight in the channels with the given `open_channel_ids`, ordered by their
+// expiry. LDK reports these as balances which may be claimable on chain if the channel was closed,
+// while the HTLC balances of already closed channels are being resolved on chain and are skipped.
+fn pending_htlcs(
+	balances: &[LightningBalance], open_channel_ids: &HashSet<ChannelId>,
+) -> Vec<PendingHtlc> {
+	let mut htlcs: Vec<PendingHtlc> = balances
+		.iter()
+		.filter_map(|balance| match balance {
+			LightningBalance::MaybeTimeoutClaimableHTLC {
+				channel_id,
+				counterparty_node_id,
+				amount_satoshis,
+				claimable_height,
+				payment_hash,
+				..
+			} if open_channel_ids.contains(channel_id) => Some(PendingHtlc {
+				channel_id: channel_id.0.to_lower_hex_string(),
+				counterparty_node_id: counterparty_node_id.to_string(),
+				direction: PaymentDirection::Outbound.into(),
+				amount_sats: *amount_satoshis,
+				cltv_expiry: *claimable_height,
+				payment_hash: payment_hash.0.to_lower_hex_string(),
+			}),
+			LightningBalance::MaybePreimageClaimableHTLC {
+				channel_id,
+				counterparty_node_id,
+				amount_satoshis,
+				expiry_height,
+				payment_hash,
+			} if open_channel_ids.contains(channel_id) => Some(PendingHtlc {
+				channel_id: channel_id.0.to_lower_hex_string(),
+				counterparty_node_id: counterparty_node_id.to_string(),
+				direction: PaymentDirection::Inbound.into(),
+				amount_sats: *amount_satoshis,
+				cltv_expiry: *expiry_height,
+				payment_hash: payment_hash.0.to_lower_hex_string(),
+			}),
+			_ => None,
+		})
+		.collect();
+	htlcs.sort_by_key(|htlc| htlc.cltv_expiry);
+	htlcs
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::bitcoin::secp256k1::PublicKey;
+	use ldk_node::lightning::ln::PaymentHash;
+	use std::str::FromStr;
+
+	fn counterparty() -> PublicKey {
+		PublicKey::from_str("02eec7245d6b7d2ccb30380bfbe2a3648cd7a942653f5aa340edcea1f283686619")
+			.unwrap()
+	}
+
+	#[test]
+	fn test_pending_htlcs() {
+		let (open_channel, closed_channel) = (ChannelId([1; 32]), ChannelId([2; 32]));
+		let balances = [
+			LightningBalance::ClaimableOnChannelClose {
+				channel_id: open_channel,
+				counterparty_node_id: counterparty(),
+				amount_satoshis: 500_000,
+				transaction_fee_satoshis: 300,
+				outbound_payment_htlc_rounded_msat: 0,
+				outbound_forwarded_htlc_rounded_msat: 0,
+				inbound_claiming_htlc_rounded_msat: 0,
+				inbound_htlc_rounded_msat: 0,
+			},
+			// An outbound payment awaiting resolution by the counterparty.
+			LightningBalance::MaybeTimeoutClaimableHTLC {
+				channel_id: open_channel,
+				counterparty_node_id: counterparty(),
+				amount_satoshis: 20_000,
+				claimable_height: 900_144,
+				payment_hash: PaymentHash([3; 32]),
+				outbound_payment: true,
+			},
+			// An inbound payment to a hold invoice that is neither settled nor cancelled yet.
+			LightningBalance::MaybePreimageClaimableHTLC {
+				channel_id: open_channel,
+				counterparty_node_id: counterparty(),
+				amount_satoshis: 10_000,
+				expiry_height: 900_080,
+				payment_hash: PaymentHash([4; 32]),
+			},
+			LightningBalance::MaybeTimeoutClaimableHTLC {
+				channel_id: closed_channel,
+				counterparty_node_id: counterparty(),
+				amount_satoshis: 30_000,
+				claimable_height: 900_010,
+				payment_hash: PaymentHash([5; 32]),
+				outbound_payment: false,
+			},
+		];
+
+		let htlcs = pending_htlcs(&balances, &HashSet::from([open_channel]));
+		assert_eq!(htlcs.len(), 2);
+
+		let held = &htlcs[0];
+		assert_eq!(held.channel_id, open_channel.0.to_lower_hex_string());
+		assert_eq!(held.counterparty_node_id, counterparty().to_string());
+		assert_eq!(held.direction, PaymentDirection::Inbound as i32);
+		assert_eq!(held.amount_sats, 10_000);
+		assert_eq!(held.cltv_expiry, 900_080);
+		assert_eq!(held.payment_hash, [4; 32].to_lower_hex_string());
+
+		assert_eq!(htlcs[1].direction, PaymentDirection::Outbound as i32);
+		assert_eq!(htlcs[1].amount_sats, 20_000);
+		assert_eq!(htlcs[1].cltv_expiry, 900_144);
+
+		assert!(pending_htlcs(&balances, &HashSet::new()).is_empty());
+	}
+}
@@ -1,15 +1,52 @@
+pub(crate) mod await_channel_ready;
 pub(crate) mod bolt11_receive;
 pub(crate) mod bolt11_send;
 pub(crate) mod bolt12_receive;
 pub(crate) mod bolt12_send;
+pub(crate) mod can_send;
+pub(crate) mod cancel_hold_invoice;
+pub(crate) mod claim_lnurl_withdraw;
 pub(crate) mod close_channel;
+pub(crate) mod create_hold_invoice;
+pub(crate) mod create_invoice_group;
 pub(crate) mod error;
+pub(crate) mod estimate_confirmation;
+pub(crate) mod export_channel_monitors;
+pub(crate) mod get_address_scope;
 pub(crate) mod get_balances;
+pub(crate) mod get_capabilities;
+pub(crate) mod get_close_balances;
+pub(crate) mod get_dust_exposure;
+pub(crate) mod get_inbound_payment_status;
+pub(crate) mod get_invoice_group_status;
+pub(crate) mod get_liquidity_summary;
+pub(crate) mod get_mempool_info;
 pub(crate) mod get_node_info;
+pub(crate) mod get_offer_status;
 pub(crate) mod get_payment_details;
+pub(crate) mod get_payment_invoice;
+pub(crate) mod get_peer_health;
+pub(crate) mod get_wallet_descriptor;
+pub(crate) mod import_channel_monitors;
 pub(crate) mod list_channels;
 pub(crate) mod list_payments;
+pub(crate) mod list_peers;
+pub(crate) mod list_pending_htlcs;
 pub(crate) mod onchain_receive;
 pub(crate) mod onchain_send;
 pub(crate) mod open_channel;
+pub(crate) mod pay_lightning_address;
+pub(crate) mod pay_uri;
+pub(crate) mod ping;
+pub(crate) mod query_events;
+pub(crate) mod recover_channels;
+pub(crate) mod reload_config;
+pub(crate) mod rotate_auth_token;
+pub(crate) mod self_test;
+pub(crate) mod send_many_bolt11;
+pub(crate) mod set_dust_exposure;
+pub(crate) mod settle_hold_invoice;
+pub(crate) mod shutdown;
+#[cfg(feature = "test-hooks")]
+pub(crate) mod simulate_payment_failure;
 pub(crate) mod update_channel_config;
@@ -1,12 +1,90 @@
-use ldk_node::Node;
+use crate::api::bolt11_receive::validate_scope;
+use crate::api::error::LdkServerErrorCode::InternalServerError;
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::issued_addresses::{write_issued_address_record, IssuedAddressRecord};
+use crate::service::Context;
 use ldk_server_protos::api::{OnchainReceiveRequest, OnchainReceiveResponse};
-use std::sync::Arc;
+use ldk_server_protos::types::AddressType;
+use std::time::Instant;
 
 pub(crate) const ONCHAIN_RECEIVE_PATH: &str = "OnchainReceive";
 pub(crate) fn handle_onchain_receive_request(
-	node: Arc<Node>, _request: OnchainReceiveRequest,
-) -> Result<OnchainReceiveResponse, ldk_node::NodeError> {
-	let response =
-		OnchainReceiveResponse { address: node.onchain_payment().new_address()?.to_string() };
+	context: Context, request: OnchainReceiveRequest,
+) -> Result<OnchainReceiveResponse, LdkServerError> {
+	validate_request(&request)?;
+
+	let new_address = || {
+		let address = context.node.onchain_payment().new_address()?.to_string();
+		// Keep track of the addresses we handed out, so that deposits can be attributed to their
+		// scope via `GetAddressScope`.
+		let record = IssuedAddressRecord { scope: request.scope.clone() };
+		write_issued_address_record(context.paginated_kv_store.as_ref(), &address, &record)
+			.map_err(|e| {
+				LdkServerError::new(InternalServerError, format!("Failed to record address: {}", e))
+			})?;
+		Ok(address)
+	};
+	let dedup_window = context.config.read().unwrap().onchain_receive_dedup_window;
+	let address = match dedup_window {
+		Some(window) => context.receive_addresses.get_or_generate(
+			AddressType::SegwitV0,
+			request.scope.as_deref(),
+			window,
+			Instant::now(),
+			new_address,
+		)?,
+		None => new_address()?,
+	};
+
+	let response = OnchainReceiveResponse { address };
 	Ok(response)
 }
+
+// Validates the fields of `request`.
+fn validate_request(request: &OnchainReceiveRequest) -> Result<(), LdkServerError> {
+	// LDK Node's on-chain wallet only derives native SegWit (P2WPKH) addresses.
+	match request.address_type.map(AddressType::from_i32) {
+		None | Some(Some(AddressType::SegwitV0)) => {},
+		Some(Some(address_type)) => {
+			return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+				"address_type",
+				format!("{} is not supported", address_type.as_str_name()),
+			)]));
+		},
+		Some(None) => {
+			return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+				"address_type",
+				"is unknown",
+			)]));
+		},
+	}
+	if let Some(scope) = request.scope.as_deref() {
+		validate_scope(scope)?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode;
+
+	#[test]
+	fn test_only_segwit_v0_addresses_are_supported() {
+		assert!(validate_request(&OnchainReceiveRequest::default()).is_ok());
+		let request = OnchainReceiveRequest {
+			address_type: Some(AddressType::SegwitV0 as i32),
+			..Default::default()
+		};
+		assert!(validate_request(&request).is_ok());
+
+		for address_type in [AddressType::Taproot as i32, i32::MAX] {
+			let request =
+				OnchainReceiveRequest { address_type: Some(address_type), ..Default::default() };
+			let err = validate_request(&request).unwrap_err();
+			assert_eq!(err.error_code, LdkServerErrorCode::InvalidRequestError);
+			assert_eq!(err.validation_errors.len(), 1);
+			assert_eq!(err.validation_errors[0].field, "address_type");
+		}
+	}
+}
@@ -1,25 +1,204 @@
-use ldk_node::bitcoin::Address;
-use ldk_node::Node;
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{
+	ConflictError, InternalServerError, InvalidRequestError,
+};
+use crate::io::onchain_sends::{
+	read_onchain_send_record, remove_onchain_send_record, write_onchain_send_record,
+	OnchainSendRecord,
+};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::service::Context;
+use crate::util::address::parse_address_checked;
+use crate::util::amount::validate_amount_sats;
+use crate::util::request_nonce::check_request_nonce;
+use ldk_node::NodeError;
 use ldk_server_protos::api::{OnchainSendRequest, OnchainSendResponse};
-use std::str::FromStr;
-use std::sync::Arc;
+use ldk_server_protos::types::PaymentRail;
 
 pub(crate) const ONCHAIN_SEND_PATH: &str = "OnchainSend";
 
+/// The maximum length of an idempotency key in bytes.
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 64;
+
 pub(crate) fn handle_onchain_send_request(
-	node: Arc<Node>, request: OnchainSendRequest,
-) -> Result<OnchainSendResponse, ldk_node::NodeError> {
-	let address = Address::from_str(&request.address)
-		.map_err(|_| ldk_node::NodeError::InvalidAddress)?
-		.require_network(node.config().network)
-		.map_err(|_| ldk_node::NodeError::InvalidAddress)?;
-	let txid = match (request.amount_sats, request.send_all) {
-		(Some(amount_sats), None) => {
-			node.onchain_payment().send_to_address(&address, amount_sats)?
-		},
-		(None, Some(true)) => node.onchain_payment().send_all_to_address(&address)?,
-		_ => return Err(ldk_node::NodeError::InvalidAmount),
+	context: Context, request: OnchainSendRequest,
+) -> Result<OnchainSendResponse, LdkServerError> {
+	if let Some(amount_sats) = request.amount_sats {
+		validate_amount_sats("amount_sats", amount_sats)?;
+	}
+	if let Some(idempotency_key) = request.idempotency_key.as_deref() {
+		if idempotency_key.is_empty() || idempotency_key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+			return Err(LdkServerError::new(
+				InvalidRequestError,
+				format!(
+					"Invalid idempotency_key: must be 1 to {} bytes long.",
+					MAX_IDEMPOTENCY_KEY_LEN
+				),
+			));
+		}
+	}
+
+	let address =
+		parse_address_checked("address", &request.address, context.node.config().network)?;
+	let amount_sats = match (request.amount_sats, request.send_all) {
+		(Some(amount_sats), None) => Some(amount_sats),
+		(None, Some(true)) => None,
+		_ => return Err(ldk_node::NodeError::InvalidAmount.into()),
 	};
-	let response = OnchainSendResponse { txid: txid.to_string() };
+
+	// On-chain sends are made one at a time, such that a retry can't race the send it retries.
+	let _send_guard = context.onchain_send_lock.lock().unwrap();
+	let record = OnchainSendRecord { address: request.address.clone(), amount_sats, txid: None };
+	let txid = send_idempotently(
+		context.paginated_kv_store.as_ref(),
+		request.idempotency_key.as_deref(),
+		record,
+		|| {
+			check_request_nonce(&context, request.request_nonce.as_deref())?;
+			let onchain_payment = context.node.onchain_payment();
+			let txid = match amount_sats {
+				Some(amount_sats) => match onchain_payment.send_to_address(&address, amount_sats) {
+					Ok(txid) => txid,
+					Err(NodeError::InsufficientFunds) => {
+						let available_sats =
+							context.node.list_balances().spendable_onchain_balance_sats;
+						return Err(LdkServerError::insufficient_funds(
+							amount_sats,
+							available_sats,
+						));
+					},
+					Err(e) => return Err(e.into()),
+				},
+				None => onchain_payment.send_all_to_address(&address)?,
+			};
+			let amount_msat = amount_sats.map(|amount_sats| amount_sats * 1000);
+			context.payment_metrics.record_payment(PaymentRail::Onchain, amount_msat);
+			Ok(txid.to_string())
+		},
+	)?;
+
+	let response = OnchainSendResponse { txid };
 	Ok(response)
 }
+
+// Makes the send described by `record` via `send`, returning the txid of its transaction.
+//
+// If a send was already made with the given `idempotency_key`, e.g., by a request the client timed
+// out on, its txid is returned instead of sending again.
+fn send_idempotently(
+	store: &dyn PaginatedKVStore, idempotency_key: Option<&str>, mut record: OnchainSendRecord,
+	send: impl FnOnce() -> Result<String, LdkServerError>,
+) -> Result<String, LdkServerError> {
+	let idempotency_key = match idempotency_key {
+		Some(idempotency_key) => idempotency_key,
+		None => return send(),
+	};
+
+	let original = read_onchain_send_record(store, idempotency_key).map_err(|e| {
+		LdkServerError::new(InternalServerError, format!("Failed to read on-chain send: {}", e))
+	})?;
+	match original {
+		Some(original) if !original.is_same_send(&record) => Err(LdkServerError::new(
+			ConflictError,
+			"The idempotency_key was already used for a different send.",
+		)),
+		Some(OnchainSendRecord { txid: Some(txid), .. }) => Ok(txid),
+		Some(_) => Err(LdkServerError::new(
+			ConflictError,
+			"An earlier send with the idempotency_key was interrupted and may have been broadcast.",
+		)),
+		None => {
+			// Record the send before making it, such that it isn't repeated even if we crash midway.
+			write_onchain_send_record(store, idempotency_key, &record).map_err(|e| {
+				LdkServerError::new(
+					InternalServerError,
+					format!("Failed to record on-chain send: {}", e),
+				)
+			})?;
+			match send() {
+				Ok(txid) => {
+					record.txid = Some(txid.clone());
+					if let Err(e) = write_onchain_send_record(store, idempotency_key, &record) {
+						eprintln!("Failed to record txid {} of on-chain send: {}", txid, e);
+					}
+					Ok(txid)
+				},
+				Err(err) => {
+					// Nothing was broadcast, so the send may be retried with the same key.
+					if let Err(e) = remove_onchain_send_record(store, idempotency_key) {
+						eprintln!("Failed to remove record of failed on-chain send: {}", e);
+					}
+					Err(err)
+				},
+			}
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::ServiceUnavailableError;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use std::cell::Cell;
+
+	fn record(amount_sats: Option<u64>) -> OnchainSendRecord {
+		OnchainSendRecord {
+			address: "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080".to_string(),
+			amount_sats,
+			txid: None,
+		}
+	}
+
+	#[test]
+	fn test_retried_send_is_made_once() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let sends = Cell::new(0);
+		let send = || {
+			sends.set(sends.get() + 1);
+			Ok(format!("txid-{}", sends.get()))
+		};
+
+		// The client times out on the first request after the transaction was broadcast, and
+		// retries it with the same key.
+		let txid = send_idempotently(&store, Some("key-1"), record(Some(1000)), send).unwrap();
+		let retried_txid =
+			send_idempotently(&store, Some("key-1"), record(Some(1000)), send).unwrap();
+		assert_eq!(txid, "txid-1");
+		assert_eq!(retried_txid, txid);
+		assert_eq!(sends.get(), 1);
+
+		// Reusing the key for a different send is rejected.
+		let err = send_idempotently(&store, Some("key-1"), record(Some(2000)), send).unwrap_err();
+		assert_eq!(err.error_code, ConflictError);
+		assert_eq!(sends.get(), 1);
+
+		// Fresh keys, or no key at all, result in new sends.
+		assert_eq!(send_idempotently(&store, Some("key-2"), record(None), send).unwrap(), "txid-2");
+		assert_eq!(send_idempotently(&store, None, record(Some(1000)), send).unwrap(), "txid-3");
+	}
+
+	#[test]
+	fn test_failed_send_can_be_retried() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let err = send_idempotently(&store, Some("key"), record(Some(1000)), || {
+			Err(ldk_node::NodeError::WalletOperationFailed.into())
+		})
+		.unwrap_err();
+		assert_eq!(err.error_code, ServiceUnavailableError);
+
+		let txid =
+			send_idempotently(&store, Some("key"), record(Some(1000)), || Ok("txid".to_string()))
+				.unwrap();
+		assert_eq!(txid, "txid");
+
+		// A send interrupted before its txid was recorded isn't retried.
+		write_onchain_send_record(&store, "interrupted", &record(Some(1000))).unwrap();
+		let err = send_idempotently(&store, Some("interrupted"), record(Some(1000)), || {
+			Ok("txid".to_string())
+		})
+		.unwrap_err();
+		assert_eq!(err.error_code, ConflictError);
+	}
+}
@@ -1,23 +1,31 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use crate::util::amount::validate_amount_sats;
 use bytes::Bytes;
 use ldk_node::bitcoin::secp256k1::PublicKey;
 use ldk_node::lightning::ln::msgs::SocketAddress;
-use ldk_node::Node;
 use ldk_server_protos::api::{OpenChannelRequest, OpenChannelResponse};
 use std::str::FromStr;
-use std::sync::Arc;
 
 pub(crate) const OPEN_CHANNEL_PATH: &str = "OpenChannel";
 
+/// The channel size below which on-chain fees for opening and closing a channel make up a large
+/// share of its value, such that we warn about opening it.
+const RECOMMENDED_MIN_CHANNEL_AMOUNT_SATS: u64 = 100_000;
+
+/// The smallest channel LDK opens or accepts, as each side has to keep a channel reserve of at
+/// least 1000 sats the other side can't dip into. Channels at this size still fail to open if
+/// their value can't pay the initial commitment transaction fee, and counterparties running other
+/// implementations commonly require larger channels, e.g., 20,000 sats by default for LND.
+const MIN_CHANNEL_AMOUNT_SATS: u64 = 2 * 1000;
+
 pub(crate) fn handle_open_channel(
-	node: Arc<Node>, request: OpenChannelRequest,
-) -> Result<OpenChannelResponse, ldk_node::NodeError> {
-	let node_id = PublicKey::from_str(&request.node_pubkey)
-		.map_err(|_| ldk_node::NodeError::InvalidPublicKey)?;
-	let address = SocketAddress::from_str(&request.address)
-		.map_err(|_| ldk_node::NodeError::InvalidSocketAddress)?;
+	context: Context, request: OpenChannelRequest,
+) -> Result<OpenChannelResponse, LdkServerError> {
+	let (node_id, address) = validate_open_channel_request(&request)?;
 
 	let user_channel_id = if request.announce_channel {
-		node.open_announced_channel(
+		context.node.open_announced_channel(
 			node_id,
 			address,
 			request.channel_amount_sats,
@@ -26,7 +34,7 @@ pub(crate) fn handle_open_channel(
 			None,
 		)?
 	} else {
-		node.open_channel(
+		context.node.open_channel(
 			node_id,
 			address,
 			request.channel_amount_sats,
@@ -37,6 +45,151 @@ pub(crate) fn handle_open_channel(
 
 	let response = OpenChannelResponse {
 		user_channel_id: Bytes::from(user_channel_id.0.to_be_bytes().to_vec()),
+		warnings: open_channel_warnings(&request),
 	};
 	Ok(response)
 }
+
+// Validates all fields of `request`, reporting every invalid one rather than just the first.
+fn validate_open_channel_request(
+	request: &OpenChannelRequest,
+) -> Result<(PublicKey, SocketAddress), LdkServerError> {
+	let mut validation_errors = Vec::new();
+
+	let node_id = match PublicKey::from_str(&request.node_pubkey) {
+		Ok(node_id) => Some(node_id),
+		Err(_) => {
+			validation_errors.push(ValidationError::new("node_pubkey", "invalid public key"));
+			None
+		},
+	};
+	let address = match parse_socket_address(&request.address) {
+		Ok(address) => Some(address),
+		Err(e) => {
+			validation_errors.extend(e.validation_errors);
+			None
+		},
+	};
+	if let Err(e) = validate_amount_sats("channel_amount_sats", request.channel_amount_sats) {
+		validation_errors.extend(e.validation_errors);
+	} else if request.channel_amount_sats < MIN_CHANNEL_AMOUNT_SATS {
+		// LDK Node only reports a generic channel creation failure for channels that are too small.
+		validation_errors.push(ValidationError::new(
+			"channel_amount_sats",
+			format!(
+				"must be at least {} sats to cover both sides' channel reserves",
+				MIN_CHANNEL_AMOUNT_SATS
+			),
+		));
+	}
+	if let Some(push_to_counterparty_msat) = request.push_to_counterparty_msat {
+		// Pushing more than the channel's value likely means satoshis were taken as millisatoshis.
+		if push_to_counterparty_msat > request.channel_amount_sats.saturating_mul(1000) {
+			validation_errors.push(ValidationError::new(
+				"push_to_counterparty_msat",
+				"exceeds channel_amount_sats",
+			));
+		}
+	}
+
+	match (node_id, address) {
+		(Some(node_id), Some(address)) if validation_errors.is_empty() => Ok((node_id, address)),
+		_ => Err(LdkServerError::invalid_fields(validation_errors)),
+	}
+}
+
+// Returns warnings about channels the `request` opens successfully that the operator should still
+// be made aware of.
+fn open_channel_warnings(request: &OpenChannelRequest) -> Vec<String> {
+	let mut warnings = Vec::new();
+	if request.channel_amount_sats < RECOMMENDED_MIN_CHANNEL_AMOUNT_SATS {
+		warnings.push(format!(
+			"The channel amount of {} sats is below the recommended minimum of {} sats, on-chain fees may consume a large share of it.",
+			request.channel_amount_sats, RECOMMENDED_MIN_CHANNEL_AMOUNT_SATS
+		));
+	}
+	warnings
+}
+
+// Parses a peer address, which may also be a Tor onion address, e.g., `<onion address>.onion:9735`.
+fn parse_socket_address(address: &str) -> Result<SocketAddress, LdkServerError> {
+	SocketAddress::from_str(address).map_err(|e| {
+		LdkServerError::invalid_fields(vec![ValidationError::new(
+			"address",
+			format!("invalid address {}: {}", address, e),
+		)])
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+
+	#[test]
+	fn test_parse_onion_address() {
+		let onion_address = "pg6mmjiyjmcrsslvykfwnntlaru7p5svn6y2ymmju6nubxndf4pscryd.onion:9735";
+		let address = parse_socket_address(onion_address).unwrap();
+		assert!(matches!(address, SocketAddress::OnionV3 { port: 9735, .. }));
+		assert_eq!(address.to_string().to_lowercase(), onion_address);
+
+		let err = parse_socket_address("invalid.onion:9735").unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert!(err.message.contains("Invalid onion v3 address"));
+	}
+
+	#[test]
+	fn test_all_invalid_fields_are_reported() {
+		let request = OpenChannelRequest {
+			node_pubkey: "00".repeat(33),
+			address: "not an address".to_string(),
+			channel_amount_sats: 100_000,
+			push_to_counterparty_msat: Some(100_000_001),
+			channel_config: None,
+			announce_channel: false,
+		};
+		let err = validate_open_channel_request(&request).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		let fields: Vec<_> = err.validation_errors.iter().map(|e| e.field.as_str()).collect();
+		assert_eq!(fields, ["node_pubkey", "address", "push_to_counterparty_msat"]);
+	}
+
+	#[test]
+	fn test_small_channel_open_warns() {
+		let mut request = OpenChannelRequest {
+			node_pubkey: "02".repeat(33),
+			address: "127.0.0.1:9735".to_string(),
+			channel_amount_sats: RECOMMENDED_MIN_CHANNEL_AMOUNT_SATS - 1,
+			push_to_counterparty_msat: None,
+			channel_config: None,
+			announce_channel: false,
+		};
+		let warnings = open_channel_warnings(&request);
+		assert_eq!(warnings.len(), 1);
+		assert!(warnings[0].contains("below the recommended minimum"));
+
+		request.channel_amount_sats = RECOMMENDED_MIN_CHANNEL_AMOUNT_SATS;
+		assert!(open_channel_warnings(&request).is_empty());
+	}
+
+	#[test]
+	fn test_too_small_channel_open_states_minimum() {
+		let mut request = OpenChannelRequest {
+			node_pubkey: "02eadbd9e7557375161df8b646776a547c5cbc2e95b3071ec81553f8ec2cea3b8c"
+				.to_string(),
+			address: "127.0.0.1:9735".to_string(),
+			channel_amount_sats: MIN_CHANNEL_AMOUNT_SATS - 1,
+			push_to_counterparty_msat: None,
+			channel_config: None,
+			announce_channel: false,
+		};
+		let err = validate_open_channel_request(&request).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert_eq!(err.validation_errors.len(), 1);
+		assert_eq!(err.validation_errors[0].field, "channel_amount_sats");
+		assert!(err.validation_errors[0].reason.contains("at least 2000 sats"));
+
+		request.channel_amount_sats = MIN_CHANNEL_AMOUNT_SATS;
+		assert!(validate_open_channel_request(&request).is_ok());
+	}
+}
@@ -0,0 +1,295 @@
+use crate::api::error::LdkServerErrorCode::LightningError;
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use crate::util::amount::validate_amount_msat;
+use crate::util::fee_limit::routing_fee_limit;
+use crate::util::lnurl::{lnurl_client, lnurl_get};
+use crate::util::payment_invoice::record_bolt11_invoice;
+use crate::util::request_nonce::check_request_nonce;
+use bytes::Bytes;
+use ldk_node::bitcoin::hashes::{sha256, Hash};
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_node::lightning_invoice::{Bolt11Invoice, Bolt11InvoiceDescription};
+use ldk_node::payment::SendingParameters;
+use ldk_server_protos::api::{PayLightningAddressRequest, PayLightningAddressResponse};
+use ldk_server_protos::types::PaymentRail;
+use serde::Deserialize;
+use std::str::FromStr;
+
+pub(crate) const PAY_LIGHTNING_ADDRESS_PATH: &str = "PayLightningAddress";
+
+/// The parameters of an LNURL-pay request, as returned by the recipient's LNURL service (LUD-06).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PayRequest {
+	tag: String,
+	callback: String,
+	min_sendable: u64,
+	max_sendable: u64,
+	metadata: String,
+	/// The maximum length of comments in characters, if comments are accepted at all (LUD-12).
+	#[serde(default)]
+	comment_allowed: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct InvoiceResponse {
+	pr: String,
+}
+
+pub(crate) fn handle_pay_lightning_address_request(
+	context: Context, request: PayLightningAddressRequest,
+) -> Result<PayLightningAddressResponse, LdkServerError> {
+	check_request_nonce(&context, request.request_nonce.as_deref())?;
+	validate_amount_msat("amount_msat", request.amount_msat)?;
+	let url = lightning_address_url(&request.address)?;
+
+	let max_routing_fee_msat = context.config.read().unwrap().max_routing_fee_msat;
+	let sending_parameters =
+		routing_fee_limit(max_routing_fee_msat, None, false)?.map(|limit_msat| SendingParameters {
+			max_total_routing_fee_msat: Some(Some(limit_msat)),
+			max_total_cltv_expiry_delta: None,
+			max_path_count: None,
+			max_channel_saturation_power_of_half: None,
+		});
+
+	let client = lnurl_client()?;
+	// Handlers are synchronous, so we block this worker thread rather than the runtime.
+	let (payment_id, invoice) = tokio::task::block_in_place(|| {
+		tokio::runtime::Handle::current().block_on(pay_lightning_address(
+			&client,
+			&url,
+			request.amount_msat,
+			request.comment.as_deref(),
+			|invoice| Ok(context.node.bolt11_payment().send(invoice, sending_parameters)?),
+		))
+	})?;
+
+	record_bolt11_invoice(context.paginated_kv_store.as_ref(), &payment_id, &invoice);
+	context.payment_metrics.record_payment(PaymentRail::Bolt11, Some(request.amount_msat));
+
+	let response = PayLightningAddressResponse {
+		payment_id: Bytes::from(payment_id.0.to_vec()),
+		invoice: invoice.to_string(),
+	};
+	Ok(response)
+}
+
+// Returns the URL of the LNURL-pay endpoint of a lightning address `user@domain` (LUD-16).
+fn lightning_address_url(address: &str) -> Result<String, LdkServerError> {
+	let invalid = |reason: &str| {
+		LdkServerError::invalid_fields(vec![ValidationError::new("address", reason.to_string())])
+	};
+	let address = address.to_lowercase();
+	let (user, domain) = address.split_once('@').ok_or_else(|| invalid("must be user@domain"))?;
+	let is_valid_user_char =
+		|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '+';
+	if user.is_empty() || !user.chars().all(is_valid_user_char) {
+		return Err(invalid("invalid user name"));
+	}
+	let is_valid_domain_char =
+		|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == ':';
+	if domain.is_empty() || !domain.chars().all(is_valid_domain_char) {
+		return Err(invalid("invalid domain"));
+	}
+	// Onion services are reached via plain HTTP, as Tor already encrypts the connection.
+	let scheme = if domain.ends_with(".onion") { "http" } else { "https" };
+	Ok(format!("{}://{}/.well-known/lnurlp/{}", scheme, domain, user))
+}
+
+// Resolves the LNURL-pay endpoint at `url`, fetches an invoice for `amount_msat` from it, and makes
+// the payment via `pay`, returning the payment's id along with the invoice paid.
+async fn pay_lightning_address<F: FnOnce(&Bolt11Invoice) -> Result<PaymentId, LdkServerError>>(
+	client: &reqwest::Client, url: &str, amount_msat: u64, comment: Option<&str>, pay: F,
+) -> Result<(PaymentId, Bolt11Invoice), LdkServerError> {
+	let pay_request: PayRequest = lnurl_get(client.get(url)).await?;
+	check_pay_request(&pay_request, amount_msat, comment)?;
+
+	let mut callback = client.get(&pay_request.callback).query(&[("amount", amount_msat)]);
+	if let Some(comment) = comment {
+		callback = callback.query(&[("comment", comment)]);
+	}
+	let invoice_response: InvoiceResponse = lnurl_get(callback).await?;
+	let invoice = Bolt11Invoice::from_str(&invoice_response.pr).map_err(|e| {
+		LdkServerError::new(
+			LightningError,
+			format!("LNURL service returned invalid invoice: {}", e),
+		)
+	})?;
+	check_invoice(&invoice, &pay_request, amount_msat)?;
+
+	let payment_id = pay(&invoice)?;
+	Ok((payment_id, invoice))
+}
+
+// Fails if paying `amount_msat` with `comment` isn't allowed by `pay_request`.
+fn check_pay_request(
+	pay_request: &PayRequest, amount_msat: u64, comment: Option<&str>,
+) -> Result<(), LdkServerError> {
+	if pay_request.tag != "payRequest" {
+		return Err(LdkServerError::new(
+			LightningError,
+			format!("LNURL service returned unexpected tag {}.", pay_request.tag),
+		));
+	}
+	let mut validation_errors = Vec::new();
+	if amount_msat < pay_request.min_sendable || amount_msat > pay_request.max_sendable {
+		validation_errors.push(ValidationError::new(
+			"amount_msat",
+			format!(
+				"must be between {} and {} msat for this address",
+				pay_request.min_sendable, pay_request.max_sendable
+			),
+		));
+	}
+	let comment_len = comment.map_or(0, |comment| comment.chars().count());
+	if comment_len > pay_request.comment_allowed {
+		let reason = if pay_request.comment_allowed == 0 {
+			"isn't accepted by this address".to_string()
+		} else {
+			format!("must be no longer than {} characters", pay_request.comment_allowed)
+		};
+		validation_errors.push(ValidationError::new("comment", reason));
+	}
+	if !validation_errors.is_empty() {
+		return Err(LdkServerError::invalid_fields(validation_errors));
+	}
+	Ok(())
+}
+
+// Fails unless `invoice` is for `amount_msat` and commits to the metadata of `pay_request`, as
+// LNURL-pay requires wallets to verify.
+fn check_invoice(
+	invoice: &Bolt11Invoice, pay_request: &PayRequest, amount_msat: u64,
+) -> Result<(), LdkServerError> {
+	if invoice.amount_milli_satoshis() != Some(amount_msat) {
+		return Err(LdkServerError::new(
+			LightningError,
+			format!(
+				"LNURL service returned an invoice for {:?} msat rather than {} msat.",
+				invoice.amount_milli_satoshis(),
+				amount_msat
+			),
+		));
+	}
+	let metadata_hash = sha256::Hash::hash(pay_request.metadata.as_bytes());
+	match invoice.description() {
+		Bolt11InvoiceDescription::Hash(hash) if hash.0 == metadata_hash => Ok(()),
+		_ => Err(LdkServerError::new(
+			LightningError,
+			"LNURL service returned an invoice not committing to its metadata.",
+		)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+	use crate::util::lnurl::tests::mock_lnurl_server;
+	use ldk_node::bitcoin::secp256k1::{Secp256k1, SecretKey};
+	use ldk_node::lightning::ln::types::PaymentSecret;
+	use ldk_node::lightning_invoice::{Currency, InvoiceBuilder};
+	use std::sync::{Arc, Mutex};
+
+	const METADATA: &str = "[[\"text/identifier\",\"alice@example.com\"]]";
+
+	fn invoice(amount_msat: u64, metadata: &str) -> Bolt11Invoice {
+		let secp_ctx = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&[42; 32]).unwrap();
+		InvoiceBuilder::new(Currency::Regtest)
+			.description_hash(sha256::Hash::hash(metadata.as_bytes()))
+			.payment_hash(sha256::Hash::hash(&[1; 32]))
+			.payment_secret(PaymentSecret([1; 32]))
+			.current_timestamp()
+			.min_final_cltv_expiry_delta(144)
+			.amount_milli_satoshis(amount_msat)
+			.build_signed(|hash| secp_ctx.sign_ecdsa_recoverable(hash, &secret_key))
+			.unwrap()
+	}
+
+	// Serves a lightning address' LNURL-pay endpoint on localhost, returning its URL along with the
+	// request targets of the invoice requests made.
+	async fn mock_lightning_address_server() -> (String, Arc<Mutex<Vec<String>>>) {
+		let invoice_requests = Arc::new(Mutex::new(Vec::new()));
+		let requests = invoice_requests.clone();
+		let base_url = mock_lnurl_server(move |base_url, target| {
+			if target == "/.well-known/lnurlp/alice" {
+				return serde_json::json!({
+					"tag": "payRequest",
+					"callback": format!("{}/callback", base_url),
+					"minSendable": 1_000,
+					"maxSendable": 1_000_000,
+					"metadata": METADATA,
+					"commentAllowed": 10,
+				});
+			}
+			requests.lock().unwrap().push(target.to_string());
+			let amount_msat = target.split("amount=").nth(1).unwrap();
+			let amount_msat = amount_msat.split('&').next().unwrap().parse().unwrap();
+			serde_json::json!({ "pr": invoice(amount_msat, METADATA).to_string(), "routes": [] })
+		})
+		.await;
+		(format!("{}/.well-known/lnurlp/alice", base_url), invoice_requests)
+	}
+
+	#[tokio::test]
+	async fn test_resolve_and_pay() {
+		let (url, invoice_requests) = mock_lightning_address_server().await;
+		let client = reqwest::Client::new();
+
+		let mut paid = None;
+		let (payment_id, invoice) =
+			pay_lightning_address(&client, &url, 21_000, Some("thanks"), |invoice| {
+				paid = Some(invoice.clone());
+				Ok(PaymentId(*invoice.payment_hash().as_byte_array()))
+			})
+			.await
+			.unwrap();
+		assert_eq!(invoice.amount_milli_satoshis(), Some(21_000));
+		assert_eq!(paid, Some(invoice.clone()));
+		assert_eq!(payment_id.0, *invoice.payment_hash().as_byte_array());
+		assert_eq!(*invoice_requests.lock().unwrap(), ["/callback?amount=21000&comment=thanks"]);
+
+		// Requests the service would reject are failed before asking for an invoice.
+		let pay = |_: &Bolt11Invoice| -> Result<PaymentId, LdkServerError> { unreachable!() };
+		let err = pay_lightning_address(&client, &url, 2_000_000, None, pay).await.unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert_eq!(err.validation_errors[0].field, "amount_msat");
+		assert!(err.validation_errors[0].reason.contains("between 1000 and 1000000 msat"));
+
+		let comment = Some("a comment that is too long");
+		let err = pay_lightning_address(&client, &url, 21_000, comment, pay).await.unwrap_err();
+		assert_eq!(err.validation_errors[0].field, "comment");
+		assert_eq!(invoice_requests.lock().unwrap().len(), 1);
+	}
+
+	#[test]
+	fn test_lightning_address_url() {
+		assert_eq!(
+			lightning_address_url("Alice@Example.com").unwrap(),
+			"https://example.com/.well-known/lnurlp/alice"
+		);
+		assert!(lightning_address_url("alice@ex4mpl3.onion").unwrap().starts_with("http://"));
+		for address in ["alice", "@example.com", "alice@", "al ice@example.com", "alice@ex/ample"] {
+			let err = lightning_address_url(address).unwrap_err();
+			assert_eq!(err.validation_errors[0].field, "address");
+		}
+	}
+
+	#[test]
+	fn test_invoice_must_commit_to_metadata() {
+		let pay_request = PayRequest {
+			tag: "payRequest".to_string(),
+			callback: "https://example.com/callback".to_string(),
+			min_sendable: 1_000,
+			max_sendable: 1_000_000,
+			metadata: METADATA.to_string(),
+			comment_allowed: 0,
+		};
+		assert!(check_invoice(&invoice(21_000, METADATA), &pay_request, 21_000).is_ok());
+		let err = check_invoice(&invoice(21_000, METADATA), &pay_request, 22_000).unwrap_err();
+		assert_eq!(err.error_code, LightningError);
+		assert!(check_invoice(&invoice(21_000, "other"), &pay_request, 21_000).is_err());
+	}
+}
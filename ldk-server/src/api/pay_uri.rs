@@ -0,0 +1,355 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+use crate::service::Context;
+use crate::util::address::parse_address_checked;
+use crate::util::amount::{msat_to_sats_ceil, validate_invoice_amount, MAX_MONEY_SATS};
+use crate::util::fee_limit::routing_fee_limit;
+use crate::util::payment_invoice::{record_bolt11_invoice, record_bolt12_offer};
+use crate::util::request_nonce::check_request_nonce;
+use bytes::Bytes;
+use ldk_node::bitcoin::{Address, Network};
+use ldk_node::lightning::offers::offer::{Amount, Offer};
+use ldk_node::lightning_invoice::Bolt11Invoice;
+use ldk_node::payment::SendingParameters;
+use ldk_node::NodeError;
+use ldk_server_protos::api::{PayUriRequest, PayUriResponse};
+use ldk_server_protos::types::PaymentRail;
+use std::str::FromStr;
+
+pub(crate) const PAY_URI_PATH: &str = "PayUri";
+
+const BIP21_SCHEME: &str = "bitcoin:";
+
+const SATS_PER_BTC: u64 = 100_000_000;
+
+pub(crate) fn handle_pay_uri_request(
+	context: Context, request: PayUriRequest,
+) -> Result<PayUriResponse, LdkServerError> {
+	check_request_nonce(&context, request.request_nonce.as_deref())?;
+
+	let uri = parse_payment_uri(&request.uri, context.node.config().network)?;
+
+	let max_sendable_msat = context
+		.node
+		.list_channels()
+		.iter()
+		.filter(|channel| channel.is_usable)
+		.map(|channel| channel.next_outbound_htlc_limit_msat)
+		.sum();
+	let max_routing_fee_msat = context.config.read().unwrap().max_routing_fee_msat;
+	let rail = choose_rail(&uri, max_sendable_msat, max_routing_fee_msat.is_none())?;
+
+	let mut response = PayUriResponse { rail: rail as i32, payment_id: None, txid: None };
+	match rail {
+		PaymentRail::Bolt12 => {
+			// unwrap safety: the rail is only chosen if the URI carries an offer.
+			let offer = uri.offer.as_ref().unwrap();
+			let payment_id = match offer_amount_msat(offer) {
+				Some(_) => context.node.bolt12_payment().send(offer, None, None)?,
+				// unwrap safety: the rail is only chosen if the amount to pay is known.
+				None => context.node.bolt12_payment().send_using_amount(
+					offer,
+					uri.amount_msat().unwrap(),
+					None,
+					None,
+				)?,
+			};
+			record_bolt12_offer(context.paginated_kv_store.as_ref(), &payment_id, offer);
+			let amount_msat = offer_amount_msat(offer).or(uri.amount_msat());
+			context.payment_metrics.record_payment(PaymentRail::Bolt12, amount_msat);
+			response.payment_id = Some(Bytes::from(payment_id.0.to_vec()));
+		},
+		PaymentRail::Bolt11 => {
+			// unwrap safety: the rail is only chosen if the URI carries an invoice.
+			let invoice = uri.invoice.as_ref().unwrap();
+			let sending_parameters =
+				routing_fee_limit(max_routing_fee_msat, None, false)?.map(|limit_msat| {
+					SendingParameters {
+						max_total_routing_fee_msat: Some(Some(limit_msat)),
+						max_total_cltv_expiry_delta: None,
+						max_path_count: None,
+						max_channel_saturation_power_of_half: None,
+					}
+				});
+			let payment_id = match invoice.amount_milli_satoshis() {
+				Some(_) => context.node.bolt11_payment().send(invoice, sending_parameters)?,
+				// unwrap safety: the rail is only chosen if the amount to pay is known.
+				None => context.node.bolt11_payment().send_using_amount(
+					invoice,
+					uri.amount_msat().unwrap(),
+					sending_parameters,
+				)?,
+			};
+			record_bolt11_invoice(context.paginated_kv_store.as_ref(), &payment_id, invoice);
+			let amount_msat = invoice.amount_milli_satoshis().or(uri.amount_msat());
+			context.payment_metrics.record_payment(PaymentRail::Bolt11, amount_msat);
+			response.payment_id = Some(Bytes::from(payment_id.0.to_vec()));
+		},
+		PaymentRail::Onchain => {
+			// unwrap safety: the rail is only chosen if the URI carries an address and an amount.
+			let address = uri.address.as_ref().unwrap();
+			let amount_sats = uri.amount_sats.unwrap();
+			let txid = match context.node.onchain_payment().send_to_address(address, amount_sats) {
+				Ok(txid) => txid,
+				Err(NodeError::InsufficientFunds) => {
+					let available_sats =
+						context.node.list_balances().spendable_onchain_balance_sats;
+					return Err(LdkServerError::insufficient_funds(amount_sats, available_sats));
+				},
+				Err(e) => return Err(e.into()),
+			};
+			context.payment_metrics.record_payment(PaymentRail::Onchain, uri.amount_msat());
+			response.txid = Some(txid.to_string());
+		},
+	}
+	Ok(response)
+}
+
+// The payment rails and amount of a BIP21 URI.
+struct PaymentUri {
+	address: Option<Address>,
+	amount_sats: Option<u64>,
+	invoice: Option<Bolt11Invoice>,
+	offer: Option<Offer>,
+}
+
+impl PaymentUri {
+	fn amount_msat(&self) -> Option<u64> {
+		self.amount_sats.map(|amount_sats| amount_sats * 1000)
+	}
+}
+
+fn parse_payment_uri(uri: &str, network: Network) -> Result<PaymentUri, LdkServerError> {
+	let invalid_uri = |reason: String| {
+		LdkServerError::new(InvalidRequestError, format!("Invalid URI: {}", reason))
+	};
+
+	let has_scheme = uri
+		.get(..BIP21_SCHEME.len())
+		.map_or(false, |scheme| scheme.eq_ignore_ascii_case(BIP21_SCHEME));
+	if !has_scheme {
+		return Err(invalid_uri(format!("must start with '{}'", BIP21_SCHEME)));
+	}
+	let uri = &uri[BIP21_SCHEME.len()..];
+	let (address, query) = uri.split_once('?').unwrap_or((uri, ""));
+
+	let address = if address.is_empty() {
+		None
+	} else {
+		Some(parse_address_checked("uri", address, network)?)
+	};
+
+	let mut payment_uri = PaymentUri { address, amount_sats: None, invoice: None, offer: None };
+	for param in query.split('&').filter(|param| !param.is_empty()) {
+		let (key, value) = param.split_once('=').unwrap_or((param, ""));
+		match key.to_ascii_lowercase().as_str() {
+			"amount" => {
+				payment_uri.amount_sats = Some(
+					parse_btc_amount(value)
+						.ok_or_else(|| invalid_uri(format!("invalid amount {}", value)))?,
+				);
+			},
+			"lightning" => {
+				let invoice =
+					Bolt11Invoice::from_str(value).map_err(|_| NodeError::InvalidInvoice)?;
+				if invoice.network() != network {
+					return Err(NodeError::InvalidInvoice.into());
+				}
+				validate_invoice_amount("uri", &invoice)?;
+				payment_uri.invoice = Some(invoice);
+			},
+			"lno" => {
+				payment_uri.offer =
+					Some(Offer::from_str(value).map_err(|_| NodeError::InvalidOffer)?);
+			},
+			// Per BIP21, URIs with required parameters we don't understand must not be paid.
+			key if key.starts_with("req-") => {
+				return Err(invalid_uri(format!("unsupported required parameter {}", key)));
+			},
+			_ => {},
+		}
+	}
+	Ok(payment_uri)
+}
+
+// Parses a decimal amount of bitcoin, as used by the BIP21 `amount` parameter, into satoshis.
+fn parse_btc_amount(amount: &str) -> Option<u64> {
+	let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+	let is_numeric = |digits: &str| digits.bytes().all(|digit| digit.is_ascii_digit());
+	if (whole.is_empty() && fraction.is_empty())
+		|| fraction.len() > 8
+		|| !is_numeric(whole)
+		|| !is_numeric(fraction)
+	{
+		return None;
+	}
+
+	let whole_sats = if whole.is_empty() { 0 } else { whole.parse::<u64>().ok()? };
+	let fraction_sats = format!("{:0<8}", fraction).parse::<u64>().ok()?;
+	let amount_sats = whole_sats.checked_mul(SATS_PER_BTC)?.checked_add(fraction_sats)?;
+	if amount_sats > MAX_MONEY_SATS {
+		return None;
+	}
+	Some(amount_sats)
+}
+
+fn offer_amount_msat(offer: &Offer) -> Option<u64> {
+	match offer.amount() {
+		Some(Amount::Bitcoin { amount_msats }) => Some(amount_msats),
+		_ => None,
+	}
+}
+
+// Chooses the preferred payment rail of `uri` that we can currently use, falling back from BOLT12
+// offers to BOLT11 invoices to on-chain addresses.
+fn choose_rail(
+	uri: &PaymentUri, max_sendable_msat: u64, allow_bolt12: bool,
+) -> Result<PaymentRail, LdkServerError> {
+	let mut lightning_amount_msat = None;
+	let mut can_pay_lightning = |amount_msat: Option<u64>| match amount_msat {
+		Some(amount_msat) => {
+			lightning_amount_msat = Some(amount_msat);
+			amount_msat > 0 && amount_msat <= max_sendable_msat
+		},
+		None => false,
+	};
+
+	if let Some(offer) = uri.offer.as_ref().filter(|_| allow_bolt12) {
+		if can_pay_lightning(offer_amount_msat(offer).or(uri.amount_msat())) {
+			return Ok(PaymentRail::Bolt12);
+		}
+	}
+	if let Some(invoice) = uri.invoice.as_ref() {
+		if can_pay_lightning(invoice.amount_milli_satoshis().or(uri.amount_msat())) {
+			return Ok(PaymentRail::Bolt11);
+		}
+	}
+	if uri.address.is_some() && uri.amount_sats.is_some() {
+		return Ok(PaymentRail::Onchain);
+	}
+
+	match lightning_amount_msat {
+		// We could have paid over Lightning if we had the liquidity.
+		Some(amount_msat) if amount_msat > 0 => Err(LdkServerError::insufficient_funds(
+			msat_to_sats_ceil(amount_msat),
+			max_sendable_msat / 1000,
+		)),
+		_ => Err(LdkServerError::new(
+			InvalidRequestError,
+			"Invalid URI: no payment rail with a known amount to pay.",
+		)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InsufficientFundsError;
+	use ldk_node::bitcoin::hashes::{sha256, Hash};
+	use ldk_node::bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+	use ldk_node::lightning::ln::types::PaymentSecret;
+	use ldk_node::lightning::offers::offer::OfferBuilder;
+	use ldk_node::lightning_invoice::{Currency, InvoiceBuilder};
+
+	const ADDRESS: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+
+	fn invoice(amount_msat: u64) -> Bolt11Invoice {
+		let secp_ctx = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&[42; 32]).unwrap();
+		InvoiceBuilder::new(Currency::Regtest)
+			.description("coffee".to_string())
+			.payment_hash(sha256::Hash::hash(&[42; 32]))
+			.payment_secret(PaymentSecret([42; 32]))
+			.current_timestamp()
+			.min_final_cltv_expiry_delta(144)
+			.amount_milli_satoshis(amount_msat)
+			.build_signed(|hash| secp_ctx.sign_ecdsa_recoverable(hash, &secret_key))
+			.unwrap()
+	}
+
+	fn offer(amount_msat: u64) -> Offer {
+		let secp_ctx = Secp256k1::new();
+		let signing_pubkey =
+			PublicKey::from_secret_key(&secp_ctx, &SecretKey::from_slice(&[42; 32]).unwrap());
+		OfferBuilder::new(signing_pubkey).amount_msats(amount_msat).build().unwrap()
+	}
+
+	#[test]
+	fn test_parse_uri_with_invoice() {
+		let invoice = invoice(50_000_000);
+		let uri = format!("BITCOIN:{}?amount=0.0005&lightning={}", ADDRESS, invoice);
+		let payment_uri = parse_payment_uri(&uri, Network::Regtest).unwrap();
+
+		assert_eq!(payment_uri.address.unwrap().to_string(), ADDRESS);
+		assert_eq!(payment_uri.amount_sats, Some(50_000));
+		assert_eq!(payment_uri.invoice, Some(invoice));
+		assert!(payment_uri.offer.is_none());
+
+		// Invoices for other networks are rejected.
+		assert!(parse_payment_uri(&uri, Network::Bitcoin).is_err());
+	}
+
+	#[test]
+	fn test_parse_uri_with_only_address() {
+		let uri = format!("bitcoin:{}", ADDRESS);
+		let payment_uri = parse_payment_uri(&uri, Network::Regtest).unwrap();
+		assert_eq!(payment_uri.address.unwrap().to_string(), ADDRESS);
+		assert_eq!(payment_uri.amount_sats, None);
+		assert!(payment_uri.invoice.is_none() && payment_uri.offer.is_none());
+
+		// Without an amount, there is nothing we can pay.
+		let payment_uri = parse_payment_uri(&uri, Network::Regtest).unwrap();
+		assert!(choose_rail(&payment_uri, u64::MAX, true).is_err());
+		let uri = format!("bitcoin:{}?amount=1.5&label=coffee", ADDRESS);
+		let payment_uri = parse_payment_uri(&uri, Network::Regtest).unwrap();
+		assert_eq!(payment_uri.amount_sats, Some(150_000_000));
+		assert_eq!(choose_rail(&payment_uri, 0, true).unwrap(), PaymentRail::Onchain);
+
+		assert!(parse_payment_uri(ADDRESS, Network::Regtest).is_err());
+		// Addresses for another network are rejected.
+		let uri = format!("bitcoin:{}", ADDRESS);
+		let err = parse_payment_uri(&uri, Network::Bitcoin).err().unwrap();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert_eq!(err.validation_errors[0].field, "uri");
+		let uri = format!("bitcoin:{}?amount=1&req-unknown=1", ADDRESS);
+		assert!(parse_payment_uri(&uri, Network::Regtest).is_err());
+	}
+
+	#[test]
+	fn test_choose_rail_falls_back() {
+		let mut payment_uri = PaymentUri {
+			address: Some(Address::from_str(ADDRESS).unwrap().assume_checked()),
+			amount_sats: Some(50_000),
+			invoice: Some(invoice(50_000_000)),
+			offer: Some(offer(50_000_000)),
+		};
+
+		// Offers are preferred over invoices, which are preferred over paying on-chain.
+		assert_eq!(choose_rail(&payment_uri, 50_000_000, true).unwrap(), PaymentRail::Bolt12);
+		assert_eq!(choose_rail(&payment_uri, 50_000_000, false).unwrap(), PaymentRail::Bolt11);
+		assert_eq!(choose_rail(&payment_uri, 49_999_999, true).unwrap(), PaymentRail::Onchain);
+
+		// Without an address, a lack of Lightning liquidity is reported as such.
+		payment_uri.address = None;
+		let err = choose_rail(&payment_uri, 10_000_000, true).unwrap_err();
+		assert_eq!(err.error_code, InsufficientFundsError);
+		assert_eq!(err.needed_sats, Some(50_000));
+		assert_eq!(err.available_sats, Some(10_000));
+	}
+
+	#[test]
+	fn test_parse_btc_amount() {
+		assert_eq!(parse_btc_amount("1"), Some(100_000_000));
+		assert_eq!(parse_btc_amount("0.00000001"), Some(1));
+		assert_eq!(parse_btc_amount(".5"), Some(50_000_000));
+		assert_eq!(parse_btc_amount("21000000"), Some(MAX_MONEY_SATS));
+		assert_eq!(parse_btc_amount("21000000.00000001"), None);
+		assert_eq!(parse_btc_amount("0.000000001"), None);
+		assert_eq!(parse_btc_amount("1e3"), None);
+		assert_eq!(parse_btc_amount(""), None);
+		assert_eq!(parse_btc_amount("."), None);
+		// Amounts overflowing `u64` in satoshis are rejected rather than wrapped.
+		assert_eq!(parse_btc_amount(&u64::MAX.to_string()), None);
+		assert_eq!(parse_btc_amount("184467440737.09551616"), None);
+	}
+}
@@ -0,0 +1,34 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
+use ldk_server_protos::api::{PingRequest, PingResponse};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) const PING_PATH: &str = "Ping";
+
+pub(crate) fn handle_ping_request(
+	_context: Context, request: PingRequest,
+) -> Result<PingResponse, LdkServerError> {
+	Ok(ping_response(request))
+}
+
+fn ping_response(request: PingRequest) -> PingResponse {
+	let server_time_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+	PingResponse { nonce: request.nonce, server_time_ms }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bytes::Bytes;
+
+	#[test]
+	fn test_ping_response() {
+		let before_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+		let nonce = Bytes::from_static(b"nonce");
+		let response = ping_response(PingRequest { nonce: nonce.clone() });
+		let after_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+
+		assert_eq!(response.nonce, nonce);
+		assert!(before_ms <= response.server_time_ms && response.server_time_ms <= after_ms);
+	}
+}
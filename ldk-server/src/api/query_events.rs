@@ -0,0 +1,186 @@
+use crate::api::error::LdkServerErrorCode::InternalServerError;
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::events::{read_event_records, EventRecord};
+use crate::service::Context;
+use crate::util::metrics::EVENT_TYPES;
+use crate::util::proto_adapter::event_record_to_proto;
+use ldk_server_protos::api::{QueryEventsRequest, QueryEventsResponse};
+
+pub(crate) const QUERY_EVENTS_PATH: &str = "QueryEvents";
+
+/// The maximum number of events returned in a single page.
+const QUERY_EVENTS_MAX_PAGE_SIZE: usize = 100;
+
+pub(crate) fn handle_query_events_request(
+	context: Context, request: QueryEventsRequest,
+) -> Result<QueryEventsResponse, LdkServerError> {
+	let filter = EventFilter::try_from(&request)?;
+	let offset = match request.page_token {
+		Some(page_token) => {
+			context.page_token_codec.decode_offset(&page_token, QUERY_EVENTS_PATH)? as usize
+		},
+		None => 0,
+	};
+
+	let records = read_event_records(context.paginated_kv_store.as_ref()).map_err(|e| {
+		LdkServerError::new(InternalServerError, format!("Failed to read events: {}", e))
+	})?;
+	let (records, next_offset) =
+		query_events_page(records, &filter, offset, QUERY_EVENTS_MAX_PAGE_SIZE);
+	let next_page_token = next_offset.map(|next_offset| {
+		context.page_token_codec.encode_offset(QUERY_EVENTS_PATH, next_offset as u64)
+	});
+
+	let events = records.into_iter().map(event_record_to_proto).collect();
+
+	let response = QueryEventsResponse { events, next_page_token };
+	Ok(response)
+}
+
+// The filters of a `QueryEventsRequest`.
+#[derive(Debug, Default)]
+struct EventFilter {
+	event_types: Vec<String>,
+	start_ms: Option<u64>,
+	end_ms: Option<u64>,
+}
+
+impl EventFilter {
+	fn matches(&self, record: &EventRecord) -> bool {
+		(self.event_types.is_empty() || self.event_types.contains(&record.event_type))
+			&& self.start_ms.map_or(true, |start_ms| record.timestamp_ms >= start_ms)
+			&& self.end_ms.map_or(true, |end_ms| record.timestamp_ms < end_ms)
+	}
+}
+
+impl TryFrom<&QueryEventsRequest> for EventFilter {
+	type Error = LdkServerError;
+
+	fn try_from(request: &QueryEventsRequest) -> Result<Self, LdkServerError> {
+		let mut validation_errors = Vec::new();
+
+		for event_type in &request.event_types {
+			if !EVENT_TYPES.contains(&event_type.as_str()) {
+				validation_errors.push(ValidationError::new(
+					"event_types",
+					format!("unknown event type {}", event_type),
+				));
+			}
+		}
+		if let (Some(start_ms), Some(end_ms)) = (request.start_ms, request.end_ms) {
+			if start_ms > end_ms {
+				validation_errors.push(ValidationError::new("end_ms", "must not precede start_ms"));
+			}
+		}
+
+		if !validation_errors.is_empty() {
+			return Err(LdkServerError::invalid_fields(validation_errors));
+		}
+		Ok(EventFilter {
+			event_types: request.event_types.clone(),
+			start_ms: request.start_ms,
+			end_ms: request.end_ms,
+		})
+	}
+}
+
+// Returns the page of `records`, given along with their event ids, matching `filter` starting at
+// `offset`, along with the offset of the next page, if any. Events are ordered oldest first, so that
+// offsets remain stable as new events are persisted.
+fn query_events_page(
+	mut records: Vec<(String, EventRecord)>, filter: &EventFilter, offset: usize, page_size: usize,
+) -> (Vec<EventRecord>, Option<usize>) {
+	records.retain(|(_, record)| filter.matches(record));
+	records.sort_unstable_by(|(a_id, a), (b_id, b)| {
+		a.timestamp_ms.cmp(&b.timestamp_ms).then_with(|| a_id.cmp(b_id))
+	});
+
+	let next_offset = offset.saturating_add(page_size);
+	let next_offset = if next_offset < records.len() { Some(next_offset) } else { None };
+
+	let records = records.into_iter().skip(offset).take(page_size).map(|(_, record)| record);
+	(records.collect(), next_offset)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use crate::util::config::BufferedEventsPolicy;
+	use crate::util::event_log::EventLog;
+	use crate::util::metrics::EventMetrics;
+	use ldk_node::lightning::ln::channelmanager::PaymentId;
+	use ldk_node::lightning::ln::types::ChannelId;
+	use ldk_node::lightning::ln::PaymentHash;
+	use ldk_node::{Event, UserChannelId};
+	use std::time::{Duration, UNIX_EPOCH};
+
+	#[test]
+	fn test_events_are_queried_after_restart() {
+		let storage_path = random_storage_path();
+		let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+		let store = SqliteStore::new(storage_path.clone(), None, None).unwrap();
+		let metrics = EventMetrics::new();
+		let mut event_log = EventLog::load(&store).unwrap();
+		let policy = BufferedEventsPolicy::DropOldest;
+		for i in 0..3u8 {
+			let received = Event::PaymentReceived {
+				payment_id: Some(PaymentId([i; 32])),
+				payment_hash: PaymentHash([i; 32]),
+				amount_msat: 1_000 * (i as u64 + 1),
+			};
+			let now = start + Duration::from_secs(i as u64);
+			event_log.persist(&store, &received, now, 10, policy, &metrics);
+		}
+		let closed = Event::ChannelClosed {
+			channel_id: ChannelId([7; 32]),
+			user_channel_id: UserChannelId(7),
+			counterparty_node_id: None,
+			reason: None,
+		};
+		let now = start + Duration::from_secs(3);
+		event_log.persist(&store, &closed, now, 10, policy, &metrics);
+
+		// As after a restart, a fresh store reads the events back from disk. The test store removes
+		// its directory when dropped, so we keep the first one around.
+		let store = SqliteStore::new(storage_path, None, None).unwrap();
+		let records = read_event_records(&store).unwrap();
+		assert_eq!(records.len(), 4);
+
+		let request = QueryEventsRequest {
+			event_types: vec!["payment_received".to_string()],
+			start_ms: Some(1_700_000_001_000),
+			..Default::default()
+		};
+		let filter = EventFilter::try_from(&request).unwrap();
+		let (page, next_offset) = query_events_page(records.clone(), &filter, 0, 1);
+		assert_eq!(page[0].amount_msat, Some(2_000));
+		assert_eq!(next_offset, Some(1));
+		let (page, next_offset) = query_events_page(records.clone(), &filter, 1, 1);
+		assert_eq!(page[0].amount_msat, Some(3_000));
+		assert_eq!(next_offset, None);
+
+		let (page, _) = query_events_page(records, &EventFilter::default(), 0, 10);
+		let event_types: Vec<_> = page.iter().map(|record| record.event_type.as_str()).collect();
+		assert_eq!(
+			event_types,
+			["payment_received", "payment_received", "payment_received", "channel_closed"]
+		);
+		assert_eq!(page[3].user_channel_id, Some("7".to_string()));
+		assert_eq!(page[3].timestamp_ms, 1_700_000_003_000);
+	}
+
+	#[test]
+	fn test_invalid_filters_are_rejected() {
+		let request = QueryEventsRequest {
+			event_types: vec!["payment_received".to_string(), "payment_sent".to_string()],
+			start_ms: Some(2_000),
+			end_ms: Some(1_000),
+			..Default::default()
+		};
+		let err = EventFilter::try_from(&request).unwrap_err();
+		let fields: Vec<_> = err.validation_errors.iter().map(|e| e.field.as_str()).collect();
+		assert_eq!(fields, ["event_types", "end_ms"]);
+	}
+}
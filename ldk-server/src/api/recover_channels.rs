@@ -0,0 +1,197 @@
+use crate::api::error::LdkServerErrorCode::InternalServerError;
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::imported_monitors::read_imported_monitor_keys;
+use crate::service::Context;
+use ldk_node::bitcoin::hex::{DisplayHex, FromHex};
+use ldk_node::bitcoin::secp256k1::PublicKey;
+use ldk_node::bitcoin::OutPoint;
+use ldk_node::lightning::ln::types::ChannelId;
+use ldk_node::{NodeError, UserChannelId};
+use ldk_server_protos::api::{RecoverChannelsRequest, RecoverChannelsResponse};
+use ldk_server_protos::types::{ChannelRecovery, ChannelRecoveryAction};
+use std::collections::HashSet;
+
+pub(crate) const RECOVER_CHANNELS_PATH: &str = "RecoverChannels";
+
+const RECOVERY_FORCE_CLOSE_REASON: &str = "Recovering funds from an unreachable counterparty.";
+
+// The channels `RecoverChannels` may force-close.
+enum RecoveryScope {
+	// The channels with the given ids.
+	Channels(HashSet<ChannelId>),
+	// The channels whose monitors were imported, identified by the keys of their monitors.
+	ImportedMonitors(HashSet<String>),
+}
+
+impl RecoveryScope {
+	fn contains(&self, channel_id: &ChannelId, funding_txo: Option<OutPoint>) -> bool {
+		match self {
+			RecoveryScope::Channels(channel_ids) => channel_ids.contains(channel_id),
+			RecoveryScope::ImportedMonitors(monitor_keys) => funding_txo
+				.map_or(false, |txo| monitor_keys.contains(&format!("{}_{}", txo.txid, txo.vout))),
+		}
+	}
+}
+
+pub(crate) fn handle_recover_channels_request(
+	context: Context, request: RecoverChannelsRequest,
+) -> Result<RecoverChannelsResponse, LdkServerError> {
+	if !request.confirm_force_close {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"confirm_force_close",
+			"must be set to confirm force-closing channels",
+		)]));
+	}
+
+	let scope = if request.channel_ids.is_empty() {
+		let monitor_keys = read_imported_monitor_keys(context.paginated_kv_store.as_ref())
+			.map_err(|e| {
+				LdkServerError::new(
+					InternalServerError,
+					format!("Failed to read imported channel monitors: {}", e),
+				)
+			})?;
+		RecoveryScope::ImportedMonitors(monitor_keys)
+	} else {
+		let channel_ids = request
+			.channel_ids
+			.iter()
+			.map(|channel_id| <[u8; 32]>::from_hex(channel_id).map(ChannelId))
+			.collect::<Result<_, _>>()
+			.map_err(|_| {
+				LdkServerError::invalid_fields(vec![ValidationError::new(
+					"channel_ids",
+					"must be hex-encoded 32-byte channel ids",
+				)])
+			})?;
+		RecoveryScope::Channels(channel_ids)
+	};
+
+	let connected_peers: HashSet<PublicKey> = context
+		.node
+		.list_peers()
+		.into_iter()
+		.filter(|peer| peer.is_connected)
+		.map(|peer| peer.node_id)
+		.collect();
+	let channels = context
+		.node
+		.list_channels()
+		.into_iter()
+		.filter(|channel| scope.contains(&channel.channel_id, channel.funding_txo))
+		.map(|channel| (channel.channel_id, channel.user_channel_id, channel.counterparty_node_id));
+	let recoveries = recover_channels(channels, &connected_peers, |user_channel_id, node_id| {
+		context.node.force_close_channel(
+			user_channel_id,
+			node_id,
+			Some(RECOVERY_FORCE_CLOSE_REASON.to_string()),
+		)
+	});
+
+	let response = RecoverChannelsResponse { recoveries };
+	Ok(response)
+}
+
+// Force-closes the given channels via `force_close` unless their counterparty is among the
+// `connected_peers`, reporting the action taken for each.
+fn recover_channels<F: FnMut(&UserChannelId, PublicKey) -> Result<(), NodeError>>(
+	channels: impl IntoIterator<Item = (ChannelId, UserChannelId, PublicKey)>,
+	connected_peers: &HashSet<PublicKey>, mut force_close: F,
+) -> Vec<ChannelRecovery> {
+	channels
+		.into_iter()
+		.map(|(channel_id, user_channel_id, counterparty_node_id)| {
+			let (action, error) = if connected_peers.contains(&counterparty_node_id) {
+				(ChannelRecoveryAction::PeerConnected, None)
+			} else {
+				match force_close(&user_channel_id, counterparty_node_id) {
+					Ok(()) => (ChannelRecoveryAction::ForceClosed, None),
+					Err(e) => (ChannelRecoveryAction::ForceCloseFailed, Some(e.to_string())),
+				}
+			};
+			ChannelRecovery {
+				channel_id: channel_id.0.to_lower_hex_string(),
+				counterparty_node_id: counterparty_node_id.to_string(),
+				action: action as i32,
+				error,
+			}
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::imported_monitors::write_imported_monitor_record;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use ldk_node::bitcoin::hashes::Hash;
+	use ldk_node::bitcoin::Txid;
+	use std::str::FromStr;
+
+	#[test]
+	fn test_recover_channels() {
+		let connected_peer = PublicKey::from_str(
+			"02eec7245d6b7d2ccb30380bfbe2a3648cd7a942653f5aa340edcea1f283686619",
+		)
+		.unwrap();
+		let unreachable_peer = PublicKey::from_str(
+			"0324653eac434488002cc06bbfb7f10fe18991e35f9fe4302dbea6d2353dc0ab1c",
+		)
+		.unwrap();
+		let funding_txo = |vout| OutPoint { txid: Txid::from_byte_array([9; 32]), vout };
+		let channels = vec![
+			(ChannelId([1; 32]), UserChannelId(1), connected_peer, Some(funding_txo(1))),
+			(ChannelId([2; 32]), UserChannelId(2), unreachable_peer, Some(funding_txo(2))),
+			(ChannelId([3; 32]), UserChannelId(3), unreachable_peer, Some(funding_txo(3))),
+			(ChannelId([4; 32]), UserChannelId(4), unreachable_peer, Some(funding_txo(4))),
+			(ChannelId([5; 32]), UserChannelId(5), unreachable_peer, None),
+		];
+
+		// The monitors of all but the fourth and fifth channel were imported.
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		for vout in 1..=3 {
+			let txo = funding_txo(vout);
+			write_imported_monitor_record(&store, &format!("{}_{}", txo.txid, txo.vout)).unwrap();
+		}
+		let scope = RecoveryScope::ImportedMonitors(read_imported_monitor_keys(&store).unwrap());
+		let channels = channels
+			.into_iter()
+			.filter(|(channel_id, _, _, funding_txo)| scope.contains(channel_id, *funding_txo))
+			.map(|(channel_id, user_channel_id, node_id, _)| {
+				(channel_id, user_channel_id, node_id)
+			});
+
+		let mut force_closed = Vec::new();
+		let recoveries =
+			recover_channels(channels, &HashSet::from([connected_peer]), |user_channel_id, _| {
+				force_closed.push(user_channel_id.0);
+				if user_channel_id.0 == 3 {
+					return Err(NodeError::ChannelClosingFailed);
+				}
+				Ok(())
+			});
+
+		// Only the imported channels with the unreachable peer went through the recovery path.
+		assert_eq!(force_closed, vec![2, 3]);
+		let actions: Vec<i32> = recoveries.iter().map(|recovery| recovery.action).collect();
+		assert_eq!(
+			actions,
+			vec![
+				ChannelRecoveryAction::PeerConnected as i32,
+				ChannelRecoveryAction::ForceClosed as i32,
+				ChannelRecoveryAction::ForceCloseFailed as i32,
+			]
+		);
+		assert_eq!(recoveries[1].channel_id, "02".repeat(32));
+		assert_eq!(recoveries[1].error, None);
+		assert!(recoveries[2].error.is_some());
+	}
+
+	#[test]
+	fn test_recovery_scope_of_listed_channels() {
+		let scope = RecoveryScope::Channels(HashSet::from([ChannelId([1; 32])]));
+		assert!(scope.contains(&ChannelId([1; 32]), None));
+		assert!(!scope.contains(&ChannelId([2; 32]), None));
+	}
+}
@@ -0,0 +1,189 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, InvalidRequestError};
+use crate::service::Context;
+use crate::util::auth::effective_auth_token;
+use crate::util::config::{load_config, Config};
+use ldk_server_protos::api::{ReloadConfigRequest, ReloadConfigResponse};
+
+pub(crate) const RELOAD_CONFIG_PATH: &str = "ReloadConfig";
+
+pub(crate) fn handle_reload_config_request(
+	context: Context, _request: ReloadConfigRequest,
+) -> Result<ReloadConfigResponse, LdkServerError> {
+	let mut new_config = load_config(context.config_path.as_ref()).map_err(|e| {
+		LdkServerError::new(InvalidRequestError, format!("Invalid configuration: {}", e))
+	})?;
+	// A token set via `RotateAuthToken` supersedes the one in the config file it replaced.
+	new_config.auth_token = effective_auth_token(
+		context.paginated_kv_store.as_ref(),
+		new_config.auth_token,
+	)
+	.map_err(|e| {
+		LdkServerError::new(InternalServerError, format!("Failed to read auth token: {}", e))
+	})?;
+
+	let mut config = context.config.write().unwrap();
+	let updated_settings = updated_settings(&config, &new_config)?;
+
+	context
+		.chain_source_breaker
+		.set_policy(new_config.chain_source_failure_threshold, new_config.chain_source_retry_after);
+	*config = new_config;
+
+	let response = ReloadConfigResponse {
+		updated_settings: updated_settings.into_iter().map(String::from).collect(),
+	};
+	Ok(response)
+}
+
+// Returns the names of the settings that changed between `current` and `new`, failing if any of
+// them can't be changed at runtime.
+fn updated_settings(current: &Config, new: &Config) -> Result<Vec<&'static str>, LdkServerError> {
+	let immutable_settings = [
+		("listening_address", current.listening_addr != new.listening_addr),
+		("network", current.network != new.network),
+		("rest_service_address", current.rest_service_addr != new.rest_service_addr),
+		("storage_dir_path", current.storage_dir_path != new.storage_dir_path),
+		("bitcoind_rpc_address", current.bitcoind_rpc_addr != new.bitcoind_rpc_addr),
+		("bitcoind_rpc_user", current.bitcoind_rpc_user != new.bitcoind_rpc_user),
+		("bitcoind_rpc_password", current.bitcoind_rpc_password != new.bitcoind_rpc_password),
+		("node_alias", current.node_alias != new.node_alias),
+		("max_connections", current.max_connections != new.max_connections),
+		("auth_token", current.auth_token != new.auth_token),
+		(
+			"allow_unauthenticated_ping",
+			current.allow_unauthenticated_ping != new.allow_unauthenticated_ping,
+		),
+		("trusted_peers_0conf", current.trusted_peers_0conf != new.trusted_peers_0conf),
+	];
+	let changed_immutable_settings: Vec<&str> =
+		immutable_settings.iter().filter(|(_, changed)| *changed).map(|(name, _)| *name).collect();
+	if !changed_immutable_settings.is_empty() {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			format!(
+				"Settings can only be changed by restarting the server: {}",
+				changed_immutable_settings.join(", ")
+			),
+		));
+	}
+
+	let mutable_settings = [
+		("header_read_timeout_secs", current.header_read_timeout != new.header_read_timeout),
+		(
+			"chain_source_failure_threshold",
+			current.chain_source_failure_threshold != new.chain_source_failure_threshold,
+		),
+		(
+			"chain_source_retry_after_secs",
+			current.chain_source_retry_after != new.chain_source_retry_after,
+		),
+		("disabled_endpoints", current.disabled_endpoints != new.disabled_endpoints),
+		("max_routing_fee_msat", current.max_routing_fee_msat != new.max_routing_fee_msat),
+		("require_request_nonce", current.require_request_nonce != new.require_request_nonce),
+		("request_nonce_window_secs", current.request_nonce_window != new.request_nonce_window),
+		(
+			"max_invoice_description_len",
+			current.max_invoice_description_len != new.max_invoice_description_len,
+		),
+		(
+			"default_invoice_expiry_secs",
+			current.default_invoice_expiry_secs != new.default_invoice_expiry_secs,
+		),
+		(
+			"list_supported_endpoints",
+			current.list_supported_endpoints != new.list_supported_endpoints,
+		),
+		(
+			"onchain_receive_dedup_window_secs",
+			current.onchain_receive_dedup_window != new.onchain_receive_dedup_window,
+		),
+		(
+			"outbound_payment_timeout_secs",
+			current.outbound_payment_timeout != new.outbound_payment_timeout,
+		),
+		("max_buffered_events", current.max_buffered_events != new.max_buffered_events),
+		("buffered_events_policy", current.buffered_events_policy != new.buffered_events_policy),
+		("cors_allowed_origins", current.cors.allowed_origins != new.cors.allowed_origins),
+		("cors_allowed_methods", current.cors.allowed_methods != new.cors.allowed_methods),
+		("cors_allowed_headers", current.cors.allowed_headers != new.cors.allowed_headers),
+	];
+	Ok(mutable_settings.iter().filter(|(_, changed)| *changed).map(|(name, _)| *name).collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode;
+	use crate::util::config::{
+		BufferedEventsPolicy, DEFAULT_INVOICE_EXPIRY_SECS, DEFAULT_MAX_BUFFERED_EVENTS,
+		DEFAULT_REQUEST_NONCE_WINDOW_SECS, MAX_INVOICE_DESCRIPTION_LEN,
+	};
+	use crate::util::cors::CorsPolicy;
+	use ldk_node::bitcoin::Network;
+	use ldk_node::lightning::ln::msgs::SocketAddress;
+	use std::collections::HashSet;
+	use std::str::FromStr;
+	use std::time::Duration;
+
+	fn test_config() -> Config {
+		Config {
+			listening_addr: SocketAddress::from_str("localhost:3001").unwrap(),
+			network: Network::Regtest,
+			rest_service_addr: "127.0.0.1:3002".parse().unwrap(),
+			storage_dir_path: "/tmp".to_string(),
+			bitcoind_rpc_addr: "127.0.0.1:8332".parse().unwrap(),
+			bitcoind_rpc_user: "user".to_string(),
+			bitcoind_rpc_password: "password".to_string(),
+			node_alias: None,
+			max_connections: 128,
+			header_read_timeout: Duration::from_secs(30),
+			chain_source_failure_threshold: 5,
+			chain_source_retry_after: Duration::from_secs(30),
+			disabled_endpoints: HashSet::new(),
+			max_routing_fee_msat: None,
+			require_request_nonce: false,
+			request_nonce_window: Duration::from_secs(DEFAULT_REQUEST_NONCE_WINDOW_SECS),
+			max_invoice_description_len: MAX_INVOICE_DESCRIPTION_LEN,
+			default_invoice_expiry_secs: DEFAULT_INVOICE_EXPIRY_SECS,
+			list_supported_endpoints: false,
+			auth_token: None,
+			allow_unauthenticated_ping: false,
+			onchain_receive_dedup_window: None,
+			outbound_payment_timeout: None,
+			cors: CorsPolicy::default(),
+			trusted_peers_0conf: Vec::new(),
+			max_buffered_events: DEFAULT_MAX_BUFFERED_EVENTS,
+			buffered_events_policy: BufferedEventsPolicy::DropOldest,
+		}
+	}
+
+	#[test]
+	fn test_updated_settings() {
+		let current = test_config();
+		assert!(updated_settings(&current, &current).unwrap().is_empty());
+
+		let mut new = test_config();
+		new.chain_source_retry_after = Duration::from_secs(60);
+		new.disabled_endpoints.insert("OnchainSend".to_string());
+		assert_eq!(
+			updated_settings(&current, &new).unwrap(),
+			vec!["chain_source_retry_after_secs", "disabled_endpoints"]
+		);
+
+		let mut new = test_config();
+		new.cors.allowed_origins.insert("https://dashboard.example.com".to_string());
+		new.cors.allowed_methods.push("GET".to_string());
+		new.cors.allowed_headers.push("X-Custom".to_string());
+		assert_eq!(
+			updated_settings(&current, &new).unwrap(),
+			vec!["cors_allowed_origins", "cors_allowed_methods", "cors_allowed_headers"]
+		);
+
+		new.network = Network::Bitcoin;
+		new.max_connections = 1;
+		let err = updated_settings(&current, &new).unwrap_err();
+		assert_eq!(err.error_code, LdkServerErrorCode::InvalidRequestError);
+		assert!(err.message.ends_with("network, max_connections"));
+	}
+}
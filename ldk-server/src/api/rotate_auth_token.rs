@@ -0,0 +1,172 @@
+use crate::api::error::LdkServerErrorCode::{AuthError, InternalServerError};
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::io::rotated_auth_token::{
+	read_rotated_auth_token_record, write_rotated_auth_token_record, RotatedAuthTokenRecord,
+};
+use crate::service::Context;
+use crate::util::auth::{RetiredAuthToken, AUTH_TOKEN_GRACE_PERIOD};
+use ldk_server_protos::api::{RotateAuthTokenRequest, RotateAuthTokenResponse};
+use std::time::SystemTime;
+
+pub(crate) const ROTATE_AUTH_TOKEN_PATH: &str = "RotateAuthToken";
+
+pub(crate) fn handle_rotate_auth_token_request(
+	context: Context, request: RotateAuthTokenRequest,
+) -> Result<RotateAuthTokenResponse, LdkServerError> {
+	// Requests are authenticated against the config, so holding its lock swaps the tokens atomically.
+	let mut config = context.config.write().unwrap();
+	rotate_auth_token(
+		context.paginated_kv_store.as_ref(),
+		&mut config.auth_token,
+		&context.retired_auth_token,
+		request.new_token,
+		SystemTime::now(),
+	)?;
+	Ok(RotateAuthTokenResponse { grace_period_secs: AUTH_TOKEN_GRACE_PERIOD.as_secs() as u32 })
+}
+
+// Replaces `auth_token` with `new_token` at `now`, retiring the replaced token. The new token is
+// persisted to `store` first, such that it stays in effect across restarts and config reloads.
+fn rotate_auth_token(
+	store: &dyn PaginatedKVStore, auth_token: &mut Option<String>,
+	retired_auth_token: &RetiredAuthToken, new_token: String, now: SystemTime,
+) -> Result<(), LdkServerError> {
+	if auth_token.is_none() {
+		return Err(LdkServerError::new(
+			AuthError,
+			"Rotating the auth token requires an auth_token to be configured.",
+		));
+	}
+	// The token is sent in the `Authorization` header, so it must be a valid header value.
+	if new_token.is_empty() || !new_token.bytes().all(|b| b.is_ascii_graphic()) {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"new_token",
+			"must be non-empty printable ASCII without whitespace",
+		)]));
+	}
+	if auth_token.as_deref() == Some(new_token.as_str()) {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"new_token",
+			"must differ from the current auth token",
+		)]));
+	}
+
+	let persist_failed = |e| {
+		LdkServerError::new(InternalServerError, format!("Failed to persist auth token: {}", e))
+	};
+	// Keep track of the token in the config file across repeated rotations.
+	let configured_auth_token =
+		match read_rotated_auth_token_record(store).map_err(persist_failed)? {
+			Some(record) if Some(&record.auth_token) == auth_token.as_ref() => {
+				record.configured_auth_token
+			},
+			_ => auth_token.clone(),
+		};
+	let record = RotatedAuthTokenRecord { auth_token: new_token.clone(), configured_auth_token };
+	write_rotated_auth_token_record(store, &record).map_err(persist_failed)?;
+
+	// unwrap safety: we checked an auth token is configured above.
+	let replaced_token = auth_token.replace(new_token).unwrap();
+	retired_auth_token.retire(replaced_token, now);
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use crate::util::auth::{check_rotated_bearer_token, effective_auth_token};
+
+	#[test]
+	fn test_rotated_token_is_accepted_along_with_new_one_briefly() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let mut auth_token = Some("old-token".to_string());
+		let retired_auth_token = RetiredAuthToken::new();
+		let rotated_at = SystemTime::now();
+		rotate_auth_token(
+			&store,
+			&mut auth_token,
+			&retired_auth_token,
+			"new-token".to_string(),
+			rotated_at,
+		)
+		.unwrap();
+		assert_eq!(auth_token.as_deref(), Some("new-token"));
+
+		let check = |authorization: &[u8], now| {
+			check_rotated_bearer_token(
+				auth_token.as_deref(),
+				&retired_auth_token,
+				Some(authorization),
+				now,
+			)
+		};
+		assert!(check(b"Bearer old-token", rotated_at).is_ok());
+		assert!(check(b"Bearer new-token", rotated_at).is_ok());
+
+		let after_grace_period = rotated_at + AUTH_TOKEN_GRACE_PERIOD;
+		assert_eq!(
+			check(b"Bearer old-token", after_grace_period).unwrap_err().error_code,
+			AuthError
+		);
+		assert!(check(b"Bearer new-token", after_grace_period).is_ok());
+	}
+
+	#[test]
+	fn test_invalid_rotations_are_rejected() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let retired_auth_token = RetiredAuthToken::new();
+		let now = SystemTime::now();
+		let mut auth_token = None;
+		let err =
+			rotate_auth_token(&store, &mut auth_token, &retired_auth_token, "new".to_string(), now)
+				.unwrap_err();
+		assert_eq!(err.error_code, AuthError);
+		assert_eq!(auth_token, None);
+
+		let mut auth_token = Some("old".to_string());
+		for new_token in ["", "has space", "old"] {
+			let err = rotate_auth_token(
+				&store,
+				&mut auth_token,
+				&retired_auth_token,
+				new_token.to_string(),
+				now,
+			)
+			.unwrap_err();
+			assert_eq!(err.error_code, InvalidRequestError);
+			assert_eq!(err.validation_errors[0].field, "new_token");
+		}
+		assert_eq!(auth_token.as_deref(), Some("old"));
+		assert_eq!(effective_auth_token(&store, Some("old".to_string())).unwrap().unwrap(), "old");
+	}
+
+	#[test]
+	fn test_rotated_token_survives_restart_until_config_changes() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let retired_auth_token = RetiredAuthToken::new();
+		let now = SystemTime::now();
+		let mut auth_token = Some("configured".to_string());
+		for new_token in ["first", "second"] {
+			rotate_auth_token(
+				&store,
+				&mut auth_token,
+				&retired_auth_token,
+				new_token.to_string(),
+				now,
+			)
+			.unwrap();
+		}
+
+		// On restart or reload the rotated token supersedes the one still in the config file.
+		let configured = Some("configured".to_string());
+		assert_eq!(effective_auth_token(&store, configured).unwrap().as_deref(), Some("second"));
+
+		// Once the config file is edited, its token takes precedence again.
+		let edited = Some("edited".to_string());
+		assert_eq!(effective_auth_token(&store, edited).unwrap().as_deref(), Some("edited"));
+	}
+}
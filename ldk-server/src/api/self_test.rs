@@ -0,0 +1,113 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
+use crate::util::bitcoind_rpc::call_bitcoind_rpc;
+use crate::util::wallet_sync::sync_wallets;
+use ldk_server_protos::api::{SelfTestRequest, SelfTestResponse};
+use ldk_server_protos::types::SelfTestCheck;
+
+pub(crate) const SELF_TEST_PATH: &str = "SelfTest";
+
+pub(crate) fn handle_self_test_request(
+	context: Context, _request: SelfTestRequest,
+) -> Result<SelfTestResponse, LdkServerError> {
+	let config = context.config.read().unwrap().clone();
+	let chain_tip_height = || {
+		let blockchain_info =
+			call_bitcoind_rpc(&config, "getblockchaininfo", &[]).map_err(|e| e.to_string())?;
+		blockchain_info["blocks"]
+			.as_u64()
+			.ok_or_else(|| "chain source returned invalid blockchain info".to_string())
+	};
+	let sync = || sync_wallets(&context).map_err(|e| e.message);
+	let usable_channels =
+		context.node.list_channels().iter().filter(|channel| channel.is_usable).count();
+	let fee_rate_cache_update_timestamp =
+		context.node.status().latest_fee_rate_cache_update_timestamp;
+	Ok(self_test_response(run_checks(
+		chain_tip_height,
+		sync,
+		usable_channels,
+		fee_rate_cache_update_timestamp,
+	)))
+}
+
+// Runs the self-test's checks, given how to query the chain source for its `chain_tip_height` and
+// how to `sync` the wallets, along with the number of `usable_channels` and when the fee estimator
+// last updated its fee rates, if ever.
+fn run_checks<H, S>(
+	chain_tip_height: H, sync: S, usable_channels: usize,
+	fee_rate_cache_update_timestamp: Option<u64>,
+) -> Vec<SelfTestCheck>
+where
+	H: FnOnce() -> Result<u64, String>,
+	S: FnOnce() -> Result<(), String>,
+{
+	let check = |name: &str, result: Result<String, String>| {
+		let (passed, detail) = match result {
+			Ok(detail) => (true, detail),
+			Err(detail) => (false, detail),
+		};
+		SelfTestCheck { name: name.to_string(), passed, detail }
+	};
+	vec![
+		check("chain_source", chain_tip_height().map(|height| format!("chain tip at {}", height))),
+		check("wallet_sync", sync().map(|()| "wallets synced".to_string())),
+		check(
+			"usable_channel",
+			match usable_channels {
+				0 => Err("no usable channel".to_string()),
+				count => Ok(format!("{} usable channels", count)),
+			},
+		),
+		// LDK Node falls back to default fee rates if it has none, so we check for an update.
+		check(
+			"fee_estimator",
+			fee_rate_cache_update_timestamp
+				.map(|timestamp| format!("fee rates updated at {}", timestamp))
+				.ok_or_else(|| "fee rates never updated".to_string()),
+		),
+	]
+}
+
+fn self_test_response(checks: Vec<SelfTestCheck>) -> SelfTestResponse {
+	SelfTestResponse { passed: checks.iter().all(|check| check.passed), checks }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_each_check_reports() {
+		let response = self_test_response(run_checks(|| Ok(144), || Ok(()), 2, Some(1700000000)));
+		assert!(response.passed);
+		let names: Vec<_> = response.checks.iter().map(|check| check.name.as_str()).collect();
+		assert_eq!(names, ["chain_source", "wallet_sync", "usable_channel", "fee_estimator"]);
+		assert_eq!(response.checks[0].detail, "chain tip at 144");
+		assert_eq!(response.checks[2].detail, "2 usable channels");
+	}
+
+	#[test]
+	fn test_down_chain_source_fails_check() {
+		let connection_refused = || "Connection refused (os error 111)".to_string();
+		let response = self_test_response(run_checks(
+			|| Err(connection_refused()),
+			|| Err(connection_refused()),
+			1,
+			Some(1700000000),
+		));
+		assert!(!response.passed);
+		let failed: Vec<_> = response
+			.checks
+			.iter()
+			.filter(|check| !check.passed)
+			.map(|check| check.name.as_str())
+			.collect();
+		assert_eq!(failed, ["chain_source", "wallet_sync"]);
+		assert_eq!(response.checks[0].detail, connection_refused());
+
+		let response = self_test_response(run_checks(|| Ok(144), || Ok(()), 0, None));
+		assert!(!response.passed);
+		assert!(!response.checks[2].passed && !response.checks[3].passed);
+	}
+}
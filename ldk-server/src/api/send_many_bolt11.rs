@@ -0,0 +1,183 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use crate::util::amount::{validate_amount_msat, validate_invoice_amount};
+use crate::util::fee_limit::routing_fee_limit;
+use crate::util::payment_invoice::record_bolt11_invoice;
+use crate::util::proto_adapter::to_error_response;
+use crate::util::request_nonce::check_request_nonce;
+use bytes::Bytes;
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_node::lightning_invoice::Bolt11Invoice;
+use ldk_node::payment::SendingParameters;
+use ldk_server_protos::api::{SendManyBolt11Request, SendManyBolt11Response};
+use ldk_server_protos::types::{PaymentRail, SendManyBolt11Payment, SendManyBolt11Result};
+use std::str::FromStr;
+
+pub(crate) const SEND_MANY_BOLT11_PATH: &str = "SendManyBolt11";
+
+/// The maximum number of payments a single request may make.
+const MAX_BATCH_SIZE: usize = 100;
+
+pub(crate) fn handle_send_many_bolt11_request(
+	context: Context, request: SendManyBolt11Request,
+) -> Result<SendManyBolt11Response, LdkServerError> {
+	if request.payments.is_empty() || request.payments.len() > MAX_BATCH_SIZE {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"payments",
+			format!("must contain between 1 and {} payments", MAX_BATCH_SIZE),
+		)]));
+	}
+	check_request_nonce(&context, request.request_nonce.as_deref())?;
+	if let Some(max_routing_fee_msat) = request.max_routing_fee_msat {
+		validate_amount_msat("max_routing_fee_msat", max_routing_fee_msat)?;
+	}
+
+	let configured_max_msat = context.config.read().unwrap().max_routing_fee_msat;
+	let fee_limit_msat = routing_fee_limit(
+		configured_max_msat,
+		request.max_routing_fee_msat,
+		request.override_fee_limit,
+	)?;
+	let sending_parameters = fee_limit_msat.map(|limit_msat| SendingParameters {
+		max_total_routing_fee_msat: Some(Some(limit_msat)),
+		max_total_cltv_expiry_delta: None,
+		max_path_count: None,
+		max_channel_saturation_power_of_half: None,
+	});
+
+	let results = send_many(&request.payments, |payment, invoice| {
+		let payment_id = match payment.amount_msat {
+			None => context.node.bolt11_payment().send(invoice, sending_parameters.clone())?,
+			Some(amount_msat) => context.node.bolt11_payment().send_using_amount(
+				invoice,
+				amount_msat,
+				sending_parameters.clone(),
+			)?,
+		};
+		record_bolt11_invoice(context.paginated_kv_store.as_ref(), &payment_id, invoice);
+		context.payment_metrics.record_payment(
+			PaymentRail::Bolt11,
+			payment.amount_msat.or(invoice.amount_milli_satoshis()),
+		);
+		Ok(payment_id)
+	});
+	Ok(SendManyBolt11Response { results })
+}
+
+// Validates each of the `payments` and makes the valid ones via `send`, in order. Payments that
+// are invalid or fail to be sent are reported in their result rather than aborting the batch.
+fn send_many<
+	F: FnMut(&SendManyBolt11Payment, &Bolt11Invoice) -> Result<PaymentId, LdkServerError>,
+>(
+	payments: &[SendManyBolt11Payment], mut send: F,
+) -> Vec<SendManyBolt11Result> {
+	payments
+		.iter()
+		.map(|payment| {
+			let res = validate_payment(payment).and_then(|invoice| send(payment, &invoice));
+			match res {
+				Ok(payment_id) => SendManyBolt11Result {
+					payment_id: Some(Bytes::from(payment_id.0.to_vec())),
+					error: None,
+				},
+				Err(e) => {
+					SendManyBolt11Result { payment_id: None, error: Some(to_error_response(e).0) }
+				},
+			}
+		})
+		.collect()
+}
+
+fn validate_payment(payment: &SendManyBolt11Payment) -> Result<Bolt11Invoice, LdkServerError> {
+	if let Some(amount_msat) = payment.amount_msat {
+		validate_amount_msat("amount_msat", amount_msat)?;
+	}
+	let invoice = Bolt11Invoice::from_str(&payment.invoice)
+		.map_err(|_| ldk_node::NodeError::InvalidInvoice)?;
+	validate_invoice_amount("invoice", &invoice)?;
+	Ok(invoice)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::bitcoin::hashes::{sha256, Hash};
+	use ldk_node::bitcoin::secp256k1::{Secp256k1, SecretKey};
+	use ldk_node::lightning::ln::types::PaymentSecret;
+	use ldk_node::lightning_invoice::{Currency, InvoiceBuilder};
+	use ldk_server_protos::error::ErrorCode;
+
+	fn invoice(id: u8) -> String {
+		let secp_ctx = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&[42; 32]).unwrap();
+		InvoiceBuilder::new(Currency::Regtest)
+			.description("salary".to_string())
+			.payment_hash(sha256::Hash::hash(&[id; 32]))
+			.payment_secret(PaymentSecret([id; 32]))
+			.current_timestamp()
+			.min_final_cltv_expiry_delta(144)
+			.amount_milli_satoshis(100_000)
+			.build_signed(|hash| secp_ctx.sign_ecdsa_recoverable(hash, &secret_key))
+			.unwrap()
+			.to_string()
+	}
+
+	#[test]
+	fn test_invalid_payment_does_not_abort_batch() {
+		let payments = [
+			SendManyBolt11Payment { invoice: invoice(1), amount_msat: None },
+			SendManyBolt11Payment { invoice: "lnbcrt1invalid".to_string(), amount_msat: None },
+			SendManyBolt11Payment { invoice: invoice(3), amount_msat: Some(150_000) },
+		];
+
+		let mut sent = Vec::new();
+		let results = send_many(&payments, |payment, invoice| {
+			sent.push(payment.invoice.clone());
+			Ok(PaymentId(*invoice.payment_hash().as_byte_array()))
+		});
+
+		assert_eq!(sent, [payments[0].invoice.clone(), payments[2].invoice.clone()]);
+		assert_eq!(results.len(), 3);
+		for (result, id) in [(&results[0], 1), (&results[2], 3)] {
+			let payment_hash = sha256::Hash::hash(&[id; 32]);
+			assert_eq!(result.payment_id.as_deref(), Some(&payment_hash.as_byte_array()[..]));
+			assert!(result.error.is_none());
+		}
+		let error = results[1].error.as_ref().unwrap();
+		assert_eq!(error.error_code, ErrorCode::InvalidRequestError as i32);
+		assert!(results[1].payment_id.is_none());
+	}
+
+	#[test]
+	fn test_u64_max_amount_is_rejected() {
+		let payments = [
+			SendManyBolt11Payment { invoice: invoice(1), amount_msat: Some(u64::MAX) },
+			SendManyBolt11Payment { invoice: invoice(2), amount_msat: None },
+		];
+		let results = send_many(&payments, |_, invoice| {
+			Ok(PaymentId(*invoice.payment_hash().as_byte_array()))
+		});
+		let error = results[0].error.as_ref().unwrap();
+		assert_eq!(error.error_code, ErrorCode::InvalidRequestError as i32);
+		assert!(error.message.contains("amount_msat"));
+		assert!(results[1].payment_id.is_some());
+	}
+
+	#[test]
+	fn test_failed_send_does_not_abort_batch() {
+		let payments: Vec<_> = (1..=3)
+			.map(|id| SendManyBolt11Payment { invoice: invoice(id), amount_msat: None })
+			.collect();
+		let mut attempts = 0;
+		let results = send_many(&payments, |_, invoice| {
+			attempts += 1;
+			if attempts == 1 {
+				return Err(ldk_node::NodeError::DuplicatePayment.into());
+			}
+			Ok(PaymentId(*invoice.payment_hash().as_byte_array()))
+		});
+		assert_eq!(attempts, 3);
+		assert!(results[0].error.is_some());
+		assert!(results[1..].iter().all(|result| result.payment_id.is_some()));
+	}
+}
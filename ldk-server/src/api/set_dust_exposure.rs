@@ -0,0 +1,76 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use ldk_node::config::{ChannelConfig, MaxDustHTLCExposure};
+use ldk_server_protos::api::{SetDustExposureRequest, SetDustExposureResponse};
+use std::ops::RangeInclusive;
+
+pub(crate) const SET_DUST_EXPOSURE_PATH: &str = "SetDustExposure";
+
+/// The range of dust exposure limits operators may set. Lower limits reject nearly all dust HTLCs,
+/// rendering channels unusable for small payments, while higher limits leave channels open to
+/// losing significant amounts to dust attacks.
+const DUST_EXPOSURE_LIMIT_RANGE_MSAT: RangeInclusive<u64> = 1_000..=1_000_000_000;
+
+pub(crate) fn handle_set_dust_exposure_request(
+	context: Context, request: SetDustExposureRequest,
+) -> Result<SetDustExposureResponse, LdkServerError> {
+	let limit_msat = request.max_dust_htlc_exposure_msat;
+	if !DUST_EXPOSURE_LIMIT_RANGE_MSAT.contains(&limit_msat) {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"max_dust_htlc_exposure_msat",
+			format!(
+				"must be between {} and {}",
+				DUST_EXPOSURE_LIMIT_RANGE_MSAT.start(),
+				DUST_EXPOSURE_LIMIT_RANGE_MSAT.end()
+			),
+		)]));
+	}
+
+	let channels = context.node.list_channels();
+	for channel in &channels {
+		context.node.update_channel_config(
+			&channel.user_channel_id,
+			channel.counterparty_node_id,
+			with_fixed_dust_exposure(channel.config, limit_msat),
+		)?;
+	}
+	Ok(SetDustExposureResponse { updated_channel_count: channels.len() as u32 })
+}
+
+// Returns `config` with its dust exposure limited to `limit_msat`, leaving its other settings as is.
+fn with_fixed_dust_exposure(config: ChannelConfig, limit_msat: u64) -> ChannelConfig {
+	ChannelConfig {
+		max_dust_htlc_exposure: MaxDustHTLCExposure::FixedLimit { limit_msat },
+		..config
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::proto_adapter::max_dust_htlc_exposure_to_proto;
+	use ldk_server_protos::types::channel_dust_exposure::MaxDustHtlcExposure;
+
+	#[test]
+	fn test_set_dust_exposure_reads_back() {
+		let config = ChannelConfig {
+			forwarding_fee_base_msat: 1_000,
+			max_dust_htlc_exposure: MaxDustHTLCExposure::FeeRateMultiplier { multiplier: 10_000 },
+			..ChannelConfig::default()
+		};
+		let updated_config = with_fixed_dust_exposure(config, 2_500_000);
+
+		assert_eq!(
+			max_dust_htlc_exposure_to_proto(updated_config.max_dust_htlc_exposure),
+			MaxDustHtlcExposure::FixedLimitMsat(2_500_000)
+		);
+		assert_eq!(updated_config.forwarding_fee_base_msat, 1_000);
+		assert_eq!(
+			updated_config,
+			ChannelConfig {
+				max_dust_htlc_exposure: MaxDustHTLCExposure::FixedLimit { limit_msat: 2_500_000 },
+				..config
+			}
+		);
+	}
+}
@@ -0,0 +1,111 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, InvalidRequestError};
+use crate::io::inbound_invoices::{read_inbound_invoice_record, write_inbound_invoice_record};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::service::Context;
+use hex::{DisplayHex, FromHex};
+use ldk_node::bitcoin::hashes::{sha256, Hash};
+use ldk_node::lightning::ln::{PaymentHash, PaymentPreimage};
+use ldk_node::NodeError;
+use ldk_server_protos::api::{SettleHoldInvoiceRequest, SettleHoldInvoiceResponse};
+
+pub(crate) const SETTLE_HOLD_INVOICE_PATH: &str = "SettleHoldInvoice";
+
+pub(crate) fn handle_settle_hold_invoice_request(
+	context: Context, request: SettleHoldInvoiceRequest,
+) -> Result<SettleHoldInvoiceResponse, LdkServerError> {
+	let preimage = <[u8; 32]>::from_hex(&request.preimage)
+		.map(PaymentPreimage)
+		.map_err(|_| NodeError::InvalidPaymentPreimage)?;
+
+	settle_held_payment(
+		context.paginated_kv_store.as_ref(),
+		preimage,
+		|payment_hash, amount_msat| {
+			context.node.bolt11_payment().claim_for_hash(payment_hash, amount_msat, preimage)
+		},
+	)?;
+
+	Ok(SettleHoldInvoiceResponse {})
+}
+
+// Claims the payment held for the hold invoice of `preimage` via `claim`, clearing the held amount
+// from its record once claimed.
+fn settle_held_payment<F: FnOnce(PaymentHash, u64) -> Result<(), NodeError>>(
+	store: &dyn PaginatedKVStore, preimage: PaymentPreimage, claim: F,
+) -> Result<(), LdkServerError> {
+	let payment_hash = PaymentHash(sha256::Hash::hash(&preimage.0).to_byte_array());
+	let payment_hash_hex = payment_hash.0.to_lower_hex_string();
+	let mut record = read_inbound_invoice_record(store, &payment_hash_hex)
+		.map_err(|e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to read inbound invoice record: {}", e),
+			)
+		})?
+		.ok_or(NodeError::InvalidPaymentHash)?;
+
+	let claimable_amount_msat = record.claimable_amount_msat.ok_or_else(|| {
+		LdkServerError::new(
+			InvalidRequestError,
+			format!("No payment is currently held for payment hash {}", payment_hash),
+		)
+	})?;
+
+	claim(payment_hash, claimable_amount_msat)?;
+
+	// The payment is no longer held, so it can't be settled again.
+	record.claimable_amount_msat = None;
+	write_inbound_invoice_record(store, &payment_hash_hex, &record).map_err(|e| {
+		LdkServerError::new(
+			InternalServerError,
+			format!("Failed to persist inbound invoice record: {}", e),
+		)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::inbound_invoices::InboundInvoiceRecord;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+
+	#[test]
+	fn test_settle_claims_held_amount_once() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let preimage = PaymentPreimage([42; 32]);
+		let payment_hash = PaymentHash(sha256::Hash::hash(&preimage.0).to_byte_array());
+		let payment_hash_hex = payment_hash.0.to_lower_hex_string();
+
+		let claim = |_, _| -> Result<(), NodeError> { panic!("nothing to claim") };
+		let err = settle_held_payment(&store, preimage, claim).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+
+		let record = InboundInvoiceRecord {
+			expected_amount_msat: Some(1000),
+			preimage: None,
+			require_exact_amount: false,
+			claimable_amount_msat: None,
+			scope: None,
+			expires_at: None,
+		};
+		write_inbound_invoice_record(&store, &payment_hash_hex, &record).unwrap();
+		let err = settle_held_payment(&store, preimage, claim).unwrap_err();
+		assert!(err.message.contains("No payment is currently held"));
+
+		let held_record = InboundInvoiceRecord { claimable_amount_msat: Some(1500), ..record };
+		write_inbound_invoice_record(&store, &payment_hash_hex, &held_record).unwrap();
+		let mut claimed = Vec::new();
+		settle_held_payment(&store, preimage, |hash, amount_msat| {
+			claimed.push((hash, amount_msat));
+			Ok(())
+		})
+		.unwrap();
+		assert_eq!(claimed, vec![(payment_hash, 1500)]);
+
+		let record = read_inbound_invoice_record(&store, &payment_hash_hex).unwrap().unwrap();
+		assert_eq!(record.claimable_amount_msat, None);
+		assert!(settle_held_payment(&store, preimage, claim).is_err());
+	}
+}
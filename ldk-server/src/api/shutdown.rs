@@ -0,0 +1,71 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{AuthError, InvalidRequestError};
+use crate::service::Context;
+use ldk_server_protos::api::{ShutdownRequest, ShutdownResponse};
+use tokio::sync::Notify;
+
+pub(crate) const SHUTDOWN_PATH: &str = "Shutdown";
+
+pub(crate) fn handle_shutdown_request(
+	context: Context, request: ShutdownRequest,
+) -> Result<ShutdownResponse, LdkServerError> {
+	let auth_configured = context.config.read().unwrap().auth_token.is_some();
+	request_shutdown(
+		auth_configured,
+		&context.node.node_id().to_string(),
+		&request.confirmation,
+		&context.shutdown_signal,
+	)?;
+	Ok(ShutdownResponse {})
+}
+
+// Notifies `shutdown_signal` if requests are authenticated and the `confirmation` names the node,
+// given by its `node_id`. The server then stops once this request was answered.
+fn request_shutdown(
+	auth_configured: bool, node_id: &str, confirmation: &str, shutdown_signal: &Notify,
+) -> Result<(), LdkServerError> {
+	if !auth_configured {
+		return Err(LdkServerError::new(
+			AuthError,
+			"Shutting down requires an auth_token to be configured.",
+		));
+	}
+	if !confirmation.eq_ignore_ascii_case(node_id) {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			"The confirmation must be the id of the node to shut down.",
+		));
+	}
+	// Stores a permit if the event loop isn't waiting right now, so the request is never lost.
+	shutdown_signal.notify_one();
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	const NODE_ID: &str = "02eadbd9e7557375161df8b646776a547c5cbc2e95b3071ec81553f8ec2cea3b8c";
+
+	async fn is_notified(shutdown_signal: &Notify) -> bool {
+		tokio::time::timeout(Duration::from_millis(10), shutdown_signal.notified()).await.is_ok()
+	}
+
+	#[tokio::test]
+	async fn test_shutdown_requires_confirmation() {
+		let shutdown_signal = Notify::new();
+
+		let err = request_shutdown(true, NODE_ID, "yes", &shutdown_signal).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert!(!is_notified(&shutdown_signal).await);
+
+		// Unauthenticated servers can't be shut down, not even with the correct confirmation.
+		let err = request_shutdown(false, NODE_ID, NODE_ID, &shutdown_signal).unwrap_err();
+		assert_eq!(err.error_code, AuthError);
+		assert!(!is_notified(&shutdown_signal).await);
+
+		request_shutdown(true, NODE_ID, &NODE_ID.to_uppercase(), &shutdown_signal).unwrap();
+		assert!(is_notified(&shutdown_signal).await);
+	}
+}
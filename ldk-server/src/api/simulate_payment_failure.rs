@@ -0,0 +1,19 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use ldk_server_protos::api::{SimulatePaymentFailureRequest, SimulatePaymentFailureResponse};
+use ldk_server_protos::types::PaymentFailureReason;
+
+pub(crate) const SIMULATE_PAYMENT_FAILURE_PATH: &str = "SimulatePaymentFailure";
+
+pub(crate) fn handle_simulate_payment_failure_request(
+	context: Context, request: SimulatePaymentFailureRequest,
+) -> Result<SimulatePaymentFailureResponse, LdkServerError> {
+	let reason = PaymentFailureReason::from_i32(request.reason).ok_or_else(|| {
+		LdkServerError::invalid_fields(vec![ValidationError::new(
+			"reason",
+			"unknown payment failure reason",
+		)])
+	})?;
+	context.simulated_payment_failure.set(reason);
+	Ok(SimulatePaymentFailureResponse {})
+}
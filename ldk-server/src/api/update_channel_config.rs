@@ -1,63 +1,76 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::service::Context;
+use crate::util::amount::{validate_amount_msat, validate_amount_sats};
 use ldk_node::bitcoin::secp256k1::PublicKey;
 use ldk_node::config::{ChannelConfig, MaxDustHTLCExposure};
-use ldk_node::{Node, UserChannelId};
+use ldk_node::UserChannelId;
 use ldk_server_protos::api::{UpdateChannelConfigRequest, UpdateChannelConfigResponse};
 use ldk_server_protos::types::channel_config::MaxDustHtlcExposure;
 use std::str::FromStr;
-use std::sync::Arc;
 
 pub(crate) const UPDATE_CHANNEL_CONFIG_PATH: &str = "UpdateChannelConfig";
 
 pub(crate) fn handle_update_channel_config_request(
-	node: Arc<Node>, request: UpdateChannelConfigRequest,
-) -> Result<UpdateChannelConfigResponse, ldk_node::NodeError> {
+	context: Context, request: UpdateChannelConfigRequest,
+) -> Result<UpdateChannelConfigResponse, LdkServerError> {
 	let user_channel_id: u128 =
 		request.user_channel_id.parse().map_err(|_| ldk_node::NodeError::InvalidChannelId)?;
 
 	//FIXME: Use ldk/ldk-node's partial config update api.
-	let current_config = node
+	let current_config = context
+		.node
 		.list_channels()
 		.into_iter()
 		.find(|c| c.user_channel_id.0 == user_channel_id)
-		.ok_or_else(|| ldk_node::NodeError::InvalidChannelId)?
+		.ok_or(ldk_node::NodeError::InvalidChannelId)?
 		.config;
 
+	let proto_channel_config = request.channel_config.ok_or_else(|| {
+		LdkServerError::invalid_fields(vec![ValidationError::new("channel_config", "is required")])
+	})?;
 	let updated_channel_config =
-		build_updated_channel_config(current_config, request.channel_config.unwrap());
+		build_updated_channel_config(current_config, proto_channel_config)?;
 
 	let counterparty_node_id = PublicKey::from_str(&request.counterparty_node_id)
 		.map_err(|_| ldk_node::NodeError::InvalidPublicKey)?;
-	node.update_channel_config(
+	context.node.update_channel_config(
 		&UserChannelId(user_channel_id),
 		counterparty_node_id,
 		updated_channel_config,
-	)
-	.map_err(ldk_node::NodeError::from)?;
+	)?;
 
 	Ok(UpdateChannelConfigResponse {})
 }
 
 fn build_updated_channel_config(
 	current_config: ChannelConfig, proto_channel_config: ldk_server_protos::types::ChannelConfig,
-) -> ChannelConfig {
-	let max_dust_htlc_exposure = proto_channel_config
-		.max_dust_htlc_exposure
-		.map(|max_dust_htlc_exposure| match max_dust_htlc_exposure {
-			MaxDustHtlcExposure::FixedLimitMsat(limit_msat) => {
-				MaxDustHTLCExposure::FixedLimit { limit_msat }
-			},
-			MaxDustHtlcExposure::FeeRateMultiplier(multiplier) => {
-				MaxDustHTLCExposure::FeeRateMultiplier { multiplier }
-			},
-		})
-		.unwrap_or(current_config.max_dust_htlc_exposure);
+) -> Result<ChannelConfig, LdkServerError> {
+	let max_dust_htlc_exposure = match proto_channel_config.max_dust_htlc_exposure {
+		Some(MaxDustHtlcExposure::FixedLimitMsat(limit_msat)) => {
+			validate_amount_msat("fixed_limit_msat", limit_msat)?;
+			MaxDustHTLCExposure::FixedLimit { limit_msat }
+		},
+		Some(MaxDustHtlcExposure::FeeRateMultiplier(multiplier)) => {
+			MaxDustHTLCExposure::FeeRateMultiplier { multiplier }
+		},
+		None => current_config.max_dust_htlc_exposure,
+	};
 
-	let cltv_expiry_delta = proto_channel_config
-		.cltv_expiry_delta
-		.map(|c| u16::try_from(c).unwrap())
-		.unwrap_or(current_config.cltv_expiry_delta);
+	let cltv_expiry_delta = match proto_channel_config.cltv_expiry_delta {
+		Some(cltv_expiry_delta) => u16::try_from(cltv_expiry_delta).map_err(|_| {
+			LdkServerError::invalid_fields(vec![ValidationError::new(
+				"cltv_expiry_delta",
+				format!("must be at most {}", u16::MAX),
+			)])
+		})?,
+		None => current_config.cltv_expiry_delta,
+	};
 
-	ChannelConfig {
+	if let Some(max_fee_sats) = proto_channel_config.force_close_avoidance_max_fee_satoshis {
+		validate_amount_sats("force_close_avoidance_max_fee_satoshis", max_fee_sats)?;
+	}
+
+	Ok(ChannelConfig {
 		forwarding_fee_proportional_millionths: proto_channel_config
 			.forwarding_fee_proportional_millionths
 			.unwrap_or(current_config.forwarding_fee_proportional_millionths),
@@ -72,5 +85,51 @@ fn build_updated_channel_config(
 		accept_underpaying_htlcs: proto_channel_config
 			.accept_underpaying_htlcs
 			.unwrap_or(current_config.accept_underpaying_htlcs),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_out_of_range_values_are_rejected() {
+		let update = |proto_channel_config| {
+			build_updated_channel_config(ChannelConfig::default(), proto_channel_config)
+		};
+		let proto_channel_config = ldk_server_protos::types::ChannelConfig {
+			cltv_expiry_delta: Some(144),
+			max_dust_htlc_exposure: Some(MaxDustHtlcExposure::FixedLimitMsat(5_000_000)),
+			..Default::default()
+		};
+		let config = update(proto_channel_config.clone()).unwrap();
+		assert_eq!(config.cltv_expiry_delta, 144);
+
+		for (field, proto_channel_config) in [
+			(
+				"fixed_limit_msat",
+				ldk_server_protos::types::ChannelConfig {
+					max_dust_htlc_exposure: Some(MaxDustHtlcExposure::FixedLimitMsat(u64::MAX)),
+					..proto_channel_config.clone()
+				},
+			),
+			(
+				"cltv_expiry_delta",
+				ldk_server_protos::types::ChannelConfig {
+					cltv_expiry_delta: Some(u32::MAX),
+					..proto_channel_config.clone()
+				},
+			),
+			(
+				"force_close_avoidance_max_fee_satoshis",
+				ldk_server_protos::types::ChannelConfig {
+					force_close_avoidance_max_fee_satoshis: Some(u64::MAX),
+					..proto_channel_config.clone()
+				},
+			),
+		] {
+			let err = update(proto_channel_config).unwrap_err();
+			assert_eq!(err.validation_errors[0].field, field);
+		}
 	}
 }
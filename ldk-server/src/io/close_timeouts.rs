@@ -0,0 +1,64 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which the timeouts of cooperative channel closes are persisted.
+pub(crate) const CLOSE_TIMEOUTS_PRIMARY_NAMESPACE: &str = "close_timeouts";
+
+/// The secondary namespace under which the timeouts of cooperative channel closes are persisted.
+pub(crate) const CLOSE_TIMEOUTS_SECONDARY_NAMESPACE: &str = "";
+
+/// When a cooperative close still not completed times out, keyed by the user channel id.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CloseTimeoutRecord {
+	/// The hex-encoded public key of the channel's counterparty.
+	pub(crate) counterparty_node_id: String,
+	/// The time, in seconds since the UNIX epoch, after which the close times out.
+	pub(crate) timeout_at_secs: u64,
+	/// Whether the channel is force-closed once the close timed out.
+	pub(crate) force_close_on_timeout: bool,
+}
+
+pub(crate) fn write_close_timeout_record(
+	store: &dyn PaginatedKVStore, user_channel_id: &str, record: &CloseTimeoutRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		CLOSE_TIMEOUTS_PRIMARY_NAMESPACE,
+		CLOSE_TIMEOUTS_SECONDARY_NAMESPACE,
+		user_channel_id,
+		time,
+		&buf,
+	)
+}
+
+/// Returns the record for the given `user_channel_id`, or `None` if no close of the channel has a
+/// timeout.
+pub(crate) fn read_close_timeout_record(
+	store: &dyn PaginatedKVStore, user_channel_id: &str,
+) -> io::Result<Option<CloseTimeoutRecord>> {
+	match store.read(
+		CLOSE_TIMEOUTS_PRIMARY_NAMESPACE,
+		CLOSE_TIMEOUTS_SECONDARY_NAMESPACE,
+		user_channel_id,
+	) {
+		Ok(buf) => serde_json::from_slice(&buf)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+pub(crate) fn remove_close_timeout_record(
+	store: &dyn PaginatedKVStore, user_channel_id: &str,
+) -> io::Result<()> {
+	store.remove(
+		CLOSE_TIMEOUTS_PRIMARY_NAMESPACE,
+		CLOSE_TIMEOUTS_SECONDARY_NAMESPACE,
+		user_channel_id,
+		false,
+	)
+}
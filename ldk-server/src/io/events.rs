@@ -0,0 +1,67 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// The primary namespace under which the events emitted by the node are persisted.
+pub(crate) const EVENTS_PRIMARY_NAMESPACE: &str = "events";
+
+/// The secondary namespace under which the events emitted by the node are persisted.
+pub(crate) const EVENTS_SECONDARY_NAMESPACE: &str = "";
+
+/// An event emitted by the node, keyed by an identifier unique to it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct EventRecord {
+	/// The type of the event, as labelled in the metrics.
+	pub(crate) event_type: String,
+	/// The time the event was handled, in milliseconds since the UNIX epoch.
+	pub(crate) timestamp_ms: u64,
+	pub(crate) channel_id: Option<String>,
+	pub(crate) user_channel_id: Option<String>,
+	pub(crate) counterparty_node_id: Option<String>,
+	pub(crate) payment_id: Option<String>,
+	pub(crate) payment_hash: Option<String>,
+	pub(crate) amount_msat: Option<u64>,
+	pub(crate) fee_paid_msat: Option<u64>,
+	pub(crate) reason: Option<String>,
+}
+
+/// Persists the given `record`, ordered by the time the event was handled.
+pub(crate) fn write_event_record(
+	store: &dyn PaginatedKVStore, event_id: &str, record: &EventRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	store.write(
+		EVENTS_PRIMARY_NAMESPACE,
+		EVENTS_SECONDARY_NAMESPACE,
+		event_id,
+		record.timestamp_ms as i64,
+		&buf,
+	)
+}
+
+/// Returns all persisted records along with their event ids, the most recent first.
+pub(crate) fn read_event_records(
+	store: &dyn PaginatedKVStore,
+) -> io::Result<Vec<(String, EventRecord)>> {
+	let mut records = Vec::new();
+	let mut page_token = None;
+	loop {
+		let response =
+			store.list(EVENTS_PRIMARY_NAMESPACE, EVENTS_SECONDARY_NAMESPACE, page_token)?;
+		for event_id in response.keys {
+			let buf =
+				store.read(EVENTS_PRIMARY_NAMESPACE, EVENTS_SECONDARY_NAMESPACE, &event_id)?;
+			let record = serde_json::from_slice(&buf)
+				.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+			records.push((event_id, record));
+		}
+		match response.next_page_token {
+			Some(next_page_token) => page_token = Some(next_page_token),
+			None => return Ok(records),
+		}
+	}
+}
+
+pub(crate) fn remove_event_record(store: &dyn PaginatedKVStore, event_id: &str) -> io::Result<()> {
+	store.remove(EVENTS_PRIMARY_NAMESPACE, EVENTS_SECONDARY_NAMESPACE, event_id, false)
+}
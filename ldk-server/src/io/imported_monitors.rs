@@ -0,0 +1,45 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use std::collections::HashSet;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which the channel monitors imported from backups are recorded.
+pub(crate) const IMPORTED_MONITORS_PRIMARY_NAMESPACE: &str = "imported_monitors";
+
+/// The secondary namespace under which the channel monitors imported from backups are recorded.
+pub(crate) const IMPORTED_MONITORS_SECONDARY_NAMESPACE: &str = "";
+
+/// Records that the channel monitor stored under `monitor_key`, i.e., the funding outpoint of its
+/// channel, was imported from a backup.
+pub(crate) fn write_imported_monitor_record(
+	store: &dyn PaginatedKVStore, monitor_key: &str,
+) -> io::Result<()> {
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		IMPORTED_MONITORS_PRIMARY_NAMESPACE,
+		IMPORTED_MONITORS_SECONDARY_NAMESPACE,
+		monitor_key,
+		time,
+		&[],
+	)
+}
+
+/// Returns the keys of all channel monitors imported from backups.
+pub(crate) fn read_imported_monitor_keys(
+	store: &dyn PaginatedKVStore,
+) -> io::Result<HashSet<String>> {
+	let mut keys = HashSet::new();
+	let mut page_token = None;
+	loop {
+		let response = store.list(
+			IMPORTED_MONITORS_PRIMARY_NAMESPACE,
+			IMPORTED_MONITORS_SECONDARY_NAMESPACE,
+			page_token,
+		)?;
+		keys.extend(response.keys);
+		match response.next_page_token {
+			Some(next_page_token) => page_token = Some(next_page_token),
+			None => return Ok(keys),
+		}
+	}
+}
@@ -0,0 +1,53 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which records of invoice groups created by us are persisted.
+pub(crate) const INVOICE_GROUPS_PRIMARY_NAMESPACE: &str = "invoice_groups";
+
+/// The secondary namespace under which records of invoice groups created by us are persisted.
+pub(crate) const INVOICE_GROUPS_SECONDARY_NAMESPACE: &str = "";
+
+/// Server-side bookkeeping for a group of BOLT11 invoices fulfilling a single order, keyed by the
+/// group's id.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct InvoiceGroupRecord {
+	/// The total amount of the order.
+	pub(crate) total_amount_msat: u64,
+
+	/// The hex-encoded payment hashes of the group's invoices.
+	pub(crate) payment_hashes: Vec<String>,
+
+	/// The account the group's invoices were issued for, to which payments are attributed.
+	#[serde(default)]
+	pub(crate) scope: Option<String>,
+}
+
+pub(crate) fn write_invoice_group_record(
+	store: &dyn PaginatedKVStore, group_id: &str, record: &InvoiceGroupRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		INVOICE_GROUPS_PRIMARY_NAMESPACE,
+		INVOICE_GROUPS_SECONDARY_NAMESPACE,
+		group_id,
+		time,
+		&buf,
+	)
+}
+
+/// Returns the record for the given `group_id`, or `None` if the group is unknown.
+pub(crate) fn read_invoice_group_record(
+	store: &dyn PaginatedKVStore, group_id: &str,
+) -> io::Result<Option<InvoiceGroupRecord>> {
+	match store.read(INVOICE_GROUPS_PRIMARY_NAMESPACE, INVOICE_GROUPS_SECONDARY_NAMESPACE, group_id)
+	{
+		Ok(buf) => serde_json::from_slice(&buf)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
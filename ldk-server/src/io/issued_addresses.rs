@@ -0,0 +1,74 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which records of on-chain addresses handed out by us are persisted.
+pub(crate) const ISSUED_ADDRESSES_PRIMARY_NAMESPACE: &str = "issued_addresses";
+
+/// The secondary namespace under which records of on-chain addresses handed out by us are
+/// persisted.
+pub(crate) const ISSUED_ADDRESSES_SECONDARY_NAMESPACE: &str = "";
+
+/// Server-side bookkeeping for an on-chain address handed out via `OnchainReceive`, keyed by the
+/// address.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct IssuedAddressRecord {
+	/// The account the address was handed out to, to which deposits are attributed.
+	#[serde(default)]
+	pub(crate) scope: Option<String>,
+}
+
+pub(crate) fn write_issued_address_record(
+	store: &dyn PaginatedKVStore, address: &str, record: &IssuedAddressRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		ISSUED_ADDRESSES_PRIMARY_NAMESPACE,
+		ISSUED_ADDRESSES_SECONDARY_NAMESPACE,
+		address,
+		time,
+		&buf,
+	)
+}
+
+/// Returns the record for the given `address`, or `None` if it wasn't handed out by us.
+pub(crate) fn read_issued_address_record(
+	store: &dyn PaginatedKVStore, address: &str,
+) -> io::Result<Option<IssuedAddressRecord>> {
+	match store.read(
+		ISSUED_ADDRESSES_PRIMARY_NAMESPACE,
+		ISSUED_ADDRESSES_SECONDARY_NAMESPACE,
+		address,
+	) {
+		Ok(buf) => serde_json::from_slice(&buf)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+
+	#[test]
+	fn test_issued_address_records() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let alice = IssuedAddressRecord { scope: Some("alice".to_string()) };
+		let bob = IssuedAddressRecord { scope: Some("bob".to_string()) };
+		let alice_address = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+		let bob_address = "bcrt1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qzf5tl6";
+		write_issued_address_record(&store, alice_address, &alice).unwrap();
+		write_issued_address_record(&store, bob_address, &bob).unwrap();
+
+		assert_eq!(read_issued_address_record(&store, alice_address).unwrap(), Some(alice));
+		assert_eq!(read_issued_address_record(&store, bob_address).unwrap(), Some(bob));
+		let unknown_address = "bcrt1q6rz28mcfaxtmd6v789l9rrlrusdprr9pz3cppk";
+		assert_eq!(read_issued_address_record(&store, unknown_address).unwrap(), None);
+	}
+}
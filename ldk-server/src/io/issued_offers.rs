@@ -0,0 +1,85 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which records of BOLT12 offers issued by us are persisted.
+pub(crate) const ISSUED_OFFERS_PRIMARY_NAMESPACE: &str = "issued_offers";
+
+/// The secondary namespace under which records of BOLT12 offers issued by us are persisted.
+pub(crate) const ISSUED_OFFERS_SECONDARY_NAMESPACE: &str = "";
+
+/// Server-side bookkeeping for a BOLT12 offer issued by us, keyed by its offer id.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct IssuedOfferRecord {
+	/// The account the offer was issued for, to which payments are attributed.
+	#[serde(default)]
+	pub(crate) scope: Option<String>,
+}
+
+pub(crate) fn write_issued_offer_record(
+	store: &dyn PaginatedKVStore, offer_id: &str, record: &IssuedOfferRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		ISSUED_OFFERS_PRIMARY_NAMESPACE,
+		ISSUED_OFFERS_SECONDARY_NAMESPACE,
+		offer_id,
+		time,
+		&buf,
+	)
+}
+
+/// Returns the record for the given hex-encoded `offer_id`, or `None` if the offer wasn't issued
+/// by us.
+pub(crate) fn read_issued_offer_record(
+	store: &dyn PaginatedKVStore, offer_id: &str,
+) -> io::Result<Option<IssuedOfferRecord>> {
+	match store.read(ISSUED_OFFERS_PRIMARY_NAMESPACE, ISSUED_OFFERS_SECONDARY_NAMESPACE, offer_id) {
+		// Offers issued before records were introduced were persisted without a payload.
+		Ok(buf) if buf.is_empty() => Ok(Some(IssuedOfferRecord::default())),
+		Ok(buf) => serde_json::from_slice(&buf)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+
+	#[test]
+	fn test_issued_offer_records() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let alice = IssuedOfferRecord { scope: Some("alice".to_string()) };
+		let bob = IssuedOfferRecord { scope: Some("bob".to_string()) };
+		write_issued_offer_record(&store, "aa", &alice).unwrap();
+		write_issued_offer_record(&store, "bb", &bob).unwrap();
+		write_issued_offer_record(&store, "cc", &IssuedOfferRecord::default()).unwrap();
+
+		assert_eq!(read_issued_offer_record(&store, "aa").unwrap(), Some(alice));
+		assert_eq!(read_issued_offer_record(&store, "bb").unwrap(), Some(bob));
+		assert_eq!(read_issued_offer_record(&store, "cc").unwrap().unwrap().scope, None);
+		assert_eq!(read_issued_offer_record(&store, "dd").unwrap(), None);
+
+		let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+		store
+			.write(
+				ISSUED_OFFERS_PRIMARY_NAMESPACE,
+				ISSUED_OFFERS_SECONDARY_NAMESPACE,
+				"ee",
+				time,
+				&[],
+			)
+			.unwrap();
+		assert_eq!(
+			read_issued_offer_record(&store, "ee").unwrap(),
+			Some(IssuedOfferRecord::default())
+		);
+	}
+}
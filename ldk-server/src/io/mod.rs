@@ -1,3 +1,16 @@
+pub(crate) mod close_timeouts;
+pub(crate) mod events;
+pub(crate) mod imported_monitors;
+pub(crate) mod inbound_invoices;
+pub(crate) mod invoice_groups;
+pub(crate) mod issued_addresses;
+pub(crate) mod issued_offers;
+pub(crate) mod onchain_sends;
 pub(crate) mod paginated_kv_store;
+pub(crate) mod payment_diagnostics;
+pub(crate) mod payment_invoices;
+pub(crate) mod payment_timeouts;
+pub(crate) mod request_nonces;
+pub(crate) mod rotated_auth_token;
 pub(crate) mod sqlite_store;
 pub(crate) mod utils;
@@ -0,0 +1,86 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use hex::DisplayHex;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which records of on-chain sends made with an idempotency key are
+/// persisted.
+pub(crate) const ONCHAIN_SENDS_PRIMARY_NAMESPACE: &str = "onchain_sends";
+
+/// The secondary namespace under which records of on-chain sends made with an idempotency key are
+/// persisted.
+pub(crate) const ONCHAIN_SENDS_SECONDARY_NAMESPACE: &str = "";
+
+/// Server-side bookkeeping for an on-chain send made with an idempotency key, keyed by the key's
+/// hash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct OnchainSendRecord {
+	/// The address the coins were sent to.
+	pub(crate) address: String,
+
+	/// The amount sent, unset if the full balance was sent.
+	pub(crate) amount_sats: Option<u64>,
+
+	/// The id of the broadcast transaction, unset while the send is in progress. A record left
+	/// without it means the send was interrupted, e.g., by a crash, and may or may not have been
+	/// broadcast.
+	pub(crate) txid: Option<String>,
+}
+
+impl OnchainSendRecord {
+	/// Returns whether `other` records a send of the same coins to the same address.
+	pub(crate) fn is_same_send(&self, other: &OnchainSendRecord) -> bool {
+		self.address == other.address && self.amount_sats == other.amount_sats
+	}
+}
+
+// Idempotency keys are chosen by clients and may contain characters which aren't valid in store
+// keys, so records are keyed by their hash instead.
+fn record_key(idempotency_key: &str) -> String {
+	ring::digest::digest(&ring::digest::SHA256, idempotency_key.as_bytes())
+		.as_ref()
+		.to_lower_hex_string()
+}
+
+pub(crate) fn write_onchain_send_record(
+	store: &dyn PaginatedKVStore, idempotency_key: &str, record: &OnchainSendRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		ONCHAIN_SENDS_PRIMARY_NAMESPACE,
+		ONCHAIN_SENDS_SECONDARY_NAMESPACE,
+		&record_key(idempotency_key),
+		time,
+		&buf,
+	)
+}
+
+/// Returns the record for the given `idempotency_key`, or `None` if no send was made with it.
+pub(crate) fn read_onchain_send_record(
+	store: &dyn PaginatedKVStore, idempotency_key: &str,
+) -> io::Result<Option<OnchainSendRecord>> {
+	match store.read(
+		ONCHAIN_SENDS_PRIMARY_NAMESPACE,
+		ONCHAIN_SENDS_SECONDARY_NAMESPACE,
+		&record_key(idempotency_key),
+	) {
+		Ok(buf) => serde_json::from_slice(&buf)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+pub(crate) fn remove_onchain_send_record(
+	store: &dyn PaginatedKVStore, idempotency_key: &str,
+) -> io::Result<()> {
+	store.remove(
+		ONCHAIN_SENDS_PRIMARY_NAMESPACE,
+		ONCHAIN_SENDS_SECONDARY_NAMESPACE,
+		&record_key(idempotency_key),
+		false,
+	)
+}
@@ -0,0 +1,51 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which diagnostics of outbound payments are persisted.
+pub(crate) const PAYMENT_DIAGNOSTICS_PRIMARY_NAMESPACE: &str = "payment_diagnostics";
+
+/// The secondary namespace under which diagnostics of outbound payments are persisted.
+pub(crate) const PAYMENT_DIAGNOSTICS_SECONDARY_NAMESPACE: &str = "";
+
+/// Details of an outbound payment recorded from payment events, keyed by its payment id.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PaymentDiagnosticsRecord {
+	/// The total fee paid to intermediate hops, if the payment succeeded.
+	pub(crate) fee_paid_msat: Option<u64>,
+
+	/// The reason the payment failed as a protobuf `PaymentFailureReason`, if it failed.
+	pub(crate) failure_reason: Option<i32>,
+}
+
+pub(crate) fn write_payment_diagnostics_record(
+	store: &dyn PaginatedKVStore, payment_id: &str, record: &PaymentDiagnosticsRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		PAYMENT_DIAGNOSTICS_PRIMARY_NAMESPACE,
+		PAYMENT_DIAGNOSTICS_SECONDARY_NAMESPACE,
+		payment_id,
+		time,
+		&buf,
+	)
+}
+
+/// Returns the record for the given `payment_id`, or `None` if nothing was recorded for it yet.
+pub(crate) fn read_payment_diagnostics_record(
+	store: &dyn PaginatedKVStore, payment_id: &str,
+) -> io::Result<Option<PaymentDiagnosticsRecord>> {
+	match store.read(
+		PAYMENT_DIAGNOSTICS_PRIMARY_NAMESPACE,
+		PAYMENT_DIAGNOSTICS_SECONDARY_NAMESPACE,
+		payment_id,
+	) {
+		Ok(buf) => serde_json::from_slice(&buf)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
@@ -0,0 +1,52 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which the invoices and offers of outbound payments are persisted.
+pub(crate) const PAYMENT_INVOICES_PRIMARY_NAMESPACE: &str = "payment_invoices";
+
+/// The secondary namespace under which the invoices and offers of outbound payments are persisted.
+pub(crate) const PAYMENT_INVOICES_SECONDARY_NAMESPACE: &str = "";
+
+/// What an outbound payment was sent to, keyed by its payment id.
+///
+/// LDK Node only keeps the payment hash of payments, so we keep the invoice or offer ourselves.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PaymentInvoiceRecord {
+	/// The BOLT11 invoice paid, if any.
+	pub(crate) bolt11_invoice: Option<String>,
+	/// The BOLT12 offer paid, if any.
+	pub(crate) bolt12_offer: Option<String>,
+}
+
+pub(crate) fn write_payment_invoice_record(
+	store: &dyn PaginatedKVStore, payment_id: &str, record: &PaymentInvoiceRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		PAYMENT_INVOICES_PRIMARY_NAMESPACE,
+		PAYMENT_INVOICES_SECONDARY_NAMESPACE,
+		payment_id,
+		time,
+		&buf,
+	)
+}
+
+/// Returns the record for the given `payment_id`, or `None` if none was persisted.
+pub(crate) fn read_payment_invoice_record(
+	store: &dyn PaginatedKVStore, payment_id: &str,
+) -> io::Result<Option<PaymentInvoiceRecord>> {
+	match store.read(
+		PAYMENT_INVOICES_PRIMARY_NAMESPACE,
+		PAYMENT_INVOICES_SECONDARY_NAMESPACE,
+		payment_id,
+	) {
+		Ok(buf) => serde_json::from_slice(&buf)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
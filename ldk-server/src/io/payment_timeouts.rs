@@ -0,0 +1,59 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which the timeouts of outbound payments are persisted.
+pub(crate) const PAYMENT_TIMEOUTS_PRIMARY_NAMESPACE: &str = "payment_timeouts";
+
+/// The secondary namespace under which the timeouts of outbound payments are persisted.
+pub(crate) const PAYMENT_TIMEOUTS_SECONDARY_NAMESPACE: &str = "";
+
+/// When an outbound payment still pending is abandoned, keyed by its payment id.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PaymentTimeoutRecord {
+	/// The time, in seconds since the UNIX epoch, after which the payment is abandoned.
+	pub(crate) timeout_at_secs: u64,
+}
+
+pub(crate) fn write_payment_timeout_record(
+	store: &dyn PaginatedKVStore, payment_id: &str, record: &PaymentTimeoutRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		PAYMENT_TIMEOUTS_PRIMARY_NAMESPACE,
+		PAYMENT_TIMEOUTS_SECONDARY_NAMESPACE,
+		payment_id,
+		time,
+		&buf,
+	)
+}
+
+/// Returns the record for the given `payment_id`, or `None` if the payment has no timeout.
+pub(crate) fn read_payment_timeout_record(
+	store: &dyn PaginatedKVStore, payment_id: &str,
+) -> io::Result<Option<PaymentTimeoutRecord>> {
+	match store.read(
+		PAYMENT_TIMEOUTS_PRIMARY_NAMESPACE,
+		PAYMENT_TIMEOUTS_SECONDARY_NAMESPACE,
+		payment_id,
+	) {
+		Ok(buf) => serde_json::from_slice(&buf)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+pub(crate) fn remove_payment_timeout_record(
+	store: &dyn PaginatedKVStore, payment_id: &str,
+) -> io::Result<()> {
+	store.remove(
+		PAYMENT_TIMEOUTS_PRIMARY_NAMESPACE,
+		PAYMENT_TIMEOUTS_SECONDARY_NAMESPACE,
+		payment_id,
+		false,
+	)
+}
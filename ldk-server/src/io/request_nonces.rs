@@ -0,0 +1,83 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use hex::DisplayHex;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which the nonces of payment-initiating requests are persisted.
+pub(crate) const REQUEST_NONCES_PRIMARY_NAMESPACE: &str = "request_nonces";
+
+/// The secondary namespace under which the nonces of payment-initiating requests are persisted.
+pub(crate) const REQUEST_NONCES_SECONDARY_NAMESPACE: &str = "";
+
+// Nonces are chosen by clients and may contain characters which aren't valid in store keys, so
+// they are keyed by their hash instead.
+fn record_key(nonce: &str) -> String {
+	ring::digest::digest(&ring::digest::SHA256, nonce.as_bytes()).as_ref().to_lower_hex_string()
+}
+
+/// Records `nonce`, created at `created_at` seconds since the UNIX epoch, as used, returning the
+/// key of its record.
+pub(crate) fn write_request_nonce(
+	store: &dyn PaginatedKVStore, nonce: &str, created_at: u64,
+) -> io::Result<String> {
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	let key = record_key(nonce);
+	store.write(
+		REQUEST_NONCES_PRIMARY_NAMESPACE,
+		REQUEST_NONCES_SECONDARY_NAMESPACE,
+		&key,
+		time,
+		&created_at.to_be_bytes(),
+	)?;
+	Ok(key)
+}
+
+/// Returns whether `nonce` was recorded as used.
+pub(crate) fn is_request_nonce_used(store: &dyn PaginatedKVStore, nonce: &str) -> io::Result<bool> {
+	match store.read(
+		REQUEST_NONCES_PRIMARY_NAMESPACE,
+		REQUEST_NONCES_SECONDARY_NAMESPACE,
+		&record_key(nonce),
+	) {
+		Ok(_) => Ok(true),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+		Err(e) => Err(e),
+	}
+}
+
+/// Returns the key and creation time of every recorded nonce.
+pub(crate) fn read_request_nonce_records(
+	store: &dyn PaginatedKVStore,
+) -> io::Result<Vec<(String, u64)>> {
+	let mut records = Vec::new();
+	let mut page_token = None;
+	loop {
+		let response = store.list(
+			REQUEST_NONCES_PRIMARY_NAMESPACE,
+			REQUEST_NONCES_SECONDARY_NAMESPACE,
+			page_token,
+		)?;
+		for key in response.keys {
+			let buf = store.read(
+				REQUEST_NONCES_PRIMARY_NAMESPACE,
+				REQUEST_NONCES_SECONDARY_NAMESPACE,
+				&key,
+			)?;
+			let created_at = buf.try_into().map(u64::from_be_bytes).map_err(|_| {
+				io::Error::new(io::ErrorKind::InvalidData, "Invalid request nonce record")
+			})?;
+			records.push((key, created_at));
+		}
+		match response.next_page_token {
+			Some(next_page_token) => page_token = Some(next_page_token),
+			None => return Ok(records),
+		}
+	}
+}
+
+/// Removes the record with the given `key`, as returned by [`write_request_nonce`].
+pub(crate) fn remove_request_nonce_record(
+	store: &dyn PaginatedKVStore, key: &str,
+) -> io::Result<()> {
+	store.remove(REQUEST_NONCES_PRIMARY_NAMESPACE, REQUEST_NONCES_SECONDARY_NAMESPACE, key, false)
+}
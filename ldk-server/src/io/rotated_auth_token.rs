@@ -0,0 +1,55 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The primary namespace under which the auth token set via `RotateAuthToken` is persisted.
+pub(crate) const ROTATED_AUTH_TOKEN_PRIMARY_NAMESPACE: &str = "rotated_auth_token";
+
+/// The secondary namespace under which the auth token set via `RotateAuthToken` is persisted.
+pub(crate) const ROTATED_AUTH_TOKEN_SECONDARY_NAMESPACE: &str = "";
+
+const ROTATED_AUTH_TOKEN_KEY: &str = "latest";
+
+/// The auth token set by the latest rotation, along with the token configured in the config file
+/// it supersedes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RotatedAuthTokenRecord {
+	/// The auth token set by the latest rotation.
+	pub(crate) auth_token: String,
+
+	/// The auth token configured in the config file when the token was first rotated. The rotated
+	/// token only supersedes this one, such that editing the config file takes precedence again.
+	pub(crate) configured_auth_token: Option<String>,
+}
+
+pub(crate) fn write_rotated_auth_token_record(
+	store: &dyn PaginatedKVStore, record: &RotatedAuthTokenRecord,
+) -> io::Result<()> {
+	let buf = serde_json::to_vec(record).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+	store.write(
+		ROTATED_AUTH_TOKEN_PRIMARY_NAMESPACE,
+		ROTATED_AUTH_TOKEN_SECONDARY_NAMESPACE,
+		ROTATED_AUTH_TOKEN_KEY,
+		time,
+		&buf,
+	)
+}
+
+/// Returns the record of the latest rotation, or `None` if the auth token was never rotated.
+pub(crate) fn read_rotated_auth_token_record(
+	store: &dyn PaginatedKVStore,
+) -> io::Result<Option<RotatedAuthTokenRecord>> {
+	match store.read(
+		ROTATED_AUTH_TOKEN_PRIMARY_NAMESPACE,
+		ROTATED_AUTH_TOKEN_SECONDARY_NAMESPACE,
+		ROTATED_AUTH_TOKEN_KEY,
+	) {
+		Ok(buf) => serde_json::from_slice(&buf)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
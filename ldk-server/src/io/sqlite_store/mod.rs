@@ -20,6 +20,7 @@ const LIST_KEYS_MAX_PAGE_SIZE: i32 = 100;
 
 pub struct SqliteStore {
 	connection: Arc<Mutex<Connection>>,
+	#[allow(dead_code)]
 	data_dir: PathBuf,
 	paginated_kv_table_name: String,
 }
@@ -55,7 +56,7 @@ impl SqliteStore {
 			io::Error::new(io::ErrorKind::Other, msg)
 		})?;
 
-		let sql = format!("SELECT user_version FROM pragma_user_version");
+		let sql = "SELECT user_version FROM pragma_user_version".to_string();
 		let version_res: u16 = connection.query_row(&sql, [], |row| row.get(0)).unwrap();
 
 		if version_res == 0 {
@@ -96,7 +97,7 @@ impl SqliteStore {
 		})?;
 
 		let index_creation_time_sql = format!(
-			"CREATE INDEX idx_creation_time ON {} (creation_time);",
+			"CREATE INDEX IF NOT EXISTS idx_creation_time ON {} (creation_time);",
 			paginated_kv_table_name
 		);
 
@@ -113,6 +114,7 @@ impl SqliteStore {
 	}
 
 	/// Returns the data directory.
+	#[allow(dead_code)]
 	pub fn get_data_dir(&self) -> PathBuf {
 		self.data_dir.clone()
 	}
@@ -326,7 +328,7 @@ impl PaginatedKVStore for SqliteStore {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
 	use super::*;
 	use ldk_node::lightning::util::persist::KVSTORE_NAMESPACE_KEY_MAX_LEN;
 	use rand::distributions::Alphanumeric;
@@ -335,9 +337,8 @@ mod tests {
 
 	impl Drop for SqliteStore {
 		fn drop(&mut self) {
-			match fs::remove_dir_all(&self.data_dir) {
-				Err(e) => println!("Failed to remove test store directory: {}", e),
-				_ => {},
+			if let Err(e) = fs::remove_dir_all(&self.data_dir) {
+				println!("Failed to remove test store directory: {}", e);
 			}
 		}
 	}
@@ -419,8 +420,7 @@ mod tests {
 		assert_eq!(listed_keys.len(), 109);
 
 		// Ensure we have no issue operating with primary_namespace/secondary_namespace/key being KVSTORE_NAMESPACE_KEY_MAX_LEN
-		let max_chars: String =
-			std::iter::repeat('A').take(KVSTORE_NAMESPACE_KEY_MAX_LEN).collect();
+		let max_chars: String = "A".repeat(KVSTORE_NAMESPACE_KEY_MAX_LEN);
 		kv_store.write(&max_chars, &max_chars, &max_chars, 0, &data).unwrap();
 
 		println!("{:?}", listed_keys);
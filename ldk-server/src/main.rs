@@ -3,39 +3,104 @@ mod io;
 mod service;
 mod util;
 
-use crate::service::NodeService;
+use crate::service::{serve_connection, Context, NodeService};
 
-use ldk_node::{Builder, Event, LogLevel};
+use ldk_node::{Builder, Event, LogLevel, Node};
+
+use hyper_util::server::graceful::GracefulShutdown;
 
 use tokio::net::TcpListener;
 use tokio::signal::unix::SignalKind;
+use tokio::sync::{broadcast, Notify, Semaphore};
 
-use hyper::server::conn::http1;
-use hyper_util::rt::TokioIo;
-
+use crate::io::inbound_invoices::{read_inbound_invoice_record, write_inbound_invoice_record};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::io::payment_diagnostics::{write_payment_diagnostics_record, PaymentDiagnosticsRecord};
+use crate::io::sqlite_store::SqliteStore;
+use crate::util::auth::{check_rest_service_exposure, effective_auth_token, RetiredAuthToken};
+use crate::util::circuit_breaker::CircuitBreaker;
+use crate::util::close_timeout::{cancel_close_timeout, escalate_timed_out_closes};
 use crate::util::config::load_config;
+use crate::util::event_log::EventLog;
+use crate::util::metrics::{EventMetrics, PaymentMetrics};
+use crate::util::page_token::PageTokenCodec;
+use crate::util::payment_timeout::abandon_timed_out_payments;
+use crate::util::peer_health::PeerHealthTracker;
+use crate::util::proto_adapter::payment_failure_reason_to_proto;
+use crate::util::receive_address_cache::ReceiveAddressCache;
+use crate::util::request_nonce::RequestNonceTracker;
+#[cfg(feature = "test-hooks")]
+use crate::util::test_hooks::SimulatedPaymentFailure;
+use hex::{DisplayHex, FromHex};
 use ldk_node::config::Config;
-use std::path::Path;
-use std::sync::Arc;
+use ldk_node::io::sqlite_store::{
+	SqliteStore as NodeSqliteStore, KV_TABLE_NAME, SQLITE_DB_FILE_NAME,
+};
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_node::lightning::ln::{PaymentHash, PaymentPreimage};
+use ldk_node::lightning::util::persist::KVStore;
+use rand::RngCore;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+// The number of `ChannelReady` notifications buffered for handlers awaiting channels.
+const CHANNEL_READY_CHANNEL_CAPACITY: usize = 64;
+
+// How often the connection state of our peers is sampled to track their health.
+const PEER_HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// How often outbound payments are checked for having timed out.
+const PAYMENT_TIMEOUT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+// How often cooperative channel closes are checked for having timed out.
+const CLOSE_TIMEOUT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+// How often events past their retention are pruned.
+const EVENT_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// How long we wait for requests in flight to be answered when shutting down.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
 
 fn main() {
 	let args: Vec<String> = std::env::args().collect();
 
-	if args.len() < 2 {
-		eprintln!("Usage: {} config_path", args[0]);
+	let flags = args.get(2..).unwrap_or_default();
+	let insecure = flags.iter().any(|flag| flag == "--insecure");
+	// In recovery mode the node is not started, such that channel monitors can be imported.
+	let recovery = flags.iter().any(|flag| flag == "--recovery");
+	if args.len() < 2 || flags.iter().any(|flag| flag != "--insecure" && flag != "--recovery") {
+		eprintln!("Usage: {} config_path [--insecure] [--recovery]", args[0]);
 		std::process::exit(-1);
 	}
 
 	let mut ldk_node_config = Config::default();
 	let config_file = load_config(Path::new(&args[1])).expect("Invalid configuration file.");
+	if let Err(e) = check_rest_service_exposure(
+		config_file.rest_service_addr,
+		config_file.auth_token.as_deref(),
+		insecure,
+	) {
+		eprintln!("{}", e);
+		std::process::exit(-1);
+	}
+	let shared_config = Arc::new(RwLock::new(config_file.clone()));
 
 	ldk_node_config.log_level = LogLevel::Trace;
-	ldk_node_config.storage_dir_path = config_file.storage_dir_path;
+	ldk_node_config.storage_dir_path = config_file.storage_dir_path.clone();
 	ldk_node_config.listening_addresses = Some(vec![config_file.listening_addr]);
 	ldk_node_config.network = config_file.network;
+	ldk_node_config.trusted_peers_0conf = config_file.trusted_peers_0conf.clone();
 
 	let mut builder = Builder::from_config(ldk_node_config);
 
+	if let Some(node_alias) = config_file.node_alias {
+		if let Err(e) = builder.set_node_alias(node_alias) {
+			eprintln!("Failed to set node alias: {}", e);
+			std::process::exit(-1);
+		}
+	}
+
 	let bitcoind_rpc_addr = config_file.bitcoind_rpc_addr;
 
 	builder.set_chain_source_bitcoind_rpc(
@@ -53,21 +118,56 @@ fn main() {
 		},
 	};
 
-	let node = match builder.build() {
-		Ok(node) => Arc::new(node),
+	let paginated_kv_store: Arc<dyn PaginatedKVStore> =
+		match SqliteStore::new(PathBuf::from(&config_file.storage_dir_path), None, None) {
+			Ok(store) => Arc::new(store),
+			Err(e) => {
+				eprintln!("Failed to create SqliteStore: {:?}", e);
+				std::process::exit(-1);
+			},
+		};
+
+	// A token set via `RotateAuthToken` supersedes the one in the config file it replaced.
+	match effective_auth_token(paginated_kv_store.as_ref(), config_file.auth_token.clone()) {
+		Ok(auth_token) => shared_config.write().unwrap().auth_token = auth_token,
 		Err(e) => {
-			eprintln!("Failed to build LDK Node: {}", e);
+			eprintln!("Failed to read rotated auth token: {}", e);
+			std::process::exit(-1);
+		},
+	}
+
+	// The store is shared with LDK Node, such that its state can be accessed, e.g., for backups.
+	let node_kv_store: Arc<dyn KVStore + Send + Sync> = match NodeSqliteStore::new(
+		PathBuf::from(&config_file.storage_dir_path),
+		Some(SQLITE_DB_FILE_NAME.to_string()),
+		Some(KV_TABLE_NAME.to_string()),
+	) {
+		Ok(store) => Arc::new(store),
+		Err(e) => {
+			eprintln!("Failed to create LDK Node's SqliteStore: {:?}", e);
 			std::process::exit(-1);
 		},
 	};
 
-	println!("Starting up...");
-	match node.start_with_runtime(Arc::clone(&runtime)) {
-		Ok(()) => {},
+	let node = match builder.build_with_store(Arc::clone(&node_kv_store)) {
+		Ok(node) => Arc::new(node),
 		Err(e) => {
-			eprintln!("Failed to start up LDK Node: {}", e);
+			eprintln!("Failed to build LDK Node: {}", e);
 			std::process::exit(-1);
 		},
+	};
+
+	if recovery {
+		println!("Starting up in recovery mode, the node is not started...");
+	} else {
+		println!("Starting up...");
+		match node.start_with_runtime(Arc::clone(&runtime)) {
+			Ok(()) => {},
+			Err(e) => {
+				eprintln!("Failed to start up LDK Node: {}", e);
+				std::process::exit(-1);
+			},
+		}
 	}
 
 	println!(
@@ -84,13 +184,71 @@ fn main() {
 				std::process::exit(-1);
 			},
 		};
+		let mut page_token_key = [0u8; 32];
+		rand::thread_rng().fill_bytes(&mut page_token_key);
+		let page_token_codec = PageTokenCodec::new(page_token_key);
+
+		let connection_limiter = Arc::new(Semaphore::new(config_file.max_connections));
+		let (channel_ready_sender, _) = broadcast::channel(CHANNEL_READY_CHANNEL_CAPACITY);
+		let chain_source_breaker = Arc::new(CircuitBreaker::new(
+			config_file.chain_source_failure_threshold,
+			config_file.chain_source_retry_after,
+		));
+		let peer_health = Arc::new(PeerHealthTracker::new());
+		let mut peer_health_interval = tokio::time::interval(PEER_HEALTH_POLL_INTERVAL);
+		let mut payment_timeout_interval = tokio::time::interval(PAYMENT_TIMEOUT_POLL_INTERVAL);
+		let mut close_timeout_interval = tokio::time::interval(CLOSE_TIMEOUT_POLL_INTERVAL);
+		let mut event_prune_interval = tokio::time::interval(EVENT_PRUNE_INTERVAL);
+		let shutdown_signal = Arc::new(Notify::new());
+		let event_metrics = Arc::new(EventMetrics::new());
+		let graceful = GracefulShutdown::new();
+		let context = Context {
+			node: Arc::clone(&node),
+			node_kv_store: Arc::clone(&node_kv_store),
+			paginated_kv_store: Arc::clone(&paginated_kv_store),
+			page_token_codec,
+			chain_source_breaker,
+			channel_ready_sender: channel_ready_sender.clone(),
+			config: Arc::clone(&shared_config),
+			config_path: Arc::new(PathBuf::from(&args[1])),
+			peer_health: Arc::clone(&peer_health),
+			request_nonces: Arc::new(
+				RequestNonceTracker::load(paginated_kv_store.as_ref())
+					.expect("Failed to load request nonces"),
+			),
+			retired_auth_token: Arc::new(RetiredAuthToken::new()),
+			onchain_send_lock: Arc::new(Mutex::new(())),
+			payment_metrics: Arc::new(PaymentMetrics::new()),
+			event_metrics: Arc::clone(&event_metrics),
+			receive_addresses: Arc::new(ReceiveAddressCache::new()),
+			shutdown_signal: Arc::clone(&shutdown_signal),
+			#[cfg(feature = "test-hooks")]
+			simulated_payment_failure: Arc::new(SimulatedPaymentFailure::new()),
+		};
+
+		let mut event_log =
+			EventLog::load(paginated_kv_store.as_ref()).expect("Failed to load persisted events");
+
 		let event_node = Arc::clone(&node);
 		let rest_svc_listener = TcpListener::bind(config_file.rest_service_addr)
 			.await
 			.expect("Failed to bind listening port");
 		loop {
+			let (max_buffered_events, buffered_events_policy) = {
+				let config = shared_config.read().unwrap();
+				(config.max_buffered_events, config.buffered_events_policy)
+			};
 			tokio::select! {
 				event = event_node.next_event_async() => {
+					event_metrics.record_event(&event);
+					event_log.persist(
+						paginated_kv_store.as_ref(),
+						&event,
+						SystemTime::now(),
+						max_buffered_events,
+						buffered_events_policy,
+						&event_metrics,
+					);
 					match event {
 						Event::ChannelPending { channel_id, counterparty_node_id, .. } => {
 							println!(
@@ -98,11 +256,20 @@ fn main() {
 								channel_id, counterparty_node_id
 							);
 						},
-						Event::ChannelReady { channel_id, counterparty_node_id, .. } => {
+						Event::ChannelReady { channel_id, user_channel_id, counterparty_node_id } => {
 							println!(
 								"CHANNEL_READY: {} from counterparty {:?}",
 								channel_id, counterparty_node_id
 							);
+							// Sending only fails if nobody awaits any channel.
+							let _ = channel_ready_sender.send(user_channel_id);
+						},
+						Event::ChannelClosed { channel_id, user_channel_id, counterparty_node_id, .. } => {
+							println!(
+								"CHANNEL_CLOSED: {} from counterparty {:?}",
+								channel_id, counterparty_node_id
+							);
+							cancel_close_timeout(paginated_kv_store.as_ref(), &user_channel_id);
 						},
 						Event::PaymentReceived { payment_id, payment_hash, amount_msat } => {
 							println!(
@@ -110,19 +277,64 @@ fn main() {
 								payment_id, payment_hash, amount_msat
 							);
 						},
+						Event::PaymentSuccessful { payment_id: Some(payment_id), fee_paid_msat, .. } => {
+							let record = PaymentDiagnosticsRecord { fee_paid_msat, failure_reason: None };
+							persist_payment_diagnostics(paginated_kv_store.as_ref(), payment_id, &record);
+						},
+						Event::PaymentFailed { payment_id: Some(payment_id), reason, .. } => {
+							let record = PaymentDiagnosticsRecord {
+								fee_paid_msat: None,
+								failure_reason: reason.map(payment_failure_reason_to_proto),
+							};
+							persist_payment_diagnostics(paginated_kv_store.as_ref(), payment_id, &record);
+						},
+						Event::PaymentClaimable { payment_hash, claimable_amount_msat, .. } => {
+							handle_payment_claimable(
+								&event_node,
+								paginated_kv_store.as_ref(),
+								payment_hash,
+								claimable_amount_msat,
+							);
+						},
 						_ => {},
 					}
 					event_node.event_handled();
 				},
+				_ = peer_health_interval.tick() => {
+					let peers = event_node.list_peers().into_iter();
+					let peers = peers.map(|peer| (peer.node_id, peer.is_connected));
+					peer_health.update(peers, SystemTime::now());
+				},
+				// Abandoning payments on the event loop orders it with the handling of payment events.
+				_ = payment_timeout_interval.tick() => {
+					abandon_timed_out_payments(&event_node, paginated_kv_store.as_ref(), SystemTime::now());
+				},
+				_ = close_timeout_interval.tick() => {
+					escalate_timed_out_closes(&event_node, paginated_kv_store.as_ref(), SystemTime::now());
+				},
+				_ = event_prune_interval.tick() => {
+					event_log.prune(paginated_kv_store.as_ref(), SystemTime::now());
+				},
 				res = rest_svc_listener.accept() => {
 					match res {
 						Ok((stream, _)) => {
-							let io_stream = TokioIo::new(stream);
-							let node_service = NodeService::new(Arc::clone(&node));
+							// Refuse connections beyond the configured limit by dropping them right away.
+							let permit = match Arc::clone(&connection_limiter).try_acquire_owned() {
+								Ok(permit) => permit,
+								Err(_) => {
+									eprintln!("Refusing connection: maximum number of connections reached");
+									continue;
+								},
+							};
+							let node_service = NodeService::new(context.clone());
+							let header_read_timeout = shared_config.read().unwrap().header_read_timeout;
+							let connection =
+								serve_connection(stream, node_service, header_read_timeout, &graceful);
 							runtime.spawn(async move {
-								if let Err(err) = http1::Builder::new().serve_connection(io_stream, node_service).await {
+								if let Err(err) = connection.await {
 									eprintln!("Failed to serve connection: {}", err);
 								}
+								drop(permit);
 							});
 						},
 						Err(e) => eprintln!("Failed to accept connection: {}", e),
@@ -136,10 +348,87 @@ fn main() {
 					println!("Received SIGTERM, shutting down..");
 					break;
 				}
+				_ = shutdown_signal.notified() => {
+					println!("Received shutdown request, shutting down..");
+					break;
+				}
 			}
 		}
+
+		// Answer the requests in flight before stopping the node, closing idle connections right away.
+		if tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, graceful.shutdown()).await.is_err() {
+			eprintln!("Timed out waiting for requests in flight to be answered");
+		}
 	});
 
-	node.stop().expect("Shutdown should always succeed.");
+	if !recovery {
+		node.stop().expect("Shutdown should always succeed.");
+	}
 	println!("Shutdown complete..");
 }
+
+// Claims or fails back payments to invoices for which claiming is managed by us rather than by
+// LDK Node, depending on whether the claimable amount satisfies the invoice. Payments to hold
+// invoices are kept pending until they are settled or cancelled via the API.
+fn handle_payment_claimable(
+	node: &Node, paginated_kv_store: &dyn PaginatedKVStore, payment_hash: PaymentHash,
+	claimable_amount_msat: u64,
+) {
+	let mut record =
+		match read_inbound_invoice_record(paginated_kv_store, &payment_hash.to_string()) {
+			Ok(Some(record)) => record,
+			Ok(None) => {
+				eprintln!("PAYMENT_CLAIMABLE: unknown payment hash {}", payment_hash);
+				return;
+			},
+			Err(e) => {
+				eprintln!("Failed to read inbound invoice record for {}: {}", payment_hash, e);
+				return;
+			},
+		};
+
+	let preimage = match record.preimage.as_deref().map(<[u8; 32]>::from_hex) {
+		Some(Ok(preimage)) => PaymentPreimage(preimage),
+		Some(Err(e)) => {
+			eprintln!("Invalid preimage stored for payment hash {}: {}", payment_hash, e);
+			return;
+		},
+		None => {
+			// Hold invoice, remember the claimable amount until the payment is settled or cancelled.
+			println!(
+				"PAYMENT_HELD: hash {}, claimable_amount_msat {}",
+				payment_hash, claimable_amount_msat
+			);
+			record.claimable_amount_msat = Some(claimable_amount_msat);
+			if let Err(e) =
+				write_inbound_invoice_record(paginated_kv_store, &payment_hash.to_string(), &record)
+			{
+				eprintln!("Failed to persist held payment with hash {}: {}", payment_hash, e);
+			}
+			return;
+		},
+	};
+
+	let res = if record.accepts_amount(claimable_amount_msat) {
+		node.bolt11_payment().claim_for_hash(payment_hash, claimable_amount_msat, preimage)
+	} else {
+		println!(
+			"PAYMENT_REJECTED: hash {}, claimable_amount_msat {} doesn't match expected_amount_msat {:?}",
+			payment_hash, claimable_amount_msat, record.expected_amount_msat
+		);
+		node.bolt11_payment().fail_for_hash(payment_hash)
+	};
+	if let Err(e) = res {
+		eprintln!("Failed to handle claimable payment with hash {}: {}", payment_hash, e);
+	}
+}
+
+fn persist_payment_diagnostics(
+	paginated_kv_store: &dyn PaginatedKVStore, payment_id: PaymentId,
+	record: &PaymentDiagnosticsRecord,
+) {
+	let payment_id = payment_id.0.to_lower_hex_string();
+	if let Err(e) = write_payment_diagnostics_record(paginated_kv_store, &payment_id, record) {
+		eprintln!("Failed to persist diagnostics for payment {}: {}", payment_id, e);
+	}
+}
@@ -1,118 +1,949 @@
-use ldk_node::Node;
+use ldk_node::lightning::util::persist::KVStore;
+use ldk_node::{Node, UserChannelId};
 
-use http_body_util::{BodyExt, Full};
-use hyper::body::{Bytes, Incoming};
+use http_body_util::BodyExt;
+use hyper::body::{Body, Bytes, Incoming};
+use hyper::header::{
+	HeaderValue, ACCESS_CONTROL_REQUEST_METHOD, ALLOW, AUTHORIZATION, CONTENT_TYPE, ORIGIN,
+	RETRY_AFTER,
+};
+use hyper::server::conn::http1;
 use hyper::service::Service;
-use hyper::{Request, Response, StatusCode};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use hyper_util::server::graceful::GracefulShutdown;
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, Notify};
 
 use prost::Message;
 
+use std::collections::HashSet;
 use std::future::Future;
+use std::path::PathBuf;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::api::await_channel_ready::{
+	handle_await_channel_ready_request, AWAIT_CHANNEL_READY_PATH,
+};
 use crate::api::bolt11_receive::{handle_bolt11_receive_request, BOLT11_RECEIVE_PATH};
 use crate::api::bolt11_send::{handle_bolt11_send_request, BOLT11_SEND_PATH};
 use crate::api::bolt12_receive::{handle_bolt12_receive_request, BOLT12_RECEIVE_PATH};
 use crate::api::bolt12_send::{handle_bolt12_send_request, BOLT12_SEND_PATH};
+use crate::api::can_send::{handle_can_send_request, CAN_SEND_PATH};
+use crate::api::cancel_hold_invoice::{
+	handle_cancel_hold_invoice_request, CANCEL_HOLD_INVOICE_PATH,
+};
+use crate::api::claim_lnurl_withdraw::{
+	handle_claim_lnurl_withdraw_request, CLAIM_LNURL_WITHDRAW_PATH,
+};
 use crate::api::close_channel::{handle_close_channel_request, CLOSE_CHANNEL_PATH};
+use crate::api::create_hold_invoice::{
+	handle_create_hold_invoice_request, CREATE_HOLD_INVOICE_PATH,
+};
+use crate::api::create_invoice_group::{
+	handle_create_invoice_group_request, CREATE_INVOICE_GROUP_PATH,
+};
+use crate::api::error::{LdkServerError, LdkServerErrorCode};
+use crate::api::estimate_confirmation::{
+	handle_estimate_confirmation_request, ESTIMATE_CONFIRMATION_PATH,
+};
+use crate::api::export_channel_monitors::{
+	handle_export_channel_monitors_request, EXPORT_CHANNEL_MONITORS_PATH,
+};
+use crate::api::get_address_scope::{handle_get_address_scope_request, GET_ADDRESS_SCOPE_PATH};
 use crate::api::get_balances::{handle_get_balances_request, GET_BALANCES};
+use crate::api::get_capabilities::{handle_get_capabilities_request, GET_CAPABILITIES_PATH};
+use crate::api::get_close_balances::{handle_get_close_balances_request, GET_CLOSE_BALANCES_PATH};
+use crate::api::get_dust_exposure::{handle_get_dust_exposure_request, GET_DUST_EXPOSURE_PATH};
+use crate::api::get_inbound_payment_status::{
+	handle_get_inbound_payment_status_request, GET_INBOUND_PAYMENT_STATUS_PATH,
+};
+use crate::api::get_invoice_group_status::{
+	handle_get_invoice_group_status_request, GET_INVOICE_GROUP_STATUS_PATH,
+};
+use crate::api::get_liquidity_summary::{
+	handle_get_liquidity_summary_request, GET_LIQUIDITY_SUMMARY_PATH,
+};
+use crate::api::get_mempool_info::{handle_get_mempool_info_request, GET_MEMPOOL_INFO_PATH};
 use crate::api::get_node_info::{handle_get_node_info_request, GET_NODE_INFO};
+use crate::api::get_offer_status::{handle_get_offer_status_request, GET_OFFER_STATUS_PATH};
 use crate::api::get_payment_details::{
 	handle_get_payment_details_request, GET_PAYMENT_DETAILS_PATH,
 };
-use crate::api::list_channels::{handle_list_channels_request, LIST_CHANNELS_PATH};
+use crate::api::get_payment_invoice::{
+	handle_get_payment_invoice_request, GET_PAYMENT_INVOICE_PATH,
+};
+use crate::api::get_peer_health::{handle_get_peer_health_request, GET_PEER_HEALTH_PATH};
+use crate::api::get_wallet_descriptor::{
+	handle_get_wallet_descriptor_request, GET_WALLET_DESCRIPTOR_PATH,
+};
+use crate::api::import_channel_monitors::{
+	handle_import_channel_monitors_request, IMPORT_CHANNEL_MONITORS_PATH,
+};
+use crate::api::list_channels::{
+	handle_list_channels_request, list_channels_body, LIST_CHANNELS_PATH,
+};
 use crate::api::list_payments::{handle_list_payments_request, LIST_PAYMENTS_PATH};
+use crate::api::list_peers::{handle_list_peers_request, LIST_PEERS_PATH};
+use crate::api::list_pending_htlcs::{handle_list_pending_htlcs_request, LIST_PENDING_HTLCS_PATH};
 use crate::api::onchain_receive::{handle_onchain_receive_request, ONCHAIN_RECEIVE_PATH};
 use crate::api::onchain_send::{handle_onchain_send_request, ONCHAIN_SEND_PATH};
 use crate::api::open_channel::{handle_open_channel, OPEN_CHANNEL_PATH};
+use crate::api::pay_lightning_address::{
+	handle_pay_lightning_address_request, PAY_LIGHTNING_ADDRESS_PATH,
+};
+use crate::api::pay_uri::{handle_pay_uri_request, PAY_URI_PATH};
+use crate::api::ping::{handle_ping_request, PING_PATH};
+use crate::api::query_events::{handle_query_events_request, QUERY_EVENTS_PATH};
+use crate::api::recover_channels::{handle_recover_channels_request, RECOVER_CHANNELS_PATH};
+use crate::api::reload_config::{handle_reload_config_request, RELOAD_CONFIG_PATH};
+use crate::api::rotate_auth_token::{handle_rotate_auth_token_request, ROTATE_AUTH_TOKEN_PATH};
+use crate::api::self_test::{handle_self_test_request, SELF_TEST_PATH};
+use crate::api::send_many_bolt11::{handle_send_many_bolt11_request, SEND_MANY_BOLT11_PATH};
+use crate::api::set_dust_exposure::{handle_set_dust_exposure_request, SET_DUST_EXPOSURE_PATH};
+use crate::api::settle_hold_invoice::{
+	handle_settle_hold_invoice_request, SETTLE_HOLD_INVOICE_PATH,
+};
+use crate::api::shutdown::{handle_shutdown_request, SHUTDOWN_PATH};
+#[cfg(feature = "test-hooks")]
+use crate::api::simulate_payment_failure::{
+	handle_simulate_payment_failure_request, SIMULATE_PAYMENT_FAILURE_PATH,
+};
 use crate::api::update_channel_config::{
 	handle_update_channel_config_request, UPDATE_CHANNEL_CONFIG_PATH,
 };
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::util::auth::{check_bearer_token, check_rotated_bearer_token, RetiredAuthToken};
+use crate::util::circuit_breaker::CircuitBreaker;
+use crate::util::config::Config;
+use crate::util::health::{liveness_response, readiness_response, LIVEZ_PATH, READYZ_PATH};
+use crate::util::metrics::{EventMetrics, PaymentMetrics, METRICS_PATH};
+use crate::util::page_token::PageTokenCodec;
+use crate::util::peer_health::PeerHealthTracker;
+use crate::util::proto_adapter::to_error_response;
+use crate::util::receive_address_cache::ReceiveAddressCache;
+use crate::util::request_nonce::RequestNonceTracker;
+use crate::util::response_body::{full_body, ResponseBody};
+use crate::util::store_probe::check_store_writable;
+#[cfg(feature = "test-hooks")]
+use crate::util::test_hooks::SimulatedPaymentFailure;
+
+/// The header used to negotiate the framing of request and response bodies.
+const MESSAGE_FRAMING_HEADER: &str = "X-Message-Framing";
+
+/// The value of [`MESSAGE_FRAMING_HEADER`] selecting length-delimited framing, i.e., messages being
+/// prefixed by their varint-encoded length.
+const LENGTH_DELIMITED_FRAMING: &str = "length-delimited";
+
+/// The header carrying the server's time when a response was sent, as milliseconds since the UNIX
+/// epoch. It is set on every response, allowing clients to detect clock skew and to order events.
+const SERVER_TIMESTAMP_HEADER: &str = "X-Server-Timestamp-Ms";
+
+/// The content type of metrics in the Prometheus text exposition format.
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// The paths of all endpoints served by [`NodeService`], without the leading '/'.
+const SUPPORTED_ENDPOINTS: &[&str] = &[
+	GET_NODE_INFO,
+	GET_CAPABILITIES_PATH,
+	GET_BALANCES,
+	GET_CLOSE_BALANCES_PATH,
+	GET_LIQUIDITY_SUMMARY_PATH,
+	GET_MEMPOOL_INFO_PATH,
+	ESTIMATE_CONFIRMATION_PATH,
+	ONCHAIN_RECEIVE_PATH,
+	ONCHAIN_SEND_PATH,
+	BOLT11_RECEIVE_PATH,
+	BOLT11_SEND_PATH,
+	SEND_MANY_BOLT11_PATH,
+	CREATE_HOLD_INVOICE_PATH,
+	SETTLE_HOLD_INVOICE_PATH,
+	CANCEL_HOLD_INVOICE_PATH,
+	CREATE_INVOICE_GROUP_PATH,
+	GET_INVOICE_GROUP_STATUS_PATH,
+	BOLT12_RECEIVE_PATH,
+	BOLT12_SEND_PATH,
+	OPEN_CHANNEL_PATH,
+	CLOSE_CHANNEL_PATH,
+	LIST_CHANNELS_PATH,
+	UPDATE_CHANNEL_CONFIG_PATH,
+	GET_DUST_EXPOSURE_PATH,
+	SET_DUST_EXPOSURE_PATH,
+	GET_PAYMENT_DETAILS_PATH,
+	GET_PAYMENT_INVOICE_PATH,
+	GET_OFFER_STATUS_PATH,
+	GET_ADDRESS_SCOPE_PATH,
+	GET_PEER_HEALTH_PATH,
+	GET_WALLET_DESCRIPTOR_PATH,
+	GET_INBOUND_PAYMENT_STATUS_PATH,
+	LIST_PAYMENTS_PATH,
+	LIST_PEERS_PATH,
+	LIST_PENDING_HTLCS_PATH,
+	EXPORT_CHANNEL_MONITORS_PATH,
+	IMPORT_CHANNEL_MONITORS_PATH,
+	RECOVER_CHANNELS_PATH,
+	AWAIT_CHANNEL_READY_PATH,
+	CAN_SEND_PATH,
+	RELOAD_CONFIG_PATH,
+	PAY_URI_PATH,
+	PAY_LIGHTNING_ADDRESS_PATH,
+	CLAIM_LNURL_WITHDRAW_PATH,
+	SELF_TEST_PATH,
+	QUERY_EVENTS_PATH,
+	ROTATE_AUTH_TOKEN_PATH,
+	#[cfg(feature = "test-hooks")]
+	SIMULATE_PAYMENT_FAILURE_PATH,
+	PING_PATH,
+	SHUTDOWN_PATH,
+	METRICS_PATH,
+	LIVEZ_PATH,
+	READYZ_PATH,
+];
+
+/// The methods allowed on API endpoints, which take their request in the body.
+static API_METHODS: [Method; 2] = [Method::POST, Method::OPTIONS];
+
+/// The methods allowed on the endpoints serving the health probes and metrics, which only read.
+static READ_METHODS: [Method; 3] = [Method::GET, Method::HEAD, Method::OPTIONS];
+
+/// Serves a single REST connection, dropping it if a client takes longer than
+/// `header_read_timeout` to send the request headers.
+///
+/// The connection is watched by `graceful`, such that shutting down closes it once the request in
+/// flight, if any, was answered.
+pub(crate) fn serve_connection<S>(
+	stream: TcpStream, service: S, header_read_timeout: Duration, graceful: &GracefulShutdown,
+) -> impl Future<Output = Result<(), hyper::Error>>
+where
+	S: Service<Request<Incoming>, Response = Response<ResponseBody>>,
+	S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+	let connection = http1::Builder::new()
+		.timer(TokioTimer::new())
+		.header_read_timeout(header_read_timeout)
+		.serve_connection(TokioIo::new(stream), service);
+	graceful.watch(connection)
+}
 
 #[derive(Clone)]
 pub struct NodeService {
-	node: Arc<Node>,
+	context: Context,
 }
 
 impl NodeService {
-	pub(crate) fn new(node: Arc<Node>) -> Self {
-		Self { node }
+	pub(crate) fn new(context: Context) -> Self {
+		Self { context }
 	}
 }
 
+/// The state shared with all API handlers.
+#[derive(Clone)]
+pub(crate) struct Context {
+	pub(crate) node: Arc<Node>,
+	/// The store LDK Node persists its state to.
+	pub(crate) node_kv_store: Arc<dyn KVStore + Send + Sync>,
+	pub(crate) paginated_kv_store: Arc<dyn PaginatedKVStore>,
+	pub(crate) page_token_codec: PageTokenCodec,
+	pub(crate) chain_source_breaker: Arc<CircuitBreaker>,
+	/// Notified with the `user_channel_id` of every channel that became ready.
+	pub(crate) channel_ready_sender: broadcast::Sender<UserChannelId>,
+	/// The current configuration, of which some settings can be changed at runtime.
+	pub(crate) config: Arc<RwLock<Config>>,
+	/// The path the configuration was loaded from.
+	pub(crate) config_path: Arc<PathBuf>,
+	pub(crate) peer_health: Arc<PeerHealthTracker>,
+	pub(crate) request_nonces: Arc<RequestNonceTracker>,
+	/// The auth token replaced by the latest `RotateAuthToken` request, if any.
+	pub(crate) retired_auth_token: Arc<RetiredAuthToken>,
+	/// Held while making an on-chain send, such that sends are made one at a time.
+	pub(crate) onchain_send_lock: Arc<Mutex<()>>,
+	pub(crate) payment_metrics: Arc<PaymentMetrics>,
+	pub(crate) event_metrics: Arc<EventMetrics>,
+	pub(crate) receive_addresses: Arc<ReceiveAddressCache>,
+	/// Notified to gracefully shut down the server, as requested via the API.
+	pub(crate) shutdown_signal: Arc<Notify>,
+	/// Set to make the next Lightning send fail, for integration tests of clients.
+	#[cfg(feature = "test-hooks")]
+	pub(crate) simulated_payment_failure: Arc<SimulatedPaymentFailure>,
+}
+
 impl Service<Request<Incoming>> for NodeService {
-	type Response = Response<Full<Bytes>>;
+	type Response = Response<ResponseBody>;
 	type Error = hyper::Error;
 	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
 	fn call(&self, req: Request<Incoming>) -> Self::Future {
-		let node = Arc::clone(&self.node);
+		let cors = self.context.config.read().unwrap().cors.clone();
+		// Browsers announce the method of the cross-origin request they're about to make.
+		let is_preflight = req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD);
+		if req.method() == Method::OPTIONS && is_preflight {
+			let response = with_server_timestamp(cors.preflight_response(req.headers()));
+			return Box::pin(async { Ok(response) });
+		}
+		let origin = req.headers().get(ORIGIN).cloned();
+		let response = self.route(req);
+		Box::pin(async move {
+			response.await.map(|response| {
+				with_server_timestamp(cors.with_cors_headers(origin.as_ref(), response))
+			})
+		})
+	}
+}
+
+impl NodeService {
+	fn route(&self, req: Request<Incoming>) -> <Self as Service<Request<Incoming>>>::Future {
+		// Orchestrators probe health without credentials, and the probes reveal nothing sensitive.
+		let path = &req.uri().path()[1..];
+		if path == LIVEZ_PATH || path == READYZ_PATH {
+			if let Some(response) = method_response(req.method(), allowed_methods(path)) {
+				return Box::pin(async { Ok(response) });
+			}
+		}
+		match &req.uri().path()[1..] {
+			LIVEZ_PATH => {
+				let response = liveness_response();
+				return Box::pin(async { Ok(response) });
+			},
+			READYZ_PATH => {
+				let response = readiness_response(&self.context.node.status(), SystemTime::now());
+				return Box::pin(async { Ok(response) });
+			},
+			_ => {},
+		}
+
+		let authorization = req.headers().get(AUTHORIZATION).map(HeaderValue::as_bytes);
+		let auth_result = {
+			let (auth_token, allow_unauthenticated_ping) = {
+				let config = self.context.config.read().unwrap();
+				(config.auth_token.clone(), config.allow_unauthenticated_ping)
+			};
+			if skips_auth(&req.uri().path()[1..], allow_unauthenticated_ping) {
+				Ok(())
+			} else if req.uri().path()[1..] == *ROTATE_AUTH_TOKEN_PATH {
+				// Only the current token may rotate it, such that a leaked token can't be renewed.
+				check_bearer_token(auth_token.as_deref(), authorization)
+			} else {
+				check_rotated_bearer_token(
+					auth_token.as_deref(),
+					&self.context.retired_auth_token,
+					authorization,
+					SystemTime::now(),
+				)
+			}
+		};
+		if let Err(e) = auth_result {
+			let response = error_response(e, is_length_delimited(&req));
+			return Box::pin(async { Ok(response) });
+		}
+
 		// Exclude '/' from path pattern matching.
+		let path = &req.uri().path()[1..];
+		let disabled_response = disabled_endpoint_response(
+			&self.context.config.read().unwrap().disabled_endpoints,
+			path,
+			is_length_delimited(&req),
+		);
+		if let Some(response) = disabled_response {
+			return Box::pin(async { Ok(response) });
+		}
+
+		if SUPPORTED_ENDPOINTS.contains(&path) {
+			if let Some(response) = method_response(req.method(), allowed_methods(path)) {
+				return Box::pin(async { Ok(response) });
+			}
+		}
+
+		let context = self.context.clone();
 		match &req.uri().path()[1..] {
-			GET_NODE_INFO => Box::pin(handle_request(node, req, handle_get_node_info_request)),
-			GET_BALANCES => Box::pin(handle_request(node, req, handle_get_balances_request)),
-			ONCHAIN_RECEIVE_PATH => {
-				Box::pin(handle_request(node, req, handle_onchain_receive_request))
+			GET_NODE_INFO => Box::pin(handle_request(context, req, handle_get_node_info_request)),
+			GET_CAPABILITIES_PATH => {
+				Box::pin(handle_request(context, req, handle_get_capabilities_request))
+			},
+			GET_BALANCES => Box::pin(handle_request(context, req, handle_get_balances_request)),
+			GET_CLOSE_BALANCES_PATH => {
+				Box::pin(handle_request(context, req, handle_get_close_balances_request))
 			},
-			ONCHAIN_SEND_PATH => Box::pin(handle_request(node, req, handle_onchain_send_request)),
+			GET_LIQUIDITY_SUMMARY_PATH => {
+				Box::pin(handle_request(context, req, handle_get_liquidity_summary_request))
+			},
+			GET_MEMPOOL_INFO_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_chain_source_breaker(handle_get_mempool_info_request),
+			)),
+			ESTIMATE_CONFIRMATION_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_chain_source_breaker(handle_estimate_confirmation_request),
+			)),
+			ONCHAIN_RECEIVE_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_chain_source_breaker(handle_onchain_receive_request),
+			)),
+			ONCHAIN_SEND_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_writable_store(with_chain_source_breaker(handle_onchain_send_request)),
+			)),
 			BOLT11_RECEIVE_PATH => {
-				Box::pin(handle_request(node, req, handle_bolt11_receive_request))
+				Box::pin(handle_request(context, req, handle_bolt11_receive_request))
+			},
+			BOLT11_SEND_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_writable_store(with_simulated_payment_failure(handle_bolt11_send_request)),
+			)),
+			SEND_MANY_BOLT11_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_writable_store(with_simulated_payment_failure(
+					handle_send_many_bolt11_request,
+				)),
+			)),
+			CREATE_HOLD_INVOICE_PATH => {
+				Box::pin(handle_request(context, req, handle_create_hold_invoice_request))
+			},
+			SETTLE_HOLD_INVOICE_PATH => {
+				Box::pin(handle_request(context, req, handle_settle_hold_invoice_request))
+			},
+			CANCEL_HOLD_INVOICE_PATH => {
+				Box::pin(handle_request(context, req, handle_cancel_hold_invoice_request))
+			},
+			CREATE_INVOICE_GROUP_PATH => {
+				Box::pin(handle_request(context, req, handle_create_invoice_group_request))
+			},
+			GET_INVOICE_GROUP_STATUS_PATH => {
+				Box::pin(handle_request(context, req, handle_get_invoice_group_status_request))
 			},
-			BOLT11_SEND_PATH => Box::pin(handle_request(node, req, handle_bolt11_send_request)),
 			BOLT12_RECEIVE_PATH => {
-				Box::pin(handle_request(node, req, handle_bolt12_receive_request))
+				Box::pin(handle_request(context, req, handle_bolt12_receive_request))
+			},
+			BOLT12_SEND_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_writable_store(with_simulated_payment_failure(handle_bolt12_send_request)),
+			)),
+			OPEN_CHANNEL_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_chain_source_breaker(handle_open_channel),
+			)),
+			CLOSE_CHANNEL_PATH => {
+				Box::pin(handle_request(context, req, handle_close_channel_request))
 			},
-			BOLT12_SEND_PATH => Box::pin(handle_request(node, req, handle_bolt12_send_request)),
-			OPEN_CHANNEL_PATH => Box::pin(handle_request(node, req, handle_open_channel)),
-			CLOSE_CHANNEL_PATH => Box::pin(handle_request(node, req, handle_close_channel_request)),
-			LIST_CHANNELS_PATH => Box::pin(handle_request(node, req, handle_list_channels_request)),
+			LIST_CHANNELS_PATH => Box::pin(handle_request_with_body(
+				context,
+				req,
+				handle_list_channels_request,
+				list_channels_body,
+			)),
 			UPDATE_CHANNEL_CONFIG_PATH => {
-				Box::pin(handle_request(node, req, handle_update_channel_config_request))
+				Box::pin(handle_request(context, req, handle_update_channel_config_request))
+			},
+			GET_DUST_EXPOSURE_PATH => {
+				Box::pin(handle_request(context, req, handle_get_dust_exposure_request))
+			},
+			SET_DUST_EXPOSURE_PATH => {
+				Box::pin(handle_request(context, req, handle_set_dust_exposure_request))
 			},
 			GET_PAYMENT_DETAILS_PATH => {
-				Box::pin(handle_request(node, req, handle_get_payment_details_request))
+				Box::pin(handle_request(context, req, handle_get_payment_details_request))
+			},
+			GET_PAYMENT_INVOICE_PATH => {
+				Box::pin(handle_request(context, req, handle_get_payment_invoice_request))
+			},
+			GET_OFFER_STATUS_PATH => {
+				Box::pin(handle_request(context, req, handle_get_offer_status_request))
+			},
+			GET_ADDRESS_SCOPE_PATH => {
+				Box::pin(handle_request(context, req, handle_get_address_scope_request))
+			},
+			GET_PEER_HEALTH_PATH => {
+				Box::pin(handle_request(context, req, handle_get_peer_health_request))
+			},
+			GET_WALLET_DESCRIPTOR_PATH => {
+				Box::pin(handle_request(context, req, handle_get_wallet_descriptor_request))
+			},
+			GET_INBOUND_PAYMENT_STATUS_PATH => {
+				Box::pin(handle_request(context, req, handle_get_inbound_payment_status_request))
+			},
+			LIST_PAYMENTS_PATH => {
+				Box::pin(handle_request(context, req, handle_list_payments_request))
+			},
+			LIST_PEERS_PATH => Box::pin(handle_request(context, req, handle_list_peers_request)),
+			LIST_PENDING_HTLCS_PATH => {
+				Box::pin(handle_request(context, req, handle_list_pending_htlcs_request))
+			},
+			EXPORT_CHANNEL_MONITORS_PATH => {
+				Box::pin(handle_request(context, req, handle_export_channel_monitors_request))
+			},
+			IMPORT_CHANNEL_MONITORS_PATH => {
+				Box::pin(handle_request(context, req, handle_import_channel_monitors_request))
+			},
+			RECOVER_CHANNELS_PATH => {
+				Box::pin(handle_request(context, req, handle_recover_channels_request))
+			},
+			AWAIT_CHANNEL_READY_PATH => {
+				Box::pin(handle_request(context, req, handle_await_channel_ready_request))
+			},
+			CAN_SEND_PATH => Box::pin(handle_request(context, req, handle_can_send_request)),
+			RELOAD_CONFIG_PATH => {
+				Box::pin(handle_request(context, req, handle_reload_config_request))
+			},
+			PAY_URI_PATH => {
+				Box::pin(handle_request(context, req, with_writable_store(handle_pay_uri_request)))
+			},
+			PAY_LIGHTNING_ADDRESS_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_writable_store(with_simulated_payment_failure(
+					handle_pay_lightning_address_request,
+				)),
+			)),
+			SELF_TEST_PATH => Box::pin(handle_request(context, req, handle_self_test_request)),
+			QUERY_EVENTS_PATH => {
+				Box::pin(handle_request(context, req, handle_query_events_request))
+			},
+			ROTATE_AUTH_TOKEN_PATH => {
+				Box::pin(handle_request(context, req, handle_rotate_auth_token_request))
+			},
+			#[cfg(feature = "test-hooks")]
+			SIMULATE_PAYMENT_FAILURE_PATH => {
+				Box::pin(handle_request(context, req, handle_simulate_payment_failure_request))
+			},
+			CLAIM_LNURL_WITHDRAW_PATH => Box::pin(handle_request(
+				context,
+				req,
+				with_writable_store(handle_claim_lnurl_withdraw_request),
+			)),
+			PING_PATH => Box::pin(handle_request(context, req, handle_ping_request)),
+			SHUTDOWN_PATH => Box::pin(handle_request(context, req, handle_shutdown_request)),
+			METRICS_PATH => {
+				let response = Response::builder()
+					.header(CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)
+					.body(full_body(
+						context.payment_metrics.render() + &context.event_metrics.render(),
+					))
+					// unwrap safety: body only errors when previous chained calls failed.
+					.unwrap();
+				Box::pin(async { Ok(response) })
 			},
-			LIST_PAYMENTS_PATH => Box::pin(handle_request(node, req, handle_list_payments_request)),
 			path => {
-				let error = format!("Unknown request: {}", path).into_bytes();
-				Box::pin(async {
-					Ok(Response::builder()
-						.status(StatusCode::BAD_REQUEST)
-						.body(Full::new(Bytes::from(error)))
-						// unwrap safety: body only errors when previous chained calls failed.
-						.unwrap())
-				})
+				let supported_endpoints = {
+					let config = context.config.read().unwrap();
+					config
+						.list_supported_endpoints
+						.then(|| supported_endpoints(&config.disabled_endpoints))
+				};
+				let length_delimited = is_length_delimited(&req);
+				let response =
+					unknown_endpoint_response(path, supported_endpoints, length_delimited);
+				Box::pin(async { Ok(response) })
 			},
 		}
 	}
 }
 
+// Sets the `SERVER_TIMESTAMP_HEADER` of `response` to the current time.
+fn with_server_timestamp(
+	mut response: <NodeService as Service<Request<Incoming>>>::Response,
+) -> <NodeService as Service<Request<Incoming>>>::Response {
+	let timestamp_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+	response.headers_mut().insert(SERVER_TIMESTAMP_HEADER, HeaderValue::from(timestamp_ms as u64));
+	response
+}
+
+// Whether requests to the endpoint at `path` are served without checking their auth token.
+fn skips_auth(path: &str, allow_unauthenticated_ping: bool) -> bool {
+	allow_unauthenticated_ping && path == PING_PATH
+}
+
+// Returns a `403 Forbidden` response if the endpoint at `path` was disabled by the operator.
+fn disabled_endpoint_response(
+	disabled_endpoints: &HashSet<String>, path: &str, length_delimited: bool,
+) -> Option<<NodeService as Service<Request<Incoming>>>::Response> {
+	if !disabled_endpoints.contains(path) {
+		return None;
+	}
+	let error = LdkServerError::new(
+		LdkServerErrorCode::EndpointDisabledError,
+		format!("Endpoint disabled: {}", path),
+	);
+	Some(error_response(error, length_delimited))
+}
+
+// Returns the methods allowed on the supported endpoint at `path`.
+//
+// `HEAD` requests are served like `GET` requests, with hyper leaving out the body.
+fn allowed_methods(path: &str) -> &'static [Method] {
+	match path {
+		LIVEZ_PATH | READYZ_PATH | METRICS_PATH => &READ_METHODS,
+		_ => &API_METHODS,
+	}
+}
+
+// Answers a request using `method` on an endpoint allowing `allowed_methods`, listing them, if it's
+// an `OPTIONS` request, or with `405 Method Not Allowed` if `method` isn't allowed.
+fn method_response(
+	method: &Method, allowed_methods: &[Method],
+) -> Option<<NodeService as Service<Request<Incoming>>>::Response> {
+	let (status, body) = if method == Method::OPTIONS {
+		(StatusCode::NO_CONTENT, Bytes::new())
+	} else if !allowed_methods.contains(method) {
+		(StatusCode::METHOD_NOT_ALLOWED, Bytes::from(format!("Method not allowed: {}", method)))
+	} else {
+		return None;
+	};
+	let allow = allowed_methods.iter().map(Method::as_str).collect::<Vec<_>>().join(", ");
+	Some(
+		Response::builder()
+			.status(status)
+			.header(ALLOW, allow)
+			.body(full_body(body))
+			// unwrap safety: body only errors when previous chained calls failed.
+			.unwrap(),
+	)
+}
+
+// Returns the paths of the supported endpoints, except for those disabled by the operator.
+pub(crate) fn supported_endpoints(disabled_endpoints: &HashSet<String>) -> Vec<String> {
+	SUPPORTED_ENDPOINTS
+		.iter()
+		.filter(|path| !disabled_endpoints.contains(**path))
+		.map(|path| path.to_string())
+		.collect()
+}
+
+// Returns a `404 Not Found` response for a request to the unknown endpoint at `path`, listing the
+// `supported_endpoints` if given.
+fn unknown_endpoint_response(
+	path: &str, supported_endpoints: Option<Vec<String>>, length_delimited: bool,
+) -> <NodeService as Service<Request<Incoming>>>::Response {
+	let error = LdkServerError {
+		supported_endpoints: supported_endpoints.unwrap_or_default(),
+		..LdkServerError::new(
+			LdkServerErrorCode::NotFoundError,
+			format!("Unknown request: {}", path),
+		)
+	};
+	error_response(error, length_delimited)
+}
+
+// Returns a response carrying `error` for a request that was rejected before reaching a handler.
+fn error_response(
+	error: LdkServerError, length_delimited: bool,
+) -> <NodeService as Service<Request<Incoming>>>::Response {
+	let (error_response, status_code) = to_error_response(error);
+	let mut response_builder = Response::builder().status(status_code);
+	if length_delimited {
+		response_builder =
+			response_builder.header(MESSAGE_FRAMING_HEADER, LENGTH_DELIMITED_FRAMING);
+	}
+	response_builder
+		.body(full_body(encode_message(&error_response, length_delimited)))
+		// unwrap safety: body only errors when previous chained calls failed.
+		.unwrap()
+}
+
+// Wraps `handler` of a request depending on the chain source, such that it fails right away while
+// the chain source is failing.
+fn with_chain_source_breaker<T, R, F: Fn(Context, T) -> Result<R, LdkServerError>>(
+	handler: F,
+) -> impl Fn(Context, T) -> Result<R, LdkServerError> {
+	move |context, request| {
+		let breaker = Arc::clone(&context.chain_source_breaker);
+		breaker.check()?;
+		let res = handler(context, request);
+		breaker.record(res.as_ref().err());
+		res
+	}
+}
+
+// Wraps `handler` of a Lightning send, such that the send fails right away if a failure was
+// simulated via `SimulatePaymentFailure`, which is only served with the `test-hooks` feature.
+fn with_simulated_payment_failure<T, R, F: Fn(Context, T) -> Result<R, LdkServerError>>(
+	handler: F,
+) -> impl Fn(Context, T) -> Result<R, LdkServerError> {
+	move |context, request| {
+		#[cfg(feature = "test-hooks")]
+		if let Some(e) = context.simulated_payment_failure.take() {
+			return Err(e);
+		}
+		handler(context, request)
+	}
+}
+
+// Wraps `handler` of a request initiating a payment, such that it fails right away if the node's
+// store isn't writable, as the payment couldn't be persisted.
+fn with_writable_store<T, R, F: Fn(Context, T) -> Result<R, LdkServerError>>(
+	handler: F,
+) -> impl Fn(Context, T) -> Result<R, LdkServerError> {
+	move |context, request| {
+		check_store_writable(context.node_kv_store.as_ref())?;
+		handler(context, request)
+	}
+}
+
+// Note on cancellation: hyper drops this future if the client disconnects while it is pending,
+// which only happens while the request body is still being read. Handlers are synchronous and
+// thus always run to completion once started, so a state-changing operation (e.g., a send) is
+// never aborted halfway through.
 async fn handle_request<
 	T: Message + Default,
 	R: Message,
-	F: Fn(Arc<Node>, T) -> Result<R, ldk_node::NodeError>,
+	F: Fn(Context, T) -> Result<R, LdkServerError>,
 >(
-	node: Arc<Node>, request: Request<Incoming>, handler: F,
+	context: Context, request: Request<Incoming>, handler: F,
 ) -> Result<<NodeService as Service<Request<Incoming>>>::Response, hyper::Error> {
+	handle_request_with_body(context, request, handler, |response, length_delimited| {
+		full_body(encode_message(&response, length_delimited))
+	})
+	.await
+}
+
+// Like `handle_request`, but with the body of successful responses built by `into_body` from the
+// response and whether it is to be length-delimited, e.g., to stream it.
+async fn handle_request_with_body<
+	T: Message + Default,
+	R: Message,
+	F: Fn(Context, T) -> Result<R, LdkServerError>,
+	B: FnOnce(R, bool) -> ResponseBody,
+>(
+	context: Context, request: Request<Incoming>, handler: F, into_body: B,
+) -> Result<<NodeService as Service<Request<Incoming>>>::Response, hyper::Error> {
+	let length_delimited = is_length_delimited(&request);
+
 	// TODO: we should bound the amount of data we read to avoid allocating too much memory.
-	let bytes = request.into_body().collect().await?.to_bytes();
+	let bytes = if length_delimited {
+		match read_length_delimited(request.into_body()).await? {
+			Some(bytes) => bytes,
+			None => return Ok(bad_request_response()),
+		}
+	} else {
+		request.into_body().collect().await?.to_bytes()
+	};
+
+	let mut response_builder = Response::builder();
+	if length_delimited {
+		response_builder =
+			response_builder.header(MESSAGE_FRAMING_HEADER, LENGTH_DELIMITED_FRAMING);
+	}
+
+	let retry_after = context.chain_source_breaker.retry_after();
 	match T::decode(bytes) {
-		Ok(request) => match handler(node, request) {
-			Ok(response) => Ok(Response::builder()
-				.body(Full::new(Bytes::from(response.encode_to_vec())))
-				// unwrap safety: body only errors when previous chained calls failed.
-				.unwrap()),
-			Err(e) => Ok(Response::builder()
-				.status(StatusCode::INTERNAL_SERVER_ERROR)
-				.body(Full::new(Bytes::from(e.to_string().into_bytes())))
+		Ok(request) => match handler(context, request) {
+			Ok(response) => Ok(response_builder
+				.body(into_body(response, length_delimited))
 				// unwrap safety: body only errors when previous chained calls failed.
 				.unwrap()),
+			Err(e) => {
+				if e.error_code == LdkServerErrorCode::ServiceUnavailableError {
+					response_builder =
+						response_builder.header(RETRY_AFTER, retry_after.as_secs().to_string());
+				}
+				let (error_response, status_code) = to_error_response(e);
+				Ok(response_builder
+					.status(status_code)
+					.body(full_body(encode_message(&error_response, length_delimited)))
+					// unwrap safety: body only errors when previous chained calls failed.
+					.unwrap())
+			},
 		},
-		Err(_) => Ok(Response::builder()
-			.status(StatusCode::BAD_REQUEST)
-			.body(Full::new(Bytes::from(b"Error parsing request".to_vec())))
-			// unwrap safety: body only errors when previous chained calls failed.
-			.unwrap()),
+		Err(_) => Ok(bad_request_response()),
+	}
+}
+
+fn is_length_delimited<B>(request: &Request<B>) -> bool {
+	request
+		.headers()
+		.get(MESSAGE_FRAMING_HEADER)
+		.map_or(false, |framing| framing == LENGTH_DELIMITED_FRAMING)
+}
+
+fn bad_request_response() -> <NodeService as Service<Request<Incoming>>>::Response {
+	Response::builder()
+		.status(StatusCode::BAD_REQUEST)
+		.body(full_body(b"Error parsing request".to_vec()))
+		// unwrap safety: body only errors when previous chained calls failed.
+		.unwrap()
+}
+
+fn encode_message<M: Message>(message: &M, length_delimited: bool) -> Vec<u8> {
+	if length_delimited {
+		message.encode_length_delimited_to_vec()
+	} else {
+		message.encode_to_vec()
+	}
+}
+
+// Reads a single length-delimited message from `body`, stopping as soon as the message is complete
+// rather than consuming the body to its end. Returns `None` if the body ends before the message
+// does or the length prefix is invalid.
+async fn read_length_delimited<B: Body<Data = Bytes> + Unpin>(
+	mut body: B,
+) -> Result<Option<Bytes>, B::Error> {
+	let mut buf = Vec::new();
+	loop {
+		match prost::decode_length_delimiter(&buf[..]) {
+			Ok(len) => {
+				let prefix_len = prost::length_delimiter_len(len);
+				if buf.len() >= prefix_len + len {
+					return Ok(Some(Bytes::copy_from_slice(&buf[prefix_len..prefix_len + len])));
+				}
+			},
+			// The length prefix is at most 10 bytes long, so more data won't make it valid.
+			Err(_) if buf.len() >= 10 => return Ok(None),
+			Err(_) => {},
+		}
+
+		match body.frame().await {
+			Some(Ok(frame)) => {
+				if let Ok(data) = frame.into_data() {
+					buf.extend_from_slice(&data);
+				}
+			},
+			Some(Err(e)) => return Err(e),
+			None => return Ok(None),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use http_body_util::Full;
+	use ldk_server_protos::api::GetPaymentDetailsRequest;
+	use ldk_server_protos::error::{ErrorCode, ErrorResponse};
+
+	#[tokio::test]
+	async fn test_disabled_endpoint_is_forbidden() {
+		let disabled_endpoints = HashSet::from([ONCHAIN_SEND_PATH.to_string()]);
+		let response =
+			disabled_endpoint_response(&disabled_endpoints, ONCHAIN_SEND_PATH, false).unwrap();
+		assert_eq!(response.status(), StatusCode::FORBIDDEN);
+		let body = response.into_body().collect().await.unwrap();
+		let error_response = ErrorResponse::decode(body.to_bytes()).unwrap();
+		assert_eq!(error_response.error_code, ErrorCode::EndpointDisabledError as i32);
+		assert!(disabled_endpoint_response(&disabled_endpoints, GET_NODE_INFO, false).is_none());
+	}
+
+	#[test]
+	fn test_ping_skips_auth_only_if_allowed() {
+		assert!(skips_auth(PING_PATH, true));
+		assert!(!skips_auth(PING_PATH, false));
+		assert!(!skips_auth(GET_NODE_INFO, true));
+		assert!(!skips_auth(ROTATE_AUTH_TOKEN_PATH, true));
+	}
+
+	#[test]
+	fn test_options_lists_allowed_methods() {
+		let response = method_response(&Method::OPTIONS, allowed_methods(GET_NODE_INFO)).unwrap();
+		assert_eq!(response.status(), StatusCode::NO_CONTENT);
+		assert_eq!(response.headers()[ALLOW], "POST, OPTIONS");
+
+		let response = method_response(&Method::OPTIONS, allowed_methods(READYZ_PATH)).unwrap();
+		assert_eq!(response.status(), StatusCode::NO_CONTENT);
+		assert_eq!(response.headers()[ALLOW], "GET, HEAD, OPTIONS");
+	}
+
+	#[tokio::test]
+	async fn test_wrong_method_is_not_allowed() {
+		let response = method_response(&Method::GET, allowed_methods(GET_NODE_INFO)).unwrap();
+		assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+		assert_eq!(response.headers()[ALLOW], "POST, OPTIONS");
+		let body = response.into_body().collect().await.unwrap().to_bytes();
+		assert_eq!(body, "Method not allowed: GET");
+
+		let response = method_response(&Method::POST, allowed_methods(METRICS_PATH)).unwrap();
+		assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+		assert_eq!(response.headers()[ALLOW], "GET, HEAD, OPTIONS");
+
+		assert!(method_response(&Method::POST, allowed_methods(GET_NODE_INFO)).is_none());
+		assert!(method_response(&Method::HEAD, allowed_methods(LIVEZ_PATH)).is_none());
+	}
+
+	#[tokio::test]
+	async fn test_unknown_endpoint_lists_supported_endpoints() {
+		async fn decode_error(response: Response<ResponseBody>) -> ErrorResponse {
+			assert_eq!(response.status(), StatusCode::NOT_FOUND);
+			let body = response.into_body().collect().await.unwrap();
+			ErrorResponse::decode(body.to_bytes()).unwrap()
+		}
+
+		let disabled_endpoints = HashSet::from([ONCHAIN_SEND_PATH.to_string()]);
+		let supported_endpoints = supported_endpoints(&disabled_endpoints);
+		let response = unknown_endpoint_response("Unknown", Some(supported_endpoints), false);
+		let error_response = decode_error(response).await;
+		assert_eq!(error_response.error_code, ErrorCode::NotFoundError as i32);
+		assert_eq!(error_response.supported_endpoints.len(), SUPPORTED_ENDPOINTS.len() - 1);
+		assert!(error_response.supported_endpoints.contains(&GET_NODE_INFO.to_string()));
+		assert!(!error_response.supported_endpoints.contains(&ONCHAIN_SEND_PATH.to_string()));
+
+		let error_response = decode_error(unknown_endpoint_response("Unknown", None, false)).await;
+		assert_eq!(error_response.error_code, ErrorCode::NotFoundError as i32);
+		assert!(error_response.supported_endpoints.is_empty());
+	}
+
+	#[test]
+	fn test_responses_are_timestamped() {
+		let timestamp_ms = |response: &Response<ResponseBody>| -> u64 {
+			let header = response.headers().get(SERVER_TIMESTAMP_HEADER).unwrap();
+			header.to_str().unwrap().parse().unwrap()
+		};
+
+		let first = with_server_timestamp(bad_request_response());
+		let second = with_server_timestamp(Response::new(full_body(Bytes::new())));
+		let (first_ms, second_ms) = (timestamp_ms(&first), timestamp_ms(&second));
+		assert!(first_ms > 0);
+		assert!(first_ms <= second_ms);
+	}
+
+	#[tokio::test]
+	async fn test_slow_headers_are_dropped() {
+		use hyper::service::service_fn;
+		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::net::TcpListener;
+
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		tokio::spawn(async move {
+			let (stream, _) = listener.accept().await.unwrap();
+			let service = service_fn(|_| async {
+				Ok::<_, hyper::Error>(Response::new(full_body(Bytes::new())))
+			});
+			let graceful = GracefulShutdown::new();
+			let _ = serve_connection(stream, service, Duration::from_millis(500), &graceful).await;
+		});
+
+		let mut stream = TcpStream::connect(addr).await.unwrap();
+		// Start sending a request, but never finish the headers.
+		stream.write_all(b"POST /GetNodeInfo HTTP/1.1\r\nHost: localhost\r\n").await.unwrap();
+
+		let mut buf = [0u8; 1024];
+		let res = tokio::time::timeout(Duration::from_secs(10), stream.read(&mut buf)).await;
+		// The server either closes the connection or responds with an error before closing it.
+		match res.expect("Connection should have been dropped") {
+			Ok(0) | Err(_) => {},
+			Ok(len) => assert!(String::from_utf8_lossy(&buf[..len]).starts_with("HTTP/1.1 408")),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_read_length_delimited() {
+		let request = GetPaymentDetailsRequest { payment_id: "00".repeat(32), verbose: false };
+		let mut body = request.encode_length_delimited_to_vec();
+		// Trailing data following the message is left unread.
+		body.extend_from_slice(b"trailing data");
+
+		let bytes = read_length_delimited(Full::new(Bytes::from(body))).await.unwrap().unwrap();
+		assert_eq!(GetPaymentDetailsRequest::decode(bytes).unwrap(), request);
+
+		// Truncated messages are rejected.
+		let mut body = request.encode_length_delimited_to_vec();
+		body.truncate(body.len() - 1);
+		assert!(read_length_delimited(Full::new(Bytes::from(body))).await.unwrap().is_none());
 	}
 }
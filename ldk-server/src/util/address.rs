@@ -0,0 +1,52 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use ldk_node::bitcoin::{Address, Network};
+use std::str::FromStr;
+
+/// Parses the Bitcoin `address` given in `field`, failing unless it is valid for `network`.
+///
+/// Every endpoint accepting an address must parse it this way, such that funds can't be sent to an
+/// address meant for another network, e.g., a mainnet address on a testnet node.
+pub(crate) fn parse_address_checked(
+	field: &str, address: &str, network: Network,
+) -> Result<Address, LdkServerError> {
+	let invalid_address =
+		|reason: String| LdkServerError::invalid_fields(vec![ValidationError::new(field, reason)]);
+	let address = Address::from_str(address)
+		.map_err(|e| invalid_address(format!("{} is not a valid address: {}", address, e)))?;
+	if !address.is_valid_for_network(network) {
+		return Err(invalid_address(format!(
+			"{} is not an address for the node's network, {}",
+			address.assume_checked_ref(),
+			network
+		)));
+	}
+	Ok(address.assume_checked())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+
+	const REGTEST_ADDRESS: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+	const MAINNET_ADDRESS: &str = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+
+	#[test]
+	fn test_wrong_network_address_is_rejected() {
+		let address = parse_address_checked("address", REGTEST_ADDRESS, Network::Regtest).unwrap();
+		assert_eq!(address.to_string(), REGTEST_ADDRESS);
+		assert!(parse_address_checked("address", MAINNET_ADDRESS, Network::Bitcoin).is_ok());
+
+		for (address, network) in
+			[(MAINNET_ADDRESS, Network::Testnet), (REGTEST_ADDRESS, Network::Bitcoin)]
+		{
+			let err = parse_address_checked("address", address, network).unwrap_err();
+			assert_eq!(err.error_code, InvalidRequestError);
+			assert_eq!(err.validation_errors[0].field, "address");
+			assert!(err.validation_errors[0].reason.contains("network"));
+		}
+
+		let err = parse_address_checked("address", "not-an-address", Network::Regtest).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+	}
+}
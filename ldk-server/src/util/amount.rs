@@ -0,0 +1,159 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use ldk_node::lightning_invoice::Bolt11Invoice;
+
+/// The maximum number of satoshis that will ever exist.
+pub(crate) const MAX_MONEY_SATS: u64 = 21_000_000 * 100_000_000;
+
+/// The maximum number of millisatoshis that will ever exist.
+pub(crate) const MAX_MONEY_MSAT: u64 = MAX_MONEY_SATS * 1000;
+
+/// Fails if the satoshi amount of `field` exceeds the total supply of bitcoin.
+///
+/// Request fields suffixed with `_sats` denote satoshis and fields suffixed with `_msat`
+/// millisatoshis. Validating against the total supply catches amounts given in the wrong unit,
+/// e.g., millisatoshis passed where satoshis are expected.
+pub(crate) fn validate_amount_sats(field: &str, amount_sats: u64) -> Result<(), LdkServerError> {
+	if amount_sats > MAX_MONEY_SATS {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			field,
+			format!(
+				"{} exceeds the total supply of bitcoin, is it given in millisatoshis?",
+				amount_sats
+			),
+		)]));
+	}
+	Ok(())
+}
+
+/// Fails if the millisatoshi amount of `field` exceeds the total supply of bitcoin.
+///
+/// Amounts within the total supply can't overflow in the arithmetic we and LDK do on them, e.g.,
+/// adding fees or converting to satoshis, while amounts close to `u64::MAX` would.
+pub(crate) fn validate_amount_msat(field: &str, amount_msat: u64) -> Result<(), LdkServerError> {
+	if amount_msat > MAX_MONEY_MSAT {
+		return Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			field,
+			format!("{} exceeds the total supply of bitcoin", amount_msat),
+		)]));
+	}
+	Ok(())
+}
+
+/// Fails if any of the millisatoshi `amounts`, given along with the fields they're set in,
+/// exceeds the total supply of bitcoin, reporting all such fields.
+pub(crate) fn validate_amounts_msat(amounts: &[(&str, Option<u64>)]) -> Result<(), LdkServerError> {
+	let validation_errors: Vec<_> = amounts
+		.iter()
+		.filter_map(|(field, amount_msat)| validate_amount_msat(field, (*amount_msat)?).err())
+		.flat_map(|e| e.validation_errors)
+		.collect();
+	if !validation_errors.is_empty() {
+		return Err(LdkServerError::invalid_fields(validation_errors));
+	}
+	Ok(())
+}
+
+/// Returns the amount of `invoice` in millisatoshis, if any, failing with a validation error on
+/// `field` if it exceeds the total supply of bitcoin.
+///
+/// Invoices whose amount overflows when converted to millisatoshis are reported to have no amount
+/// by LDK, which would make them payable with any amount, so we reject those as well.
+pub(crate) fn validate_invoice_amount(
+	field: &str, invoice: &Bolt11Invoice,
+) -> Result<Option<u64>, LdkServerError> {
+	match invoice.amount_milli_satoshis() {
+		Some(amount_msat) => validate_amount_msat(field, amount_msat).map(|()| Some(amount_msat)),
+		None if invoice.clone().into_signed_raw().raw_invoice().hrp.raw_amount.is_some() => {
+			Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+				field,
+				"amount exceeds the total supply of bitcoin",
+			)]))
+		},
+		None => Ok(None),
+	}
+}
+
+/// Converts `amount_msat` to satoshis, rounding up, without overflowing for any amount.
+pub(crate) fn msat_to_sats_ceil(amount_msat: u64) -> u64 {
+	amount_msat / 1000 + u64::from(amount_msat % 1000 != 0)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+	use ldk_node::bitcoin::hashes::{sha256, Hash};
+	use ldk_node::bitcoin::secp256k1::{Secp256k1, SecretKey};
+	use ldk_node::lightning::ln::types::PaymentSecret;
+	use ldk_node::lightning_invoice::{Currency, InvoiceBuilder};
+
+	fn invoice(amount_msat: u64) -> Bolt11Invoice {
+		let secp_ctx = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&[42; 32]).unwrap();
+		InvoiceBuilder::new(Currency::Regtest)
+			.description("test".to_string())
+			.payment_hash(sha256::Hash::hash(&[1; 32]))
+			.payment_secret(PaymentSecret([1; 32]))
+			.current_timestamp()
+			.min_final_cltv_expiry_delta(144)
+			.amount_milli_satoshis(amount_msat)
+			.build_signed(|hash| secp_ctx.sign_ecdsa_recoverable(hash, &secret_key))
+			.unwrap()
+	}
+
+	/// Returns a validly signed invoice for `u64::MAX` bitcoin, whose amount overflows when
+	/// converted to pico-bitcoin.
+	pub(crate) fn overflowing_invoice() -> Bolt11Invoice {
+		let secp_ctx = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&[42; 32]).unwrap();
+		let mut raw_invoice = invoice(1_000).into_signed_raw().raw_invoice().clone();
+		raw_invoice.hrp.raw_amount = Some(u64::MAX);
+		raw_invoice.hrp.si_prefix = None;
+		let signed_invoice = raw_invoice
+			.sign(|hash| Ok::<_, ()>(secp_ctx.sign_ecdsa_recoverable(hash, &secret_key)))
+			.unwrap();
+		Bolt11Invoice::from_signed(signed_invoice).unwrap()
+	}
+
+	#[test]
+	fn test_sats_passed_as_msat_are_rejected() {
+		// 1 BTC is fine in both units.
+		assert!(validate_amount_sats("amount_sats", 100_000_000).is_ok());
+		assert!(validate_amount_msat("amount_msat", 100_000_000_000).is_ok());
+
+		// 1M BTC, given in millisatoshis, is not a valid amount of satoshis.
+		let err = validate_amount_sats("amount_sats", 100_000_000_000_000_000).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert_eq!(err.validation_errors[0].field, "amount_sats");
+		assert!(validate_amount_sats("amount_sats", MAX_MONEY_SATS).is_ok());
+		assert!(validate_amount_sats("amount_sats", MAX_MONEY_SATS + 1).is_err());
+
+		assert!(validate_amount_msat("amount_msat", MAX_MONEY_MSAT).is_ok());
+		assert!(validate_amount_msat("amount_msat", MAX_MONEY_MSAT + 1).is_err());
+	}
+
+	#[test]
+	fn test_amounts_near_u64_max_are_rejected() {
+		assert!(validate_amounts_msat(&[("amount_msat", Some(1_000)), ("fee_msat", None)]).is_ok());
+		let err = validate_amounts_msat(&[
+			("amount_msat", Some(u64::MAX)),
+			("fee_msat", Some(1_000)),
+			("max_routing_fee_msat", Some(u64::MAX)),
+		])
+		.unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		let fields: Vec<_> = err.validation_errors.iter().map(|e| e.field.as_str()).collect();
+		assert_eq!(fields, ["amount_msat", "max_routing_fee_msat"]);
+
+		assert_eq!(validate_invoice_amount("invoice", &invoice(1_000)).unwrap(), Some(1_000));
+		// LDK takes an invoice whose amount overflows to have none.
+		let overflowing = overflowing_invoice();
+		assert_eq!(overflowing.amount_milli_satoshis(), None);
+		let err = validate_invoice_amount("invoice", &overflowing).unwrap_err();
+		assert_eq!(err.validation_errors[0].field, "invoice");
+
+		assert_eq!(msat_to_sats_ceil(0), 0);
+		assert_eq!(msat_to_sats_ceil(1_001), 2);
+		assert_eq!(msat_to_sats_ceil(u64::MAX), u64::MAX / 1000 + 1);
+	}
+}
@@ -0,0 +1,144 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::AuthError;
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::io::rotated_auth_token::read_rotated_auth_token_record;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// The time during which an auth token replaced by a rotation is still accepted, such that clients
+/// with requests in flight, or yet to pick up the new token, aren't locked out.
+pub(crate) const AUTH_TOKEN_GRACE_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+/// Checks the `Authorization` header of a request against the configured `auth_token`, requiring
+/// it to be `Bearer <auth_token>`. Requests are accepted without checks if no token is configured.
+pub(crate) fn check_bearer_token(
+	auth_token: Option<&str>, authorization: Option<&[u8]>,
+) -> Result<(), LdkServerError> {
+	let auth_token = match auth_token {
+		Some(auth_token) => auth_token,
+		None => return Ok(()),
+	};
+	let token = authorization.and_then(|authorization| authorization.strip_prefix(b"Bearer "));
+	match token {
+		// Compare in constant time to not leak the token through response timings.
+		Some(token)
+			if ring::constant_time::verify_slices_are_equal(token, auth_token.as_bytes())
+				.is_ok() =>
+		{
+			Ok(())
+		},
+		Some(_) => Err(LdkServerError::new(AuthError, "Invalid auth token.")),
+		None => Err(LdkServerError::new(AuthError, "Missing bearer auth token.")),
+	}
+}
+
+/// The auth token replaced by the latest rotation, accepted until its grace period ends.
+pub(crate) struct RetiredAuthToken {
+	retired: Mutex<Option<(String, SystemTime)>>,
+}
+
+impl RetiredAuthToken {
+	pub(crate) fn new() -> Self {
+		Self { retired: Mutex::new(None) }
+	}
+
+	/// Retires `auth_token` at `now`, accepting it for another [`AUTH_TOKEN_GRACE_PERIOD`] and
+	/// replacing any token retired before.
+	pub(crate) fn retire(&self, auth_token: String, now: SystemTime) {
+		*self.retired.lock().unwrap() = Some((auth_token, now + AUTH_TOKEN_GRACE_PERIOD));
+	}
+
+	fn check(&self, authorization: Option<&[u8]>, now: SystemTime) -> Result<(), LdkServerError> {
+		match &*self.retired.lock().unwrap() {
+			Some((auth_token, accepted_until)) if now < *accepted_until => {
+				check_bearer_token(Some(auth_token), authorization)
+			},
+			_ => Err(LdkServerError::new(AuthError, "Invalid auth token.")),
+		}
+	}
+}
+
+/// Checks the `Authorization` header of a request like [`check_bearer_token`], also accepting the
+/// `retired_auth_token` while it's within its grace period at `now`.
+pub(crate) fn check_rotated_bearer_token(
+	auth_token: Option<&str>, retired_auth_token: &RetiredAuthToken, authorization: Option<&[u8]>,
+	now: SystemTime,
+) -> Result<(), LdkServerError> {
+	check_bearer_token(auth_token, authorization)
+		.or_else(|e| retired_auth_token.check(authorization, now).map_err(|_| e))
+}
+
+/// Returns the auth token in effect given the `configured_auth_token` of the config file, i.e., the
+/// token set via `RotateAuthToken` unless the config file was changed since.
+pub(crate) fn effective_auth_token(
+	store: &dyn PaginatedKVStore, configured_auth_token: Option<String>,
+) -> io::Result<Option<String>> {
+	match read_rotated_auth_token_record(store)? {
+		Some(record) if record.configured_auth_token == configured_auth_token => {
+			Ok(Some(record.auth_token))
+		},
+		_ => Ok(configured_auth_token),
+	}
+}
+
+/// Refuses serving the API on `rest_service_addr` if it is reachable from other hosts while
+/// requests are unauthenticated, unless `insecure` is explicitly set.
+pub(crate) fn check_rest_service_exposure(
+	rest_service_addr: SocketAddr, auth_token: Option<&str>, insecure: bool,
+) -> Result<(), String> {
+	if rest_service_addr.ip().is_loopback() || auth_token.is_some() || insecure {
+		return Ok(());
+	}
+	Err(format!(
+		"Refusing to serve unauthenticated requests on non-loopback address {}: configure an \
+		 `auth_token` or pass --insecure to start anyway.",
+		rest_service_addr
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_check_bearer_token() {
+		assert!(check_bearer_token(None, None).is_ok());
+		assert!(check_bearer_token(None, Some(b"Bearer anything")).is_ok());
+
+		let auth_token = Some("secret");
+		assert!(check_bearer_token(auth_token, Some(b"Bearer secret")).is_ok());
+		for authorization in [None, Some(&b"Bearer wrong"[..]), Some(b"secret"), Some(b"Bearer ")] {
+			let err = check_bearer_token(auth_token, authorization).unwrap_err();
+			assert_eq!(err.error_code, AuthError);
+		}
+	}
+
+	#[test]
+	fn test_retired_token_expires() {
+		let retired_auth_token = RetiredAuthToken::new();
+		let now = SystemTime::now();
+		assert!(retired_auth_token.check(Some(b"Bearer old"), now).is_err());
+
+		retired_auth_token.retire("old".to_string(), now);
+		let last_accepted_at = now + AUTH_TOKEN_GRACE_PERIOD - Duration::from_secs(1);
+		assert!(retired_auth_token.check(Some(b"Bearer old"), last_accepted_at).is_ok());
+		assert!(retired_auth_token.check(Some(b"Bearer other"), now).is_err());
+		let after_grace_period = now + AUTH_TOKEN_GRACE_PERIOD;
+		assert!(retired_auth_token.check(Some(b"Bearer old"), after_grace_period).is_err());
+	}
+
+	#[test]
+	fn test_check_rest_service_exposure() {
+		let any_addr: SocketAddr = "0.0.0.0:3002".parse().unwrap();
+		assert!(check_rest_service_exposure(any_addr, None, false).is_err());
+		assert!(check_rest_service_exposure(any_addr, Some("secret"), false).is_ok());
+		assert!(check_rest_service_exposure(any_addr, None, true).is_ok());
+
+		let loopback_addr: SocketAddr = "127.0.0.1:3002".parse().unwrap();
+		assert!(check_rest_service_exposure(loopback_addr, None, false).is_ok());
+		let loopback_addr: SocketAddr = "[::1]:3002".parse().unwrap();
+		assert!(check_rest_service_exposure(loopback_addr, None, false).is_ok());
+	}
+}
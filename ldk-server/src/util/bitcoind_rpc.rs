@@ -0,0 +1,39 @@
+use crate::util::config::Config;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use lightning_block_sync::http::HttpEndpoint;
+use lightning_block_sync::rpc::{RpcClient, RpcError};
+use std::io;
+
+/// The error code bitcoind returns for unknown transactions, blocks, etc.
+pub(crate) const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+
+/// Calls `method` with `params` on the bitcoind chain source configured in `config`.
+///
+/// Handlers are synchronous, so this blocks the worker thread rather than the runtime.
+pub(crate) fn call_bitcoind_rpc(
+	config: &Config, method: &str, params: &[serde_json::Value],
+) -> io::Result<serde_json::Value> {
+	let rpc_addr = config.bitcoind_rpc_addr;
+	let credentials = format!("{}:{}", config.bitcoind_rpc_user, config.bitcoind_rpc_password);
+	let endpoint = HttpEndpoint::for_host(rpc_addr.ip().to_string()).with_port(rpc_addr.port());
+	let rpc_client = RpcClient::new(&STANDARD.encode(credentials), endpoint)?;
+	tokio::task::block_in_place(|| {
+		tokio::runtime::Handle::current().block_on(rpc_client.call_method(method, params))
+	})
+}
+
+/// Returns the code of the error bitcoind responded with, if `e` is such an error rather than a
+/// failure to reach it.
+pub(crate) fn rpc_error_code(e: &io::Error) -> Option<i64> {
+	e.get_ref().and_then(|inner| inner.downcast_ref::<RpcError>()).map(|e| e.code)
+}
+
+/// Converts a feerate in BTC per 1000 virtual bytes, as given by bitcoind, to satoshis per 1000
+/// weight units.
+///
+/// Rounds up, so that transactions paying the converted feerate still meet the original one.
+pub(crate) fn btc_per_kvb_to_sat_per_kw(btc_per_kvb: f64) -> u64 {
+	let sat_per_kvb = (btc_per_kvb * 100_000_000.0).round() as u64;
+	(sat_per_kvb + 3) / 4
+}
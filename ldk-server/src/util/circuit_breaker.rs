@@ -0,0 +1,125 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::ServiceUnavailableError;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Short-circuits requests depending on the chain source once it failed repeatedly.
+///
+/// The breaker opens after `failure_threshold` consecutive chain source failures, failing requests
+/// right away. Once `retry_after` has passed, a single probe request is let through, closing the
+/// breaker again if it succeeds.
+pub(crate) struct CircuitBreaker {
+	state: Mutex<BreakerState>,
+}
+
+struct BreakerState {
+	failure_threshold: u32,
+	retry_after: Duration,
+	consecutive_failures: u32,
+	opened_at: Option<Instant>,
+	probe_in_flight: bool,
+}
+
+impl CircuitBreaker {
+	pub(crate) fn new(failure_threshold: u32, retry_after: Duration) -> Self {
+		let state = BreakerState {
+			failure_threshold,
+			retry_after,
+			consecutive_failures: 0,
+			opened_at: None,
+			probe_in_flight: false,
+		};
+		Self { state: Mutex::new(state) }
+	}
+
+	/// Updates the failure threshold and retry delay, taking effect from the next request on.
+	pub(crate) fn set_policy(&self, failure_threshold: u32, retry_after: Duration) {
+		let mut state = self.state.lock().unwrap();
+		state.failure_threshold = failure_threshold;
+		state.retry_after = retry_after;
+	}
+
+	/// The time clients are advised to wait before retrying while the breaker is open.
+	pub(crate) fn retry_after(&self) -> Duration {
+		self.state.lock().unwrap().retry_after
+	}
+
+	/// Fails with a [`ServiceUnavailableError`] if the breaker is open and no probe is due.
+	pub(crate) fn check(&self) -> Result<(), LdkServerError> {
+		let mut state = self.state.lock().unwrap();
+		if let Some(opened_at) = state.opened_at {
+			if state.probe_in_flight || opened_at.elapsed() < state.retry_after {
+				return Err(LdkServerError::new(
+					ServiceUnavailableError,
+					"Chain source is unavailable, please retry later.",
+				));
+			}
+			state.probe_in_flight = true;
+		}
+		Ok(())
+	}
+
+	/// Records the outcome of a request let through by [`CircuitBreaker::check`].
+	///
+	/// Only [`ServiceUnavailableError`]s are considered chain source failures, while other errors
+	/// leave the breaker as is.
+	pub(crate) fn record(&self, error: Option<&LdkServerError>) {
+		let mut state = self.state.lock().unwrap();
+		state.probe_in_flight = false;
+		match error {
+			None => {
+				state.consecutive_failures = 0;
+				state.opened_at = None;
+			},
+			Some(e) if e.error_code == ServiceUnavailableError => {
+				state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+				if state.consecutive_failures >= state.failure_threshold {
+					state.opened_at = Some(Instant::now());
+				}
+			},
+			Some(_) => {},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+	use std::thread::sleep;
+
+	#[test]
+	fn test_breaker_opens_and_recovers() {
+		let breaker = CircuitBreaker::new(3, Duration::from_millis(100));
+		let chain_source_error = LdkServerError::new(ServiceUnavailableError, "");
+		let invalid_request_error = LdkServerError::new(InvalidRequestError, "");
+
+		for _ in 0..2 {
+			assert!(breaker.check().is_ok());
+			breaker.record(Some(&chain_source_error));
+		}
+		// Unrelated errors neither count as failures nor reset the count.
+		assert!(breaker.check().is_ok());
+		breaker.record(Some(&invalid_request_error));
+
+		assert!(breaker.check().is_ok());
+		breaker.record(Some(&chain_source_error));
+		let err = breaker.check().unwrap_err();
+		assert_eq!(err.error_code, ServiceUnavailableError);
+
+		// A failing probe keeps the breaker open.
+		sleep(Duration::from_millis(150));
+		assert!(breaker.check().is_ok());
+		assert!(breaker.check().is_err());
+		breaker.record(Some(&chain_source_error));
+		assert!(breaker.check().is_err());
+
+		// A succeeding probe closes it.
+		sleep(Duration::from_millis(150));
+		assert!(breaker.check().is_ok());
+		breaker.record(None);
+		assert!(breaker.check().is_ok());
+		breaker.record(Some(&chain_source_error));
+		assert!(breaker.check().is_ok());
+	}
+}
@@ -0,0 +1,197 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::close_timeouts::{
+	read_close_timeout_record, remove_close_timeout_record, write_close_timeout_record,
+	CloseTimeoutRecord,
+};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use ldk_node::bitcoin::secp256k1::PublicKey;
+use ldk_node::{Node, NodeError, UserChannelId};
+use std::io;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The reason given for force-closing a channel whose cooperative close timed out.
+const CLOSE_TIMEOUT_REASON: &str = "Cooperative close timed out";
+
+/// Returns the timeout of a cooperative close whose request sets `cooperative_timeout_secs`, if
+/// any, failing on a zero timeout or if `force_close_on_timeout` is set without one.
+pub(crate) fn close_timeout(
+	cooperative_timeout_secs: Option<u32>, force_close_on_timeout: bool,
+) -> Result<Option<Duration>, LdkServerError> {
+	match cooperative_timeout_secs {
+		Some(0) => Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"cooperative_timeout_secs",
+			"must be greater than zero".to_string(),
+		)])),
+		Some(timeout_secs) => Ok(Some(Duration::from_secs(timeout_secs as u64))),
+		None if force_close_on_timeout => {
+			Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+				"force_close_on_timeout",
+				"can only be set along with cooperative_timeout_secs".to_string(),
+			)]))
+		},
+		None => Ok(None),
+	}
+}
+
+/// Records that the cooperative close of the channel with the given id times out if the channel
+/// is still open `timeout` after `now`, force-closing it then if `force_close_on_timeout` is set.
+pub(crate) fn schedule_close_timeout(
+	store: &dyn PaginatedKVStore, user_channel_id: &UserChannelId, counterparty_node_id: PublicKey,
+	timeout: Duration, force_close_on_timeout: bool, now: SystemTime,
+) -> io::Result<()> {
+	let record = CloseTimeoutRecord {
+		counterparty_node_id: counterparty_node_id.to_string(),
+		timeout_at_secs: unix_secs(now) + timeout.as_secs(),
+		force_close_on_timeout,
+	};
+	write_close_timeout_record(store, &close_timeout_key(user_channel_id), &record)
+}
+
+/// Forgets the timeout of the close of the channel with the given id, e.g., once it's closed.
+pub(crate) fn cancel_close_timeout(store: &dyn PaginatedKVStore, user_channel_id: &UserChannelId) {
+	let key = close_timeout_key(user_channel_id);
+	if let Err(e) = remove_close_timeout_record(store, &key) {
+		eprintln!("Failed to remove close timeout of channel {}: {}", key, e);
+	}
+}
+
+/// Handles the cooperative closes that timed out, i.e., whose channels are still open past their
+/// timeout, force-closing the channels of those that are to be escalated.
+pub(crate) fn escalate_timed_out_closes(
+	node: &Node, store: &dyn PaginatedKVStore, now: SystemTime,
+) {
+	let open_channels: Vec<_> =
+		node.list_channels().into_iter().map(|channel| channel.user_channel_id).collect();
+	let force_close = |user_channel_id: &UserChannelId, counterparty_node_id: PublicKey| {
+		node.force_close_channel(
+			user_channel_id,
+			counterparty_node_id,
+			Some(CLOSE_TIMEOUT_REASON.to_string()),
+		)
+	};
+	for (user_channel_id, escalated) in time_out_closes(store, &open_channels, now, force_close) {
+		let action = if escalated { "force-closed" } else { "left open" };
+		println!("CLOSE_TIMED_OUT: channel {}, {}", close_timeout_key(&user_channel_id), action);
+	}
+}
+
+// Times out the closes of those of the `open_channels` past their timeout at `now`, force-closing
+// the channels via `force_close` if requested. Returns the ids of the channels whose closes timed
+// out, along with whether they were force-closed.
+//
+// Each close times out once, so that a force-close that fails, e.g., because the channel closed
+// meanwhile, isn't retried.
+fn time_out_closes<F>(
+	store: &dyn PaginatedKVStore, open_channels: &[UserChannelId], now: SystemTime,
+	mut force_close: F,
+) -> Vec<(UserChannelId, bool)>
+where
+	F: FnMut(&UserChannelId, PublicKey) -> Result<(), NodeError>,
+{
+	let mut timed_out = Vec::new();
+	for user_channel_id in open_channels {
+		let key = close_timeout_key(user_channel_id);
+		let record = match read_close_timeout_record(store, &key) {
+			Ok(Some(record)) if record.timeout_at_secs <= unix_secs(now) => record,
+			Ok(_) => continue,
+			Err(e) => {
+				eprintln!("Failed to read close timeout of channel {}: {}", key, e);
+				continue;
+			},
+		};
+		if let Err(e) = remove_close_timeout_record(store, &key) {
+			eprintln!("Failed to remove close timeout of channel {}: {}", key, e);
+			continue;
+		}
+		let escalated = record.force_close_on_timeout
+			&& match PublicKey::from_str(&record.counterparty_node_id) {
+				Ok(counterparty_node_id) => {
+					match force_close(user_channel_id, counterparty_node_id) {
+						Ok(()) => true,
+						Err(e) => {
+							eprintln!("Failed to force-close channel {}: {}", key, e);
+							false
+						},
+					}
+				},
+				Err(e) => {
+					eprintln!("Invalid counterparty stored for channel {}: {}", key, e);
+					false
+				},
+			};
+		timed_out.push((*user_channel_id, escalated));
+	}
+	timed_out
+}
+
+fn close_timeout_key(user_channel_id: &UserChannelId) -> String {
+	format!("{:032x}", user_channel_id.0)
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+
+	#[test]
+	fn test_close_with_unresponsive_peer_escalates_after_timeout() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let counterparty_node_id = PublicKey::from_str(
+			"02eadbd9e7557375161df8b646776a547c5cbc2e95b3071ec81553f8ec2cea3b8c",
+		)
+		.unwrap();
+		let timeout = close_timeout(Some(60), true).unwrap().unwrap();
+		assert_eq!(timeout, Duration::from_secs(60));
+
+		// The peer never responds to our shutdown, so both channels stay open.
+		let (escalating, lingering) = (UserChannelId(1), UserChannelId(2));
+		let closed_at = SystemTime::now();
+		for (user_channel_id, force_close_on_timeout) in [(escalating, true), (lingering, false)] {
+			schedule_close_timeout(
+				&store,
+				&user_channel_id,
+				counterparty_node_id,
+				timeout,
+				force_close_on_timeout,
+				closed_at,
+			)
+			.unwrap();
+		}
+		let open_channels = [escalating, lingering];
+
+		let mut force_closed = Vec::new();
+		let mut force_close = |user_channel_id: &UserChannelId, node_id: PublicKey| {
+			assert_eq!(node_id, counterparty_node_id);
+			force_closed.push(*user_channel_id);
+			Ok(())
+		};
+		let before_timeout = closed_at + Duration::from_secs(59);
+		assert!(
+			time_out_closes(&store, &open_channels, before_timeout, &mut force_close).is_empty()
+		);
+
+		let after_timeout = closed_at + Duration::from_secs(60);
+		let timed_out = time_out_closes(&store, &open_channels, after_timeout, &mut force_close);
+		assert_eq!(timed_out, [(escalating, true), (lingering, false)]);
+
+		// Closes only time out once.
+		assert!(time_out_closes(&store, &open_channels, after_timeout, &mut force_close).is_empty());
+		assert_eq!(force_closed, [escalating]);
+	}
+
+	#[test]
+	fn test_close_timeout_validation() {
+		assert_eq!(close_timeout(None, false).unwrap(), None);
+		assert_eq!(close_timeout(Some(30), false).unwrap(), Some(Duration::from_secs(30)));
+		let err = close_timeout(Some(0), true).unwrap_err();
+		assert_eq!(err.validation_errors[0].field, "cooperative_timeout_secs");
+		let err = close_timeout(None, true).unwrap_err();
+		assert_eq!(err.validation_errors[0].field, "force_close_on_timeout");
+	}
+}
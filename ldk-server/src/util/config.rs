@@ -1,13 +1,56 @@
+use crate::util::cors::{CorsPolicy, DEFAULT_CORS_ALLOWED_HEADERS, DEFAULT_CORS_ALLOWED_METHODS};
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::Method;
+use ldk_node::bitcoin::secp256k1::PublicKey;
 use ldk_node::bitcoin::Network;
 use ldk_node::lightning::ln::msgs::SocketAddress;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::path::Path;
 use std::str::FromStr;
+use std::time::Duration;
 use std::{fs, io};
 
+/// The default maximum number of concurrently served REST connections.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 128;
+
+/// The default time in seconds a client may take to send the request headers.
+pub const DEFAULT_HEADER_READ_TIMEOUT_SECS: u64 = 30;
+
+/// The default number of consecutive chain source failures after which requests depending on the
+/// chain source are failed right away.
+pub const DEFAULT_CHAIN_SOURCE_FAILURE_THRESHOLD: u32 = 5;
+
+/// The default time in seconds after which a request is let through to probe a failing chain source.
+pub const DEFAULT_CHAIN_SOURCE_RETRY_AFTER_SECS: u64 = 30;
+
+/// The default time in seconds for which request nonces are tracked.
+pub const DEFAULT_REQUEST_NONCE_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+/// The maximum length in bytes of a BOLT11 invoice description.
+pub const MAX_INVOICE_DESCRIPTION_LEN: usize = 639;
+
+/// The default expiry time in seconds of BOLT11 invoices for which none was requested.
+pub const DEFAULT_INVOICE_EXPIRY_SECS: u32 = 3600;
+
+/// The default maximum number of events buffered for `QueryEvents`.
+pub const DEFAULT_MAX_BUFFERED_EVENTS: usize = 100_000;
+
+/// What happens to newly emitted events once `max_buffered_events` are buffered.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BufferedEventsPolicy {
+	/// The oldest buffered events are dropped to make room.
+	#[default]
+	DropOldest,
+	/// Newly emitted events are dropped until buffered events past their retention are pruned.
+	/// The node's events are still handled either way.
+	DropNewest,
+}
+
 /// Configuration for LDK Server.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Config {
 	pub listening_addr: SocketAddress,
 	pub network: Network,
@@ -16,6 +59,26 @@ pub struct Config {
 	pub bitcoind_rpc_addr: SocketAddr,
 	pub bitcoind_rpc_user: String,
 	pub bitcoind_rpc_password: String,
+	pub node_alias: Option<String>,
+	pub max_connections: usize,
+	pub header_read_timeout: Duration,
+	pub chain_source_failure_threshold: u32,
+	pub chain_source_retry_after: Duration,
+	pub disabled_endpoints: HashSet<String>,
+	pub max_routing_fee_msat: Option<u64>,
+	pub require_request_nonce: bool,
+	pub request_nonce_window: Duration,
+	pub max_invoice_description_len: usize,
+	pub default_invoice_expiry_secs: u32,
+	pub list_supported_endpoints: bool,
+	pub auth_token: Option<String>,
+	pub allow_unauthenticated_ping: bool,
+	pub onchain_receive_dedup_window: Option<Duration>,
+	pub outbound_payment_timeout: Option<Duration>,
+	pub cors: CorsPolicy,
+	pub trusted_peers_0conf: Vec<PublicKey>,
+	pub max_buffered_events: usize,
+	pub buffered_events_policy: BufferedEventsPolicy,
 }
 
 impl TryFrom<JsonConfig> for Config {
@@ -45,6 +108,126 @@ impl TryFrom<JsonConfig> for Config {
 				)
 			})?;
 
+		if let Some(node_alias) = json_config.node_alias.as_ref() {
+			if node_alias.len() > 32 {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidInput,
+					"Invalid node alias configured: must be no longer than 32 bytes",
+				));
+			}
+		}
+
+		let max_connections = json_config.max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS);
+		if max_connections == 0 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"Invalid max connections configured: must be greater than zero",
+			));
+		}
+		let header_read_timeout = Duration::from_secs(
+			json_config.header_read_timeout_secs.unwrap_or(DEFAULT_HEADER_READ_TIMEOUT_SECS),
+		);
+
+		let chain_source_failure_threshold = json_config
+			.chain_source_failure_threshold
+			.unwrap_or(DEFAULT_CHAIN_SOURCE_FAILURE_THRESHOLD);
+		if chain_source_failure_threshold == 0 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"Invalid chain source failure threshold configured: must be greater than zero",
+			));
+		}
+		let chain_source_retry_after = Duration::from_secs(
+			json_config
+				.chain_source_retry_after_secs
+				.unwrap_or(DEFAULT_CHAIN_SOURCE_RETRY_AFTER_SECS),
+		);
+
+		let request_nonce_window_secs =
+			json_config.request_nonce_window_secs.unwrap_or(DEFAULT_REQUEST_NONCE_WINDOW_SECS);
+		if request_nonce_window_secs == 0 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"Invalid request nonce window configured: must be greater than zero",
+			));
+		}
+
+		let max_invoice_description_len =
+			json_config.max_invoice_description_len.unwrap_or(MAX_INVOICE_DESCRIPTION_LEN);
+		if max_invoice_description_len > MAX_INVOICE_DESCRIPTION_LEN {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				format!(
+					"Invalid max invoice description length configured: must be no more than {} bytes",
+					MAX_INVOICE_DESCRIPTION_LEN
+				),
+			));
+		}
+
+		let default_invoice_expiry_secs =
+			json_config.default_invoice_expiry_secs.unwrap_or(DEFAULT_INVOICE_EXPIRY_SECS);
+		if default_invoice_expiry_secs == 0 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"Invalid default invoice expiry configured: must be greater than zero",
+			));
+		}
+
+		if json_config.auth_token.as_deref() == Some("") {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"Invalid auth token configured: must not be empty",
+			));
+		}
+
+		let onchain_receive_dedup_window = match json_config.onchain_receive_dedup_window_secs {
+			Some(0) => {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidInput,
+					"Invalid on-chain receive dedup window configured: must be greater than zero",
+				));
+			},
+			window_secs => window_secs.map(Duration::from_secs),
+		};
+
+		let outbound_payment_timeout = match json_config.outbound_payment_timeout_secs {
+			Some(0) => {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidInput,
+					"Invalid outbound payment timeout configured: must be greater than zero",
+				));
+			},
+			timeout_secs => timeout_secs.map(Duration::from_secs),
+		};
+
+		let trusted_peers_0conf = json_config
+			.trusted_peers_0conf
+			.iter()
+			.map(|node_id| {
+				PublicKey::from_str(node_id).map_err(|e| {
+					io::Error::new(
+						io::ErrorKind::InvalidInput,
+						format!("Invalid trusted 0conf peer configured: {}: {}", node_id, e),
+					)
+				})
+			})
+			.collect::<io::Result<Vec<_>>>()?;
+
+		let max_buffered_events =
+			json_config.max_buffered_events.unwrap_or(DEFAULT_MAX_BUFFERED_EVENTS);
+		if max_buffered_events == 0 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"Invalid max buffered events configured: must be greater than zero",
+			));
+		}
+
+		let cors = cors_policy(
+			json_config.cors_allowed_origins,
+			json_config.cors_allowed_methods,
+			json_config.cors_allowed_headers,
+		)?;
+
 		Ok(Config {
 			listening_addr,
 			network: json_config.network,
@@ -53,10 +236,68 @@ impl TryFrom<JsonConfig> for Config {
 			bitcoind_rpc_addr,
 			bitcoind_rpc_user: json_config.bitcoind_rpc_user,
 			bitcoind_rpc_password: json_config.bitcoind_rpc_password,
+			node_alias: json_config.node_alias,
+			max_connections,
+			header_read_timeout,
+			chain_source_failure_threshold,
+			chain_source_retry_after,
+			disabled_endpoints: json_config.disabled_endpoints.into_iter().collect(),
+			max_routing_fee_msat: json_config.max_routing_fee_msat,
+			require_request_nonce: json_config.require_request_nonce,
+			request_nonce_window: Duration::from_secs(request_nonce_window_secs),
+			max_invoice_description_len,
+			default_invoice_expiry_secs,
+			list_supported_endpoints: json_config.list_supported_endpoints,
+			auth_token: json_config.auth_token,
+			allow_unauthenticated_ping: json_config.allow_unauthenticated_ping,
+			onchain_receive_dedup_window,
+			outbound_payment_timeout,
+			cors,
+			trusted_peers_0conf,
+			max_buffered_events,
+			buffered_events_policy: json_config.buffered_events_policy,
 		})
 	}
 }
 
+fn cors_policy(
+	allowed_origins: Vec<String>, allowed_methods: Option<Vec<String>>,
+	allowed_headers: Option<Vec<String>>,
+) -> io::Result<CorsPolicy> {
+	let invalid = |setting: &str, value: &str| {
+		io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("Invalid CORS {} configured: {}", setting, value),
+		)
+	};
+	for origin in &allowed_origins {
+		if origin.is_empty() || HeaderValue::from_str(origin).is_err() {
+			return Err(invalid("origin", origin));
+		}
+	}
+	let allowed_methods = allowed_methods.unwrap_or_else(|| {
+		DEFAULT_CORS_ALLOWED_METHODS.iter().map(|method| method.to_string()).collect()
+	});
+	for method in &allowed_methods {
+		if Method::from_bytes(method.as_bytes()).is_err() {
+			return Err(invalid("method", method));
+		}
+	}
+	let allowed_headers = allowed_headers.unwrap_or_else(|| {
+		DEFAULT_CORS_ALLOWED_HEADERS.iter().map(|header| header.to_string()).collect()
+	});
+	for header in &allowed_headers {
+		if HeaderName::from_bytes(header.as_bytes()).is_err() {
+			return Err(invalid("header", header));
+		}
+	}
+	Ok(CorsPolicy {
+		allowed_origins: allowed_origins.into_iter().collect(),
+		allowed_methods,
+		allowed_headers,
+	})
+}
+
 /// Configuration loaded from a JSON file.
 #[derive(Deserialize, Serialize)]
 pub struct JsonConfig {
@@ -67,6 +308,50 @@ pub struct JsonConfig {
 	bitcoind_rpc_address: String,
 	bitcoind_rpc_user: String,
 	bitcoind_rpc_password: String,
+	#[serde(default)]
+	node_alias: Option<String>,
+	#[serde(default)]
+	max_connections: Option<usize>,
+	#[serde(default)]
+	header_read_timeout_secs: Option<u64>,
+	#[serde(default)]
+	chain_source_failure_threshold: Option<u32>,
+	#[serde(default)]
+	chain_source_retry_after_secs: Option<u64>,
+	#[serde(default)]
+	disabled_endpoints: Vec<String>,
+	#[serde(default)]
+	max_routing_fee_msat: Option<u64>,
+	#[serde(default)]
+	require_request_nonce: bool,
+	#[serde(default)]
+	request_nonce_window_secs: Option<u64>,
+	#[serde(default)]
+	max_invoice_description_len: Option<usize>,
+	#[serde(default)]
+	default_invoice_expiry_secs: Option<u32>,
+	#[serde(default)]
+	list_supported_endpoints: bool,
+	#[serde(default)]
+	auth_token: Option<String>,
+	#[serde(default)]
+	allow_unauthenticated_ping: bool,
+	#[serde(default)]
+	onchain_receive_dedup_window_secs: Option<u64>,
+	#[serde(default)]
+	outbound_payment_timeout_secs: Option<u64>,
+	#[serde(default)]
+	cors_allowed_origins: Vec<String>,
+	#[serde(default)]
+	cors_allowed_methods: Option<Vec<String>>,
+	#[serde(default)]
+	cors_allowed_headers: Option<Vec<String>>,
+	#[serde(default)]
+	trusted_peers_0conf: Vec<String>,
+	#[serde(default)]
+	max_buffered_events: Option<usize>,
+	#[serde(default)]
+	buffered_events_policy: BufferedEventsPolicy,
 }
 
 /// Loads the configuration from a JSON file at the given path.
@@ -85,14 +370,29 @@ pub fn load_config<P: AsRef<Path>>(config_path: P) -> io::Result<Config> {
 			format!("Config file contains invalid JSON format: {}", e),
 		)
 	})?;
-	Ok(Config::try_from(json_config)?)
+	Config::try_from(json_config)
 }
 
 fn remove_json_comments(s: &str) -> String {
-	s.lines()
-		.map(|line| if let Some(pos) = line.find("//") { &line[..pos] } else { line })
-		.collect::<Vec<&str>>()
-		.join("\n")
+	s.lines().map(strip_line_comment).collect::<Vec<&str>>().join("\n")
+}
+
+// Strips a trailing `//` comment from `line`, leaving `//` within strings, e.g., URLs, untouched.
+fn strip_line_comment(line: &str) -> &str {
+	let mut in_string = false;
+	let mut escaped = false;
+	let mut prev = None;
+	for (pos, c) in line.char_indices() {
+		match c {
+			_ if escaped => escaped = false,
+			'\\' if in_string => escaped = true,
+			'"' => in_string = !in_string,
+			'/' if !in_string && prev == Some('/') => return &line[..pos - 1],
+			_ => {},
+		}
+		prev = Some(c);
+	}
+	line
 }
 
 #[cfg(test)]
@@ -114,6 +414,25 @@ mod tests {
 			"bitcoind_rpc_address":"127.0.0.1:8332", // comment-1
 			"bitcoind_rpc_user": "bitcoind-testuser",
 			"bitcoind_rpc_password": "bitcoind-testpassword",
+			"node_alias": "ldk-server",
+			"header_read_timeout_secs": 10,
+			"chain_source_retry_after_secs": 60,
+			"disabled_endpoints": ["OnchainSend"],
+			"max_routing_fee_msat": 10000,
+			"require_request_nonce": true,
+			"request_nonce_window_secs": 600,
+			"max_invoice_description_len": 100,
+			"default_invoice_expiry_secs": 900,
+			"list_supported_endpoints": true,
+			"auth_token": "secret",
+			"allow_unauthenticated_ping": true,
+			"onchain_receive_dedup_window_secs": 10,
+			"outbound_payment_timeout_secs": 300,
+			"cors_allowed_origins": ["https://dashboard.example.com"],
+			"cors_allowed_methods": ["POST", "GET"],
+			"trusted_peers_0conf": ["02eadbd9e7557375161df8b646776a547c5cbc2e95b3071ec81553f8ec2cea3b8c"],
+			"max_buffered_events": 1000,
+			"buffered_events_policy": "drop_newest",
 			"unknown_key": "random-value"
 			// comment-2
 			}"#;
@@ -130,7 +449,51 @@ mod tests {
 				bitcoind_rpc_addr: SocketAddr::from_str("127.0.0.1:8332").unwrap(),
 				bitcoind_rpc_user: "bitcoind-testuser".to_string(),
 				bitcoind_rpc_password: "bitcoind-testpassword".to_string(),
+				node_alias: Some("ldk-server".to_string()),
+				max_connections: DEFAULT_MAX_CONNECTIONS,
+				header_read_timeout: Duration::from_secs(10),
+				chain_source_failure_threshold: DEFAULT_CHAIN_SOURCE_FAILURE_THRESHOLD,
+				chain_source_retry_after: Duration::from_secs(60),
+				disabled_endpoints: HashSet::from(["OnchainSend".to_string()]),
+				max_routing_fee_msat: Some(10000),
+				require_request_nonce: true,
+				request_nonce_window: Duration::from_secs(600),
+				max_invoice_description_len: 100,
+				default_invoice_expiry_secs: 900,
+				list_supported_endpoints: true,
+				auth_token: Some("secret".to_string()),
+				allow_unauthenticated_ping: true,
+				onchain_receive_dedup_window: Some(Duration::from_secs(10)),
+				outbound_payment_timeout: Some(Duration::from_secs(300)),
+				cors: CorsPolicy {
+					allowed_origins: HashSet::from(["https://dashboard.example.com".to_string()]),
+					allowed_methods: vec!["POST".to_string(), "GET".to_string()],
+					..CorsPolicy::default()
+				},
+				trusted_peers_0conf: vec![PublicKey::from_str(
+					"02eadbd9e7557375161df8b646776a547c5cbc2e95b3071ec81553f8ec2cea3b8c"
+				)
+				.unwrap()],
+				max_buffered_events: 1000,
+				buffered_events_policy: BufferedEventsPolicy::DropNewest,
 			}
 		)
 	}
+
+	#[test]
+	fn test_sample_config_is_valid() {
+		let sample_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("ldk-server.config");
+		let config = load_config(&sample_path).unwrap();
+		assert_eq!(config.network, Network::Regtest);
+
+		// Uncommenting the optional settings keeps the config valid.
+		let sample = fs::read_to_string(&sample_path).unwrap();
+		let uncommented = sample.replace("  // \"", "  \"");
+		assert_ne!(uncommented, sample);
+		let uncommented_path = std::env::temp_dir().join("uncommented-sample-config.json");
+		fs::write(&uncommented_path, uncommented).unwrap();
+		let config = load_config(uncommented_path).unwrap();
+		assert_eq!(config.auth_token.as_deref(), Some("a-long-random-string"));
+		assert_eq!(config.buffered_events_policy, BufferedEventsPolicy::DropOldest);
+	}
 }
@@ -0,0 +1,160 @@
+use crate::util::response_body::{full_body, ResponseBody};
+use hyper::body::Bytes;
+use hyper::header::{
+	HeaderMap, HeaderValue, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+	ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+	VARY,
+};
+use hyper::{Response, StatusCode};
+use std::collections::HashSet;
+
+/// The methods browsers may use for cross-origin requests unless configured otherwise.
+pub const DEFAULT_CORS_ALLOWED_METHODS: [&str; 1] = ["POST"];
+
+/// The headers browsers may send with cross-origin requests unless configured otherwise.
+pub const DEFAULT_CORS_ALLOWED_HEADERS: [&str; 3] =
+	["Authorization", "Content-Type", "X-Message-Framing"];
+
+/// The origin allowing cross-origin requests from any origin.
+const ANY_ORIGIN: &str = "*";
+
+/// The time in seconds browsers may cache the outcome of a preflight request.
+const PREFLIGHT_MAX_AGE_SECS: u64 = 600;
+
+/// The cross-origin resource sharing (CORS) policy browsers are told to enforce, allowing web
+/// dashboards on other origins to talk to the API directly.
+///
+/// Cross-origin requests are denied unless their origin is explicitly allowed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CorsPolicy {
+	/// The origins allowed to make requests, e.g., `https://dashboard.example.com`, or `*` for any.
+	pub allowed_origins: HashSet<String>,
+	pub allowed_methods: Vec<String>,
+	pub allowed_headers: Vec<String>,
+}
+
+impl Default for CorsPolicy {
+	fn default() -> Self {
+		Self {
+			allowed_origins: HashSet::new(),
+			allowed_methods: DEFAULT_CORS_ALLOWED_METHODS.iter().map(|m| m.to_string()).collect(),
+			allowed_headers: DEFAULT_CORS_ALLOWED_HEADERS.iter().map(|h| h.to_string()).collect(),
+		}
+	}
+}
+
+impl CorsPolicy {
+	/// Answers a preflight `OPTIONS` request with the given `headers`, allowing the actual request
+	/// if it's made from an allowed origin using an allowed method.
+	///
+	/// Preflight requests never carry credentials, so they are answered before authentication.
+	pub(crate) fn preflight_response(&self, headers: &HeaderMap) -> Response<ResponseBody> {
+		let origin = headers.get(ORIGIN).filter(|origin| self.allows_origin(origin));
+		let method = headers.get(ACCESS_CONTROL_REQUEST_METHOD);
+		let allows_method = method.map_or(false, |method| {
+			self.allowed_methods.iter().any(|allowed| allowed.as_bytes() == method.as_bytes())
+		});
+		let response = match origin {
+			Some(origin) if allows_method => Response::builder()
+				.status(StatusCode::NO_CONTENT)
+				.header(ACCESS_CONTROL_ALLOW_ORIGIN, origin)
+				.header(ACCESS_CONTROL_ALLOW_METHODS, self.allowed_methods.join(", "))
+				.header(ACCESS_CONTROL_ALLOW_HEADERS, self.allowed_headers.join(", "))
+				.header(ACCESS_CONTROL_MAX_AGE, PREFLIGHT_MAX_AGE_SECS)
+				.header(VARY, "Origin"),
+			// Without any CORS headers, browsers refuse making the actual request.
+			_ => Response::builder().status(StatusCode::FORBIDDEN),
+		};
+		// unwrap safety: the allowed methods and headers were validated when loading the config.
+		response.body(full_body(Bytes::new())).unwrap()
+	}
+
+	/// Allows the browser to expose `response` to the page making the request from `origin`, if
+	/// it's an allowed origin.
+	pub(crate) fn with_cors_headers(
+		&self, origin: Option<&HeaderValue>, mut response: Response<ResponseBody>,
+	) -> Response<ResponseBody> {
+		if let Some(origin) = origin.filter(|origin| self.allows_origin(origin)) {
+			response.headers_mut().insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+			response.headers_mut().append(VARY, HeaderValue::from_static("Origin"));
+		}
+		response
+	}
+
+	fn allows_origin(&self, origin: &HeaderValue) -> bool {
+		origin.to_str().map_or(false, |origin| {
+			self.allowed_origins.contains(ANY_ORIGIN) || self.allowed_origins.contains(origin)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DASHBOARD_ORIGIN: &str = "https://dashboard.example.com";
+
+	fn policy() -> CorsPolicy {
+		CorsPolicy {
+			allowed_origins: HashSet::from([DASHBOARD_ORIGIN.to_string()]),
+			..CorsPolicy::default()
+		}
+	}
+
+	fn preflight_headers(origin: &'static str, method: &'static str) -> HeaderMap {
+		let mut headers = HeaderMap::new();
+		headers.insert(ORIGIN, HeaderValue::from_static(origin));
+		headers.insert(ACCESS_CONTROL_REQUEST_METHOD, HeaderValue::from_static(method));
+		headers
+	}
+
+	#[test]
+	fn test_preflight_request() {
+		let response = policy().preflight_response(&preflight_headers(DASHBOARD_ORIGIN, "POST"));
+		assert_eq!(response.status(), StatusCode::NO_CONTENT);
+		let headers = response.headers();
+		assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], DASHBOARD_ORIGIN);
+		assert_eq!(headers[ACCESS_CONTROL_ALLOW_METHODS], "POST");
+		assert_eq!(
+			headers[ACCESS_CONTROL_ALLOW_HEADERS],
+			"Authorization, Content-Type, X-Message-Framing"
+		);
+		assert_eq!(headers[ACCESS_CONTROL_MAX_AGE], "600");
+
+		// Other origins and methods are denied, as is everything by default.
+		for (policy, origin, method) in [
+			(policy(), "https://evil.example.com", "POST"),
+			(policy(), DASHBOARD_ORIGIN, "DELETE"),
+			(CorsPolicy::default(), DASHBOARD_ORIGIN, "POST"),
+		] {
+			let response = policy.preflight_response(&preflight_headers(origin, method));
+			assert_eq!(response.status(), StatusCode::FORBIDDEN);
+			assert!(response.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+		}
+	}
+
+	#[test]
+	fn test_allowed_origin_request() {
+		let origin = HeaderValue::from_static(DASHBOARD_ORIGIN);
+		let response =
+			policy().with_cors_headers(Some(&origin), Response::new(full_body(Bytes::new())));
+		assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], DASHBOARD_ORIGIN);
+		assert_eq!(response.headers()[VARY], "Origin");
+
+		let other_origin = HeaderValue::from_static("https://evil.example.com");
+		for (policy, origin) in [
+			(policy(), Some(&other_origin)),
+			(policy(), None),
+			(CorsPolicy::default(), Some(&origin)),
+		] {
+			let response = policy.with_cors_headers(origin, Response::new(full_body(Bytes::new())));
+			assert!(response.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+		}
+
+		// Any origin is allowed with the wildcard origin, which is echoed back as is.
+		let policy = CorsPolicy { allowed_origins: HashSet::from(["*".to_string()]), ..policy() };
+		let response =
+			policy.with_cors_headers(Some(&other_origin), Response::new(full_body(Bytes::new())));
+		assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "https://evil.example.com");
+	}
+}
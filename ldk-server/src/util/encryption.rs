@@ -0,0 +1,78 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{InternalServerError, InvalidRequestError};
+use rand::RngCore;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+
+/// The length of keys accepted by [`encrypt`] and [`decrypt`].
+pub(crate) const ENCRYPTION_KEY_LEN: usize = 32;
+
+/// Encrypts `plaintext` with ChaCha20-Poly1305 under a random nonce, returning the nonce followed
+/// by the ciphertext and authentication tag.
+pub(crate) fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, LdkServerError> {
+	let key = aead_key(key)?;
+	let mut nonce = [0u8; NONCE_LEN];
+	rand::thread_rng().fill_bytes(&mut nonce);
+
+	let mut in_out = plaintext.to_vec();
+	key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut in_out)
+		.map_err(|_| LdkServerError::new(InternalServerError, "Failed to encrypt data."))?;
+
+	let mut encrypted = nonce.to_vec();
+	encrypted.extend_from_slice(&in_out);
+	Ok(encrypted)
+}
+
+/// Decrypts data created by [`encrypt`], failing if it was not encrypted under `key` or has been
+/// tampered with.
+pub(crate) fn decrypt(key: &[u8], encrypted: &[u8]) -> Result<Vec<u8>, LdkServerError> {
+	let key = aead_key(key)?;
+	let decryption_failed =
+		|| LdkServerError::new(InvalidRequestError, "Failed to decrypt data with the given key.");
+	if encrypted.len() < NONCE_LEN {
+		return Err(decryption_failed());
+	}
+	let (nonce, ciphertext) = encrypted.split_at(NONCE_LEN);
+	// unwrap safety: `nonce` is of length `NONCE_LEN`.
+	let nonce = Nonce::try_assume_unique_for_key(nonce).unwrap();
+
+	let mut in_out = ciphertext.to_vec();
+	let plaintext_len =
+		key.open_in_place(nonce, Aad::empty(), &mut in_out).map_err(|_| decryption_failed())?.len();
+	in_out.truncate(plaintext_len);
+	Ok(in_out)
+}
+
+fn aead_key(key: &[u8]) -> Result<LessSafeKey, LdkServerError> {
+	if key.len() != ENCRYPTION_KEY_LEN {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			format!("Encryption key must be {} bytes long.", ENCRYPTION_KEY_LEN),
+		));
+	}
+	// unwrap safety: the key length matches the algorithm's.
+	Ok(LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, key).unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_encryption_round_trip() {
+		let key = [42u8; ENCRYPTION_KEY_LEN];
+		let plaintext = b"channel monitor".repeat(10);
+
+		let encrypted = encrypt(&key, &plaintext).unwrap();
+		assert_eq!(encrypted.len(), NONCE_LEN + plaintext.len() + CHACHA20_POLY1305.tag_len());
+		assert!(!encrypted.windows(plaintext.len()).any(|window| window == &plaintext[..]));
+		assert_ne!(encrypt(&key, &plaintext).unwrap(), encrypted);
+		assert_eq!(decrypt(&key, &encrypted).unwrap(), plaintext);
+
+		assert!(decrypt(&[7u8; ENCRYPTION_KEY_LEN], &encrypted).is_err());
+		let mut tampered = encrypted.clone();
+		tampered[NONCE_LEN] ^= 1;
+		assert!(decrypt(&key, &tampered).is_err());
+		assert!(decrypt(&key, &encrypted[..NONCE_LEN - 1]).is_err());
+		assert!(encrypt(&key[..16], &plaintext).is_err());
+	}
+}
@@ -0,0 +1,210 @@
+use crate::io::events::{read_event_records, remove_event_record, write_event_record, EventRecord};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::util::config::BufferedEventsPolicy;
+use crate::util::metrics::{event_type, EventMetrics};
+use hex::DisplayHex;
+use ldk_node::Event;
+use std::collections::VecDeque;
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long the events emitted by the node are retained.
+pub(crate) const EVENT_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// The events persisted for `QueryEvents`, bounded in number such that they don't grow unbounded
+/// while no client queries them.
+pub(crate) struct EventLog {
+	// The handling time, in milliseconds since the UNIX epoch, and id of each persisted event,
+	// oldest first.
+	events: VecDeque<(u64, String)>,
+}
+
+impl EventLog {
+	/// Loads the events persisted before a restart from the `store`.
+	pub(crate) fn load(store: &dyn PaginatedKVStore) -> io::Result<Self> {
+		let mut events: Vec<_> = read_event_records(store)?
+			.into_iter()
+			.map(|(event_id, record)| (record.timestamp_ms, event_id))
+			.collect();
+		events.sort_unstable();
+		Ok(Self { events: events.into() })
+	}
+
+	/// Returns the number of persisted events.
+	pub(crate) fn len(&self) -> usize {
+		self.events.len()
+	}
+
+	/// Persists the given `event`, handled at `now`, such that it can be queried after restarts,
+	/// keeping at most `max_buffered_events` as per `policy`. Events not persisted or dropped to make
+	/// room are counted in the `metrics`.
+	pub(crate) fn persist(
+		&mut self, store: &dyn PaginatedKVStore, event: &Event, now: SystemTime,
+		max_buffered_events: usize, policy: BufferedEventsPolicy, metrics: &EventMetrics,
+	) {
+		if policy == BufferedEventsPolicy::DropNewest && self.len() >= max_buffered_events {
+			metrics.record_dropped_event();
+			return;
+		}
+		while self.len() >= max_buffered_events {
+			// unwrap safety: `max_buffered_events` is greater than zero, so there's an event.
+			let (_, event_id) = self.events.pop_front().unwrap();
+			if let Err(e) = remove_event_record(store, &event_id) {
+				eprintln!("Failed to remove event {}: {}", event_id, e);
+			}
+			metrics.record_dropped_event();
+		}
+
+		let record = event_record(event, unix_millis(now));
+		// Events can be handled within the same millisecond, so the timestamp alone isn't unique.
+		let event_id = format!("{:020}_{:016x}", record.timestamp_ms, rand::random::<u64>());
+		if let Err(e) = write_event_record(store, &event_id, &record) {
+			eprintln!("Failed to persist {} event: {}", record.event_type, e);
+			return;
+		}
+		self.events.push_back((record.timestamp_ms, event_id));
+	}
+
+	/// Removes the events that were handled longer than [`EVENT_RETENTION`] before `now`.
+	pub(crate) fn prune(&mut self, store: &dyn PaginatedKVStore, now: SystemTime) {
+		let cutoff_ms = unix_millis(now).saturating_sub(EVENT_RETENTION.as_millis() as u64);
+		while self.events.front().map_or(false, |(timestamp_ms, _)| *timestamp_ms < cutoff_ms) {
+			// unwrap safety: we just checked there's an event.
+			let (_, event_id) = self.events.pop_front().unwrap();
+			if let Err(e) = remove_event_record(store, &event_id) {
+				eprintln!("Failed to remove event {}: {}", event_id, e);
+			}
+		}
+	}
+}
+
+fn event_record(event: &Event, timestamp_ms: u64) -> EventRecord {
+	let mut record = EventRecord {
+		event_type: event_type(event).to_string(),
+		timestamp_ms,
+		channel_id: None,
+		user_channel_id: None,
+		counterparty_node_id: None,
+		payment_id: None,
+		payment_hash: None,
+		amount_msat: None,
+		fee_paid_msat: None,
+		reason: None,
+	};
+	match event {
+		Event::PaymentSuccessful { payment_id, payment_hash, fee_paid_msat } => {
+			record.payment_id = payment_id.map(|id| id.0.to_lower_hex_string());
+			record.payment_hash = Some(payment_hash.to_string());
+			record.fee_paid_msat = *fee_paid_msat;
+		},
+		Event::PaymentFailed { payment_id, payment_hash, reason } => {
+			record.payment_id = payment_id.map(|id| id.0.to_lower_hex_string());
+			record.payment_hash = payment_hash.map(|hash| hash.to_string());
+			record.reason = reason.map(|reason| format!("{:?}", reason));
+		},
+		Event::PaymentReceived { payment_id, payment_hash, amount_msat } => {
+			record.payment_id = payment_id.map(|id| id.0.to_lower_hex_string());
+			record.payment_hash = Some(payment_hash.to_string());
+			record.amount_msat = Some(*amount_msat);
+		},
+		Event::PaymentClaimable { payment_id, payment_hash, claimable_amount_msat, .. } => {
+			record.payment_id = Some(payment_id.0.to_lower_hex_string());
+			record.payment_hash = Some(payment_hash.to_string());
+			record.amount_msat = Some(*claimable_amount_msat);
+		},
+		Event::ChannelPending { channel_id, user_channel_id, counterparty_node_id, .. } => {
+			record.channel_id = Some(channel_id.to_string());
+			record.user_channel_id = Some(user_channel_id.0.to_string());
+			record.counterparty_node_id = Some(counterparty_node_id.to_string());
+		},
+		Event::ChannelReady { channel_id, user_channel_id, counterparty_node_id } => {
+			record.channel_id = Some(channel_id.to_string());
+			record.user_channel_id = Some(user_channel_id.0.to_string());
+			record.counterparty_node_id = counterparty_node_id.map(|node_id| node_id.to_string());
+		},
+		Event::ChannelClosed { channel_id, user_channel_id, counterparty_node_id, reason } => {
+			record.channel_id = Some(channel_id.to_string());
+			record.user_channel_id = Some(user_channel_id.0.to_string());
+			record.counterparty_node_id = counterparty_node_id.map(|node_id| node_id.to_string());
+			record.reason = reason.as_ref().map(|reason| reason.to_string());
+		},
+	}
+	record
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use ldk_node::lightning::ln::channelmanager::PaymentId;
+	use ldk_node::lightning::ln::PaymentHash;
+
+	fn payment_received(i: u8) -> Event {
+		Event::PaymentReceived {
+			payment_id: Some(PaymentId([i; 32])),
+			payment_hash: PaymentHash([i; 32]),
+			amount_msat: 1_000 * i as u64,
+		}
+	}
+
+	#[test]
+	fn test_events_past_retention_are_pruned() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let metrics = EventMetrics::new();
+		let mut event_log = EventLog::load(&store).unwrap();
+		let policy = BufferedEventsPolicy::DropOldest;
+		let now = SystemTime::now();
+		for time in [now - EVENT_RETENTION - Duration::from_secs(1), now - EVENT_RETENTION, now] {
+			event_log.persist(&store, &payment_received(1), time, 10, policy, &metrics);
+		}
+
+		event_log.prune(&store, now);
+		assert_eq!(event_log.len(), 2);
+		let records = read_event_records(&store).unwrap();
+		let timestamps: Vec<_> = records.iter().map(|(_, record)| record.timestamp_ms).collect();
+		assert_eq!(timestamps, [unix_millis(now), unix_millis(now - EVENT_RETENTION)]);
+		assert_eq!(records[0].1.amount_msat, Some(1_000));
+		assert_eq!(records[0].1.payment_id, Some("01".repeat(32)));
+	}
+
+	#[test]
+	fn test_event_flood_without_consumer_stays_bounded() {
+		let storage_path = random_storage_path();
+		let store = SqliteStore::new(storage_path.clone(), None, None).unwrap();
+		let metrics = EventMetrics::new();
+		let mut event_log = EventLog::load(&store).unwrap();
+		let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+		for i in 0..50u8 {
+			let now = start + Duration::from_secs(i as u64);
+			let policy = BufferedEventsPolicy::DropOldest;
+			event_log.persist(&store, &payment_received(i), now, 10, policy, &metrics);
+		}
+
+		// Only the newest events are kept, the others being counted as dropped.
+		assert_eq!(event_log.len(), 10);
+		let records = read_event_records(&store).unwrap();
+		let amounts: Vec<_> = records.iter().filter_map(|(_, record)| record.amount_msat).collect();
+		assert_eq!(amounts, (40..50).rev().map(|i| 1_000 * i).collect::<Vec<_>>());
+		assert!(metrics.render().lines().any(|line| line == "ldk_server_events_dropped_total 40"));
+
+		// With drop_newest, newly emitted events aren't buffered once the log is full, also after
+		// a restart, until pruning makes room.
+		let store = SqliteStore::new(storage_path, None, None).unwrap();
+		let mut event_log = EventLog::load(&store).unwrap();
+		let policy = BufferedEventsPolicy::DropNewest;
+		let now = start + EVENT_RETENTION + Duration::from_secs(45);
+		event_log.persist(&store, &payment_received(50), now, 10, policy, &metrics);
+		assert_eq!(event_log.len(), 10);
+		assert!(metrics.render().lines().any(|line| line == "ldk_server_events_dropped_total 41"));
+		event_log.prune(&store, now);
+		event_log.persist(&store, &payment_received(50), now, 10, policy, &metrics);
+		let records = read_event_records(&store).unwrap();
+		let amounts: Vec<_> = records.iter().filter_map(|(_, record)| record.amount_msat).collect();
+		assert_eq!(amounts, [50_000, 49_000, 48_000, 47_000, 46_000, 45_000]);
+	}
+}
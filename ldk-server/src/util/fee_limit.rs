@@ -0,0 +1,77 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+
+/// Returns the routing fee limit to apply to a payment, or `None` to apply LDK's default.
+///
+/// Payments are limited to the `requested_msat` if given and otherwise to the operator's
+/// `configured_max_msat`. Requests exceeding the latter are rejected unless they opt in via
+/// `override_limit`.
+pub(crate) fn routing_fee_limit(
+	configured_max_msat: Option<u64>, requested_msat: Option<u64>, override_limit: bool,
+) -> Result<Option<u64>, LdkServerError> {
+	match (configured_max_msat, requested_msat) {
+		(Some(max_msat), Some(requested_msat)) if requested_msat > max_msat && !override_limit => {
+			Err(LdkServerError::new(
+				InvalidRequestError,
+				format!(
+					"Requested routing fee limit of {} msat exceeds the maximum of {} msat, set override_fee_limit to allow it.",
+					requested_msat, max_msat
+				),
+			))
+		},
+		(_, Some(requested_msat)) => Ok(Some(requested_msat)),
+		(Some(_), None) if override_limit => Ok(None),
+		(configured_max_msat, None) => Ok(configured_max_msat),
+	}
+}
+
+/// Returns a warning to return alongside a payment whose routing fee is limited to `fee_limit_msat`
+/// if that exceeds the operator's `configured_max_msat`, as the request set `override_limit`.
+pub(crate) fn fee_limit_warning(
+	configured_max_msat: Option<u64>, fee_limit_msat: Option<u64>,
+) -> Option<String> {
+	match (configured_max_msat, fee_limit_msat) {
+		(Some(max_msat), Some(limit_msat)) if limit_msat > max_msat => Some(format!(
+			"The routing fee limit of {} msat exceeds the configured max_routing_fee_msat of {} msat.",
+			limit_msat, max_msat
+		)),
+		(Some(max_msat), None) => Some(format!(
+			"The routing fee isn't limited to the configured max_routing_fee_msat of {} msat.",
+			max_msat
+		)),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_routing_fee_limit() {
+		// Without a configured maximum, requests are unrestricted.
+		assert_eq!(routing_fee_limit(None, None, false).unwrap(), None);
+		assert_eq!(routing_fee_limit(None, Some(5_000), false).unwrap(), Some(5_000));
+
+		// The configured maximum applies by default and can be lowered.
+		assert_eq!(routing_fee_limit(Some(1_000), None, false).unwrap(), Some(1_000));
+		assert_eq!(routing_fee_limit(Some(1_000), Some(500), false).unwrap(), Some(500));
+
+		// Exceeding it is rejected, unless explicitly overridden.
+		let err = routing_fee_limit(Some(1_000), Some(5_000), false).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert_eq!(routing_fee_limit(Some(1_000), Some(5_000), true).unwrap(), Some(5_000));
+		assert_eq!(routing_fee_limit(Some(1_000), None, true).unwrap(), None);
+	}
+
+	#[test]
+	fn test_fee_limit_warning() {
+		assert_eq!(fee_limit_warning(None, None), None);
+		assert_eq!(fee_limit_warning(None, Some(5_000)), None);
+		assert_eq!(fee_limit_warning(Some(1_000), Some(1_000)), None);
+
+		// Overriding the configured maximum succeeds with a warning.
+		assert!(fee_limit_warning(Some(1_000), Some(5_000)).unwrap().contains("5000 msat"));
+		assert!(fee_limit_warning(Some(1_000), None).is_some());
+	}
+}
@@ -0,0 +1,109 @@
+use crate::util::response_body::{full_body, ResponseBody};
+use hyper::body::Bytes;
+use hyper::{Response, StatusCode};
+use ldk_node::NodeStatus;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The path of the liveness probe, without the leading '/'.
+pub(crate) const LIVEZ_PATH: &str = "livez";
+
+/// The path of the readiness probe, without the leading '/'.
+pub(crate) const READYZ_PATH: &str = "readyz";
+
+/// The maximum time since the wallets' last sync with the chain source for the node to be ready.
+///
+/// This is well above LDK Node's background sync intervals, such that only a stuck sync, or the
+/// sync timestamps persisted before a restart, make the node unready.
+const MAX_WALLET_SYNC_AGE: Duration = Duration::from_secs(300);
+
+/// Answers the liveness probe, i.e., tells whether the process is up and responsive.
+///
+/// This never depends on the node's state, such that orchestrators don't restart a process that
+/// is merely waiting on the node, e.g., while it syncs.
+pub(crate) fn liveness_response() -> Response<ResponseBody> {
+	probe_response(StatusCode::OK, "ok")
+}
+
+/// Answers the readiness probe, i.e., tells whether the node with the given `status` is ready to
+/// serve payments at `now`, with `503 Service Unavailable` and the reason if it isn't.
+pub(crate) fn readiness_response(status: &NodeStatus, now: SystemTime) -> Response<ResponseBody> {
+	match check_ready(status, now) {
+		Ok(()) => probe_response(StatusCode::OK, "ready"),
+		Err(reason) => probe_response(StatusCode::SERVICE_UNAVAILABLE, reason),
+	}
+}
+
+// Returns why a node with the given `status` isn't ready at `now`, if it isn't.
+fn check_ready(status: &NodeStatus, now: SystemTime) -> Result<(), &'static str> {
+	if !status.is_running {
+		return Err("node is not running");
+	}
+	let now_secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+	let is_synced = |timestamp: Option<u64>| {
+		timestamp.map_or(false, |timestamp| {
+			now_secs.saturating_sub(timestamp) <= MAX_WALLET_SYNC_AGE.as_secs()
+		})
+	};
+	if !is_synced(status.latest_onchain_wallet_sync_timestamp) {
+		return Err("on-chain wallet is not synced");
+	}
+	if !is_synced(status.latest_lightning_wallet_sync_timestamp) {
+		return Err("lightning wallet is not synced");
+	}
+	if status.latest_fee_rate_cache_update_timestamp.is_none() {
+		return Err("fee rates are not available");
+	}
+	Ok(())
+}
+
+fn probe_response(status: StatusCode, body: &'static str) -> Response<ResponseBody> {
+	Response::builder()
+		.status(status)
+		.body(full_body(Bytes::from_static(body.as_bytes())))
+		// unwrap safety: body only errors when previous chained calls failed.
+		.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::bitcoin::hashes::Hash;
+	use ldk_node::bitcoin::BlockHash;
+	use ldk_node::lightning::chain::BestBlock;
+
+	fn syncing_status() -> NodeStatus {
+		NodeStatus {
+			is_running: true,
+			is_listening: true,
+			current_best_block: BestBlock { block_hash: BlockHash::all_zeros(), height: 0 },
+			latest_lightning_wallet_sync_timestamp: None,
+			latest_onchain_wallet_sync_timestamp: None,
+			latest_fee_rate_cache_update_timestamp: None,
+			latest_rgs_snapshot_timestamp: None,
+			latest_node_announcement_broadcast_timestamp: None,
+			latest_channel_monitor_archival_height: None,
+		}
+	}
+
+	#[test]
+	fn test_live_but_not_ready_during_sync() {
+		let now = SystemTime::now();
+		let mut status = syncing_status();
+		assert_eq!(liveness_response().status(), StatusCode::OK);
+		assert_eq!(readiness_response(&status, now).status(), StatusCode::SERVICE_UNAVAILABLE);
+
+		let now_secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+		status.latest_onchain_wallet_sync_timestamp = Some(now_secs);
+		status.latest_lightning_wallet_sync_timestamp = Some(now_secs);
+		status.latest_fee_rate_cache_update_timestamp = Some(now_secs);
+		assert_eq!(readiness_response(&status, now).status(), StatusCode::OK);
+
+		// A sync that got stuck, e.g., on an unreachable chain source, makes the node unready again.
+		let later = now + MAX_WALLET_SYNC_AGE + Duration::from_secs(1);
+		assert_eq!(check_ready(&status, later), Err("on-chain wallet is not synced"));
+		assert_eq!(liveness_response().status(), StatusCode::OK);
+
+		status.is_running = false;
+		assert_eq!(check_ready(&status, now), Err("node is not running"));
+	}
+}
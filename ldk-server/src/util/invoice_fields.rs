@@ -0,0 +1,320 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use hex::FromHex;
+use ldk_node::bitcoin::hashes::{sha256, Hash};
+use ldk_node::bitcoin::secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use ldk_node::bitcoin::secp256k1::PublicKey;
+use ldk_node::bitcoin::{PubkeyHash, ScriptHash, WitnessVersion};
+use ldk_node::lightning::ln::features::Bolt11InvoiceFeatures;
+use ldk_node::lightning::ln::types::PaymentSecret;
+use ldk_node::lightning::routing::gossip::RoutingFees;
+use ldk_node::lightning::routing::router::{RouteHint, RouteHintHop};
+use ldk_node::lightning_invoice::{
+	Bolt11Invoice, Description, ExpiryTime, Fallback, MinFinalCltvExpiryDelta, PayeePubKey,
+	PositiveTimestamp, PrivateRoute, RawBolt11Invoice, RawDataPart, RawHrp, RawTaggedField, Sha256,
+	TaggedField,
+};
+use ldk_server_protos::types::bolt11_tagged_field::Field;
+use ldk_server_protos::types::{Bolt11Fallback, Bolt11InvoiceFields, Bolt11RouteHint};
+use std::convert::Infallible;
+use std::str::FromStr;
+
+/// The fallback version of P2PKH addresses, following the witness versions.
+const P2PKH_FALLBACK_VERSION: u32 = 17;
+
+/// The fallback version of P2SH addresses.
+const P2SH_FALLBACK_VERSION: u32 = 18;
+
+/// Rebuilds an invoice from the `fields` a client decoded it into, failing if they're malformed or
+/// don't match the invoice's signature.
+///
+/// Note that, as for invoice strings, the signature can only be checked against the payee's public
+/// key if the invoice includes it. Otherwise, it determines the payee.
+pub(crate) fn invoice_from_fields(
+	fields: &Bolt11InvoiceFields,
+) -> Result<Bolt11Invoice, LdkServerError> {
+	let hrp = RawHrp::from_str(&fields.hrp)
+		.map_err(|e| invalid_invoice_fields(format!("invalid hrp: {}", e)))?;
+	let timestamp = PositiveTimestamp::from_unix_timestamp(fields.timestamp)
+		.map_err(|e| invalid_invoice_fields(format!("invalid timestamp: {}", e)))?;
+	let tagged_fields = fields
+		.tagged_fields
+		.iter()
+		.map(|tagged_field| match &tagged_field.field {
+			Some(field) => known_tagged_field(field).map(RawTaggedField::KnownSemantics),
+			None => Err(invalid_invoice_fields("tagged fields must be set".to_string())),
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	let signature = recoverable_signature(&fields.signature)?;
+
+	let raw_invoice = RawBolt11Invoice { hrp, data: RawDataPart { timestamp, tagged_fields } };
+	let signed_invoice = match raw_invoice.sign(|_| Ok::<_, Infallible>(signature)) {
+		Ok(signed_invoice) => signed_invoice,
+		Err(infallible) => match infallible {},
+	};
+	// This checks the signature along with the invoice's semantics, e.g., that it has a payment
+	// secret.
+	Bolt11Invoice::from_signed(signed_invoice)
+		.map_err(|e| invalid_invoice_fields(format!("invalid invoice: {}", e)))
+}
+
+fn known_tagged_field(field: &Field) -> Result<TaggedField, LdkServerError> {
+	let tagged_field = match field {
+		Field::PaymentHash(payment_hash) => TaggedField::PaymentHash(sha256_hash(payment_hash)?),
+		Field::Description(description) => TaggedField::Description(
+			Description::new(description.clone())
+				.map_err(|e| invalid_invoice_fields(format!("invalid description: {}", e)))?,
+		),
+		Field::PayeePubKey(payee_pub_key) => {
+			TaggedField::PayeePubKey(PayeePubKey(public_key(payee_pub_key)?))
+		},
+		Field::DescriptionHash(description_hash) => {
+			TaggedField::DescriptionHash(sha256_hash(description_hash)?)
+		},
+		Field::ExpiryTimeSecs(expiry_time_secs) => {
+			TaggedField::ExpiryTime(ExpiryTime::from_seconds(*expiry_time_secs))
+		},
+		Field::MinFinalCltvExpiryDelta(delta) => {
+			TaggedField::MinFinalCltvExpiryDelta(MinFinalCltvExpiryDelta(*delta))
+		},
+		Field::Fallback(fallback) => TaggedField::Fallback(fallback_address(fallback)?),
+		Field::PrivateRoute(route_hint) => TaggedField::PrivateRoute(private_route(route_hint)?),
+		Field::PaymentSecret(payment_secret) => {
+			let payment_secret = <[u8; 32]>::from_hex(payment_secret).map_err(|_| {
+				invalid_invoice_fields("payment secret must be 32 hex-encoded bytes".to_string())
+			})?;
+			TaggedField::PaymentSecret(PaymentSecret(payment_secret))
+		},
+		Field::PaymentMetadata(payment_metadata) => {
+			TaggedField::PaymentMetadata(payment_metadata.to_vec())
+		},
+		Field::Features(features) => {
+			TaggedField::Features(Bolt11InvoiceFeatures::from_le_bytes(features.to_vec()))
+		},
+	};
+	Ok(tagged_field)
+}
+
+fn fallback_address(fallback: &Bolt11Fallback) -> Result<Fallback, LdkServerError> {
+	let invalid_program = || {
+		invalid_invoice_fields(format!("invalid program for fallback version {}", fallback.version))
+	};
+	match fallback.version {
+		P2PKH_FALLBACK_VERSION => PubkeyHash::from_slice(&fallback.program)
+			.map(Fallback::PubKeyHash)
+			.map_err(|_| invalid_program()),
+		P2SH_FALLBACK_VERSION => ScriptHash::from_slice(&fallback.program)
+			.map(Fallback::ScriptHash)
+			.map_err(|_| invalid_program()),
+		version => {
+			let version = u8::try_from(version)
+				.ok()
+				.and_then(|version| WitnessVersion::try_from(version).ok())
+				.ok_or_else(|| {
+					invalid_invoice_fields(format!("invalid fallback version {}", version))
+				})?;
+			Ok(Fallback::SegWitProgram { version, program: fallback.program.to_vec() })
+		},
+	}
+}
+
+fn private_route(route_hint: &Bolt11RouteHint) -> Result<PrivateRoute, LdkServerError> {
+	let hops = route_hint
+		.hops
+		.iter()
+		.map(|hop| {
+			let cltv_expiry_delta = u16::try_from(hop.cltv_expiry_delta).map_err(|_| {
+				invalid_invoice_fields(format!(
+					"route hint CLTV expiry delta must be at most {}",
+					u16::MAX
+				))
+			})?;
+			Ok(RouteHintHop {
+				src_node_id: public_key(&hop.src_node_id)?,
+				short_channel_id: hop.short_channel_id,
+				fees: RoutingFees {
+					base_msat: hop.fee_base_msat,
+					proportional_millionths: hop.fee_proportional_millionths,
+				},
+				cltv_expiry_delta,
+				htlc_minimum_msat: None,
+				htlc_maximum_msat: None,
+			})
+		})
+		.collect::<Result<Vec<_>, LdkServerError>>()?;
+	PrivateRoute::new(RouteHint(hops))
+		.map_err(|e| invalid_invoice_fields(format!("invalid route hint: {}", e)))
+}
+
+fn recoverable_signature(signature: &[u8]) -> Result<RecoverableSignature, LdkServerError> {
+	let invalid_signature = || {
+		invalid_invoice_fields(
+			"signature must be a 64-byte compact signature followed by its recovery id".to_string(),
+		)
+	};
+	if signature.len() != 65 {
+		return Err(invalid_signature());
+	}
+	let recovery_id =
+		RecoveryId::from_i32(signature[64] as i32).map_err(|_| invalid_signature())?;
+	RecoverableSignature::from_compact(&signature[..64], recovery_id)
+		.map_err(|_| invalid_signature())
+}
+
+fn sha256_hash(hash: &str) -> Result<Sha256, LdkServerError> {
+	let hash = <[u8; 32]>::from_hex(hash)
+		.map_err(|_| invalid_invoice_fields("hashes must be 32 hex-encoded bytes".to_string()))?;
+	Ok(Sha256(sha256::Hash::from_byte_array(hash)))
+}
+
+fn public_key(public_key: &str) -> Result<PublicKey, LdkServerError> {
+	PublicKey::from_str(public_key)
+		.map_err(|_| invalid_invoice_fields(format!("invalid public key {}", public_key)))
+}
+
+fn invalid_invoice_fields(reason: String) -> LdkServerError {
+	LdkServerError::invalid_fields(vec![ValidationError::new("invoice_fields", reason)])
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+	use bytes::Bytes;
+	use hex::DisplayHex;
+	use ldk_node::bitcoin::secp256k1::{Secp256k1, SecretKey};
+	use ldk_node::lightning_invoice::{Currency, InvoiceBuilder};
+	use ldk_server_protos::types::{Bolt11RouteHintHop, Bolt11TaggedField};
+	use std::time::Duration;
+
+	/// Returns the fields of `invoice`, as a client would decode it into.
+	pub(crate) fn invoice_fields(invoice: &Bolt11Invoice) -> Bolt11InvoiceFields {
+		let signed_invoice = invoice.clone().into_signed_raw();
+		let raw_invoice = signed_invoice.raw_invoice();
+		let tagged_fields = raw_invoice
+			.data
+			.tagged_fields
+			.iter()
+			.map(|raw_field| {
+				let RawTaggedField::KnownSemantics(tagged_field) = raw_field else {
+					panic!("Unknown tagged field");
+				};
+				Bolt11TaggedField { field: Some(field_to_proto(tagged_field)) }
+			})
+			.collect();
+		let (recovery_id, compact_signature) = signed_invoice.signature().0.serialize_compact();
+		let mut signature = compact_signature.to_vec();
+		signature.push(recovery_id.to_i32() as u8);
+		Bolt11InvoiceFields {
+			hrp: raw_invoice.hrp.to_string(),
+			timestamp: raw_invoice.data.timestamp.as_unix_timestamp(),
+			tagged_fields,
+			signature: Bytes::from(signature),
+		}
+	}
+
+	fn field_to_proto(tagged_field: &TaggedField) -> Field {
+		match tagged_field {
+			TaggedField::PaymentHash(hash) => Field::PaymentHash(hash.0.to_string()),
+			TaggedField::Description(description) => {
+				Field::Description(description.as_inner().0.clone())
+			},
+			TaggedField::PayeePubKey(payee_pub_key) => {
+				Field::PayeePubKey(payee_pub_key.0.to_string())
+			},
+			TaggedField::DescriptionHash(hash) => Field::DescriptionHash(hash.0.to_string()),
+			TaggedField::ExpiryTime(expiry_time) => Field::ExpiryTimeSecs(expiry_time.as_seconds()),
+			TaggedField::MinFinalCltvExpiryDelta(delta) => Field::MinFinalCltvExpiryDelta(delta.0),
+			TaggedField::Fallback(fallback) => {
+				let (version, program) = match fallback {
+					Fallback::SegWitProgram { version, program } => {
+						(version.to_num() as u32, program.clone())
+					},
+					Fallback::PubKeyHash(hash) => {
+						(P2PKH_FALLBACK_VERSION, hash.to_byte_array().to_vec())
+					},
+					Fallback::ScriptHash(hash) => {
+						(P2SH_FALLBACK_VERSION, hash.to_byte_array().to_vec())
+					},
+				};
+				Field::Fallback(Bolt11Fallback { version, program: Bytes::from(program) })
+			},
+			TaggedField::PrivateRoute(private_route) => {
+				let hops = private_route
+					.clone()
+					.into_inner()
+					.0
+					.iter()
+					.map(|hop| Bolt11RouteHintHop {
+						src_node_id: hop.src_node_id.to_string(),
+						short_channel_id: hop.short_channel_id,
+						fee_base_msat: hop.fees.base_msat,
+						fee_proportional_millionths: hop.fees.proportional_millionths,
+						cltv_expiry_delta: hop.cltv_expiry_delta as u32,
+					})
+					.collect();
+				Field::PrivateRoute(Bolt11RouteHint { hops })
+			},
+			TaggedField::PaymentSecret(payment_secret) => {
+				Field::PaymentSecret(payment_secret.0.to_lower_hex_string())
+			},
+			TaggedField::PaymentMetadata(payment_metadata) => {
+				Field::PaymentMetadata(Bytes::from(payment_metadata.clone()))
+			},
+			TaggedField::Features(features) => {
+				Field::Features(Bytes::from(features.le_flags().to_vec()))
+			},
+		}
+	}
+
+	#[test]
+	fn test_invoice_is_rebuilt_from_its_fields() {
+		let secp_ctx = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&[42; 32]).unwrap();
+		let payee_pub_key = PublicKey::from_secret_key(&secp_ctx, &secret_key);
+		let hop = RouteHintHop {
+			src_node_id: payee_pub_key,
+			short_channel_id: 42,
+			fees: RoutingFees { base_msat: 1_000, proportional_millionths: 100 },
+			cltv_expiry_delta: 40,
+			htlc_minimum_msat: None,
+			htlc_maximum_msat: None,
+		};
+		let invoice = InvoiceBuilder::new(Currency::Regtest)
+			.description("coffee".to_string())
+			.payment_hash(sha256::Hash::hash(&[1; 32]))
+			.payment_secret(PaymentSecret([1; 32]))
+			.duration_since_epoch(Duration::from_secs(1_700_000_000))
+			.min_final_cltv_expiry_delta(144)
+			.amount_milli_satoshis(2_500_000)
+			.payee_pub_key(payee_pub_key)
+			.expiry_time(Duration::from_secs(600))
+			.fallback(Fallback::PubKeyHash(PubkeyHash::from_byte_array([2; 20])))
+			.private_route(RouteHint(vec![hop]))
+			.payment_metadata(vec![1, 2, 3])
+			.basic_mpp()
+			.build_signed(|hash| secp_ctx.sign_ecdsa_recoverable(hash, &secret_key))
+			.unwrap();
+
+		let fields = invoice_fields(&invoice);
+		assert_eq!(fields.hrp, "lnbcrt25u");
+		let rebuilt_invoice = invoice_from_fields(&fields).unwrap();
+		assert_eq!(rebuilt_invoice, invoice);
+		assert_eq!(rebuilt_invoice.to_string(), invoice.to_string());
+
+		// Fields that don't match the signature are rejected, e.g., a tampered amount or a changed
+		// field order.
+		let tampered_fields = Bolt11InvoiceFields { hrp: "lnbcrt1m".to_string(), ..fields.clone() };
+		let err = invoice_from_fields(&tampered_fields).unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+		assert_eq!(err.validation_errors[0].field, "invoice_fields");
+		assert!(err.validation_errors[0].reason.contains("signature"));
+		let mut reordered_fields = fields.clone();
+		reordered_fields.tagged_fields.swap(0, 1);
+		assert!(invoice_from_fields(&reordered_fields).is_err());
+
+		let mut malformed_fields = fields;
+		malformed_fields.signature.truncate(64);
+		let err = invoice_from_fields(&malformed_fields).unwrap_err();
+		assert!(err.validation_errors[0].reason.contains("recovery id"));
+	}
+}
@@ -0,0 +1,133 @@
+use crate::api::error::LdkServerErrorCode::{InternalServerError, LightningError};
+use crate::api::error::{LdkServerError, ValidationError};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// The time we wait for each response of an LNURL service.
+const LNURL_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The human-readable part of bech32-encoded LNURLs (LUD-01).
+const LNURL_HRP: &str = "lnurl";
+
+/// Returns an HTTP client for talking to LNURL services.
+pub(crate) fn lnurl_client() -> Result<reqwest::Client, LdkServerError> {
+	reqwest::Client::builder().timeout(LNURL_REQUEST_TIMEOUT).build().map_err(|e| {
+		LdkServerError::new(InternalServerError, format!("Failed to create HTTP client: {}", e))
+	})
+}
+
+/// Returns the URL the given bech32-encoded `lnurl` or LUD-17 `lnurlw://` URL points to, with an
+/// optional `lightning:` prefix, failing with a validation error on `field` if it's neither.
+pub(crate) fn decode_lnurl(field: &str, lnurl: &str) -> Result<String, LdkServerError> {
+	let invalid =
+		|reason: String| LdkServerError::invalid_fields(vec![ValidationError::new(field, reason)]);
+	let lnurl = lnurl.trim();
+	let lnurl = match lnurl.get(..10) {
+		Some(prefix) if prefix.eq_ignore_ascii_case("lightning:") => &lnurl[10..],
+		_ => lnurl,
+	};
+	let url = if let Some((scheme, rest)) = lnurl.split_once("://") {
+		let host = rest.split(['/', '?']).next().unwrap_or_default();
+		match scheme.to_ascii_lowercase().as_str() {
+			"lnurlw" | "lnurlp" if host.ends_with(".onion") => format!("http://{}", rest),
+			"lnurlw" | "lnurlp" => format!("https://{}", rest),
+			_ => return Err(invalid(format!("unsupported scheme {}", scheme))),
+		}
+	} else {
+		let (hrp, data) = bech32::decode(lnurl).map_err(|e| invalid(e.to_string()))?;
+		if !hrp.as_str().eq_ignore_ascii_case(LNURL_HRP) {
+			return Err(invalid(format!("must start with {}1", LNURL_HRP)));
+		}
+		String::from_utf8(data).map_err(|_| invalid("must encode a URL".to_string()))?
+	};
+	if !url.starts_with("https://") && !url.starts_with("http://") {
+		return Err(invalid("must encode an HTTP(S) URL".to_string()));
+	}
+	Ok(url)
+}
+
+/// Makes a GET `request` to an LNURL service, parsing its response as `T` unless the service
+/// responded with an error.
+pub(crate) async fn lnurl_get<T: for<'de> Deserialize<'de>>(
+	request: reqwest::RequestBuilder,
+) -> Result<T, LdkServerError> {
+	let lightning_error = |message: String| LdkServerError::new(LightningError, message);
+	let body = request
+		.send()
+		.await
+		.and_then(|response| response.error_for_status())
+		.map_err(|e| lightning_error(format!("Failed to reach LNURL service: {}", e)))?
+		.bytes()
+		.await
+		.map_err(|e| lightning_error(format!("Failed to read LNURL service response: {}", e)))?;
+	let value: serde_json::Value = serde_json::from_slice(&body)
+		.map_err(|e| lightning_error(format!("LNURL service returned invalid response: {}", e)))?;
+	if value["status"].as_str().map_or(false, |status| status.eq_ignore_ascii_case("ERROR")) {
+		let reason = value["reason"].as_str().unwrap_or("unknown reason");
+		return Err(lightning_error(format!("LNURL service returned an error: {}", reason)));
+	}
+	serde_json::from_value(value)
+		.map_err(|e| lightning_error(format!("LNURL service returned invalid response: {}", e)))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use bech32::{Bech32, Hrp};
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use tokio::net::TcpListener;
+
+	/// Serves an LNURL service on localhost, answering each GET request with the JSON `respond`
+	/// returns for the service's base URL and the request's target, i.e., its path and query.
+	/// Returns the service's base URL.
+	pub(crate) async fn mock_lnurl_server<F>(respond: F) -> String
+	where
+		F: Fn(&str, &str) -> serde_json::Value + Send + 'static,
+	{
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let base_url = format!("http://{}", listener.local_addr().unwrap());
+		let service_url = base_url.clone();
+		tokio::spawn(async move {
+			loop {
+				let (mut stream, _) = listener.accept().await.unwrap();
+				let mut buf = vec![0; 4096];
+				let len = stream.read(&mut buf).await.unwrap();
+				let head = String::from_utf8_lossy(&buf[..len]).to_string();
+				let target = head.split(' ').nth(1).unwrap();
+				let body = respond(&service_url, target).to_string();
+				let response = format!(
+					"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+					body.len(),
+					body
+				);
+				stream.write_all(response.as_bytes()).await.unwrap();
+			}
+		});
+		base_url
+	}
+
+	#[test]
+	fn test_decode_lnurl() {
+		let url = "https://service.com/api?q=3fc3645b439ce8e7f2553a69e5267081d96dcd340693afabe04be7b0ccd178df";
+		let lnurl = bech32::encode::<Bech32>(Hrp::parse("lnurl").unwrap(), url.as_bytes()).unwrap();
+		assert!(lnurl.len() > 90);
+		assert_eq!(decode_lnurl("lnurl", &lnurl).unwrap(), url);
+		assert_eq!(decode_lnurl("lnurl", &lnurl.to_uppercase()).unwrap(), url);
+		assert_eq!(decode_lnurl("lnurl", &format!("lightning:{}", lnurl)).unwrap(), url);
+
+		assert_eq!(
+			decode_lnurl("lnurl", "lnurlw://service.com/withdraw?k1=abc").unwrap(),
+			"https://service.com/withdraw?k1=abc"
+		);
+		assert_eq!(
+			decode_lnurl("lnurl", "lnurlw://service.onion/withdraw").unwrap(),
+			"http://service.onion/withdraw"
+		);
+
+		let other_hrp = bech32::encode::<Bech32>(Hrp::parse("lnbc").unwrap(), url.as_bytes());
+		for lnurl in ["ftp://service.com", "lnurl1invalid", &other_hrp.unwrap()] {
+			let err = decode_lnurl("lnurl", lnurl).unwrap_err();
+			assert_eq!(err.validation_errors[0].field, "lnurl");
+		}
+	}
+}
@@ -0,0 +1,230 @@
+use ldk_node::Event;
+use ldk_server_protos::types::PaymentRail;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The path the metrics are served at, without the leading '/'.
+pub(crate) const METRICS_PATH: &str = "metrics";
+
+/// Counts the payments initiated via the API along with their volume, per payment rail.
+pub(crate) struct PaymentMetrics {
+	onchain: RailMetrics,
+	bolt11: RailMetrics,
+	bolt12: RailMetrics,
+}
+
+#[derive(Default)]
+struct RailMetrics {
+	payments: AtomicU64,
+	volume_msat: AtomicU64,
+}
+
+impl PaymentMetrics {
+	pub(crate) fn new() -> Self {
+		Self {
+			onchain: RailMetrics::default(),
+			bolt11: RailMetrics::default(),
+			bolt12: RailMetrics::default(),
+		}
+	}
+
+	/// Records a payment initiated on `rail`, adding `amount_msat` to its volume if known.
+	pub(crate) fn record_payment(&self, rail: PaymentRail, amount_msat: Option<u64>) {
+		let metrics = self.rail_metrics(rail);
+		metrics.payments.fetch_add(1, Ordering::Relaxed);
+		metrics.volume_msat.fetch_add(amount_msat.unwrap_or(0), Ordering::Relaxed);
+	}
+
+	/// Renders the metrics in the Prometheus text exposition format.
+	pub(crate) fn render(&self) -> String {
+		let rails = [PaymentRail::Onchain, PaymentRail::Bolt11, PaymentRail::Bolt12];
+		let mut output = String::new();
+		// unwrap safety: writing to a `String` never fails.
+		writeln!(output, "# HELP ldk_server_payments_total Payments initiated via the API.")
+			.unwrap();
+		writeln!(output, "# TYPE ldk_server_payments_total counter").unwrap();
+		for rail in rails {
+			let payments = self.rail_metrics(rail).payments.load(Ordering::Relaxed);
+			writeln!(
+				output,
+				"ldk_server_payments_total{{rail=\"{}\"}} {}",
+				rail_label(rail),
+				payments
+			)
+			.unwrap();
+		}
+		writeln!(
+			output,
+			"# HELP ldk_server_payment_volume_msat_total Amount of the payments initiated via the API, in millisatoshis."
+		)
+		.unwrap();
+		writeln!(output, "# TYPE ldk_server_payment_volume_msat_total counter").unwrap();
+		for rail in rails {
+			let volume_msat = self.rail_metrics(rail).volume_msat.load(Ordering::Relaxed);
+			writeln!(
+				output,
+				"ldk_server_payment_volume_msat_total{{rail=\"{}\"}} {}",
+				rail_label(rail),
+				volume_msat
+			)
+			.unwrap();
+		}
+		output
+	}
+
+	fn rail_metrics(&self, rail: PaymentRail) -> &RailMetrics {
+		match rail {
+			PaymentRail::Onchain => &self.onchain,
+			PaymentRail::Bolt11 => &self.bolt11,
+			PaymentRail::Bolt12 => &self.bolt12,
+		}
+	}
+}
+
+/// The types of events emitted by LDK Node, as labelled in the metrics.
+pub(crate) const EVENT_TYPES: [&str; 7] = [
+	"payment_successful",
+	"payment_failed",
+	"payment_received",
+	"payment_claimable",
+	"channel_pending",
+	"channel_ready",
+	"channel_closed",
+];
+
+/// Counts the events emitted by LDK Node per event type, such that operators can alert on spikes
+/// of, e.g., failed payments or channel closures.
+pub(crate) struct EventMetrics {
+	events: [AtomicU64; EVENT_TYPES.len()],
+	dropped_events: AtomicU64,
+}
+
+impl EventMetrics {
+	pub(crate) fn new() -> Self {
+		Self { events: Default::default(), dropped_events: AtomicU64::new(0) }
+	}
+
+	/// Records an `event` handled by the event loop.
+	pub(crate) fn record_event(&self, event: &Event) {
+		self.events[event_type_index(event)].fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Records that the oldest buffered event was dropped to make room for a new one.
+	pub(crate) fn record_dropped_event(&self) {
+		self.dropped_events.fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Renders the metrics in the Prometheus text exposition format.
+	pub(crate) fn render(&self) -> String {
+		let mut output = String::new();
+		// unwrap safety: writing to a `String` never fails.
+		writeln!(output, "# HELP ldk_server_events_total Events emitted by the node.").unwrap();
+		writeln!(output, "# TYPE ldk_server_events_total counter").unwrap();
+		for (event_type, events) in EVENT_TYPES.iter().zip(&self.events) {
+			writeln!(
+				output,
+				"ldk_server_events_total{{type=\"{}\"}} {}",
+				event_type,
+				events.load(Ordering::Relaxed)
+			)
+			.unwrap();
+		}
+		writeln!(
+			output,
+			"# HELP ldk_server_events_dropped_total Buffered events dropped to make room for new ones."
+		)
+		.unwrap();
+		writeln!(output, "# TYPE ldk_server_events_dropped_total counter").unwrap();
+		writeln!(
+			output,
+			"ldk_server_events_dropped_total {}",
+			self.dropped_events.load(Ordering::Relaxed)
+		)
+		.unwrap();
+		output
+	}
+}
+
+/// Returns the type of `event`, as labelled in the metrics.
+pub(crate) fn event_type(event: &Event) -> &'static str {
+	EVENT_TYPES[event_type_index(event)]
+}
+
+fn event_type_index(event: &Event) -> usize {
+	match event {
+		Event::PaymentSuccessful { .. } => 0,
+		Event::PaymentFailed { .. } => 1,
+		Event::PaymentReceived { .. } => 2,
+		Event::PaymentClaimable { .. } => 3,
+		Event::ChannelPending { .. } => 4,
+		Event::ChannelReady { .. } => 5,
+		Event::ChannelClosed { .. } => 6,
+	}
+}
+
+fn rail_label(rail: PaymentRail) -> &'static str {
+	match rail {
+		PaymentRail::Onchain => "onchain",
+		PaymentRail::Bolt11 => "bolt11",
+		PaymentRail::Bolt12 => "bolt12",
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::lightning::ln::channelmanager::PaymentId;
+	use ldk_node::lightning::ln::types::ChannelId;
+	use ldk_node::lightning::ln::PaymentHash;
+	use ldk_node::UserChannelId;
+
+	#[test]
+	fn test_per_rail_metrics() {
+		let metrics = PaymentMetrics::new();
+		metrics.record_payment(PaymentRail::Onchain, Some(50_000_000));
+		metrics.record_payment(PaymentRail::Bolt11, Some(1_000));
+		metrics.record_payment(PaymentRail::Bolt11, Some(2_500));
+		// Payments of unknown amounts, e.g., draining the on-chain wallet, only count as payments.
+		metrics.record_payment(PaymentRail::Bolt12, None);
+
+		let output = metrics.render();
+		for line in [
+			"ldk_server_payments_total{rail=\"onchain\"} 1",
+			"ldk_server_payments_total{rail=\"bolt11\"} 2",
+			"ldk_server_payments_total{rail=\"bolt12\"} 1",
+			"ldk_server_payment_volume_msat_total{rail=\"onchain\"} 50000000",
+			"ldk_server_payment_volume_msat_total{rail=\"bolt11\"} 3500",
+			"ldk_server_payment_volume_msat_total{rail=\"bolt12\"} 0",
+		] {
+			assert!(output.lines().any(|l| l == line), "Missing {} in:\n{}", line, output);
+		}
+	}
+
+	#[test]
+	fn test_event_metrics() {
+		let metrics = EventMetrics::new();
+		let payment_failed = Event::PaymentFailed {
+			payment_id: Some(PaymentId([1; 32])),
+			payment_hash: Some(PaymentHash([1; 32])),
+			reason: None,
+		};
+		metrics.record_event(&payment_failed);
+		metrics.record_event(&payment_failed);
+		metrics.record_event(&Event::ChannelClosed {
+			channel_id: ChannelId([2; 32]),
+			user_channel_id: UserChannelId(2),
+			counterparty_node_id: None,
+			reason: None,
+		});
+
+		let output = metrics.render();
+		for line in [
+			"# TYPE ldk_server_events_total counter",
+			"ldk_server_events_total{type=\"payment_failed\"} 2",
+			"ldk_server_events_total{type=\"channel_closed\"} 1",
+			"ldk_server_events_total{type=\"payment_received\"} 0",
+		] {
+			assert!(output.lines().any(|l| l == line), "Missing {} in:\n{}", line, output);
+		}
+	}
+}
@@ -1,2 +1,28 @@
+pub(crate) mod address;
+pub(crate) mod amount;
+pub(crate) mod auth;
+pub(crate) mod bitcoind_rpc;
+pub(crate) mod circuit_breaker;
+pub(crate) mod close_timeout;
 pub(crate) mod config;
+pub(crate) mod cors;
+pub(crate) mod encryption;
+pub(crate) mod event_log;
+pub(crate) mod fee_limit;
+pub(crate) mod health;
+pub(crate) mod invoice_fields;
+pub(crate) mod lnurl;
+pub(crate) mod metrics;
+pub(crate) mod page_token;
+pub(crate) mod payment_invoice;
+pub(crate) mod payment_timeout;
+pub(crate) mod peer_health;
 pub(crate) mod proto_adapter;
+pub(crate) mod receive_address_cache;
+pub(crate) mod request_nonce;
+pub(crate) mod response_body;
+pub(crate) mod route_randomization;
+pub(crate) mod store_probe;
+#[cfg(feature = "test-hooks")]
+pub(crate) mod test_hooks;
+pub(crate) mod wallet_sync;
@@ -0,0 +1,159 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::InvalidRequestError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ldk_node::bitcoin::hashes::cmp::fixed_time_eq;
+use ldk_node::bitcoin::hashes::hmac::{Hmac, HmacEngine};
+use ldk_node::bitcoin::hashes::{sha256, Hash, HashEngine};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The time after which an issued page token is no longer accepted.
+const PAGE_TOKEN_EXPIRY: Duration = Duration::from_secs(60 * 60);
+
+const EXPIRY_LEN: usize = 8;
+const TAG_LEN: usize = 32;
+
+/// Issues and validates opaque page tokens for list endpoints.
+///
+/// A token encodes the position at which the next page starts together with its expiry time, and
+/// is authenticated using HMAC-SHA256 so that clients can neither forge nor alter positions. Tokens
+/// are bound to the endpoint and query they were issued for, so they are rejected if passed along
+/// with any other. The key is generated on startup, hence tokens don't survive a server restart.
+#[derive(Clone)]
+pub(crate) struct PageTokenCodec {
+	key: [u8; 32],
+}
+
+impl PageTokenCodec {
+	pub(crate) fn new(key: [u8; 32]) -> Self {
+		Self { key }
+	}
+
+	/// Returns a token pointing to `position` in the listing of `endpoint` for the given `query`,
+	/// e.g., the serialized filters of the request.
+	pub(crate) fn encode(&self, endpoint: &str, query: &[u8], position: &[u8]) -> String {
+		self.encode_at(endpoint, query, position, SystemTime::now())
+	}
+
+	/// Validates the given `token` for `endpoint` and `query`, and returns the position it points
+	/// to.
+	pub(crate) fn decode(
+		&self, token: &str, endpoint: &str, query: &[u8],
+	) -> Result<Vec<u8>, LdkServerError> {
+		self.decode_at(token, endpoint, query, SystemTime::now())
+	}
+
+	/// Returns a token pointing to `offset` in the listing of `endpoint`, for endpoints without a
+	/// query.
+	pub(crate) fn encode_offset(&self, endpoint: &str, offset: u64) -> String {
+		self.encode(endpoint, &[], &offset.to_be_bytes())
+	}
+
+	/// Validates the given `token` for `endpoint` like [`Self::decode`], returning the offset it
+	/// points to.
+	pub(crate) fn decode_offset(&self, token: &str, endpoint: &str) -> Result<u64, LdkServerError> {
+		let position = self.decode(token, endpoint, &[])?;
+		let offset = <[u8; 8]>::try_from(&position[..])
+			.map_err(|_| LdkServerError::new(InvalidRequestError, "Invalid page token."))?;
+		Ok(u64::from_be_bytes(offset))
+	}
+
+	fn encode_at(&self, endpoint: &str, query: &[u8], position: &[u8], now: SystemTime) -> String {
+		let expires_at = (now + PAGE_TOKEN_EXPIRY).duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+		let mut token = Vec::with_capacity(EXPIRY_LEN + position.len() + TAG_LEN);
+		token.extend_from_slice(&expires_at.to_be_bytes());
+		token.extend_from_slice(position);
+		let tag = self.tag(endpoint, query, &token);
+		token.extend_from_slice(&tag);
+		URL_SAFE_NO_PAD.encode(token)
+	}
+
+	fn decode_at(
+		&self, token: &str, endpoint: &str, query: &[u8], now: SystemTime,
+	) -> Result<Vec<u8>, LdkServerError> {
+		let invalid_token = || LdkServerError::new(InvalidRequestError, "Invalid page token.");
+
+		let token = URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid_token())?;
+		if token.len() < EXPIRY_LEN + TAG_LEN {
+			return Err(invalid_token());
+		}
+
+		let (payload, tag) = token.split_at(token.len() - TAG_LEN);
+		if !fixed_time_eq(&self.tag(endpoint, query, payload), tag) {
+			return Err(invalid_token());
+		}
+
+		let (expires_at, position) = payload.split_at(EXPIRY_LEN);
+		// unwrap safety: `expires_at` is of length `EXPIRY_LEN`.
+		let expires_at = u64::from_be_bytes(expires_at.try_into().unwrap());
+		let now_secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+		if expires_at < now_secs {
+			return Err(LdkServerError::new(InvalidRequestError, "Page token has expired."));
+		}
+
+		Ok(position.to_vec())
+	}
+
+	fn tag(&self, endpoint: &str, query: &[u8], payload: &[u8]) -> [u8; TAG_LEN] {
+		let mut engine = HmacEngine::<sha256::Hash>::new(&self.key);
+		// Length-prefix the endpoint, so that it can't be confused with the following data.
+		engine.input(&(endpoint.len() as u64).to_be_bytes());
+		engine.input(endpoint.as_bytes());
+		engine.input(sha256::Hash::hash(query).as_byte_array());
+		engine.input(payload);
+		Hmac::<sha256::Hash>::from_engine(engine).to_byte_array()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_page_token_roundtrip() {
+		let codec = PageTokenCodec::new([42u8; 32]);
+		let token = codec.encode("ListPayments", b"query", &[7; 32]);
+		assert_eq!(codec.decode(&token, "ListPayments", b"query").unwrap(), vec![7; 32]);
+
+		let token = codec.encode_offset("ListPeers", 100);
+		assert_eq!(codec.decode_offset(&token, "ListPeers").unwrap(), 100);
+	}
+
+	#[test]
+	fn test_page_token_rejects_tampering() {
+		let codec = PageTokenCodec::new([42u8; 32]);
+		let mut token = URL_SAFE_NO_PAD.decode(codec.encode_offset("ListPeers", 100)).unwrap();
+		token[EXPIRY_LEN + 7] ^= 1;
+		let tampered_token = URL_SAFE_NO_PAD.encode(token);
+		let err = codec.decode_offset(&tampered_token, "ListPeers").unwrap_err();
+		assert_eq!(err.error_code, InvalidRequestError);
+
+		// A token issued with a different key is rejected as well.
+		let other_codec = PageTokenCodec::new([43u8; 32]);
+		assert!(other_codec
+			.decode_offset(&codec.encode_offset("ListPeers", 100), "ListPeers")
+			.is_err());
+
+		assert!(codec.decode_offset("not-a-token", "ListPeers").is_err());
+	}
+
+	#[test]
+	fn test_page_token_is_bound_to_endpoint_and_query() {
+		let codec = PageTokenCodec::new([42u8; 32]);
+		let token = codec.encode("ListPayments", b"query", &[7; 32]);
+		assert!(codec.decode(&token, "QueryEvents", b"query").is_err());
+		assert!(codec.decode(&token, "ListPayments", b"other query").is_err());
+		assert!(codec.decode(&token, "ListPayments", b"").is_err());
+	}
+
+	#[test]
+	fn test_page_token_expiry() {
+		let codec = PageTokenCodec::new([42u8; 32]);
+		let issued_at = SystemTime::now();
+		let token = codec.encode_at("ListPeers", &[], &[1], issued_at);
+		let decode = |now| codec.decode_at(&token, "ListPeers", &[], now);
+		assert_eq!(decode(issued_at + PAGE_TOKEN_EXPIRY).unwrap(), vec![1]);
+		assert!(decode(issued_at + PAGE_TOKEN_EXPIRY + Duration::from_secs(1)).is_err());
+	}
+}
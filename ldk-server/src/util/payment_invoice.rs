@@ -0,0 +1,37 @@
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::io::payment_invoices::{write_payment_invoice_record, PaymentInvoiceRecord};
+use ldk_node::bitcoin::hex::DisplayHex;
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_node::lightning::offers::offer::Offer;
+use ldk_node::lightning_invoice::Bolt11Invoice;
+
+/// Records the BOLT11 `invoice` the payment with the given id was sent to.
+///
+/// The payment was already sent at this point, so failing to record its invoice is only logged
+/// rather than failing the request, which clients might retry and thereby pay twice.
+pub(crate) fn record_bolt11_invoice(
+	store: &dyn PaginatedKVStore, payment_id: &PaymentId, invoice: &Bolt11Invoice,
+) {
+	let record =
+		PaymentInvoiceRecord { bolt11_invoice: Some(invoice.to_string()), bolt12_offer: None };
+	record_payment_invoice(store, payment_id, &record);
+}
+
+/// Records the BOLT12 `offer` the payment with the given id was sent to, see
+/// [`record_bolt11_invoice`].
+pub(crate) fn record_bolt12_offer(
+	store: &dyn PaginatedKVStore, payment_id: &PaymentId, offer: &Offer,
+) {
+	let record =
+		PaymentInvoiceRecord { bolt11_invoice: None, bolt12_offer: Some(offer.to_string()) };
+	record_payment_invoice(store, payment_id, &record);
+}
+
+fn record_payment_invoice(
+	store: &dyn PaginatedKVStore, payment_id: &PaymentId, record: &PaymentInvoiceRecord,
+) {
+	let payment_id = payment_id.0.to_lower_hex_string();
+	if let Err(e) = write_payment_invoice_record(store, &payment_id, record) {
+		eprintln!("Failed to persist invoice of payment {}: {}", payment_id, e);
+	}
+}
@@ -0,0 +1,260 @@
+use crate::api::error::{LdkServerError, ValidationError};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::io::payment_diagnostics::{
+	read_payment_diagnostics_record, write_payment_diagnostics_record, PaymentDiagnosticsRecord,
+};
+use crate::io::payment_timeouts::{
+	read_payment_timeout_record, remove_payment_timeout_record, write_payment_timeout_record,
+	PaymentTimeoutRecord,
+};
+use ldk_node::bitcoin::hex::DisplayHex;
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_node::lightning::ln::PaymentHash;
+use ldk_node::payment::{PaymentDetails, PaymentDirection, PaymentKind, PaymentStatus};
+use ldk_node::{LightningBalance, Node};
+use ldk_server_protos::types::PaymentFailureReason;
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Returns the timeout of a payment whose request sets `timeout_secs`, falling back to the
+/// `configured` one. Payments without a timeout are never abandoned.
+pub(crate) fn payment_timeout(
+	configured: Option<Duration>, timeout_secs: Option<u32>,
+) -> Result<Option<Duration>, LdkServerError> {
+	match timeout_secs {
+		Some(0) => Err(LdkServerError::invalid_fields(vec![ValidationError::new(
+			"timeout_secs",
+			"must be greater than zero".to_string(),
+		)])),
+		Some(timeout_secs) => Ok(Some(Duration::from_secs(timeout_secs as u64))),
+		None => Ok(configured),
+	}
+}
+
+/// Records that the payment with the given id is to be abandoned if it's still pending `timeout`
+/// after `now`.
+///
+/// The payment was already sent at this point, so failing to record its timeout is only logged
+/// rather than failing the request, which clients might retry and thereby pay twice.
+pub(crate) fn schedule_payment_timeout(
+	store: &dyn PaginatedKVStore, payment_id: &PaymentId, timeout: Duration, now: SystemTime,
+) {
+	let payment_id = payment_id.0.to_lower_hex_string();
+	let record = PaymentTimeoutRecord { timeout_at_secs: unix_secs(now) + timeout.as_secs() };
+	if let Err(e) = write_payment_timeout_record(store, &payment_id, &record) {
+		eprintln!("Failed to persist timeout of payment {}: {}", payment_id, e);
+	}
+}
+
+/// Abandons the outbound payments that are still pending past their timeout, such that they are
+/// reported as failed with a `UserAbandoned` failure reason.
+///
+/// LDK Node offers no way to abandon a payment, so this is bookkeeping on our side only. It stops
+/// retrying failed payments on its own after a few seconds, though, so payments without HTLCs in
+/// flight are stuck for good. Payments with HTLCs in flight are kept pending, as they may still
+/// succeed. Should an abandoned payment succeed nonetheless, its `PaymentSuccessful` event
+/// overwrites the failure.
+pub(crate) fn abandon_timed_out_payments(
+	node: &Node, store: &dyn PaginatedKVStore, now: SystemTime,
+) {
+	let pending_payments = node.list_payments_with_filter(|payment| {
+		payment.direction == PaymentDirection::Outbound && payment.status == PaymentStatus::Pending
+	});
+	if pending_payments.is_empty() {
+		return;
+	}
+	let in_flight_payment_hashes = node
+		.list_balances()
+		.lightning_balances
+		.into_iter()
+		.filter_map(|balance| match balance {
+			LightningBalance::MaybeTimeoutClaimableHTLC {
+				payment_hash,
+				outbound_payment: true,
+				..
+			} => Some(payment_hash),
+			_ => None,
+		})
+		.collect();
+	for payment_id in abandon_payments(store, &pending_payments, &in_flight_payment_hashes, now) {
+		println!(
+			"PAYMENT_ABANDONED: with id {} after timing out",
+			payment_id.0.to_lower_hex_string()
+		);
+	}
+}
+
+/// Returns `payment` with the status we report for it, i.e., `Failed` if it was abandoned.
+pub(crate) fn reported_payment(
+	store: &dyn PaginatedKVStore, mut payment: PaymentDetails,
+) -> PaymentDetails {
+	if payment.direction != PaymentDirection::Outbound || payment.status != PaymentStatus::Pending {
+		return payment;
+	}
+	let payment_id = payment.id.0.to_lower_hex_string();
+	// Only abandoned payments are failed while LDK Node still considers them pending.
+	if let Ok(Some(PaymentDiagnosticsRecord { failure_reason: Some(_), .. })) =
+		read_payment_diagnostics_record(store, &payment_id)
+	{
+		payment.status = PaymentStatus::Failed;
+	}
+	payment
+}
+
+// Abandons those of the `pending_payments` past their timeout at `now` that have no HTLCs in
+// flight, returning their ids.
+//
+// LDK Node only learns the payment hash of a BOLT12 payment once it resolved, so pending payments
+// of unknown hash are taken to have HTLCs in flight if any in-flight HTLC belongs to no payment of
+// known hash.
+fn abandon_payments(
+	store: &dyn PaginatedKVStore, pending_payments: &[PaymentDetails],
+	in_flight_payment_hashes: &HashSet<PaymentHash>, now: SystemTime,
+) -> Vec<PaymentId> {
+	let known_payment_hashes: HashSet<_> =
+		pending_payments.iter().filter_map(|payment| payment_hash(&payment.kind)).collect();
+	let unattributed_htlcs_in_flight =
+		in_flight_payment_hashes.iter().any(|hash| !known_payment_hashes.contains(hash));
+	let mut abandoned = Vec::new();
+	for payment in pending_payments {
+		let in_flight = match payment_hash(&payment.kind) {
+			Some(hash) => in_flight_payment_hashes.contains(&hash),
+			None => unattributed_htlcs_in_flight,
+		};
+		if in_flight {
+			continue;
+		}
+		let payment_id = payment.id.0.to_lower_hex_string();
+		match read_payment_timeout_record(store, &payment_id) {
+			Ok(Some(record)) if record.timeout_at_secs <= unix_secs(now) => {},
+			Ok(_) => continue,
+			Err(e) => {
+				eprintln!("Failed to read timeout of payment {}: {}", payment_id, e);
+				continue;
+			},
+		}
+		let record = PaymentDiagnosticsRecord {
+			fee_paid_msat: None,
+			failure_reason: Some(PaymentFailureReason::UserAbandoned as i32),
+		};
+		if let Err(e) = write_payment_diagnostics_record(store, &payment_id, &record) {
+			eprintln!("Failed to persist abandonment of payment {}: {}", payment_id, e);
+			continue;
+		}
+		if let Err(e) = remove_payment_timeout_record(store, &payment_id) {
+			eprintln!("Failed to remove timeout of payment {}: {}", payment_id, e);
+		}
+		abandoned.push(payment.id);
+	}
+	abandoned
+}
+
+fn payment_hash(kind: &PaymentKind) -> Option<PaymentHash> {
+	match kind {
+		PaymentKind::Bolt11 { hash, .. }
+		| PaymentKind::Bolt11Jit { hash, .. }
+		| PaymentKind::Spontaneous { hash, .. } => Some(*hash),
+		PaymentKind::Bolt12Offer { hash, .. } | PaymentKind::Bolt12Refund { hash, .. } => *hash,
+		PaymentKind::Onchain => None,
+	}
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+	use ldk_node::lightning::offers::offer::OfferId;
+
+	fn pending_payment(id: u8) -> PaymentDetails {
+		PaymentDetails {
+			id: PaymentId([id; 32]),
+			kind: PaymentKind::Bolt11 { hash: PaymentHash([id; 32]), preimage: None, secret: None },
+			amount_msat: Some(1_000_000),
+			direction: PaymentDirection::Outbound,
+			status: PaymentStatus::Pending,
+			latest_update_timestamp: 0,
+		}
+	}
+
+	#[test]
+	fn test_payment_to_unreachable_destination_fails_after_timeout() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let timeout = payment_timeout(Some(Duration::from_secs(300)), Some(60)).unwrap().unwrap();
+		assert_eq!(timeout, Duration::from_secs(60));
+
+		// No route leads to the destination, so none of the payment's HTLCs are in flight.
+		let unreachable = pending_payment(1);
+		let in_flight = pending_payment(2);
+		let in_flight_payment_hashes = HashSet::from([PaymentHash([2; 32])]);
+		let sent_at = SystemTime::now();
+		for payment in [&unreachable, &in_flight] {
+			schedule_payment_timeout(&store, &payment.id, timeout, sent_at);
+		}
+		let pending_payments = [unreachable.clone(), in_flight.clone()];
+
+		let before_timeout = sent_at + Duration::from_secs(59);
+		let abandoned =
+			abandon_payments(&store, &pending_payments, &in_flight_payment_hashes, before_timeout);
+		assert!(abandoned.is_empty());
+		assert_eq!(reported_payment(&store, unreachable.clone()).status, PaymentStatus::Pending);
+
+		let after_timeout = sent_at + Duration::from_secs(60);
+		let abandoned =
+			abandon_payments(&store, &pending_payments, &in_flight_payment_hashes, after_timeout);
+		assert_eq!(abandoned, [unreachable.id]);
+		assert_eq!(reported_payment(&store, unreachable.clone()).status, PaymentStatus::Failed);
+		let record =
+			read_payment_diagnostics_record(&store, &unreachable.id.0.to_lower_hex_string());
+		let failure_reason = record.unwrap().unwrap().failure_reason;
+		assert_eq!(failure_reason, Some(PaymentFailureReason::UserAbandoned as i32));
+
+		// The payment with HTLCs in flight may still succeed and is kept pending.
+		assert_eq!(reported_payment(&store, in_flight).status, PaymentStatus::Pending);
+	}
+
+	#[test]
+	fn test_unanswered_invoice_request_times_out() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let awaiting_invoice = PaymentDetails {
+			kind: PaymentKind::Bolt12Offer {
+				hash: None,
+				preimage: None,
+				secret: None,
+				offer_id: OfferId([3; 32]),
+				payer_note: None,
+				quantity: None,
+			},
+			..pending_payment(3)
+		};
+		let sent_at = SystemTime::now();
+		schedule_payment_timeout(&store, &awaiting_invoice.id, Duration::from_secs(30), sent_at);
+		let pending_payments = [awaiting_invoice.clone(), pending_payment(4)];
+		let after_timeout = sent_at + Duration::from_secs(30);
+
+		// HTLCs not belonging to any payment of known hash may be the BOLT12 payment's.
+		let in_flight_payment_hashes = HashSet::from([PaymentHash([4; 32]), PaymentHash([5; 32])]);
+		let abandoned =
+			abandon_payments(&store, &pending_payments, &in_flight_payment_hashes, after_timeout);
+		assert!(abandoned.is_empty());
+
+		// The offer's recipient never responded, so no HTLCs were sent.
+		let in_flight_payment_hashes = HashSet::from([PaymentHash([4; 32])]);
+		let abandoned =
+			abandon_payments(&store, &pending_payments, &in_flight_payment_hashes, after_timeout);
+		assert_eq!(abandoned, [awaiting_invoice.id]);
+		assert_eq!(reported_payment(&store, awaiting_invoice).status, PaymentStatus::Failed);
+	}
+
+	#[test]
+	fn test_payment_timeout() {
+		assert_eq!(payment_timeout(None, None).unwrap(), None);
+		let configured = Some(Duration::from_secs(300));
+		assert_eq!(payment_timeout(configured, None).unwrap(), configured);
+		assert!(payment_timeout(configured, Some(0)).is_err());
+	}
+}
@@ -0,0 +1,107 @@
+use ldk_node::bitcoin::secp256k1::PublicKey;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// The connection history of a peer, as observed since the server started.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PeerHealth {
+	/// When the current connection was established, if the peer is connected.
+	pub(crate) connected_since: Option<SystemTime>,
+	/// When the peer last disconnected, if it ever did.
+	pub(crate) last_disconnected_at: Option<SystemTime>,
+	/// The number of times the peer reconnected after being disconnected.
+	pub(crate) reconnect_count: u32,
+}
+
+/// Tracks the connection health of our peers.
+///
+/// As LDK Node doesn't emit events for peers connecting or disconnecting, the tracker is fed
+/// periodic snapshots of `Node::list_peers` and accumulates the observed state transitions.
+pub(crate) struct PeerHealthTracker {
+	peers: Mutex<HashMap<PublicKey, PeerHealth>>,
+}
+
+impl PeerHealthTracker {
+	pub(crate) fn new() -> Self {
+		Self { peers: Mutex::new(HashMap::new()) }
+	}
+
+	/// Records the connection state of the given peers as observed at `now`.
+	///
+	/// Peers we've seen before but which are missing from `peers` are considered disconnected.
+	pub(crate) fn update(
+		&self, peers: impl IntoIterator<Item = (PublicKey, bool)>, now: SystemTime,
+	) {
+		let mut tracked_peers = self.peers.lock().unwrap();
+		let mut observed_connected = Vec::new();
+		for (node_id, is_connected) in peers {
+			if is_connected {
+				observed_connected.push(node_id);
+			}
+			let health = tracked_peers.entry(node_id).or_insert(PeerHealth {
+				connected_since: None,
+				last_disconnected_at: None,
+				reconnect_count: 0,
+			});
+			if is_connected && health.connected_since.is_none() {
+				if health.last_disconnected_at.is_some() {
+					health.reconnect_count += 1;
+				}
+				health.connected_since = Some(now);
+			}
+		}
+
+		for (node_id, health) in tracked_peers.iter_mut() {
+			if health.connected_since.is_some() && !observed_connected.contains(node_id) {
+				health.connected_since = None;
+				health.last_disconnected_at = Some(now);
+			}
+		}
+	}
+
+	/// Returns the connection history of the given peer, or `None` if it was never seen.
+	pub(crate) fn get(&self, node_id: &PublicKey) -> Option<PeerHealth> {
+		self.peers.lock().unwrap().get(node_id).cloned()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_node::bitcoin::secp256k1::{Secp256k1, SecretKey};
+	use std::time::Duration;
+
+	#[test]
+	fn test_peer_reconnects_are_tracked() {
+		let secp_ctx = Secp256k1::new();
+		let node_id =
+			PublicKey::from_secret_key(&secp_ctx, &SecretKey::from_slice(&[42; 32]).unwrap());
+		let tracker = PeerHealthTracker::new();
+		let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+
+		assert_eq!(tracker.get(&node_id), None);
+
+		tracker.update([(node_id, true)], start);
+		let health = tracker.get(&node_id).unwrap();
+		assert_eq!(health.connected_since, Some(start));
+		assert_eq!(health.last_disconnected_at, None);
+		assert_eq!(health.reconnect_count, 0);
+
+		// Disconnected peers may be reported as such or not at all.
+		let disconnected_at = start + Duration::from_secs(10);
+		tracker.update([], disconnected_at);
+		let health = tracker.get(&node_id).unwrap();
+		assert_eq!(health.connected_since, None);
+		assert_eq!(health.last_disconnected_at, Some(disconnected_at));
+
+		let reconnected_at = start + Duration::from_secs(20);
+		tracker.update([(node_id, false)], start + Duration::from_secs(15));
+		tracker.update([(node_id, true)], reconnected_at);
+		tracker.update([(node_id, true)], start + Duration::from_secs(30));
+		let health = tracker.get(&node_id).unwrap();
+		assert_eq!(health.connected_since, Some(reconnected_at));
+		assert_eq!(health.last_disconnected_at, Some(disconnected_at));
+		assert_eq!(health.reconnect_count, 1);
+	}
+}
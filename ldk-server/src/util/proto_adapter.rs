@@ -1,8 +1,19 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{
+	AuthError, ConflictError, EndpointDisabledError, InsufficientFundsError,
+	InsufficientInboundCapacityError, InsufficientStorageError, InternalServerError,
+	InvalidRequestError, LightningError, NotFoundError, ServiceUnavailableError, TimeoutError,
+};
+use crate::io::events::EventRecord;
 use bytes::Bytes;
 use hex::prelude::*;
+use hyper::StatusCode;
 use ldk_node::config::{ChannelConfig, MaxDustHTLCExposure};
+use ldk_node::lightning::events::PaymentFailureReason;
 use ldk_node::payment::{PaymentDetails, PaymentDirection, PaymentKind, PaymentStatus};
-use ldk_node::{ChannelDetails, LightningBalance, PendingSweepBalance};
+use ldk_node::{ChannelDetails, LightningBalance, PeerDetails, PendingSweepBalance};
+use ldk_server_protos::error::{ErrorCode, ErrorResponse, ValidationError as ProtoValidationError};
+use ldk_server_protos::types::channel_dust_exposure::MaxDustHtlcExposure as ProtoDustExposure;
 use ldk_server_protos::types::lightning_balance::BalanceType::{
 	ClaimableAwaitingConfirmations, ClaimableOnChannelClose, ContentiousClaimable,
 	CounterpartyRevokedOutputClaimable, MaybePreimageClaimableHtlc, MaybeTimeoutClaimableHtlc,
@@ -13,9 +24,19 @@ use ldk_server_protos::types::payment_kind::Kind::{
 use ldk_server_protos::types::pending_sweep_balance::BalanceType::{
 	AwaitingThresholdConfirmations, BroadcastAwaitingConfirmation, PendingBroadcast,
 };
-use ldk_server_protos::types::{Channel, LspFeeLimits, OutPoint, Payment};
+use ldk_server_protos::types::{
+	Channel, ChannelDustExposure, LspFeeLimits, NodeEvent, OutPoint, Payment,
+	PaymentFailureReason as ProtoFailureReason, Peer,
+};
 
-pub(crate) fn channel_to_proto(channel: ChannelDetails) -> Channel {
+pub(crate) fn channel_to_proto(channel: ChannelDetails, counterparty_alias: String) -> Channel {
+	let confirmations_remaining = if channel.is_channel_ready {
+		Some(0)
+	} else {
+		channel
+			.confirmations_required
+			.map(|required| required.saturating_sub(channel.confirmations.unwrap_or(0)))
+	};
 	Channel {
 		channel_id: channel.channel_id.0.to_lower_hex_string(),
 		counterparty_node_id: channel.counterparty_node_id.to_string(),
@@ -49,6 +70,8 @@ pub(crate) fn channel_to_proto(channel: ChannelDetails) -> Channel {
 		counterparty_forwarding_info_cltv_expiry_delta: channel
 			.counterparty_forwarding_info_cltv_expiry_delta
 			.map(|x| x as u32),
+		confirmations_remaining,
+		counterparty_alias,
 	}
 }
 
@@ -80,6 +103,29 @@ pub(crate) fn channel_config_to_proto(
 	}
 }
 
+pub(crate) fn channel_dust_exposure_to_proto(channel: &ChannelDetails) -> ChannelDustExposure {
+	ChannelDustExposure {
+		user_channel_id: channel.user_channel_id.0.to_string(),
+		counterparty_node_id: channel.counterparty_node_id.to_string(),
+		max_dust_htlc_exposure: Some(max_dust_htlc_exposure_to_proto(
+			channel.config.max_dust_htlc_exposure,
+		)),
+	}
+}
+
+pub(crate) fn max_dust_htlc_exposure_to_proto(
+	max_dust_htlc_exposure: MaxDustHTLCExposure,
+) -> ProtoDustExposure {
+	match max_dust_htlc_exposure {
+		MaxDustHTLCExposure::FixedLimit { limit_msat } => {
+			ProtoDustExposure::FixedLimitMsat(limit_msat)
+		},
+		MaxDustHTLCExposure::FeeRateMultiplier { multiplier } => {
+			ProtoDustExposure::FeeRateMultiplier(multiplier)
+		},
+	}
+}
+
 pub(crate) fn payment_to_proto(payment: PaymentDetails) -> Payment {
 	Payment {
 		id: payment.id.0.to_lower_hex_string(),
@@ -100,6 +146,30 @@ pub(crate) fn payment_to_proto(payment: PaymentDetails) -> Payment {
 	}
 }
 
+pub(crate) fn event_record_to_proto(record: EventRecord) -> NodeEvent {
+	NodeEvent {
+		event_type: record.event_type,
+		timestamp_ms: record.timestamp_ms,
+		channel_id: record.channel_id,
+		user_channel_id: record.user_channel_id,
+		counterparty_node_id: record.counterparty_node_id,
+		payment_id: record.payment_id,
+		payment_hash: record.payment_hash,
+		amount_msat: record.amount_msat,
+		fee_paid_msat: record.fee_paid_msat,
+		reason: record.reason,
+	}
+}
+
+pub(crate) fn peer_to_proto(peer: PeerDetails) -> Peer {
+	Peer {
+		node_id: peer.node_id.to_string(),
+		address: peer.address.to_string(),
+		is_persisted: peer.is_persisted,
+		is_connected: peer.is_connected,
+	}
+}
+
 pub(crate) fn payment_kind_to_proto(
 	payment_kind: PaymentKind,
 ) -> ldk_server_protos::types::PaymentKind {
@@ -320,3 +390,174 @@ pub(crate) fn pending_sweep_balance_to_proto(
 		},
 	}
 }
+
+pub(crate) fn payment_failure_reason_to_proto(reason: PaymentFailureReason) -> i32 {
+	let reason = match reason {
+		PaymentFailureReason::RecipientRejected => ProtoFailureReason::RecipientRejected,
+		PaymentFailureReason::UserAbandoned => ProtoFailureReason::UserAbandoned,
+		PaymentFailureReason::RetriesExhausted => ProtoFailureReason::RetriesExhausted,
+		PaymentFailureReason::PaymentExpired => ProtoFailureReason::PaymentExpired,
+		PaymentFailureReason::RouteNotFound => ProtoFailureReason::RouteNotFound,
+		PaymentFailureReason::UnexpectedError => ProtoFailureReason::UnexpectedError,
+		PaymentFailureReason::UnknownRequiredFeatures => {
+			ProtoFailureReason::UnknownRequiredFeatures
+		},
+		PaymentFailureReason::InvoiceRequestExpired => ProtoFailureReason::InvoiceRequestExpired,
+		PaymentFailureReason::InvoiceRequestRejected => ProtoFailureReason::InvoiceRequestRejected,
+	};
+	reason as i32
+}
+
+pub(crate) fn to_error_response(ldk_error: LdkServerError) -> (ErrorResponse, StatusCode) {
+	let error_code = match ldk_error.error_code {
+		InvalidRequestError => ErrorCode::InvalidRequestError,
+		AuthError => ErrorCode::AuthError,
+		LightningError => ErrorCode::LightningError,
+		InternalServerError => ErrorCode::InternalServerError,
+		NotFoundError => ErrorCode::NotFoundError,
+		InsufficientFundsError => ErrorCode::InsufficientFundsError,
+		ServiceUnavailableError => ErrorCode::ServiceUnavailableError,
+		TimeoutError => ErrorCode::TimeoutError,
+		ConflictError => ErrorCode::ConflictError,
+		InsufficientStorageError => ErrorCode::InsufficientStorageError,
+		EndpointDisabledError => ErrorCode::EndpointDisabledError,
+		InsufficientInboundCapacityError => ErrorCode::InsufficientInboundCapacityError,
+	} as i32;
+
+	let status = match ldk_error.error_code {
+		InvalidRequestError => StatusCode::BAD_REQUEST,
+		AuthError => StatusCode::UNAUTHORIZED,
+		LightningError => StatusCode::INTERNAL_SERVER_ERROR,
+		InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+		NotFoundError => StatusCode::NOT_FOUND,
+		InsufficientFundsError => StatusCode::PAYMENT_REQUIRED,
+		ServiceUnavailableError => StatusCode::SERVICE_UNAVAILABLE,
+		TimeoutError => StatusCode::REQUEST_TIMEOUT,
+		ConflictError => StatusCode::CONFLICT,
+		InsufficientStorageError => StatusCode::INSUFFICIENT_STORAGE,
+		EndpointDisabledError => StatusCode::FORBIDDEN,
+		InsufficientInboundCapacityError => StatusCode::UNPROCESSABLE_ENTITY,
+	};
+
+	let error_response = ErrorResponse {
+		message: ldk_error.message,
+		error_code,
+		needed_sats: ldk_error.needed_sats,
+		available_sats: ldk_error.available_sats,
+		validation_errors: ldk_error
+			.validation_errors
+			.into_iter()
+			.map(|e| ProtoValidationError { field: e.field, reason: e.reason })
+			.collect(),
+		supported_endpoints: ldk_error.supported_endpoints,
+	};
+	(error_response, status)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::error::ValidationError;
+	use ldk_node::bitcoin::secp256k1::PublicKey;
+	use ldk_node::lightning::ln::types::ChannelId;
+	use ldk_node::UserChannelId;
+	use std::str::FromStr;
+
+	#[test]
+	fn test_insufficient_funds_error_response() {
+		let (error_response, status) =
+			to_error_response(LdkServerError::insufficient_funds(1000, 400));
+		assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+		assert_eq!(error_response.error_code, ErrorCode::InsufficientFundsError as i32);
+		assert_eq!(error_response.needed_sats, Some(1000));
+		assert_eq!(error_response.available_sats, Some(400));
+
+		let (error_response, status) =
+			to_error_response(ldk_node::NodeError::InsufficientFunds.into());
+		assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+		assert_eq!(error_response.error_code, ErrorCode::InsufficientFundsError as i32);
+		assert_eq!(error_response.needed_sats, None);
+		assert_eq!(error_response.available_sats, None);
+	}
+
+	#[test]
+	fn test_validation_error_response() {
+		let error = LdkServerError::invalid_fields(vec![
+			ValidationError::new("address", "not a valid address"),
+			ValidationError::new("amount_sats", "must be positive"),
+		]);
+		let (error_response, status) = to_error_response(error);
+		assert_eq!(status, StatusCode::BAD_REQUEST);
+		assert_eq!(error_response.error_code, ErrorCode::InvalidRequestError as i32);
+		let fields: Vec<_> = error_response.validation_errors.iter().map(|e| &e.field).collect();
+		assert_eq!(fields, ["address", "amount_sats"]);
+		assert_eq!(error_response.validation_errors[1].reason, "must be positive");
+	}
+
+	fn channel_details() -> ChannelDetails {
+		ChannelDetails {
+			channel_id: ChannelId([1; 32]),
+			counterparty_node_id: PublicKey::from_str(
+				"02eec7245d6b7d2ccb30380bfbe2a3648cd7a942653f5aa340edcea1f283686619",
+			)
+			.unwrap(),
+			funding_txo: None,
+			channel_value_sats: 1_000_000,
+			unspendable_punishment_reserve: None,
+			user_channel_id: UserChannelId(1),
+			feerate_sat_per_1000_weight: 253,
+			outbound_capacity_msat: 1_000_000_000,
+			inbound_capacity_msat: 0,
+			confirmations_required: Some(6),
+			confirmations: Some(6),
+			is_outbound: true,
+			is_channel_ready: true,
+			is_usable: true,
+			is_announced: true,
+			cltv_expiry_delta: None,
+			counterparty_unspendable_punishment_reserve: 0,
+			counterparty_outbound_htlc_minimum_msat: None,
+			counterparty_outbound_htlc_maximum_msat: None,
+			counterparty_forwarding_info_fee_base_msat: None,
+			counterparty_forwarding_info_fee_proportional_millionths: None,
+			counterparty_forwarding_info_cltv_expiry_delta: None,
+			next_outbound_htlc_limit_msat: 1_000_000_000,
+			next_outbound_htlc_minimum_msat: 0,
+			force_close_spend_delay: None,
+			inbound_htlc_minimum_msat: 0,
+			inbound_htlc_maximum_msat: None,
+			config: ChannelConfig::default(),
+		}
+	}
+
+	#[test]
+	fn test_channel_flags_to_proto() {
+		let channel = channel_details();
+		let proto = channel_to_proto(channel.clone(), String::new());
+		assert!(proto.is_outbound);
+		assert!(proto.is_announced);
+
+		let channel = ChannelDetails { is_outbound: false, is_announced: false, ..channel };
+		let proto = channel_to_proto(channel, String::new());
+		assert!(!proto.is_outbound);
+		assert!(!proto.is_announced);
+	}
+
+	#[test]
+	fn test_channel_confirmations_remaining() {
+		let confirmations_remaining = |confirmations: Option<u32>, is_channel_ready: bool| {
+			let channel = ChannelDetails { confirmations, is_channel_ready, ..channel_details() };
+			channel_to_proto(channel, String::new()).confirmations_remaining
+		};
+		assert_eq!(confirmations_remaining(None, false), Some(6));
+		assert_eq!(confirmations_remaining(Some(2), false), Some(4));
+		assert_eq!(confirmations_remaining(Some(6), true), Some(0));
+		// More confirmations than required don't underflow, even before the channel is ready.
+		assert_eq!(confirmations_remaining(Some(10), false), Some(0));
+		assert_eq!(confirmations_remaining(Some(10), true), Some(0));
+
+		let channel = ChannelDetails { confirmations_required: None, ..channel_details() };
+		let channel = ChannelDetails { is_channel_ready: false, ..channel };
+		assert_eq!(channel_to_proto(channel, String::new()).confirmations_remaining, None);
+	}
+}
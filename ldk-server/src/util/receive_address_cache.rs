@@ -0,0 +1,107 @@
+use crate::api::error::LdkServerError;
+use ldk_server_protos::types::AddressType;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Remembers the on-chain addresses recently handed out by `OnchainReceive`, such that identical
+/// requests made in quick succession are answered with the same address.
+///
+/// Every new address advances the wallet's derivation index, so chatty clients could otherwise
+/// exhaust the gap limit wallets restored from the seed scan for.
+///
+/// Addresses are only shared between requests for the same scope, such that deposits remain
+/// attributable to a single account.
+pub(crate) struct ReceiveAddressCache {
+	// The last address generated for each address type and scope, along with when it was generated.
+	addresses: Mutex<HashMap<AddressKey, (Instant, String)>>,
+}
+
+// The address type and scope an address was requested for.
+type AddressKey = (AddressType, Option<String>);
+
+impl ReceiveAddressCache {
+	pub(crate) fn new() -> Self {
+		Self { addresses: Mutex::new(HashMap::new()) }
+	}
+
+	/// Returns the address of the given type generated for `scope` within `window` before `now`, or
+	/// one newly generated via `new_address` if there is none.
+	pub(crate) fn get_or_generate<F: FnOnce() -> Result<String, LdkServerError>>(
+		&self, address_type: AddressType, scope: Option<&str>, window: Duration, now: Instant,
+		new_address: F,
+	) -> Result<String, LdkServerError> {
+		let key = (address_type, scope.map(String::from));
+		// The lock is held while generating, such that concurrent requests share the new address.
+		let mut addresses = self.addresses.lock().unwrap();
+		if let Some((generated_at, address)) = addresses.get(&key) {
+			if now.saturating_duration_since(*generated_at) < window {
+				return Ok(address.clone());
+			}
+		}
+		let address = new_address()?;
+		addresses.insert(key, (now, address.clone()));
+		Ok(address)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::Cell;
+
+	#[test]
+	fn test_rapid_requests_share_address() {
+		let cache = ReceiveAddressCache::new();
+		let window = Duration::from_secs(10);
+		let start = Instant::now();
+		let generated = Cell::new(0);
+		let new_address = || {
+			generated.set(generated.get() + 1);
+			Ok(format!("address-{}", generated.get()))
+		};
+
+		let first = cache.get_or_generate(AddressType::SegwitV0, None, window, start, new_address);
+		let second = cache.get_or_generate(
+			AddressType::SegwitV0,
+			None,
+			window,
+			start + Duration::from_secs(1),
+			new_address,
+		);
+		assert_eq!(first.unwrap(), "address-1");
+		assert_eq!(second.unwrap(), "address-1");
+		assert_eq!(generated.get(), 1);
+
+		// Once the window passed, a new address is generated.
+		let third = cache.get_or_generate(
+			AddressType::SegwitV0,
+			None,
+			window,
+			start + Duration::from_secs(10),
+			new_address,
+		);
+		assert_eq!(third.unwrap(), "address-2");
+	}
+
+	#[test]
+	fn test_scopes_dont_share_addresses() {
+		let cache = ReceiveAddressCache::new();
+		let window = Duration::from_secs(10);
+		let now = Instant::now();
+		let generated = Cell::new(0);
+		let new_address = || {
+			generated.set(generated.get() + 1);
+			Ok(format!("address-{}", generated.get()))
+		};
+
+		let get =
+			|scope| cache.get_or_generate(AddressType::SegwitV0, scope, window, now, new_address);
+		assert_eq!(get(Some("alice")).unwrap(), "address-1");
+		assert_eq!(get(Some("bob")).unwrap(), "address-2");
+		assert_eq!(get(None).unwrap(), "address-3");
+		assert_eq!(get(Some("alice")).unwrap(), "address-1");
+		assert_eq!(get(Some("bob")).unwrap(), "address-2");
+		assert_eq!(generated.get(), 3);
+	}
+}
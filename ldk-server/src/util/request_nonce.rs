@@ -0,0 +1,206 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::{
+	ConflictError, InternalServerError, InvalidRequestError,
+};
+use crate::io::paginated_kv_store::PaginatedKVStore;
+use crate::io::request_nonces::{
+	is_request_nonce_used, read_request_nonce_records, remove_request_nonce_record,
+	write_request_nonce,
+};
+use crate::service::Context;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The maximum length of a request nonce in bytes.
+pub(crate) const MAX_REQUEST_NONCE_LEN: usize = 64;
+
+/// Registers the nonces of payment-initiating requests, such that captured requests can't be
+/// replayed.
+///
+/// Nonces start with their creation time and are only accepted within a window around the current
+/// time. Used nonces are persisted until they fall out of that window, such that requests can't be
+/// replayed after any amount of time, nor after a restart.
+pub(crate) struct RequestNonceTracker {
+	// The creation time, in seconds since the UNIX epoch, and record key of each persisted nonce,
+	// oldest first. Holding the lock also serializes registrations, such that concurrent requests
+	// can't both claim the same nonce.
+	nonces: Mutex<VecDeque<(u64, String)>>,
+}
+
+impl RequestNonceTracker {
+	/// Loads the nonces persisted before a restart from the `store`.
+	pub(crate) fn load(store: &dyn PaginatedKVStore) -> io::Result<Self> {
+		let mut nonces: Vec<_> = read_request_nonce_records(store)?
+			.into_iter()
+			.map(|(key, created_at)| (created_at, key))
+			.collect();
+		nonces.sort_unstable();
+		Ok(Self { nonces: Mutex::new(nonces.into()) })
+	}
+
+	/// Registers `nonce` as used in `store`, failing with an [`InvalidRequestError`] if it wasn't
+	/// created within `window` of `now`, or with a [`ConflictError`] if it was already used.
+	///
+	/// Nonces created longer than `window` before `now` are forgotten.
+	pub(crate) fn register(
+		&self, store: &dyn PaginatedKVStore, nonce: &str, now: SystemTime, window: Duration,
+	) -> Result<(), LdkServerError> {
+		let created_at = nonce_creation_time(nonce)?;
+		let now_secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+		let cutoff_secs = now_secs.saturating_sub(window.as_secs());
+		if created_at < cutoff_secs || created_at > now_secs.saturating_add(window.as_secs()) {
+			return Err(LdkServerError::new(
+				InvalidRequestError,
+				format!(
+					"Invalid request_nonce: must be created within {} seconds of the server's time.",
+					window.as_secs()
+				),
+			));
+		}
+		let store_failed = |e| {
+			LdkServerError::new(
+				InternalServerError,
+				format!("Failed to register request_nonce: {}", e),
+			)
+		};
+
+		let mut nonces = self.nonces.lock().unwrap();
+		while nonces.front().map_or(false, |(created_at, _)| *created_at < cutoff_secs) {
+			// unwrap safety: we just checked there's a nonce.
+			let (_, key) = nonces.pop_front().unwrap();
+			if let Err(e) = remove_request_nonce_record(store, &key) {
+				eprintln!("Failed to remove request_nonce record {}: {}", key, e);
+			}
+		}
+
+		if is_request_nonce_used(store, nonce).map_err(store_failed)? {
+			return Err(LdkServerError::new(
+				ConflictError,
+				"The request_nonce was already used by a previous request.",
+			));
+		}
+		let key = write_request_nonce(store, nonce, created_at).map_err(store_failed)?;
+		let pos = nonces.partition_point(|(other_created_at, _)| *other_created_at <= created_at);
+		nonces.insert(pos, (created_at, key));
+		Ok(())
+	}
+}
+
+// Returns the creation time, in seconds since the UNIX epoch, `nonce` starts with.
+fn nonce_creation_time(nonce: &str) -> Result<u64, LdkServerError> {
+	let created_at = nonce.split_once('-').and_then(|(created_at, _)| created_at.parse().ok());
+	created_at.ok_or_else(|| {
+		LdkServerError::new(
+			InvalidRequestError,
+			"Invalid request_nonce: must start with its creation time in seconds since the UNIX epoch, followed by a '-'.",
+		)
+	})
+}
+
+/// Registers the `request_nonce` of a payment-initiating request.
+///
+/// Requests without a nonce are rejected if the operator configured `require_request_nonce`.
+pub(crate) fn check_request_nonce(
+	context: &Context, request_nonce: Option<&str>,
+) -> Result<(), LdkServerError> {
+	let (require_request_nonce, window) = {
+		let config = context.config.read().unwrap();
+		(config.require_request_nonce, config.request_nonce_window)
+	};
+	let nonce = match request_nonce {
+		Some(nonce) => nonce,
+		None if require_request_nonce => {
+			return Err(LdkServerError::new(InvalidRequestError, "A request_nonce is required."));
+		},
+		None => return Ok(()),
+	};
+	if nonce.is_empty() || nonce.len() > MAX_REQUEST_NONCE_LEN {
+		return Err(LdkServerError::new(
+			InvalidRequestError,
+			format!("Invalid request_nonce: must be 1 to {} bytes long.", MAX_REQUEST_NONCE_LEN),
+		));
+	}
+	context.request_nonces.register(
+		context.paginated_kv_store.as_ref(),
+		nonce,
+		SystemTime::now(),
+		window,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::io::sqlite_store::tests::random_storage_path;
+	use crate::io::sqlite_store::SqliteStore;
+
+	const WINDOW: Duration = Duration::from_secs(600);
+
+	fn nonce(created_at: SystemTime, suffix: &str) -> String {
+		format!("{}-{}", created_at.duration_since(UNIX_EPOCH).unwrap().as_secs(), suffix)
+	}
+
+	#[test]
+	fn test_reused_nonce_is_rejected() {
+		let storage_path = random_storage_path();
+		let store = SqliteStore::new(storage_path.clone(), None, None).unwrap();
+		let tracker = RequestNonceTracker::load(&store).unwrap();
+		let now = SystemTime::now();
+		let (nonce_1, nonce_2) = (nonce(now, "1"), nonce(now, "2 with spaces/"));
+
+		tracker.register(&store, &nonce_1, now, WINDOW).unwrap();
+		let err = tracker.register(&store, &nonce_1, now, WINDOW).unwrap_err();
+		assert_eq!(err.error_code, ConflictError);
+
+		// Fresh nonces are accepted, including ones which aren't valid store keys.
+		tracker.register(&store, &nonce_2, now, WINDOW).unwrap();
+
+		// Nonces are still rejected after a restart.
+		let restarted_store = SqliteStore::new(storage_path, None, None).unwrap();
+		let restarted_tracker = RequestNonceTracker::load(&restarted_store).unwrap();
+		for nonce in [&nonce_1, &nonce_2] {
+			let err = restarted_tracker.register(&restarted_store, nonce, now, WINDOW).unwrap_err();
+			assert_eq!(err.error_code, ConflictError);
+		}
+		restarted_tracker.register(&restarted_store, &nonce(now, "3"), now, WINDOW).unwrap();
+	}
+
+	#[test]
+	fn test_nonces_outside_window_are_rejected() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let tracker = RequestNonceTracker::load(&store).unwrap();
+		let now = SystemTime::now();
+
+		for nonce in [
+			nonce(now - WINDOW - Duration::from_secs(1), "1"),
+			nonce(now + WINDOW + Duration::from_secs(1), "2"),
+			"nonce-3".to_string(),
+			"3".to_string(),
+		] {
+			let err = tracker.register(&store, &nonce, now, WINDOW).unwrap_err();
+			assert_eq!(err.error_code, InvalidRequestError);
+		}
+		tracker.register(&store, &nonce(now - WINDOW, "4"), now, WINDOW).unwrap();
+		tracker.register(&store, &nonce(now + WINDOW, "5"), now, WINDOW).unwrap();
+	}
+
+	#[test]
+	fn test_nonces_are_pruned_once_outside_window() {
+		let store = SqliteStore::new(random_storage_path(), None, None).unwrap();
+		let tracker = RequestNonceTracker::load(&store).unwrap();
+		let now = SystemTime::now();
+		let (old_nonce, new_nonce) = (nonce(now, "1"), nonce(now + WINDOW, "2"));
+		tracker.register(&store, &old_nonce, now, WINDOW).unwrap();
+		tracker.register(&store, &new_nonce, now, WINDOW).unwrap();
+
+		let later = now + WINDOW + Duration::from_secs(1);
+		tracker.register(&store, &nonce(later, "3"), later, WINDOW).unwrap();
+		assert!(!is_request_nonce_used(&store, &old_nonce).unwrap());
+		assert!(is_request_nonce_used(&store, &new_nonce).unwrap());
+
+		let restarted_tracker = RequestNonceTracker::load(&store).unwrap();
+		assert_eq!(restarted_tracker.nonces.lock().unwrap().len(), 2);
+	}
+}
@@ -0,0 +1,129 @@
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Body, Bytes, Frame, SizeHint};
+use prost::Message;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The body of the responses we serve, either sent in full or streamed.
+pub(crate) type ResponseBody = BoxBody<Bytes, Infallible>;
+
+/// Returns a body sending `bytes` in full.
+pub(crate) fn full_body(bytes: impl Into<Bytes>) -> ResponseBody {
+	Full::new(bytes.into()).boxed()
+}
+
+/// A body streaming a message whose bulk is a repeated message field, encoding one entry of the
+/// field at a time rather than the message as a whole.
+///
+/// Entries of a repeated message field are encoded as a sequence of length-delimited records, so
+/// the body is a valid encoding of the message, and clients may decode each entry as soon as its
+/// bytes have arrived.
+pub(crate) struct RepeatedFieldBody<M> {
+	// The optional length prefix, followed by the encoding of the message's other fields.
+	head: Option<Bytes>,
+	field_number: u32,
+	entries: std::vec::IntoIter<M>,
+	remaining_len: u64,
+}
+
+impl<M: Message> RepeatedFieldBody<M> {
+	/// Streams the encoding of `message`, where `entries` were taken out of its repeated field
+	/// `field_number`, prefixed by its length if `length_delimited`.
+	pub(crate) fn new<R: Message>(
+		message: &R, field_number: u32, entries: Vec<M>, length_delimited: bool,
+	) -> Self {
+		let entries_len: usize =
+			prost::encoding::message::encoded_len_repeated(field_number, &entries);
+		let message_len = message.encoded_len() + entries_len;
+		let mut head = Vec::new();
+		if length_delimited {
+			// unwrap safety: the buffer grows as needed.
+			prost::encode_length_delimiter(message_len, &mut head).unwrap();
+		}
+		// unwrap safety: the buffer grows as needed.
+		message.encode(&mut head).unwrap();
+		let remaining_len = (head.len() + entries_len) as u64;
+		Self {
+			head: Some(Bytes::from(head)),
+			field_number,
+			entries: entries.into_iter(),
+			remaining_len,
+		}
+	}
+}
+
+impl<M: Message + Unpin> Body for RepeatedFieldBody<M> {
+	type Data = Bytes;
+	type Error = Infallible;
+
+	fn poll_frame(
+		self: Pin<&mut Self>, _cx: &mut Context<'_>,
+	) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+		let this = self.get_mut();
+		let data = match this.head.take() {
+			Some(head) => head,
+			None => match this.entries.next() {
+				Some(entry) => {
+					let mut buf = Vec::new();
+					prost::encoding::message::encode(this.field_number, &entry, &mut buf);
+					Bytes::from(buf)
+				},
+				None => return Poll::Ready(None),
+			},
+		};
+		this.remaining_len -= data.len() as u64;
+		Poll::Ready(Some(Ok(Frame::data(data))))
+	}
+
+	fn is_end_stream(&self) -> bool {
+		self.head.is_none() && self.entries.len() == 0
+	}
+
+	fn size_hint(&self) -> SizeHint {
+		SizeHint::with_exact(self.remaining_len)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ldk_server_protos::api::ListChannelsResponse;
+	use ldk_server_protos::types::Channel;
+
+	#[tokio::test]
+	async fn test_body_is_streamed_encoding_of_message() {
+		let channels: Vec<Channel> = (0..3)
+			.map(|i| Channel { channel_id: format!("{:064x}", i), ..Default::default() })
+			.collect();
+		let response = ListChannelsResponse {
+			channels: channels.clone(),
+			data_freshness: 1,
+			data_age_ms: Some(42),
+		};
+
+		for length_delimited in [false, true] {
+			let message = ListChannelsResponse { channels: Vec::new(), ..response.clone() };
+			let mut body = RepeatedFieldBody::new(&message, 1, channels.clone(), length_delimited);
+			let expected_len = body.size_hint().exact().unwrap();
+
+			// The head and every channel are sent as separate frames.
+			let mut frames = Vec::new();
+			while let Some(frame) = body.frame().await {
+				frames.push(frame.unwrap().into_data().unwrap());
+			}
+			assert_eq!(frames.len(), 1 + channels.len());
+			assert!(body.is_end_stream());
+
+			let bytes = frames.concat();
+			assert_eq!(bytes.len() as u64, expected_len);
+			let decoded = if length_delimited {
+				ListChannelsResponse::decode_length_delimited(&bytes[..]).unwrap()
+			} else {
+				ListChannelsResponse::decode(&bytes[..]).unwrap()
+			};
+			assert_eq!(decoded, response);
+		}
+	}
+}
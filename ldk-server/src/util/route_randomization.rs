@@ -0,0 +1,63 @@
+use ldk_node::payment::SendingParameters;
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+/// The range randomized routes draw `max_channel_saturation_power_of_half` from, around LDK's
+/// default of 2, i.e., using at most a quarter of a channel's estimated capacity.
+const RANDOMIZED_CHANNEL_SATURATION_POWERS_OF_HALF: RangeInclusive<u8> = 1..=4;
+
+/// Returns `sending_parameters` adjusted such that the pathfinder doesn't deterministically choose
+/// the cheapest route, as requested via `randomize_route`.
+///
+/// LDK Node doesn't expose the scorer's parameters per payment, so we randomize how much of a
+/// channel's estimated capacity the payment may use instead. This varies the channels considered,
+/// and thereby the route chosen, from payment to payment, possibly at a higher fee.
+pub(crate) fn randomize_route<R: Rng>(
+	sending_parameters: Option<SendingParameters>, rng: &mut R,
+) -> SendingParameters {
+	let mut sending_parameters = sending_parameters.unwrap_or(SendingParameters {
+		max_total_routing_fee_msat: None,
+		max_total_cltv_expiry_delta: None,
+		max_path_count: None,
+		max_channel_saturation_power_of_half: None,
+	});
+	sending_parameters.max_channel_saturation_power_of_half =
+		Some(rng.gen_range(RANDOMIZED_CHANNEL_SATURATION_POWERS_OF_HALF));
+	sending_parameters
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::rngs::StdRng;
+	use rand::SeedableRng;
+	use std::collections::HashSet;
+
+	#[test]
+	fn test_randomize_route_alters_pathfinding_parameters() {
+		let mut rng = StdRng::seed_from_u64(42);
+		let sending_parameters = SendingParameters {
+			max_total_routing_fee_msat: Some(Some(10_000)),
+			max_total_cltv_expiry_delta: None,
+			max_path_count: None,
+			max_channel_saturation_power_of_half: None,
+		};
+
+		let mut saturation_powers = HashSet::new();
+		for _ in 0..100 {
+			let randomized = randomize_route(Some(sending_parameters.clone()), &mut rng);
+			let power = randomized.max_channel_saturation_power_of_half.unwrap();
+			assert!(RANDOMIZED_CHANNEL_SATURATION_POWERS_OF_HALF.contains(&power));
+			saturation_powers.insert(power);
+
+			// The remaining parameters, e.g., the fee limit, are kept.
+			assert_eq!(randomized.max_total_routing_fee_msat, Some(Some(10_000)));
+			assert_eq!(randomized.max_path_count, None);
+		}
+		assert_eq!(saturation_powers.len(), RANDOMIZED_CHANNEL_SATURATION_POWERS_OF_HALF.count());
+
+		let randomized = randomize_route(None, &mut rng);
+		assert!(randomized.max_channel_saturation_power_of_half.is_some());
+		assert_eq!(randomized.max_total_routing_fee_msat, None);
+	}
+}
@@ -0,0 +1,128 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::InsufficientStorageError;
+use ldk_node::lightning::util::persist::KVStore;
+
+/// The primary namespace of the key written to probe whether the node's store is writable.
+const STORE_PROBE_PRIMARY_NAMESPACE: &str = "ldk_server";
+
+/// The secondary namespace of the key written to probe whether the node's store is writable.
+const STORE_PROBE_SECONDARY_NAMESPACE: &str = "";
+
+/// The key written to probe whether the node's store is writable.
+const STORE_PROBE_KEY: &str = "write_probe";
+
+/// Fails with an [`InsufficientStorageError`] if `store` can't be written to, e.g., as the disk is
+/// full or its permissions changed.
+///
+/// Operations which need to persist state, such as sends, should check this first, so that they
+/// fail right away rather than midway through.
+pub(crate) fn check_store_writable(store: &dyn KVStore) -> Result<(), LdkServerError> {
+	store
+		.write(STORE_PROBE_PRIMARY_NAMESPACE, STORE_PROBE_SECONDARY_NAMESPACE, STORE_PROBE_KEY, &[])
+		.and_then(|()| {
+			store.remove(
+				STORE_PROBE_PRIMARY_NAMESPACE,
+				STORE_PROBE_SECONDARY_NAMESPACE,
+				STORE_PROBE_KEY,
+				false,
+			)
+		})
+		.map_err(|e| {
+			LdkServerError::new(
+				InsufficientStorageError,
+				format!("The node's store is not writable: {}", e),
+			)
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::proto_adapter::to_error_response;
+	use hyper::StatusCode;
+	use ldk_node::lightning::io;
+	use ldk_server_protos::error::ErrorCode;
+	use std::collections::HashMap;
+	use std::sync::Mutex;
+
+	/// An in-memory [`KVStore`] that can be switched to reject all writes, e.g., as if its disk
+	/// was full.
+	#[derive(Default)]
+	struct TestStore {
+		entries: Mutex<HashMap<String, Vec<u8>>>,
+		read_only: bool,
+	}
+
+	impl TestStore {
+		fn check_writable(&self) -> io::Result<()> {
+			if self.read_only {
+				return Err(io::Error::new(io::ErrorKind::PermissionDenied, "read-only store"));
+			}
+			Ok(())
+		}
+	}
+
+	impl KVStore for TestStore {
+		fn read(
+			&self, primary_namespace: &str, secondary_namespace: &str, key: &str,
+		) -> io::Result<Vec<u8>> {
+			let path = format!("{}/{}/{}", primary_namespace, secondary_namespace, key);
+			self.entries
+				.lock()
+				.unwrap()
+				.get(&path)
+				.cloned()
+				.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, path))
+		}
+
+		fn write(
+			&self, primary_namespace: &str, secondary_namespace: &str, key: &str, buf: &[u8],
+		) -> io::Result<()> {
+			self.check_writable()?;
+			let path = format!("{}/{}/{}", primary_namespace, secondary_namespace, key);
+			self.entries.lock().unwrap().insert(path, buf.to_vec());
+			Ok(())
+		}
+
+		fn remove(
+			&self, primary_namespace: &str, secondary_namespace: &str, key: &str, _lazy: bool,
+		) -> io::Result<()> {
+			self.check_writable()?;
+			let path = format!("{}/{}/{}", primary_namespace, secondary_namespace, key);
+			self.entries.lock().unwrap().remove(&path);
+			Ok(())
+		}
+
+		fn list(
+			&self, primary_namespace: &str, secondary_namespace: &str,
+		) -> io::Result<Vec<String>> {
+			let prefix = format!("{}/{}/", primary_namespace, secondary_namespace);
+			let entries = self.entries.lock().unwrap();
+			Ok(entries
+				.keys()
+				.filter_map(|path| path.strip_prefix(&prefix))
+				.map(String::from)
+				.collect())
+		}
+	}
+
+	#[test]
+	fn test_check_store_writable() {
+		let store = TestStore::default();
+		check_store_writable(&store).unwrap();
+		// The probe doesn't leave anything behind.
+		assert!(store
+			.list(STORE_PROBE_PRIMARY_NAMESPACE, STORE_PROBE_SECONDARY_NAMESPACE)
+			.unwrap()
+			.is_empty());
+
+		let store = TestStore { read_only: true, ..Default::default() };
+		let err = check_store_writable(&store).unwrap_err();
+		assert_eq!(err.error_code, InsufficientStorageError);
+		assert!(err.message.contains("read-only store"));
+
+		let (error_response, status) = to_error_response(err);
+		assert_eq!(status, StatusCode::INSUFFICIENT_STORAGE);
+		assert_eq!(error_response.error_code, ErrorCode::InsufficientStorageError as i32);
+	}
+}
@@ -0,0 +1,49 @@
+use crate::api::error::LdkServerError;
+use crate::api::error::LdkServerErrorCode::LightningError;
+use ldk_server_protos::types::PaymentFailureReason;
+use std::sync::Mutex;
+
+/// A payment failure simulated for integration tests of clients, failing the next Lightning send.
+pub(crate) struct SimulatedPaymentFailure {
+	reason: Mutex<Option<PaymentFailureReason>>,
+}
+
+impl SimulatedPaymentFailure {
+	pub(crate) fn new() -> Self {
+		Self { reason: Mutex::new(None) }
+	}
+
+	/// Makes the next send fail with the given `reason`, replacing any failure not yet taken.
+	pub(crate) fn set(&self, reason: PaymentFailureReason) {
+		*self.reason.lock().unwrap() = Some(reason);
+	}
+
+	/// Returns the error the send at hand fails with, if a failure was set, such that only that
+	/// send fails.
+	pub(crate) fn take(&self) -> Option<LdkServerError> {
+		self.reason.lock().unwrap().take().map(|reason| {
+			LdkServerError::new(
+				LightningError,
+				format!("Payment failed: {} (simulated)", reason.as_str_name()),
+			)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_simulated_failure_fails_next_send_only() {
+		let simulated_failure = SimulatedPaymentFailure::new();
+		assert!(simulated_failure.take().is_none());
+
+		simulated_failure.set(PaymentFailureReason::RecipientRejected);
+		simulated_failure.set(PaymentFailureReason::RouteNotFound);
+		let err = simulated_failure.take().unwrap();
+		assert_eq!(err.error_code, LightningError);
+		assert_eq!(err.message, "Payment failed: ROUTE_NOT_FOUND (simulated)");
+		assert!(simulated_failure.take().is_none());
+	}
+}
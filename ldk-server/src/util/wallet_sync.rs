@@ -0,0 +1,58 @@
+use crate::api::error::LdkServerError;
+use crate::service::Context;
+use ldk_server_protos::types::DataFreshness;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Syncs the on-chain and Lightning wallets with the chain source, for requests asking to reflect
+/// the latest chain state rather than the state as of the last background sync.
+///
+/// The sync counts towards the chain source circuit breaker, failing right away while it is open.
+pub(crate) fn sync_wallets(context: &Context) -> Result<(), LdkServerError> {
+	context.chain_source_breaker.check()?;
+	let res = context.node.sync_wallets().map_err(LdkServerError::from);
+	context.chain_source_breaker.record(res.as_ref().err());
+	res
+}
+
+/// Syncs the wallets via [`sync_wallets`] if the read request sets `sync_first`, returning whether
+/// the data then read is [`DataFreshness::Fresh`] or [`DataFreshness::Cached`].
+pub(crate) fn sync_wallets_for_read(
+	context: &Context, sync_first: bool,
+) -> Result<DataFreshness, LdkServerError> {
+	read_freshness(sync_first, || sync_wallets(context))
+}
+
+/// Returns how long before `now`, in milliseconds, a wallet was last synced at
+/// `latest_sync_timestamp`, in seconds since the UNIX epoch, if ever.
+pub(crate) fn data_age_ms(latest_sync_timestamp: Option<u64>, now: SystemTime) -> Option<u64> {
+	let now_ms = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+	latest_sync_timestamp.map(|timestamp| now_ms.saturating_sub(timestamp.saturating_mul(1000)))
+}
+
+fn read_freshness<S>(sync_first: bool, sync: S) -> Result<DataFreshness, LdkServerError>
+where
+	S: FnOnce() -> Result<(), LdkServerError>,
+{
+	if !sync_first {
+		return Ok(DataFreshness::Cached);
+	}
+	sync()?;
+	Ok(DataFreshness::Fresh)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	#[test]
+	fn test_data_freshness_follows_sync_first() {
+		let unreachable_sync = || -> Result<(), LdkServerError> { unreachable!() };
+		assert_eq!(read_freshness(false, unreachable_sync).unwrap(), DataFreshness::Cached);
+		assert_eq!(read_freshness(true, || Ok(())).unwrap(), DataFreshness::Fresh);
+
+		let now = UNIX_EPOCH + Duration::from_millis(1_700_000_002_500);
+		assert_eq!(data_age_ms(Some(1_700_000_000), now), Some(2_500));
+		assert_eq!(data_age_ms(None, now), None);
+	}
+}